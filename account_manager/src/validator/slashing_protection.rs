@@ -1,10 +1,12 @@
 use clap::{Arg, ArgAction, ArgMatches, Command};
 use environment::Environment;
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, InterchangeImportOutcome, SlashingDatabase,
-    SLASHING_PROTECTION_FILENAME,
+    interchange::{InterchangeMetadata, InterchangeMinifier},
+    InterchangeError, InterchangeImportOutcome, SlashingDatabase, SLASHING_PROTECTION_FILENAME,
+    SUPPORTED_INTERCHANGE_FORMAT_VERSION,
 };
 use std::fs::File;
+use std::io::BufWriter;
 use std::path::PathBuf;
 use std::str::FromStr;
 use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
@@ -12,15 +14,22 @@ use types::{Epoch, EthSpec, PublicKeyBytes, Slot};
 pub const CMD: &str = "slashing-protection";
 pub const IMPORT_CMD: &str = "import";
 pub const EXPORT_CMD: &str = "export";
+pub const MERGE_CMD: &str = "merge";
 
 pub const IMPORT_FILE_ARG: &str = "IMPORT-FILE";
 pub const EXPORT_FILE_ARG: &str = "EXPORT-FILE";
+pub const MERGE_FILES_ARG: &str = "MERGE-FILES";
 
 pub const PUBKEYS_FLAG: &str = "pubkeys";
+pub const SINCE_EPOCH_FLAG: &str = "since-epoch";
+pub const OUTPUT_FLAG: &str = "output";
 
 pub fn cli_app() -> Command {
     Command::new(CMD)
-        .about("Import or export slashing protection data to or from another client")
+        .about(
+            "Import or export slashing protection data to or from another client, or merge \
+             several interchange files into one",
+        )
         .display_order(0)
         .subcommand(
             Command::new(IMPORT_CMD)
@@ -54,6 +63,43 @@ pub fn cli_app() -> Command {
                         )
                         .display_order(0)
                 )
+                .arg(
+                    Arg::new(SINCE_EPOCH_FLAG)
+                        .long(SINCE_EPOCH_FLAG)
+                        .action(ArgAction::Set)
+                        .value_name("EPOCH")
+                        .help(
+                            "Only export blocks and attestations from this epoch onwards, omitting \
+                             validators without any. Useful for exporting the changes since a \
+                             previous export",
+                        )
+                        .display_order(0)
+                )
+        )
+        .subcommand(
+            Command::new(MERGE_CMD)
+                .about(
+                    "Merge several interchange files into a single minified interchange file, \
+                     containing the latest block and attestation of each validator",
+                )
+                .arg(
+                    Arg::new(MERGE_FILES_ARG)
+                        .action(ArgAction::Append)
+                        .value_name("FILES")
+                        .num_args(1..)
+                        .required(true)
+                        .help("The slashing protection interchange files to merge (.json)")
+                        .display_order(0)
+                )
+                .arg(
+                    Arg::new(OUTPUT_FLAG)
+                        .long(OUTPUT_FLAG)
+                        .action(ArgAction::Set)
+                        .value_name("FILE")
+                        .required(true)
+                        .help("The filename to write the merged interchange file to")
+                        .display_order(0)
+                )
         )
 }
 
@@ -82,11 +128,6 @@ pub fn cli_run<E: EthSpec>(
                 )
             })?;
 
-            eprint!("Loading JSON file into memory & deserializing");
-            let interchange = Interchange::from_json_reader(&import_file)
-                .map_err(|e| format!("Error parsing file for import: {:?}", e))?;
-            eprintln!(" [done].");
-
             let slashing_protection_database =
                 SlashingDatabase::open_or_create(&slashing_protection_db_path).map_err(|e| {
                     format!(
@@ -109,8 +150,10 @@ pub fn cli_run<E: EthSpec>(
                 }
             };
 
+            eprintln!("Importing records from {}", import_filename.display());
+
             match slashing_protection_database
-                .import_interchange_info(interchange, genesis_validators_root)
+                .import_interchange_stream(&import_file, genesis_validators_root)
             {
                 Ok(outcomes) => {
                     eprintln!("All records imported successfully:");
@@ -155,6 +198,13 @@ pub fn cli_run<E: EthSpec>(
                                 slashing protection data for them."
                         .to_string());
                 }
+                Err(InterchangeError::SerdeJsonError(e)) => {
+                    return Err(format!(
+                        "Error parsing file for import: {:?}\n\
+                         No data has been imported.",
+                        e
+                    ));
+                }
                 Err(e) => {
                     return Err(format!(
                         "Fatal error during import: {:?}\n\
@@ -188,6 +238,8 @@ pub fn cli_run<E: EthSpec>(
                 None
             };
 
+            let since_epoch: Option<Epoch> = clap_utils::parse_optional(matches, SINCE_EPOCH_FLAG)?;
+
             if !slashing_protection_db_path.exists() {
                 return Err(format!(
                     "No slashing protection database exists at: {}",
@@ -204,18 +256,65 @@ pub fn cli_run<E: EthSpec>(
                     )
                 })?;
 
-            let interchange = slashing_protection_database
-                .export_interchange_info(genesis_validators_root, selected_pubkeys.as_deref())
-                .map_err(|e| format!("Error during export: {:?}", e))?;
-
             let output_file = File::create(export_filename)
                 .map_err(|e| format!("Error creating output file: {:?}", e))?;
 
-            interchange
-                .write_to(&output_file)
+            let num_validators = slashing_protection_database
+                .export_interchange_stream(
+                    genesis_validators_root,
+                    selected_pubkeys.as_deref(),
+                    since_epoch,
+                    E::slots_per_epoch(),
+                    BufWriter::new(output_file),
+                )
+                .map_err(|e| format!("Error during export: {:?}", e))?;
+
+            eprintln!(
+                "Export of {} validators completed successfully",
+                num_validators
+            );
+
+            Ok(())
+        }
+        Some((MERGE_CMD, matches)) => {
+            let merge_filenames: Vec<PathBuf> = matches
+                .get_many::<String>(MERGE_FILES_ARG)
+                .ok_or_else(|| format!("No {} provided", MERGE_FILES_ARG))?
+                .map(PathBuf::from)
+                .collect();
+            let output_filename: PathBuf = clap_utils::parse_required(matches, OUTPUT_FLAG)?;
+
+            let mut minifier = InterchangeMinifier::new(InterchangeMetadata {
+                interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+                genesis_validators_root,
+            });
+
+            for merge_filename in &merge_filenames {
+                let merge_file = File::open(merge_filename).map_err(|e| {
+                    format!(
+                        "Unable to open file at {}: {:?}",
+                        merge_filename.display(),
+                        e
+                    )
+                })?;
+                minifier
+                    .add_json_reader(&merge_file)
+                    .map_err(|e| format!("Error merging {}: {:?}", merge_filename.display(), e))?;
+                eprintln!("Merged {}", merge_filename.display());
+            }
+
+            let output_file = File::create(&output_filename)
+                .map_err(|e| format!("Error creating output file: {:?}", e))?;
+            let num_validators = minifier
+                .write_to(BufWriter::new(output_file))
                 .map_err(|e| format!("Error writing output file: {:?}", e))?;
 
-            eprintln!("Export completed successfully");
+            eprintln!(
+                "Merged {} files containing {} validators into {}",
+                merge_filenames.len(),
+                num_validators,
+                output_filename.display()
+            );
 
             Ok(())
         }
@@ -2,7 +2,7 @@ use crate::beacon_chain::BEACON_CHAIN_DB_KEY;
 use crate::errors::BeaconChainError;
 use crate::head_tracker::{HeadTracker, SszHeadTracker};
 use crate::persisted_beacon_chain::{PersistedBeaconChain, DUMMY_CANONICAL_HEAD_BLOCK_ROOT};
-use parking_lot::Mutex;
+use parking_lot::{Mutex, MutexGuard};
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::mem;
@@ -36,6 +36,8 @@ pub struct BackgroundMigrator<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>
     prev_migration: Arc<Mutex<PrevMigration>>,
     #[allow(clippy::type_complexity)]
     tx_thread: Option<Mutex<(mpsc::Sender<Notification>, thread::JoinHandle<()>)>>,
+    /// Lock held while the migrator is running, and while it is paused.
+    pause_lock: Arc<Mutex<()>>,
     /// Genesis block root, for persisting the `PersistedBeaconChain`.
     genesis_block_root: Hash256,
     log: Logger,
@@ -141,14 +143,20 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             epoch: db.get_split_slot().epoch(E::slots_per_epoch()),
             epochs_per_migration: config.epochs_per_migration,
         }));
+        let pause_lock = Arc::new(Mutex::new(()));
         let tx_thread = if config.blocking {
             None
         } else {
-            Some(Mutex::new(Self::spawn_thread(db.clone(), log.clone())))
+            Some(Mutex::new(Self::spawn_thread(
+                db.clone(),
+                pause_lock.clone(),
+                log.clone(),
+            )))
         };
         Self {
             db,
             tx_thread,
+            pause_lock,
             prev_migration,
             genesis_block_root,
             log,
@@ -178,6 +186,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         if let Some(Notification::Finalization(notif)) =
             self.send_background_notification(Notification::Finalization(notif))
         {
+            let _pause_guard = self.pause_lock.lock();
             Self::run_migration(self.db.clone(), notif, &self.log);
         }
 
@@ -188,6 +197,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         if let Some(Notification::Reconstruction) =
             self.send_background_notification(Notification::Reconstruction)
         {
+            let _pause_guard = self.pause_lock.lock();
             Self::run_reconstruction(self.db.clone(), &self.log);
         }
     }
@@ -196,10 +206,20 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
         if let Some(Notification::PruneBlobs(data_availability_boundary)) =
             self.send_background_notification(Notification::PruneBlobs(data_availability_boundary))
         {
+            let _pause_guard = self.pause_lock.lock();
             Self::run_prune_blobs(self.db.clone(), data_availability_boundary, &self.log);
         }
     }
 
+    /// Pause the migrator until the returned guard is dropped.
+    ///
+    /// Waits up to `timeout` for a running migration, reconstruction or blob pruning to finish,
+    /// returning `None` if it does not. Notifications received while paused are processed once
+    /// the guard is dropped.
+    pub fn pause(&self, timeout: Duration) -> Option<MutexGuard<()>> {
+        self.pause_lock.try_lock_for(timeout)
+    }
+
     pub fn run_reconstruction(db: Arc<HotColdDB<E, Hot, Cold>>, log: &Logger) {
         if let Err(e) = db.reconstruct_historic_states() {
             error!(
@@ -235,7 +255,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
 
             // Restart the background thread if it has crashed.
             if let Err(tx_err) = tx.send(notif) {
-                let (new_tx, new_thread) = Self::spawn_thread(self.db.clone(), self.log.clone());
+                let (new_tx, new_thread) =
+                    Self::spawn_thread(self.db.clone(), self.pause_lock.clone(), self.log.clone());
 
                 *tx = new_tx;
                 let old_thread = mem::replace(thread, new_thread);
@@ -367,6 +388,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
             }
         };
 
+        // Prune historic states which have fallen outside of the retention window.
+        if let Err(e) = db.prune_historic_states_outside_retention() {
+            warn!(log, "Historic state pruning failed"; "error" => ?e);
+        }
+
         // Finally, compact the database so that new free space is properly reclaimed.
         if let Err(e) = Self::run_compaction(
             db,
@@ -385,6 +411,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
     /// Return a channel handle for sending requests to the thread.
     fn spawn_thread(
         db: Arc<HotColdDB<E, Hot, Cold>>,
+        pause_lock: Arc<Mutex<()>>,
         log: Logger,
     ) -> (mpsc::Sender<Notification>, thread::JoinHandle<()>) {
         let (tx, rx) = mpsc::channel();
@@ -418,6 +445,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> BackgroundMigrator<E, Ho
                         }
                     }
                 }
+                let _pause_guard = pause_lock.lock();
                 // If reconstruction is on-going, ignore finalization migration and blob pruning.
                 if reconstruction_notif.is_some() {
                     Self::run_reconstruction(db.clone(), &log);
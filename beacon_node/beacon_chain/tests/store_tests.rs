@@ -19,19 +19,25 @@ use logging::test_logger;
 use maplit::hashset;
 use rand::Rng;
 use slot_clock::{SlotClock, TestingSlotClock};
+use ssz::Encode;
 use state_processing::{state_advance::complete_state_advance, BlockReplayer};
 use std::collections::HashMap;
 use std::collections::HashSet;
 use std::convert::TryInto;
+use std::io::Write;
+use std::num::NonZeroUsize;
 use std::sync::{Arc, LazyLock};
 use std::time::Duration;
 use store::chunked_vector::Chunk;
 use store::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, STATE_UPPER_LIMIT_NO_RETAIN};
+use store::snapshot::{is_snapshot_restored, restore_snapshot, snapshot_dir};
 use store::{
     chunked_vector::{chunk_key, Field},
     get_key_for_col,
     iter::{BlockRootsIterator, StateRootsIterator},
-    BlobInfo, DBColumn, HotColdDB, KeyValueStore, KeyValueStoreOp, LevelDB, StoreConfig,
+    verify::Inconsistency,
+    BeaconNodeBackend, BlobInfo, DBColumn, HotColdDB, HotStateSummary, ItemStore, KeyValueStore,
+    KeyValueStoreOp, StateRetention, StoreConfig,
 };
 use tempfile::{tempdir, TempDir};
 use tokio::time::sleep;
@@ -49,7 +55,7 @@ static KEYPAIRS: LazyLock<Vec<Keypair>> =
 type E = MinimalEthSpec;
 type TestHarness = BeaconChainHarness<DiskHarnessType<E>>;
 
-fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+fn get_store(db_path: &TempDir) -> Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>> {
     get_store_generic(db_path, StoreConfig::default(), test_spec::<E>())
 }
 
@@ -57,7 +63,7 @@ fn get_store_generic(
     db_path: &TempDir,
     config: StoreConfig,
     spec: ChainSpec,
-) -> Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>> {
+) -> Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>> {
     let hot_path = db_path.path().join("hot_db");
     let cold_path = db_path.path().join("cold_db");
     let blobs_path = db_path.path().join("blobs_db");
@@ -76,7 +82,7 @@ fn get_store_generic(
 }
 
 fn get_harness(
-    store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
     validator_count: usize,
 ) -> TestHarness {
     // Most tests expect to retain historic states, so we use this as the default.
@@ -88,7 +94,7 @@ fn get_harness(
 }
 
 fn get_harness_generic(
-    store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
     validator_count: usize,
     chain_config: ChainConfig,
 ) -> TestHarness {
@@ -705,6 +711,56 @@ async fn randomised_skips() {
     check_iterators(&harness);
 }
 
+#[tokio::test]
+async fn hierarchical_state_diffs() {
+    let num_slots = E::slots_per_epoch() * 10;
+    let mut num_blocks_produced = 0;
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        hierarchy_config: Some("3,4,5".parse().unwrap()),
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+    let rng = &mut XorShiftRng::from_seed([42; 16]);
+
+    let mut head_slot = 0;
+
+    for slot in 1..=num_slots {
+        if rng.gen_bool(0.8) {
+            harness
+                .extend_chain(
+                    1,
+                    BlockStrategy::ForkCanonicalChainAt {
+                        previous_slot: Slot::new(head_slot),
+                        first_slot: Slot::new(slot),
+                    },
+                    AttestationStrategy::AllValidators,
+                )
+                .await;
+            harness.advance_slot();
+            num_blocks_produced += 1;
+            head_slot = slot;
+        } else {
+            harness.advance_slot();
+        }
+    }
+
+    check_split_slot(&harness, store.clone());
+    check_chain_dump(&harness, num_blocks_produced + 1);
+    check_iterators(&harness);
+
+    // Every frozen state, including those at skipped slots, should load with the correct root.
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > 0);
+    for slot in (0..split_slot.as_u64()).map(Slot::new) {
+        let expected_state_root = harness.chain.state_root_at_slot(slot).unwrap().unwrap();
+        let mut state = store.load_cold_state_by_slot(slot).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.update_tree_hash_cache().unwrap(), expected_state_root);
+    }
+}
+
 #[tokio::test]
 async fn long_skip() {
     let db_path = tempdir().unwrap();
@@ -2674,7 +2730,30 @@ async fn weak_subjectivity_sync_skips_at_genesis() {
     weak_subjectivity_sync_test(slots, checkpoint_slot).await
 }
 
+// Reconstruct states using several threads, with enough restore points for them to be encoded
+// concurrently.
+#[tokio::test]
+async fn weak_subjectivity_sync_parallel_reconstruction() {
+    let num_initial_slots = E::slots_per_epoch() * 11;
+    let checkpoint_slot = Slot::new(E::slots_per_epoch() * 9);
+    let slots = (1..num_initial_slots).map(Slot::new).collect();
+    let config = StoreConfig {
+        slots_per_restore_point: 2 * E::slots_per_epoch(),
+        reconstruction_threads: NonZeroUsize::new(3).unwrap(),
+        ..StoreConfig::default()
+    };
+    weak_subjectivity_sync_test_with_config(slots, checkpoint_slot, config).await
+}
+
 async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
+    weak_subjectivity_sync_test_with_config(slots, checkpoint_slot, StoreConfig::default()).await
+}
+
+async fn weak_subjectivity_sync_test_with_config(
+    slots: Vec<Slot>,
+    checkpoint_slot: Slot,
+    config: StoreConfig,
+) {
     // Build an initial chain on one harness, representing a synced node with full history.
     let num_final_blocks = E::slots_per_epoch() * 2;
 
@@ -2730,7 +2809,7 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
     let (shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
     let log = test_logger();
     let temp2 = tempdir().unwrap();
-    let store = get_store(&temp2);
+    let store = get_store_generic(&temp2, config, test_spec::<E>());
     let spec = test_spec::<E>();
     let seconds_per_slot = spec.seconds_per_slot;
 
@@ -2960,6 +3039,451 @@ async fn weak_subjectivity_sync_test(slots: Vec<Slot>, checkpoint_slot: Slot) {
     // Reconstruct states.
     store.clone().reconstruct_historic_states().unwrap();
     assert_eq!(store.get_anchor_slot(), None);
+    assert_eq!(store.get_reconstruction_progress(), None);
+
+    // All states can be loaded after reconstruction.
+    for (state_root, slot) in beacon_chain
+        .forwards_iter_state_roots(Slot::new(0))
+        .unwrap()
+        .map(Result::unwrap)
+    {
+        let mut state = store.get_state(&state_root, Some(slot)).unwrap().unwrap();
+        assert_eq!(state.slot(), slot);
+        assert_eq!(state.canonical_root().unwrap(), state_root);
+    }
+}
+
+/// Test that era files exported by a node with full history can be used to backfill a node that
+/// was checkpoint synced.
+#[tokio::test]
+async fn era_export_import() {
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let num_eras = 3;
+    let checkpoint_slot = Slot::new(num_eras * slots_per_era);
+
+    // Payloads must be retained for blocks to be exported.
+    let temp1 = tempdir().unwrap();
+    let config = StoreConfig {
+        prune_payloads: false,
+        ..StoreConfig::default()
+    };
+    let full_store = get_store_generic(&temp1, config, test_spec::<E>());
+    let harness = get_harness(full_store.clone(), LOW_VALIDATOR_COUNT);
+
+    // Build enough blocks to finalize the checkpoint slot.
+    harness
+        .extend_chain(
+            (checkpoint_slot + 4 * E::slots_per_epoch()).as_usize(),
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+    assert!(full_store.get_split_slot() > checkpoint_slot);
+
+    // Export every era up to the checkpoint.
+    let era_dir = tempdir().unwrap();
+    let era_paths = (0..=num_eras)
+        .map(|era| {
+            full_store
+                .export_era(era, "minimal", era_dir.path())
+                .unwrap()
+        })
+        .collect::<Vec<_>>();
+    assert!(era_paths[0]
+        .file_name()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .starts_with("minimal-00000-"));
+
+    // Initialise a new beacon chain from the checkpoint.
+    let wss_block_root = harness
+        .chain
+        .block_root_at_slot(checkpoint_slot, WhenSlotSkipped::Prev)
+        .unwrap()
+        .unwrap();
+    let wss_state_root = harness
+        .chain
+        .state_root_at_slot(checkpoint_slot)
+        .unwrap()
+        .unwrap();
+    let wss_block = full_store.get_full_block(&wss_block_root).unwrap().unwrap();
+    let wss_blobs_opt = full_store.get_blobs(&wss_block_root).unwrap();
+    let wss_state = full_store
+        .get_state(&wss_state_root, Some(checkpoint_slot))
+        .unwrap()
+        .unwrap();
+    let genesis_state = full_store
+        .load_cold_state_by_slot(Slot::new(0))
+        .unwrap()
+        .unwrap();
+
+    let (shutdown_tx, _shutdown_rx) = futures::channel::mpsc::channel(1);
+    let log = test_logger();
+    let temp2 = tempdir().unwrap();
+    let store = get_store(&temp2);
+    let spec = test_spec::<E>();
+    let kzg = get_kzg(&spec);
+    let mock =
+        mock_execution_layer_from_parts(&harness.spec, harness.runtime.task_executor.clone());
+    let slot_clock = TestingSlotClock::new(
+        Slot::new(0),
+        Duration::from_secs(harness.chain.genesis_time),
+        Duration::from_secs(spec.seconds_per_slot),
+    );
+    slot_clock.set_slot(harness.get_current_slot().as_u64());
+
+    let beacon_chain = BeaconChainBuilder::<DiskHarnessType<E>>::new(MinimalEthSpec, kzg)
+        .store(store.clone())
+        .custom_spec(test_spec::<E>().into())
+        .task_executor(harness.chain.task_executor.clone())
+        .logger(log.clone())
+        .weak_subjectivity_state(wss_state, wss_block.clone(), wss_blobs_opt, genesis_state)
+        .unwrap()
+        .store_migrator_config(MigratorConfig::default().blocking())
+        .dummy_eth1_backend()
+        .expect("should build dummy backend")
+        .slot_clock(slot_clock)
+        .shutdown_sender(shutdown_tx)
+        .chain_config(ChainConfig::default())
+        .event_handler(Some(ServerSentEventHandler::new_with_capacity(log, 1)))
+        .execution_layer(Some(mock.el))
+        .build()
+        .expect("should build");
+    assert_eq!(store.get_oldest_block_slot(), wss_block.slot());
+
+    // Importing an era that doesn't connect to the oldest known block should fail.
+    assert!(store.import_era(&era_paths[1]).is_err());
+
+    // Import the eras newest first. The era 0 file contains no blocks.
+    for era in (2..=num_eras as usize).rev() {
+        let num_imported = store.import_era(&era_paths[era]).unwrap();
+        assert!(num_imported > 0);
+    }
+
+    // Lighthouse doesn't export the genesis block, which is unsigned, but an era file containing
+    // it can still be imported. Only the blocks after genesis are counted.
+    let era_1_blocks = (1..slots_per_era)
+        .filter(|slot| {
+            harness
+                .chain
+                .block_root_at_slot(Slot::new(*slot), WhenSlotSkipped::None)
+                .unwrap()
+                .is_some()
+        })
+        .count();
+    let genesis_block = full_store
+        .get_full_block(&harness.chain.genesis_block_root)
+        .unwrap()
+        .unwrap();
+    let era_1_with_genesis = era_dir.path().join("era-1-with-genesis.era");
+    let mut bytes = std::fs::read(&era_paths[1]).unwrap();
+    let mut encoder = snap::write::FrameEncoder::new(vec![]);
+    encoder.write_all(&genesis_block.as_ssz_bytes()).unwrap();
+    let compressed_block = encoder.into_inner().unwrap();
+    // Insert the compressed block entry after the version entry, before the other blocks. The slot
+    // index isn't updated, as it's not used for importing.
+    let mut block_entry = vec![0x01, 0x00];
+    block_entry.extend_from_slice(&(compressed_block.len() as u32).to_le_bytes());
+    block_entry.extend_from_slice(&[0, 0]);
+    block_entry.extend_from_slice(&compressed_block);
+    bytes.splice(8..8, block_entry);
+    std::fs::write(&era_1_with_genesis, bytes).unwrap();
+    assert_eq!(store.import_era(&era_1_with_genesis).unwrap(), era_1_blocks);
+    assert_eq!(store.get_oldest_block_slot(), 0);
+    assert_eq!(store.import_era(&era_paths[0]).unwrap(), 0);
+
+    // Re-importing is a no-op.
+    assert_eq!(store.import_era(&era_paths[1]).unwrap(), 0);
+
+    // The block roots should match the original chain.
+    let forwards = beacon_chain
+        .forwards_iter_block_roots_until(Slot::new(0), checkpoint_slot)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    let expected = harness
+        .chain
+        .forwards_iter_block_roots_until(Slot::new(0), checkpoint_slot)
+        .unwrap()
+        .map(Result::unwrap)
+        .collect::<Vec<_>>();
+    assert_eq!(forwards, expected);
+}
+
+/// Test that historic states outside of the retention window are pruned during migration.
+#[tokio::test]
+async fn prune_historic_states_outside_retention() {
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let retention = StateRetention {
+        epochs: 4,
+        restore_point_interval: 2,
+    };
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        slots_per_restore_point,
+        state_retention: Some(retention),
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            (E::slots_per_epoch() * 12) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    // The window must be large enough for one restore point to be pruned and one retained.
+    let split_slot = store.get_split_slot();
+    let window_start_slot = split_slot - retention.epochs * E::slots_per_epoch();
+    let state_upper_limit =
+        Slot::new(window_start_slot.as_u64() / slots_per_restore_point * slots_per_restore_point);
+    assert!(state_upper_limit > 2 * slots_per_restore_point);
+    assert_eq!(
+        store.get_anchor_info().unwrap().state_upper_limit,
+        state_upper_limit
+    );
+
+    for slot in (0..split_slot.as_u64()).map(Slot::new) {
+        let retained = slot >= state_upper_limit
+            || (slot % slots_per_restore_point == 0
+                && retention.retains_restore_point(slot.as_u64() / slots_per_restore_point));
+        let state = store.load_cold_state_by_slot(slot).unwrap();
+        assert_eq!(state.is_some(), retained, "slot {slot}");
+        if let Some(state) = state {
+            assert_eq!(state.slot(), slot);
+        }
+    }
+}
+
+/// Test that reconstruction, as run on startup, doesn't regenerate states pruned by retention.
+#[tokio::test]
+async fn reconstruction_after_retention_pruning() {
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let retention = StateRetention {
+        epochs: 4,
+        restore_point_interval: 2,
+    };
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        slots_per_restore_point,
+        state_retention: Some(retention),
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            (E::slots_per_epoch() * 12) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let anchor = store.get_anchor_info().unwrap();
+    assert_eq!(anchor.state_lower_limit, 0);
+    assert_eq!(
+        store
+            .load_state_retention_info()
+            .unwrap()
+            .unwrap()
+            .pruned_state_upper_limit,
+        anchor.state_upper_limit
+    );
+
+    store.clone().reconstruct_historic_states().unwrap();
+
+    // The anchor is untouched and the pruned states remain unavailable.
+    assert_eq!(store.get_anchor_info(), Some(anchor.clone()));
+    for slot in (1..anchor.state_upper_limit.as_u64()).map(Slot::new) {
+        let retained = slot % slots_per_restore_point == 0
+            && retention.retains_restore_point(slot.as_u64() / slots_per_restore_point);
+        let state = store.load_cold_state_by_slot(slot).unwrap();
+        assert_eq!(state.is_some(), retained, "slot {slot}");
+    }
+}
+
+/// Test that a snapshot can be restored once, after which the restored database is recognised.
+#[tokio::test]
+async fn snapshot_and_restore() {
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            (E::slots_per_epoch() * 4) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let data_dir = tempdir().unwrap();
+    let dir = snapshot_dir(data_dir.path(), "backup").unwrap();
+    let report = store.snapshot(&dir).unwrap();
+    assert_eq!(report.split_slot, store.get_split_slot());
+    // Snapshots of the same name are never overwritten.
+    assert!(store.snapshot(&dir).is_err());
+
+    let restore_dir = tempdir().unwrap();
+    let hot_path = restore_dir.path().join("chain_db");
+    let cold_path = restore_dir.path().join("freezer_db");
+    let blobs_path = restore_dir.path().join("blobs_db");
+    assert!(!is_snapshot_restored(&hot_path));
+
+    let schema_version =
+        restore_snapshot::<E>(&dir, store.get_config(), &hot_path, &cold_path, &blobs_path)
+            .unwrap();
+    assert_eq!(schema_version, CURRENT_SCHEMA_VERSION);
+    assert!(is_snapshot_restored(&hot_path));
+
+    // Restoring over the restored database fails, which is why callers check first.
+    assert!(
+        restore_snapshot::<E>(&dir, store.get_config(), &hot_path, &cold_path, &blobs_path)
+            .is_err()
+    );
+}
+
+/// Test that blocks and restore points remain readable after compressing an existing database.
+#[tokio::test]
+async fn compress_existing_values() {
+    let slots_per_restore_point = 2 * E::slots_per_epoch();
+    let db_path = tempdir().unwrap();
+    let config = StoreConfig {
+        slots_per_restore_point,
+        ..StoreConfig::default()
+    };
+    let store = get_store_generic(&db_path, config, test_spec::<E>());
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            (E::slots_per_epoch() * 6) as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split_slot = store.get_split_slot();
+    assert!(split_slot > slots_per_restore_point);
+
+    let blocks = harness
+        .chain
+        .chain_dump()
+        .unwrap()
+        .into_iter()
+        .map(|snapshot| {
+            let block_root = snapshot.beacon_block_root;
+            (
+                block_root,
+                store.get_blinded_block(&block_root).unwrap().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let report = store.compress_existing_values().unwrap();
+    assert!(report.compressed >= blocks.len());
+    assert!(report.bytes_after < report.bytes_before);
+
+    // Compressed values are skipped on subsequent runs.
+    assert_eq!(store.compress_existing_values().unwrap().compressed, 0);
+
+    for (block_root, block) in blocks {
+        assert_eq!(store.get_blinded_block(&block_root).unwrap(), Some(block));
+    }
+    for slot in (0..split_slot.as_u64())
+        .step_by(slots_per_restore_point as usize)
+        .map(Slot::new)
+    {
+        let state = store.load_cold_state_by_slot(slot).unwrap().unwrap();
+        assert_eq!(
+            state.canonical_root().unwrap(),
+            harness.chain.state_root_at_slot(slot).unwrap().unwrap()
+        );
+    }
+}
+
+/// Test that `verify` detects and repairs inconsistencies in the database.
+#[tokio::test]
+async fn verify_database() {
+    let num_blocks_produced = E::slots_per_epoch() * 6;
+    let db_path = tempdir().unwrap();
+    let store = get_store(&db_path);
+    let harness = get_harness(store.clone(), LOW_VALIDATOR_COUNT);
+
+    harness
+        .extend_chain(
+            num_blocks_produced as usize,
+            BlockStrategy::OnCanonicalHead,
+            AttestationStrategy::AllValidators,
+        )
+        .await;
+
+    let split = store.get_split_info();
+    assert!(split.slot > 0);
+    let data_availability_boundary = harness.chain.data_availability_boundary();
+
+    // A freshly built database is consistent.
+    let report = store.verify(data_availability_boundary, false).unwrap();
+    assert_eq!(report.inconsistencies, vec![]);
+
+    // Add a state summary for a state older than the split, which should have been pruned.
+    let dangling_state_root = Hash256::repeat_byte(0xaa);
+    let dangling_summary = HotStateSummary {
+        slot: split.slot - 1,
+        latest_block_root: split.block_root,
+        epoch_boundary_state_root: split.state_root,
+    };
+    store
+        .hot_db
+        .put(&dangling_state_root, &dangling_summary)
+        .unwrap();
+
+    // Delete a finalized block.
+    let deleted_block_slot = Slot::new(1);
+    let deleted_block_root = harness
+        .chain
+        .block_root_at_slot(deleted_block_slot, WhenSlotSkipped::None)
+        .unwrap()
+        .unwrap();
+    store.delete_block(&deleted_block_root).unwrap();
+
+    let report = store.verify(data_availability_boundary, false).unwrap();
+    assert_eq!(report.repaired, 0);
+    assert_eq!(report.inconsistencies.len(), 2);
+    assert!(report
+        .inconsistencies
+        .contains(&Inconsistency::MissingFrozenBlock {
+            slot: deleted_block_slot,
+            block_root: deleted_block_root,
+        }));
+    assert!(report
+        .inconsistencies
+        .contains(&Inconsistency::DanglingStateSummary {
+            state_root: dangling_state_root,
+            slot: dangling_summary.slot,
+        }));
+
+    // Only the dangling summary can be repaired.
+    let report = store.verify(data_availability_boundary, true).unwrap();
+    assert_eq!(report.repaired, 1);
+    assert!(store
+        .load_hot_state_summary(&dangling_state_root)
+        .unwrap()
+        .is_none());
+
+    let report = store.verify(data_availability_boundary, false).unwrap();
+    assert_eq!(
+        report.inconsistencies,
+        vec![Inconsistency::MissingFrozenBlock {
+            slot: deleted_block_slot,
+            block_root: deleted_block_root,
+        }]
+    );
 }
 
 /// Test that blocks and attestations that refer to states around an unaligned split state are
@@ -3908,7 +4432,10 @@ fn check_finalization(harness: &TestHarness, expected_slot: u64) {
 }
 
 /// Check that the HotColdDB's split_slot is equal to the start slot of the last finalized epoch.
-fn check_split_slot(harness: &TestHarness, store: Arc<HotColdDB<E, LevelDB<E>, LevelDB<E>>>) {
+fn check_split_slot(
+    harness: &TestHarness,
+    store: Arc<HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>>,
+) {
     let split_slot = store.get_split_slot();
     assert_eq!(
         harness
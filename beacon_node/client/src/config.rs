@@ -58,6 +58,8 @@ pub struct Config {
     pub freezer_db_path: Option<PathBuf>,
     /// Path where the blobs database will be located if blobs should be in a separate database.
     pub blobs_db_path: Option<PathBuf>,
+    /// Snapshot to restore the database from before starting.
+    pub restore_from_snapshot: Option<PathBuf>,
     pub log_file: PathBuf,
     /// If true, the node will use co-ordinated junk for eth1 values.
     ///
@@ -98,6 +100,7 @@ impl Default for Config {
             db_name: "chain_db".to_string(),
             freezer_db_path: None,
             blobs_db_path: None,
+            restore_from_snapshot: None,
             log_file: PathBuf::from(""),
             genesis: <_>::default(),
             store: <_>::default(),
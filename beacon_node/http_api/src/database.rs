@@ -1,7 +1,14 @@
 use beacon_chain::store::metadata::CURRENT_SCHEMA_VERSION;
+use beacon_chain::store::snapshot::snapshot_dir;
 use beacon_chain::{BeaconChain, BeaconChainTypes};
-use eth2::lighthouse::DatabaseInfo;
+use eth2::lighthouse::{DatabaseInfo, DatabaseSnapshotRequest, DatabaseStats, SnapshotReport};
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
+use warp_utils::reject::{custom_bad_request, custom_server_error};
+
+/// Maximum time to wait for a running migration to finish before taking a snapshot.
+const MIGRATOR_PAUSE_TIMEOUT: Duration = Duration::from_secs(60);
 
 pub fn info<T: BeaconChainTypes>(
     chain: Arc<BeaconChain<T>>,
@@ -11,6 +18,7 @@ pub fn info<T: BeaconChainTypes>(
     let config = store.get_config().clone();
     let anchor = store.get_anchor_info();
     let blob_info = store.get_blob_info();
+    let reconstruction = store.get_reconstruction_progress();
 
     Ok(DatabaseInfo {
         schema_version: CURRENT_SCHEMA_VERSION.as_u64(),
@@ -18,5 +26,47 @@ pub fn info<T: BeaconChainTypes>(
         split,
         anchor,
         blob_info,
+        reconstruction,
     })
 }
+
+pub fn stats<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+) -> Result<DatabaseStats, warp::Rejection> {
+    chain
+        .store
+        .database_stats(chain.data_availability_boundary())
+        .map_err(|e| custom_server_error(format!("unable to compute database stats: {e:?}")))
+}
+
+pub fn snapshot<T: BeaconChainTypes>(
+    chain: Arc<BeaconChain<T>>,
+    data_dir: &Path,
+    request: DatabaseSnapshotRequest,
+) -> Result<SnapshotReport, warp::Rejection> {
+    let dir = snapshot_dir(data_dir, &request.name)
+        .map_err(|e| custom_bad_request(format!("invalid snapshot name: {e:?}")))?;
+
+    // Hold the migrator paused until the snapshot is complete, so that nothing is deleted from
+    // the hot database and the freezer database remains unchanged.
+    let _pause_guard = chain
+        .store_migrator
+        .pause(MIGRATOR_PAUSE_TIMEOUT)
+        .ok_or_else(|| {
+            custom_server_error(
+                "timed out waiting for the database migrator, try again later".to_string(),
+            )
+        })?;
+
+    chain
+        .persist_head_and_fork_choice()
+        .map_err(|e| custom_server_error(format!("unable to persist fork choice: {e:?}")))?;
+    chain
+        .persist_op_pool()
+        .map_err(|e| custom_server_error(format!("unable to persist op pool: {e:?}")))?;
+
+    chain
+        .store
+        .snapshot(&dir)
+        .map_err(|e| custom_server_error(format!("unable to snapshot database: {e:?}")))
+}
@@ -49,8 +49,11 @@ use eth2::types::{
     LightClientUpdatesQuery, PublishBlockRequest, ValidatorBalancesRequestBody, ValidatorId,
     ValidatorStatus, ValidatorsRequestBody,
 };
-use eth2::{CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
-use lighthouse_network::{types::SyncState, EnrExt, NetworkGlobals, PeerId, PubsubMessage};
+use eth2::{trace_context, CONSENSUS_VERSION_HEADER, CONTENT_TYPE_HEADER, SSZ_CONTENT_TYPE_HEADER};
+use lighthouse_network::peer_manager::peer_groups::peer_id_from_multiaddr;
+use lighthouse_network::{
+    types::SyncState, EnrExt, Multiaddr, NetworkGlobals, PeerId, PubsubMessage,
+};
 use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
 use network::{NetworkMessage, NetworkSenders, ValidatorSubscriptionMessage};
@@ -81,12 +84,13 @@ use tokio_stream::{
     StreamExt,
 };
 use types::{
-    fork_versioned_response::EmptyMetadata, Attestation, AttestationData, AttestationShufflingId,
-    AttesterSlashing, BeaconStateError, CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName,
-    ForkVersionedResponse, Hash256, ProposerPreparationData, ProposerSlashing, RelativeEpoch,
-    SignedAggregateAndProof, SignedBlindedBeaconBlock, SignedBlsToExecutionChange,
-    SignedContributionAndProof, SignedValidatorRegistrationData, SignedVoluntaryExit, Slot,
-    SyncCommitteeMessage, SyncContributionData,
+    consts::altair::SYNC_COMMITTEE_SUBNET_COUNT, fork_versioned_response::EmptyMetadata,
+    Attestation, AttestationData, AttestationShufflingId, AttesterSlashing, BeaconStateError,
+    CommitteeCache, ConfigAndPreset, Epoch, EthSpec, ForkName, ForkVersionedResponse, Hash256,
+    ProposerPreparationData, ProposerSlashing, RelativeEpoch, SignedAggregateAndProof,
+    SignedBlindedBeaconBlock, SignedBlsToExecutionChange, SignedContributionAndProof,
+    SignedValidatorRegistrationData, SignedVoluntaryExit, Slot, SyncCommitteeMessage,
+    SyncContributionData,
 };
 use validator::pubkey_to_validator_index;
 use version::{
@@ -192,10 +196,18 @@ impl From<String> for Error {
 }
 
 /// Creates a `warp` logging wrapper which we use to create `slog` logs.
+///
+/// The trace ID of the request's `traceparent` header is logged, if it has a valid one, so that
+/// requests from a validator client can be correlated with its traces.
 pub fn slog_logging(
     log: Logger,
 ) -> warp::filters::log::Log<impl Fn(warp::filters::log::Info) + Clone> {
     warp::log::custom(move |info| {
+        let trace_id = info
+            .request_headers()
+            .get(trace_context::TRACEPARENT_HEADER)
+            .and_then(|traceparent| traceparent.to_str().ok())
+            .and_then(trace_context::trace_id);
         match info.status() {
             status
                 if status == StatusCode::OK
@@ -209,6 +221,7 @@ pub fn slog_logging(
                     "status" => status.to_string(),
                     "path" => info.path(),
                     "method" => info.method().to_string(),
+                    "trace_id" => trace_id,
                 );
             }
             status => {
@@ -219,6 +232,7 @@ pub fn slog_logging(
                     "status" => status.to_string(),
                     "path" => info.path(),
                     "method" => info.method().to_string(),
+                    "trace_id" => trace_id,
                 );
             }
         };
@@ -3581,7 +3595,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(chain_filter.clone())
         .and(warp_utils::json::json())
-        .and(network_tx_filter)
+        .and(network_tx_filter.clone())
         .and(log_filter.clone())
         .then(
             |not_synced_filter: Result<(), Rejection>,
@@ -4042,7 +4056,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(task_spawner_filter.clone())
         .and(system_info_filter)
         .and(app_start_filter)
-        .and(data_dir_filter)
+        .and(data_dir_filter.clone())
         .and(network_globals.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
@@ -4172,7 +4186,7 @@ pub fn serve<T: BeaconChainTypes>(
         .and(warp::path("connected"))
         .and(warp::path::end())
         .and(task_spawner_filter.clone())
-        .and(network_globals)
+        .and(network_globals.clone())
         .then(
             |task_spawner: TaskSpawner<T::EthSpec>,
              network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
@@ -4190,6 +4204,182 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/peers/reputation
+    let get_lighthouse_peers_reputation = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("reputation"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(network_globals.peers.read().reputations())
+                })
+            },
+        );
+
+    // GET lighthouse/peers/groups
+    let get_lighthouse_peers_groups = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("groups"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let peer_groups = network_globals.peer_groups.read();
+                    Ok(peer_groups
+                        .groups(|peer_id| network_globals.peers.read().is_connected(peer_id)))
+                })
+            },
+        );
+
+    // POST lighthouse/peers/groups
+    let post_lighthouse_peers_groups = warp::path("lighthouse")
+        .and(warp::path("peers"))
+        .and(warp::path("groups"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(network_tx_filter.clone())
+        .then(
+            |request: eth2::lighthouse::SetPeerGroupRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    if request.name.is_empty() {
+                        return Err(warp_utils::reject::custom_bad_request(
+                            "peer group name must not be empty".to_string(),
+                        ));
+                    }
+                    let addresses = request
+                        .peers
+                        .iter()
+                        .map(|peer| {
+                            let multiaddr = peer.parse::<Multiaddr>().map_err(|e| {
+                                warp_utils::reject::custom_bad_request(format!(
+                                    "invalid multiaddr {peer}: {e}"
+                                ))
+                            })?;
+                            if peer_id_from_multiaddr(&multiaddr).is_none() {
+                                return Err(warp_utils::reject::custom_bad_request(format!(
+                                    "multiaddr {peer} does not end with /p2p/<peer_id>"
+                                )));
+                            }
+                            Ok(multiaddr)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+
+                    publish_network_message(
+                        &network_tx,
+                        NetworkMessage::SetPeerGroup {
+                            name: request.name,
+                            addresses,
+                        },
+                    )
+                })
+            },
+        );
+
+    // GET lighthouse/network/bandwidth
+    let get_lighthouse_network_bandwidth = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("bandwidth"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    Ok(network_globals.bandwidth.read().report())
+                })
+            },
+        );
+
+    // GET lighthouse/network/subscriptions
+    let get_lighthouse_network_subscriptions = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("subscriptions"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(network_globals)
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>,
+             network_globals: Arc<NetworkGlobals<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    let pinned = network_globals.pinned_subnets.read().clone();
+                    let subscribed = network_globals
+                        .gossipsub_subscriptions
+                        .read()
+                        .iter()
+                        .filter_map(|topic| topic.subnet_id())
+                        .collect::<Vec<_>>();
+                    Ok(eth2::lighthouse::NetworkSubscriptions {
+                        pinned: eth2::lighthouse::SubnetIds::from_subnets(pinned),
+                        subscribed: eth2::lighthouse::SubnetIds::from_subnets(subscribed),
+                    })
+                })
+            },
+        );
+
+    // POST lighthouse/network/subscriptions
+    let post_lighthouse_network_subscriptions = warp::path("lighthouse")
+        .and(warp::path("network"))
+        .and(warp::path("subscriptions"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(network_tx_filter)
+        .then(
+            |request: eth2::lighthouse::UpdateNetworkSubscriptionsRequest,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             network_tx: UnboundedSender<NetworkMessage<T::EthSpec>>| {
+                task_spawner.blocking_json_task(Priority::P0, move || {
+                    for ids in [&request.subscribe, &request.unsubscribe] {
+                        let kinds = [
+                            (
+                                "attestation",
+                                &ids.attestation,
+                                chain.spec.attestation_subnet_count,
+                            ),
+                            (
+                                "sync committee",
+                                &ids.sync_committee,
+                                SYNC_COMMITTEE_SUBNET_COUNT,
+                            ),
+                            (
+                                "data column",
+                                &ids.data_column,
+                                chain.spec.data_column_sidecar_subnet_count,
+                            ),
+                        ];
+                        for (kind, ids, count) in kinds {
+                            if let Some(id) = ids.iter().find(|id| **id >= count) {
+                                return Err(warp_utils::reject::custom_bad_request(format!(
+                                    "invalid {kind} subnet id {id}, there are {count} subnets"
+                                )));
+                            }
+                        }
+                    }
+
+                    publish_network_message(
+                        &network_tx,
+                        NetworkMessage::UpdatePinnedSubnets {
+                            subscribe: request.subscribe.subnets(),
+                            unsubscribe: request.unsubscribe.subnets(),
+                        },
+                    )
+                })
+            },
+        );
+
     // GET lighthouse/proto_array
     let get_lighthouse_proto_array = warp::path("lighthouse")
         .and(warp::path("proto_array"))
@@ -4363,6 +4553,18 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // GET lighthouse/database/stats
+    let get_lighthouse_database_stats = database_path
+        .and(warp::path("stats"))
+        .and(warp::path::end())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .then(
+            |task_spawner: TaskSpawner<T::EthSpec>, chain: Arc<BeaconChain<T>>| {
+                task_spawner.blocking_json_task(Priority::P1, move || database::stats(chain))
+            },
+        );
+
     // POST lighthouse/database/reconstruct
     let post_lighthouse_database_reconstruct = database_path
         .and(warp::path("reconstruct"))
@@ -4382,6 +4584,25 @@ pub fn serve<T: BeaconChainTypes>(
             },
         );
 
+    // POST lighthouse/database/snapshot
+    let post_lighthouse_database_snapshot = database_path
+        .and(warp::path("snapshot"))
+        .and(warp::path::end())
+        .and(warp_utils::json::json())
+        .and(task_spawner_filter.clone())
+        .and(chain_filter.clone())
+        .and(data_dir_filter)
+        .then(
+            |request,
+             task_spawner: TaskSpawner<T::EthSpec>,
+             chain: Arc<BeaconChain<T>>,
+             data_dir: PathBuf| {
+                task_spawner.blocking_json_task(Priority::P1, move || {
+                    database::snapshot(chain, &data_dir, request)
+                })
+            },
+        );
+
     // GET lighthouse/analysis/block_rewards
     let get_lighthouse_block_rewards = warp::path("lighthouse")
         .and(warp::path("analysis"))
@@ -4670,6 +4891,10 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_nat)
                 .uor(get_lighthouse_peers)
                 .uor(get_lighthouse_peers_connected)
+                .uor(get_lighthouse_peers_reputation)
+                .uor(get_lighthouse_peers_groups)
+                .uor(get_lighthouse_network_bandwidth)
+                .uor(get_lighthouse_network_subscriptions)
                 .uor(get_lighthouse_proto_array)
                 .uor(get_lighthouse_validator_inclusion_global)
                 .uor(get_lighthouse_validator_inclusion)
@@ -4678,6 +4903,7 @@ pub fn serve<T: BeaconChainTypes>(
                 .uor(get_lighthouse_eth1_deposit_cache)
                 .uor(get_lighthouse_staking)
                 .uor(get_lighthouse_database_info)
+                .uor(get_lighthouse_database_stats)
                 .uor(get_lighthouse_block_rewards)
                 .uor(get_lighthouse_attestation_performance)
                 .uor(
@@ -4739,6 +4965,9 @@ pub fn serve<T: BeaconChainTypes>(
                     .uor(post_validator_liveness_epoch)
                     .uor(post_lighthouse_liveness)
                     .uor(post_lighthouse_database_reconstruct)
+                    .uor(post_lighthouse_database_snapshot)
+                    .uor(post_lighthouse_network_subscriptions)
+                    .uor(post_lighthouse_peers_groups)
                     .uor(post_lighthouse_block_rewards)
                     .uor(post_lighthouse_ui_validator_metrics)
                     .uor(post_lighthouse_ui_validator_info)
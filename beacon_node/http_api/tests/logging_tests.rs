@@ -0,0 +1,48 @@
+//! Tests for the logging of HTTP API requests.
+use eth2::trace_context::TRACEPARENT_HEADER;
+use http_api::slog_logging;
+use logging::SSELoggingComponents;
+use serde_json::Value;
+use slog::{o, Drain, Logger};
+use warp::{http::StatusCode, Filter};
+
+const TRACE_ID: &str = "0af7651916cd43dd8448eb211c80319c";
+
+/// Make a request with the `traceparent` header, which fails, and return the record logged for it.
+///
+/// Failed requests are logged at the `WARN` level, which is the lowest level sent to SSE
+/// subscribers.
+async fn log_failed_request(traceparent: Option<&str>) -> Value {
+    let sse_logging_components = SSELoggingComponents::new(16);
+    let mut records = sse_logging_components.sender.subscribe();
+    let log = Logger::root(sse_logging_components.fuse(), o!());
+    let filter = warp::any()
+        .map(|| warp::reply::with_status("error", StatusCode::INTERNAL_SERVER_ERROR))
+        .with(slog_logging(log));
+
+    let mut request = warp::test::request().path("/eth/v1/node/version");
+    if let Some(traceparent) = traceparent {
+        request = request.header(TRACEPARENT_HEADER, traceparent);
+    }
+    request.reply(&filter).await;
+
+    let record = records.try_recv().unwrap().to_json_string().unwrap();
+    serde_json::from_str(&record).unwrap()
+}
+
+#[tokio::test]
+async fn request_log_has_trace_id() {
+    let record = log_failed_request(Some(&format!("00-{TRACE_ID}-b7ad6b7169203331-01"))).await;
+    assert_eq!(record["msg"], "Error processing HTTP API request");
+    assert_eq!(record["path"], "/eth/v1/node/version");
+    assert_eq!(record["trace_id"], TRACE_ID);
+}
+
+#[tokio::test]
+async fn request_log_without_trace_id() {
+    let record = log_failed_request(None).await;
+    assert_eq!(record["trace_id"], Value::Null);
+
+    let record = log_failed_request(Some("00-invalid-b7ad6b7169203331-01")).await;
+    assert_eq!(record["trace_id"], Value::Null);
+}
@@ -22,9 +22,9 @@ use http_api::{
     test_utils::{create_api_server, ApiServer},
     BlockId, StateId,
 };
-use lighthouse_network::{types::SyncState, Enr, EnrExt, PeerId};
+use lighthouse_network::{types::SyncState, Enr, EnrExt, Multiaddr, PeerId};
 use logging::test_logger;
-use network::NetworkReceivers;
+use network::{NetworkMessage, NetworkReceivers};
 use proto_array::ExecutionStatus;
 use sensitive_url::SensitiveUrl;
 use slot_clock::SlotClock;
@@ -5467,6 +5467,145 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_peers_reputation(self) -> Self {
+        let reputations = self.client.get_lighthouse_peers_reputation().await.unwrap();
+
+        // Compare peer IDs only, as the last seen time of connected peers is the current time.
+        let expected = self
+            .ctx
+            .network_globals
+            .as_ref()
+            .unwrap()
+            .peers
+            .read()
+            .reputations()
+            .into_iter()
+            .map(|reputation| reputation.peer_id)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            reputations
+                .into_iter()
+                .map(|reputation| reputation.peer_id)
+                .collect::<Vec<_>>(),
+            expected
+        );
+
+        self
+    }
+
+    pub async fn test_lighthouse_peers_groups(mut self) -> Self {
+        let peer_id = PeerId::random();
+        let address = format!("/ip4/10.0.0.1/tcp/9000/p2p/{peer_id}");
+        let request = eth2::lighthouse::SetPeerGroupRequest {
+            name: "sentries".to_string(),
+            peers: vec![address.clone()],
+        };
+        self.client
+            .post_lighthouse_peers_groups(&request)
+            .await
+            .unwrap();
+
+        match self.network_rx.network_recv.recv().await {
+            Some(NetworkMessage::SetPeerGroup { name, addresses }) => {
+                assert_eq!(name, "sentries");
+                assert_eq!(addresses, vec![address.parse::<Multiaddr>().unwrap()]);
+            }
+            other => panic!("expected SetPeerGroup, got {other:?}"),
+        }
+
+        // Addresses without a peer id are rejected.
+        let invalid = eth2::lighthouse::SetPeerGroupRequest {
+            name: "sentries".to_string(),
+            peers: vec!["/ip4/10.0.0.1/tcp/9000".to_string()],
+        };
+        let error = self
+            .client
+            .post_lighthouse_peers_groups(&invalid)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status().unwrap(), 400);
+
+        // The network service is not running in the tests, so no group has been set.
+        let groups = self.client.get_lighthouse_peers_groups().await.unwrap();
+        assert!(groups.is_empty());
+
+        self
+    }
+
+    pub async fn test_get_lighthouse_network_bandwidth(self) -> Self {
+        let report = self
+            .client
+            .get_lighthouse_network_bandwidth()
+            .await
+            .unwrap();
+
+        let expected = self
+            .ctx
+            .network_globals
+            .as_ref()
+            .unwrap()
+            .bandwidth
+            .read()
+            .report();
+        assert_eq!(report, expected);
+
+        self
+    }
+
+    pub async fn test_lighthouse_network_subscriptions(mut self) -> Self {
+        let request = eth2::lighthouse::UpdateNetworkSubscriptionsRequest {
+            subscribe: eth2::lighthouse::SubnetIds {
+                attestation: vec![3, 1, 3],
+                sync_committee: vec![0],
+                data_column: vec![],
+            },
+            unsubscribe: eth2::lighthouse::SubnetIds {
+                attestation: vec![2],
+                ..Default::default()
+            },
+        };
+        self.client
+            .post_lighthouse_network_subscriptions(&request)
+            .await
+            .unwrap();
+
+        match self.network_rx.network_recv.recv().await {
+            Some(NetworkMessage::UpdatePinnedSubnets {
+                subscribe,
+                unsubscribe,
+            }) => {
+                assert_eq!(subscribe, request.subscribe.subnets());
+                assert_eq!(unsubscribe, request.unsubscribe.subnets());
+            }
+            other => panic!("expected UpdatePinnedSubnets, got {other:?}"),
+        }
+
+        // Subnet ids beyond the number of subnets are rejected.
+        let invalid = eth2::lighthouse::UpdateNetworkSubscriptionsRequest {
+            subscribe: eth2::lighthouse::SubnetIds {
+                attestation: vec![self.chain.spec.attestation_subnet_count],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let error = self
+            .client
+            .post_lighthouse_network_subscriptions(&invalid)
+            .await
+            .unwrap_err();
+        assert_eq!(error.status().unwrap(), 400);
+
+        // The network service is not running in the tests, so no subnet has been pinned.
+        let subscriptions = self
+            .client
+            .get_lighthouse_network_subscriptions()
+            .await
+            .unwrap();
+        assert_eq!(subscriptions.pinned, eth2::lighthouse::SubnetIds::default());
+
+        self
+    }
+
     pub async fn test_get_lighthouse_proto_array(self) -> Self {
         self.client.get_lighthouse_proto_array().await.unwrap();
 
@@ -5532,6 +5671,7 @@ impl ApiTester {
 
         assert_eq!(info.anchor, self.chain.store.get_anchor_info());
         assert_eq!(info.split, self.chain.store.get_split_info());
+        assert_eq!(info.reconstruction, None);
         assert_eq!(
             info.schema_version,
             store::metadata::CURRENT_SCHEMA_VERSION.as_u64()
@@ -5540,6 +5680,21 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_lighthouse_database_stats(self) -> Self {
+        let stats = self.client.get_lighthouse_database_stats().await.unwrap();
+
+        let block_stats = stats
+            .hot
+            .iter()
+            .find(|stats| stats.column == store::DBColumn::BeaconBlock.as_str())
+            .unwrap();
+        assert!(block_stats.keys > 0);
+        assert!(block_stats.bytes > 0);
+        assert_eq!(block_stats.min_slot, None);
+
+        self
+    }
+
     pub async fn test_post_lighthouse_database_reconstruct(self) -> Self {
         let response = self
             .client
@@ -5550,6 +5705,39 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_post_lighthouse_database_snapshot(self) -> Self {
+        let dir = tempfile::tempdir().unwrap();
+        let request = eth2::lighthouse::DatabaseSnapshotRequest {
+            path: dir.path().join("snapshot"),
+        };
+        let report = self
+            .client
+            .post_lighthouse_database_snapshot(&request)
+            .await
+            .unwrap();
+
+        assert_eq!(report.path, request.path);
+        assert_eq!(report.split_slot, self.chain.store.get_split_slot());
+        assert!(report.keys > 0);
+        assert_eq!(
+            store::snapshot::read_snapshot_schema_version::<E>(
+                &request.path,
+                self.chain.store.get_config()
+            )
+            .unwrap(),
+            store::metadata::CURRENT_SCHEMA_VERSION
+        );
+
+        // An existing snapshot is not overwritten.
+        assert!(self
+            .client
+            .post_lighthouse_database_snapshot(&request)
+            .await
+            .is_err());
+
+        self
+    }
+
     pub async fn test_post_lighthouse_liveness(self) -> Self {
         let epoch = self.chain.epoch().unwrap();
         let head_state = self.chain.head_beacon_state_cloned();
@@ -6987,6 +7175,14 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_syncing()
         .await
+        .test_get_lighthouse_peers_reputation()
+        .await
+        .test_lighthouse_peers_groups()
+        .await
+        .test_get_lighthouse_network_bandwidth()
+        .await
+        .test_lighthouse_network_subscriptions()
+        .await
         .test_get_lighthouse_proto_array()
         .await
         .test_get_lighthouse_validator_inclusion()
@@ -7003,8 +7199,12 @@ async fn lighthouse_endpoints() {
         .await
         .test_get_lighthouse_database_info()
         .await
+        .test_get_lighthouse_database_stats()
+        .await
         .test_post_lighthouse_database_reconstruct()
         .await
+        .test_post_lighthouse_database_snapshot()
+        .await
         .test_post_lighthouse_liveness()
         .await;
 }
@@ -62,6 +62,7 @@ use super::rpc_proto::proto;
 use super::subscription_filter::{AllowAllSubscriptionFilter, TopicSubscriptionFilter};
 use super::time_cache::DuplicateCache;
 use super::topic::{Hasher, Topic, TopicHash};
+use super::tracer::{self, TraceConfig, Tracer};
 use super::transform::{DataTransform, IdentityTransform};
 use super::types::{
     ControlAction, FailedMessages, Message, MessageAcceptance, MessageId, PeerInfo, RawMessage,
@@ -291,6 +292,9 @@ pub struct Behaviour<D = IdentityTransform, F = AllowAllSubscriptionFilter> {
     /// Map of topics to list of peers that we publish to, but don't subscribe to.
     fanout: HashMap<TopicHash, BTreeSet<PeerId>>,
 
+    /// Topics whose mesh degree overrides that of the config, see [`Self::set_topic_mesh_n`].
+    topic_mesh_n: HashMap<TopicHash, usize>,
+
     /// The last publish time for fanout topics.
     fanout_last_pub: HashMap<TopicHash, Instant>,
 
@@ -341,6 +345,9 @@ pub struct Behaviour<D = IdentityTransform, F = AllowAllSubscriptionFilter> {
     /// Keep track of a set of internal metrics relating to gossipsub.
     metrics: Option<Metrics>,
 
+    /// Records gossipsub events to trace files, if tracing is enabled.
+    tracer: Option<Tracer>,
+
     /// Tracks the numbers of failed messages per peer-id.
     failed_messages: HashMap<PeerId, FailedMessages>,
 
@@ -452,6 +459,7 @@ where
 
         Ok(Behaviour {
             metrics: metrics.map(|(registry, cfg)| Metrics::new(registry, cfg)),
+            tracer: None,
             events: VecDeque::new(),
             publish_config: privacy.into(),
             duplicate_cache: DuplicateCache::new(config.duplicate_cache_time()),
@@ -478,6 +486,7 @@ where
             count_sent_iwant: HashMap::new(),
             connected_peers: HashMap::new(),
             published_message_ids: DuplicateCache::new(config.published_message_ids_cache_time()),
+            topic_mesh_n: HashMap::new(),
             config,
             subscription_filter,
             data_transform,
@@ -782,6 +791,10 @@ where
             metrics.register_published_message(&topic_hash);
         }
 
+        if let Some(tracer) = &self.tracer {
+            tracer.publish_message(&msg_id, &topic_hash);
+        }
+
         Ok(msg_id)
     }
 
@@ -849,6 +862,15 @@ where
                 metrics.register_msg_validation(&raw_message.topic, &acceptance);
             }
 
+            if let Some(tracer) = &self.tracer {
+                tracer.reject_message(
+                    Some(msg_id),
+                    propagation_source,
+                    &raw_message.topic,
+                    reject_reason,
+                );
+            }
+
             // Tell peer_score about reject
             // Reject the original source, and any duplicates we've seen from other peers.
             if let Some((peer_score, ..)) = &mut self.peer_score {
@@ -939,6 +961,25 @@ where
         Ok(())
     }
 
+    /// Activates tracing of gossipsub events to files in the libp2p pubsub tracer format. Should
+    /// be called before any connections are established, as RPCs to peers which are already
+    /// connected are not traced. Returns an error if the tracer got already set or the trace
+    /// directory could not be written to.
+    pub fn with_tracer(
+        &mut self,
+        config: TraceConfig,
+        local_peer_id: PeerId,
+    ) -> Result<(), String> {
+        if self.tracer.is_some() {
+            return Err("Tracer set twice".into());
+        }
+
+        let tracer = Tracer::new(config, local_peer_id)
+            .map_err(|e| format!("Failed to start gossipsub tracer: {e}"))?;
+        self.tracer = Some(tracer);
+        Ok(())
+    }
+
     /// Sets scoring parameters for a topic.
     ///
     /// The [`Self::with_peer_score()`] must first be called to initialise peer scoring.
@@ -960,6 +1001,24 @@ where
         self.peer_score.as_ref()?.0.get_topic_params(&topic.hash())
     }
 
+    /// Sets the mesh degree of a topic, or restores that of the config if `mesh_n` is `None`.
+    ///
+    /// The degree can only be lowered: the bounds of the mesh are those of the config, capped at
+    /// `mesh_n`. Receiving a topic from fewer mesh peers reduces the number of full copies of each
+    /// message which are received. Excess peers are pruned at the next heartbeat. The override is
+    /// kept until it is removed or the topic is left.
+    pub fn set_topic_mesh_n(&mut self, topic_hash: &TopicHash, mesh_n: Option<usize>) {
+        match mesh_n {
+            Some(mesh_n) => self.topic_mesh_n.insert(topic_hash.clone(), mesh_n),
+            None => self.topic_mesh_n.remove(topic_hash),
+        };
+    }
+
+    /// Returns the bounds of the mesh of a topic.
+    fn mesh_bounds(&self, topic_hash: &TopicHash) -> MeshBounds {
+        MeshBounds::new(&self.config, self.topic_mesh_n.get(topic_hash).copied())
+    }
+
     /// Sets the application specific score for a peer. Returns true if scoring is active and
     /// the peer is connected or if the score of the peer is not yet expired, false otherwise.
     pub fn set_application_score(&mut self, peer_id: &PeerId, new_score: f64) -> bool {
@@ -981,11 +1040,16 @@ where
         }
 
         let mut added_peers = HashSet::new();
+        let mesh_n = self.mesh_bounds(topic_hash).mesh_n;
 
         if let Some(m) = self.metrics.as_mut() {
             m.joined(topic_hash)
         }
 
+        if let Some(tracer) = &self.tracer {
+            tracer.join(topic_hash);
+        }
+
         // check if we have mesh_n peers in fanout[topic] and add them to the mesh if we do,
         // removing the fanout entry.
         if let Some((_, mut peers)) = self.fanout.remove_entry(topic_hash) {
@@ -1003,7 +1067,7 @@ where
 
             // Add up to mesh_n of them them to the mesh
             // NOTE: These aren't randomly added, currently FIFO
-            let add_peers = std::cmp::min(peers.len(), self.config.mesh_n());
+            let add_peers = std::cmp::min(peers.len(), mesh_n);
             tracing::debug!(
                 topic=%topic_hash,
                 "JOIN: Adding {:?} peers from the fanout for topic",
@@ -1026,12 +1090,12 @@ where
         }
 
         // check if we need to get more peers, which we randomly select
-        if added_peers.len() < self.config.mesh_n() {
+        if added_peers.len() < mesh_n {
             // get the peers
             let new_peers = get_random_peers(
                 &self.connected_peers,
                 topic_hash,
-                self.config.mesh_n() - added_peers.len(),
+                mesh_n - added_peers.len(),
                 |peer| {
                     !added_peers.contains(peer)
                         && !self.explicit_peers.contains(peer)
@@ -1059,6 +1123,9 @@ where
             if let Some((peer_score, ..)) = &mut self.peer_score {
                 peer_score.graft(&peer_id, topic_hash.clone());
             }
+            if let Some(tracer) = &self.tracer {
+                tracer.graft(&peer_id, topic_hash);
+            }
             if let Some(peer) = &mut self.connected_peers.get_mut(&peer_id) {
                 tracing::debug!(peer=%peer_id, "JOIN: Sending Graft message to peer");
                 peer.sender.graft(Graft {
@@ -1098,6 +1165,9 @@ where
         if let Some((peer_score, ..)) = &mut self.peer_score {
             peer_score.prune(peer, topic_hash.clone());
         }
+        if let Some(tracer) = &self.tracer {
+            tracer.prune(peer, topic_hash);
+        }
 
         match self.connected_peers.get(peer).map(|v| &v.kind) {
             Some(PeerKind::Floodsub) => {
@@ -1152,11 +1222,16 @@ where
     fn leave(&mut self, topic_hash: &TopicHash) {
         tracing::debug!(topic=%topic_hash, "Running LEAVE for topic");
 
+        self.topic_mesh_n.remove(topic_hash);
+
         // If our mesh contains the topic, send prune to peers and delete it from the mesh
         if let Some((_, peers)) = self.mesh.remove_entry(topic_hash) {
             if let Some(m) = self.metrics.as_mut() {
                 m.left(topic_hash)
             }
+            if let Some(tracer) = &self.tracer {
+                tracer.leave(topic_hash);
+            }
             for peer_id in peers {
                 // Send a PRUNE control message
                 let prune = self.make_prune(topic_hash, &peer_id, self.config.do_px(), true);
@@ -1448,6 +1523,8 @@ where
             let (below_zero, score) = self.score_below_threshold(peer_id, |_| 0.0);
             let now = Instant::now();
             for topic_hash in topics {
+                let mesh_bounds =
+                    MeshBounds::new(&self.config, self.topic_mesh_n.get(&topic_hash).copied());
                 if let Some(peers) = self.mesh.get_mut(&topic_hash) {
                     // if the peer is already in the mesh ignore the graft
                     if peers.contains(peer_id) {
@@ -1511,7 +1588,7 @@ where
 
                     // check mesh upper bound and only allow graft if the upper bound is not reached or
                     // if it is an outbound peer
-                    if peers.len() >= self.config.mesh_n_high()
+                    if peers.len() >= mesh_bounds.mesh_n_high
                         && !self.outbound_peers.contains(peer_id)
                     {
                         to_prune_topics.insert(topic_hash.clone());
@@ -1540,6 +1617,9 @@ where
                         &self.connected_peers,
                     );
 
+                    if let Some(tracer) = &self.tracer {
+                        tracer.graft(peer_id, &topic_hash);
+                    }
                     if let Some((peer_score, ..)) = &mut self.peer_score {
                         peer_score.graft(peer_id, topic_hash);
                     }
@@ -1608,6 +1688,9 @@ where
                 if let Some((peer_score, ..)) = &mut self.peer_score {
                     peer_score.prune(peer_id, topic_hash.clone());
                 }
+                if let Some(tracer) = &self.tracer {
+                    tracer.prune(peer_id, topic_hash);
+                }
 
                 update_backoff = true;
 
@@ -1724,6 +1807,14 @@ where
             );
             self.gossip_promises
                 .reject_message(msg_id, &RejectReason::BlackListedPeer);
+            if let Some(tracer) = &self.tracer {
+                tracer.reject_message(
+                    Some(msg_id),
+                    propagation_source,
+                    &raw_message.topic,
+                    RejectReason::BlackListedPeer,
+                );
+            }
             if let Some((peer_score, ..)) = &mut self.peer_score {
                 peer_score.reject_message(
                     propagation_source,
@@ -1824,6 +1915,9 @@ where
             if let Some((peer_score, ..)) = &mut self.peer_score {
                 peer_score.duplicated_message(propagation_source, &msg_id, &message.topic);
             }
+            if let Some(tracer) = &self.tracer {
+                tracer.duplicate_message(&msg_id, propagation_source, &message.topic);
+            }
             self.mcache.observe_duplicate(&msg_id, propagation_source);
             return;
         }
@@ -1895,6 +1989,21 @@ where
         raw_message: &RawMessage,
         reject_reason: RejectReason,
     ) {
+        if let Some(tracer) = &self.tracer {
+            // The ID is not known if the message could not be transformed.
+            let message_id = self
+                .data_transform
+                .inbound_transform(raw_message.clone())
+                .ok()
+                .map(|message| self.config.message_id(&message));
+            tracer.reject_message(
+                message_id.as_ref(),
+                propagation_source,
+                &raw_message.topic,
+                reject_reason,
+            );
+        }
+
         if let Some((peer_score, ..)) = &mut self.peer_score {
             if let Some(metrics) = self.metrics.as_mut() {
                 metrics.register_invalid_message(&raw_message.topic);
@@ -1995,10 +2104,13 @@ where
                             .backoffs
                             .is_backoff_with_slack(topic_hash, propagation_source)
                     {
+                        let mesh_n_low = MeshBounds::new(
+                            &self.config,
+                            self.topic_mesh_n.get(topic_hash).copied(),
+                        )
+                        .mesh_n_low;
                         if let Some(peers) = self.mesh.get_mut(topic_hash) {
-                            if peers.len() < self.config.mesh_n_low()
-                                && peers.insert(*propagation_source)
-                            {
+                            if peers.len() < mesh_n_low && peers.insert(*propagation_source) {
                                 tracing::debug!(
                                     peer=%propagation_source,
                                     topic=%topic_hash,
@@ -2016,6 +2128,9 @@ where
                                 if let Some((peer_score, ..)) = &mut self.peer_score {
                                     peer_score.graft(propagation_source, topic_hash.clone());
                                 }
+                                if let Some(tracer) = &self.tracer {
+                                    tracer.graft(propagation_source, topic_hash);
+                                }
                                 topics_to_graft.push(topic_hash.clone());
                             }
                         }
@@ -2154,6 +2269,7 @@ where
             let explicit_peers = &self.explicit_peers;
             let backoffs = &self.backoffs;
             let outbound_peers = &self.outbound_peers;
+            let bounds = MeshBounds::new(&self.config, self.topic_mesh_n.get(topic_hash).copied());
 
             // drop all peers with negative score, without PX
             // if there is at some point a stable retain method for BTreeSet the following can be
@@ -2191,15 +2307,15 @@ where
             }
 
             // too little peers - add some
-            if peers.len() < self.config.mesh_n_low() {
+            if peers.len() < bounds.mesh_n_low {
                 tracing::debug!(
                     topic=%topic_hash,
                     "HEARTBEAT: Mesh low. Topic contains: {} needs: {}",
                     peers.len(),
-                    self.config.mesh_n_low()
+                    bounds.mesh_n_low
                 );
                 // not enough peers - get mesh_n - current_length more
-                let desired_peers = self.config.mesh_n() - peers.len();
+                let desired_peers = bounds.mesh_n - peers.len();
                 let peer_list =
                     get_random_peers(&self.connected_peers, topic_hash, desired_peers, |peer| {
                         !peers.contains(peer)
@@ -2220,14 +2336,14 @@ where
             }
 
             // too many peers - remove some
-            if peers.len() > self.config.mesh_n_high() {
+            if peers.len() > bounds.mesh_n_high {
                 tracing::debug!(
                     topic=%topic_hash,
                     "HEARTBEAT: Mesh high. Topic contains: {} needs: {}",
                     peers.len(),
-                    self.config.mesh_n_high()
+                    bounds.mesh_n_high
                 );
-                let excess_peer_no = peers.len() - self.config.mesh_n();
+                let excess_peer_no = peers.len() - bounds.mesh_n;
 
                 // shuffle the peers and then sort by score ascending beginning with the worst
                 let mut rng = thread_rng();
@@ -2240,7 +2356,7 @@ where
                     score_p1.partial_cmp(&score_p2).unwrap_or(Ordering::Equal)
                 });
                 // shuffle everything except the last retain_scores many peers (the best ones)
-                shuffled[..peers.len() - self.config.retain_scores()].shuffle(&mut rng);
+                shuffled[..peers.len() - bounds.retain_scores].shuffle(&mut rng);
 
                 // count total number of outbound peers
                 let mut outbound = {
@@ -2259,7 +2375,7 @@ where
                         break;
                     }
                     if self.outbound_peers.contains(&peer) {
-                        if outbound <= self.config.mesh_outbound_min() {
+                        if outbound <= bounds.mesh_outbound_min {
                             // do not remove anymore outbound peers
                             continue;
                         }
@@ -2280,13 +2396,13 @@ where
             }
 
             // do we have enough outbound peers?
-            if peers.len() >= self.config.mesh_n_low() {
+            if peers.len() >= bounds.mesh_n_low {
                 // count number of outbound peers we have
                 let outbound = { peers.iter().filter(|p| outbound_peers.contains(*p)).count() };
 
                 // if we have not enough outbound peers, graft to some new outbound peers
-                if outbound < self.config.mesh_outbound_min() {
-                    let needed = self.config.mesh_outbound_min() - outbound;
+                if outbound < bounds.mesh_outbound_min {
+                    let needed = bounds.mesh_outbound_min - outbound;
                     let peer_list =
                         get_random_peers(&self.connected_peers, topic_hash, needed, |peer| {
                             !peers.contains(peer)
@@ -2611,6 +2727,9 @@ where
                 if let Some((peer_score, ..)) = &mut self.peer_score {
                     peer_score.graft(&peer_id, topic.clone());
                 }
+                if let Some(tracer) = &self.tracer {
+                    tracer.graft(&peer_id, topic);
+                }
 
                 // inform the handler of the peer being added to the mesh
                 // If the peer did not previously exist in any mesh, inform the handler
@@ -2760,6 +2879,9 @@ where
                 peer_score.deliver_message(peer, msg_id, &message.topic);
             }
         }
+        if let (Some(tracer), Some(peer)) = (&self.tracer, propagation_source) {
+            tracer.deliver_message(msg_id, peer, &message.topic);
+        }
 
         tracing::debug!(message=%msg_id, "Forwarding message");
         let mut recipient_peers = HashSet::new();
@@ -3042,6 +3164,10 @@ where
                 return;
             };
 
+            if let Some(tracer) = &self.tracer {
+                tracer.remove_peer(&peer_id);
+            }
+
             // remove peer from all mappings
             for topic in &connected_peer.topics {
                 // check the mesh for the topic
@@ -3149,7 +3275,8 @@ where
             .or_insert(PeerConnections {
                 kind: PeerKind::Floodsub,
                 connections: vec![],
-                sender: RpcSender::new(self.config.connection_handler_queue_len()),
+                sender: RpcSender::new(self.config.connection_handler_queue_len())
+                    .with_tracer(peer_id, self.tracer.clone()),
                 topics: Default::default(),
                 dont_send: LinkedHashMap::new(),
             });
@@ -3181,7 +3308,8 @@ where
             .or_insert(PeerConnections {
                 kind: PeerKind::Floodsub,
                 connections: vec![],
-                sender: RpcSender::new(self.config.connection_handler_queue_len()),
+                sender: RpcSender::new(self.config.connection_handler_queue_len())
+                    .with_tracer(peer_id, self.tracer.clone()),
                 topics: Default::default(),
                 dont_send: LinkedHashMap::new(),
             });
@@ -3227,11 +3355,18 @@ where
                         "New peer type found for peer"
                     );
                     if let PeerKind::Floodsub = conn.kind {
+                        if let Some(tracer) = &self.tracer {
+                            tracer.add_peer(&propagation_source, &kind);
+                        }
                         conn.kind = kind;
                     }
                 }
             }
             HandlerEvent::MessageDropped(rpc) => {
+                if let Some(tracer) = &self.tracer {
+                    tracer.drop_rpc(&propagation_source, tracer::rpc_out_meta(&rpc));
+                }
+
                 // Account for this in the scoring logic
                 if let Some((peer_score, _, _)) = &mut self.peer_score {
                     peer_score.failed_message_slow_peer(&propagation_source);
@@ -3272,6 +3407,9 @@ where
                 invalid_messages,
             } => {
                 // Handle the gossipsub RPC
+                if let Some(tracer) = &self.tracer {
+                    tracer.recv_rpc(&propagation_source, &rpc);
+                }
 
                 // Handle subscriptions
                 // Update connected peers topics
@@ -3288,7 +3426,7 @@ where
                 }
 
                 // Handle any invalid messages from this peer
-                if self.peer_score.is_some() {
+                if self.peer_score.is_some() || self.tracer.is_some() {
                     for (raw_message, validation_error) in invalid_messages {
                         self.handle_invalid_message(
                             &propagation_source,
@@ -3498,6 +3636,31 @@ fn peer_removed_from_mesh(
     });
 }
 
+/// The bounds of the mesh of a topic.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct MeshBounds {
+    mesh_n: usize,
+    mesh_n_low: usize,
+    mesh_n_high: usize,
+    mesh_outbound_min: usize,
+    retain_scores: usize,
+}
+
+impl MeshBounds {
+    /// Returns the bounds of the config, capped at `mesh_n` if it is set.
+    fn new(config: &Config, mesh_n: Option<usize>) -> Self {
+        let mesh_n = mesh_n.map_or(config.mesh_n(), |mesh_n| mesh_n.min(config.mesh_n()));
+        MeshBounds {
+            mesh_n,
+            mesh_n_low: config.mesh_n_low().min(mesh_n),
+            mesh_n_high: config.mesh_n_high().min(mesh_n),
+            // As in the config, at most half of the mesh is reserved for outbound peers.
+            mesh_outbound_min: config.mesh_outbound_min().min(mesh_n / 2),
+            retain_scores: config.retain_scores().min(mesh_n),
+        }
+    }
+}
+
 /// Helper function to get a subset of random gossipsub peers for a `topic_hash`
 /// filtered by the function `f`. The number of peers to get equals the output of `n_map`
 /// that gets as input the number of filtered peers.
@@ -1862,6 +1862,78 @@ fn test_mesh_subtraction() {
     assert_eq!(gs.mesh.get(&topics[0]).unwrap().len(), config.mesh_n());
 }
 
+/// Tests that the mesh of a topic is maintained at its overridden degree
+#[test]
+fn test_topic_mesh_n() {
+    let config = Config::default();
+
+    let n = config.mesh_n_high() + 10;
+    // make all outbound connections so that we allow grafting to all
+    let (mut gs, peers, _receivers, topics) = inject_nodes1()
+        .peer_no(n)
+        .topics(vec!["test".into(), "other".into()])
+        .to_subscribe(true)
+        .gs_config(config.clone())
+        .outbound(n)
+        .create_network();
+
+    for peer in peers {
+        gs.handle_graft(&peer, topics.clone());
+    }
+
+    gs.set_topic_mesh_n(&topics[0], Some(2));
+    gs.heartbeat();
+
+    // Excess peers are pruned from the topic with the override only.
+    assert_eq!(gs.mesh[&topics[0]].len(), 2);
+    assert_eq!(gs.mesh[&topics[1]].len(), config.mesh_n());
+
+    // Grafts from inbound peers are refused once the reduced mesh is full.
+    let (inbound, _receiver) = add_peer(&mut gs, &topics, false, false);
+    gs.handle_graft(&inbound, vec![topics[0].clone()]);
+    assert!(!gs.mesh[&topics[0]].contains(&inbound));
+
+    // Removing the override restores the bounds of the config.
+    gs.set_topic_mesh_n(&topics[0], None);
+    assert_eq!(gs.mesh_bounds(&topics[0]), MeshBounds::new(&config, None));
+
+    // The override is forgotten when the topic is left.
+    gs.set_topic_mesh_n(&topics[0], Some(2));
+    assert!(gs.unsubscribe(&Topic::new("test")).unwrap());
+    assert!(!gs.topic_mesh_n.contains_key(&topics[0]));
+}
+
+/// Tests that an overridden mesh degree only lowers the bounds of the config
+#[test]
+fn test_topic_mesh_bounds() {
+    let config = Config::default();
+
+    assert_eq!(
+        MeshBounds::new(&config, None),
+        MeshBounds {
+            mesh_n: config.mesh_n(),
+            mesh_n_low: config.mesh_n_low(),
+            mesh_n_high: config.mesh_n_high(),
+            mesh_outbound_min: config.mesh_outbound_min(),
+            retain_scores: config.retain_scores(),
+        }
+    );
+    assert_eq!(
+        MeshBounds::new(&config, Some(config.mesh_n_high() + 1)),
+        MeshBounds::new(&config, None)
+    );
+    assert_eq!(
+        MeshBounds::new(&config, Some(2)),
+        MeshBounds {
+            mesh_n: 2,
+            mesh_n_low: 2,
+            mesh_n_high: 2,
+            mesh_outbound_min: 1,
+            retain_scores: 2,
+        }
+    );
+}
+
 #[test]
 fn test_connect_to_px_peers_on_handle_prune() {
     let config: Config = Config::default();
@@ -0,0 +1,1010 @@
+// Automatically generated rust module for 'trace.proto' file
+
+#![allow(non_snake_case)]
+#![allow(non_upper_case_globals)]
+#![allow(non_camel_case_types)]
+#![allow(unused_imports)]
+#![allow(unknown_lints)]
+#![allow(clippy::all)]
+#![cfg_attr(rustfmt, rustfmt_skip)]
+
+
+use quick_protobuf::{MessageInfo, MessageRead, MessageWrite, BytesReader, Writer, WriterBackend, Result};
+use quick_protobuf::sizeofs::*;
+use super::super::*;
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct TraceEvent {
+    pub type_pb: Option<trace::pb::mod_TraceEvent::Type>,
+    pub peerID: Option<Vec<u8>>,
+    pub timestamp: Option<i64>,
+    pub publishMessage: Option<trace::pb::mod_TraceEvent::PublishMessage>,
+    pub rejectMessage: Option<trace::pb::mod_TraceEvent::RejectMessage>,
+    pub duplicateMessage: Option<trace::pb::mod_TraceEvent::DuplicateMessage>,
+    pub deliverMessage: Option<trace::pb::mod_TraceEvent::DeliverMessage>,
+    pub addPeer: Option<trace::pb::mod_TraceEvent::AddPeer>,
+    pub removePeer: Option<trace::pb::mod_TraceEvent::RemovePeer>,
+    pub recvRPC: Option<trace::pb::mod_TraceEvent::RecvRPC>,
+    pub sendRPC: Option<trace::pb::mod_TraceEvent::SendRPC>,
+    pub dropRPC: Option<trace::pb::mod_TraceEvent::DropRPC>,
+    pub join: Option<trace::pb::mod_TraceEvent::Join>,
+    pub leave: Option<trace::pb::mod_TraceEvent::Leave>,
+    pub graft: Option<trace::pb::mod_TraceEvent::Graft>,
+    pub prune: Option<trace::pb::mod_TraceEvent::Prune>,
+}
+
+impl<'a> MessageRead<'a> for TraceEvent {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.type_pb = Some(r.read_enum(bytes)?),
+                Ok(18) => msg.peerID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(24) => msg.timestamp = Some(r.read_int64(bytes)?),
+                Ok(34) => msg.publishMessage = Some(r.read_message::<trace::pb::mod_TraceEvent::PublishMessage>(bytes)?),
+                Ok(42) => msg.rejectMessage = Some(r.read_message::<trace::pb::mod_TraceEvent::RejectMessage>(bytes)?),
+                Ok(50) => msg.duplicateMessage = Some(r.read_message::<trace::pb::mod_TraceEvent::DuplicateMessage>(bytes)?),
+                Ok(58) => msg.deliverMessage = Some(r.read_message::<trace::pb::mod_TraceEvent::DeliverMessage>(bytes)?),
+                Ok(66) => msg.addPeer = Some(r.read_message::<trace::pb::mod_TraceEvent::AddPeer>(bytes)?),
+                Ok(74) => msg.removePeer = Some(r.read_message::<trace::pb::mod_TraceEvent::RemovePeer>(bytes)?),
+                Ok(82) => msg.recvRPC = Some(r.read_message::<trace::pb::mod_TraceEvent::RecvRPC>(bytes)?),
+                Ok(90) => msg.sendRPC = Some(r.read_message::<trace::pb::mod_TraceEvent::SendRPC>(bytes)?),
+                Ok(98) => msg.dropRPC = Some(r.read_message::<trace::pb::mod_TraceEvent::DropRPC>(bytes)?),
+                Ok(106) => msg.join = Some(r.read_message::<trace::pb::mod_TraceEvent::Join>(bytes)?),
+                Ok(114) => msg.leave = Some(r.read_message::<trace::pb::mod_TraceEvent::Leave>(bytes)?),
+                Ok(122) => msg.graft = Some(r.read_message::<trace::pb::mod_TraceEvent::Graft>(bytes)?),
+                Ok(130) => msg.prune = Some(r.read_message::<trace::pb::mod_TraceEvent::Prune>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for TraceEvent {
+    fn get_size(&self) -> usize {
+        0
+        + self.type_pb.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.peerID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.timestamp.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.publishMessage.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.rejectMessage.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.duplicateMessage.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.deliverMessage.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.addPeer.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.removePeer.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.recvRPC.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.sendRPC.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.dropRPC.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.join.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.leave.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.graft.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+        + self.prune.as_ref().map_or(0, |m| 2 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.type_pb { w.write_with_tag(8, |w| w.write_enum(*s as i32))?; }
+        if let Some(ref s) = self.peerID { w.write_with_tag(18, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.timestamp { w.write_with_tag(24, |w| w.write_int64(*s))?; }
+        if let Some(ref s) = self.publishMessage { w.write_with_tag(34, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.rejectMessage { w.write_with_tag(42, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.duplicateMessage { w.write_with_tag(50, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.deliverMessage { w.write_with_tag(58, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.addPeer { w.write_with_tag(66, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.removePeer { w.write_with_tag(74, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.recvRPC { w.write_with_tag(82, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.sendRPC { w.write_with_tag(90, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.dropRPC { w.write_with_tag(98, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.join { w.write_with_tag(106, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.leave { w.write_with_tag(114, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.graft { w.write_with_tag(122, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.prune { w.write_with_tag(130, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+pub mod mod_TraceEvent {
+
+use super::*;
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct PublishMessage {
+    pub messageID: Option<Vec<u8>>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for PublishMessage {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for PublishMessage {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.messageID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RejectMessage {
+    pub messageID: Option<Vec<u8>>,
+    pub receivedFrom: Option<Vec<u8>>,
+    pub reason: Option<String>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for RejectMessage {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.receivedFrom = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(26) => msg.reason = Some(r.read_string(bytes)?.to_owned()),
+                Ok(34) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for RejectMessage {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.receivedFrom.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.reason.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.messageID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.receivedFrom { w.write_with_tag(18, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.reason { w.write_with_tag(26, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(34, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DuplicateMessage {
+    pub messageID: Option<Vec<u8>>,
+    pub receivedFrom: Option<Vec<u8>>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for DuplicateMessage {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.receivedFrom = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(26) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for DuplicateMessage {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.receivedFrom.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.messageID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.receivedFrom { w.write_with_tag(18, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(26, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DeliverMessage {
+    pub messageID: Option<Vec<u8>>,
+    pub topic: Option<String>,
+    pub receivedFrom: Option<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for DeliverMessage {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(26) => msg.receivedFrom = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for DeliverMessage {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.receivedFrom.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.messageID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        if let Some(ref s) = self.receivedFrom { w.write_with_tag(26, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct AddPeer {
+    pub peerID: Option<Vec<u8>>,
+    pub proto: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for AddPeer {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.peerID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.proto = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for AddPeer {
+    fn get_size(&self) -> usize {
+        0
+        + self.peerID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.proto.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.peerID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.proto { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RemovePeer {
+    pub peerID: Option<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for RemovePeer {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.peerID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for RemovePeer {
+    fn get_size(&self) -> usize {
+        0
+        + self.peerID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.peerID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RecvRPC {
+    pub receivedFrom: Option<Vec<u8>>,
+    pub meta: Option<trace::pb::mod_TraceEvent::RPCMeta>,
+}
+
+impl<'a> MessageRead<'a> for RecvRPC {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.receivedFrom = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.meta = Some(r.read_message::<trace::pb::mod_TraceEvent::RPCMeta>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for RecvRPC {
+    fn get_size(&self) -> usize {
+        0
+        + self.receivedFrom.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.meta.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.receivedFrom { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.meta { w.write_with_tag(18, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SendRPC {
+    pub sendTo: Option<Vec<u8>>,
+    pub meta: Option<trace::pb::mod_TraceEvent::RPCMeta>,
+}
+
+impl<'a> MessageRead<'a> for SendRPC {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.sendTo = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.meta = Some(r.read_message::<trace::pb::mod_TraceEvent::RPCMeta>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SendRPC {
+    fn get_size(&self) -> usize {
+        0
+        + self.sendTo.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.meta.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.sendTo { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.meta { w.write_with_tag(18, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct DropRPC {
+    pub sendTo: Option<Vec<u8>>,
+    pub meta: Option<trace::pb::mod_TraceEvent::RPCMeta>,
+}
+
+impl<'a> MessageRead<'a> for DropRPC {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.sendTo = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.meta = Some(r.read_message::<trace::pb::mod_TraceEvent::RPCMeta>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for DropRPC {
+    fn get_size(&self) -> usize {
+        0
+        + self.sendTo.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.meta.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.sendTo { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.meta { w.write_with_tag(18, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Join {
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for Join {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Join {
+    fn get_size(&self) -> usize {
+        0
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.topic { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Leave {
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for Leave {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Leave {
+    fn get_size(&self) -> usize {
+        0
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Graft {
+    pub peerID: Option<Vec<u8>>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for Graft {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.peerID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Graft {
+    fn get_size(&self) -> usize {
+        0
+        + self.peerID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.peerID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct Prune {
+    pub peerID: Option<Vec<u8>>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for Prune {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.peerID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for Prune {
+    fn get_size(&self) -> usize {
+        0
+        + self.peerID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.peerID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct RPCMeta {
+    pub messages: Vec<trace::pb::mod_TraceEvent::MessageMeta>,
+    pub subscription: Vec<trace::pb::mod_TraceEvent::SubMeta>,
+    pub control: Option<trace::pb::mod_TraceEvent::ControlMeta>,
+}
+
+impl<'a> MessageRead<'a> for RPCMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messages.push(r.read_message::<trace::pb::mod_TraceEvent::MessageMeta>(bytes)?),
+                Ok(18) => msg.subscription.push(r.read_message::<trace::pb::mod_TraceEvent::SubMeta>(bytes)?),
+                Ok(26) => msg.control = Some(r.read_message::<trace::pb::mod_TraceEvent::ControlMeta>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for RPCMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.messages.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.subscription.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.control.as_ref().map_or(0, |m| 1 + sizeof_len((m).get_size()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.messages { w.write_with_tag(10, |w| w.write_message(s))?; }
+        for s in &self.subscription { w.write_with_tag(18, |w| w.write_message(s))?; }
+        if let Some(ref s) = self.control { w.write_with_tag(26, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct MessageMeta {
+    pub messageID: Option<Vec<u8>>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for MessageMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageID = Some(r.read_bytes(bytes)?.to_owned()),
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for MessageMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageID.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.messageID { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct SubMeta {
+    pub subscribe: Option<bool>,
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for SubMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(8) => msg.subscribe = Some(r.read_bool(bytes)?),
+                Ok(18) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for SubMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.subscribe.as_ref().map_or(0, |m| 1 + sizeof_varint(*(m) as u64))
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.subscribe { w.write_with_tag(8, |w| w.write_bool(*s))?; }
+        if let Some(ref s) = self.topic { w.write_with_tag(18, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ControlMeta {
+    pub ihave: Vec<trace::pb::mod_TraceEvent::ControlIHaveMeta>,
+    pub iwant: Vec<trace::pb::mod_TraceEvent::ControlIWantMeta>,
+    pub graft: Vec<trace::pb::mod_TraceEvent::ControlGraftMeta>,
+    pub prune: Vec<trace::pb::mod_TraceEvent::ControlPruneMeta>,
+    pub idontwant: Vec<trace::pb::mod_TraceEvent::ControlIDontWantMeta>,
+}
+
+impl<'a> MessageRead<'a> for ControlMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.ihave.push(r.read_message::<trace::pb::mod_TraceEvent::ControlIHaveMeta>(bytes)?),
+                Ok(18) => msg.iwant.push(r.read_message::<trace::pb::mod_TraceEvent::ControlIWantMeta>(bytes)?),
+                Ok(26) => msg.graft.push(r.read_message::<trace::pb::mod_TraceEvent::ControlGraftMeta>(bytes)?),
+                Ok(34) => msg.prune.push(r.read_message::<trace::pb::mod_TraceEvent::ControlPruneMeta>(bytes)?),
+                Ok(42) => msg.idontwant.push(r.read_message::<trace::pb::mod_TraceEvent::ControlIDontWantMeta>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ControlMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.ihave.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.iwant.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.graft.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.prune.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+        + self.idontwant.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.ihave { w.write_with_tag(10, |w| w.write_message(s))?; }
+        for s in &self.iwant { w.write_with_tag(18, |w| w.write_message(s))?; }
+        for s in &self.graft { w.write_with_tag(26, |w| w.write_message(s))?; }
+        for s in &self.prune { w.write_with_tag(34, |w| w.write_message(s))?; }
+        for s in &self.idontwant { w.write_with_tag(42, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ControlIHaveMeta {
+    pub topic: Option<String>,
+    pub messageIDs: Vec<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for ControlIHaveMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.messageIDs.push(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ControlIHaveMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.messageIDs.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.topic { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        for s in &self.messageIDs { w.write_with_tag(18, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ControlIWantMeta {
+    pub messageIDs: Vec<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for ControlIWantMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageIDs.push(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ControlIWantMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageIDs.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.messageIDs { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ControlGraftMeta {
+    pub topic: Option<String>,
+}
+
+impl<'a> MessageRead<'a> for ControlGraftMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ControlGraftMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.topic { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ControlPruneMeta {
+    pub topic: Option<String>,
+    pub peers: Vec<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for ControlPruneMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.topic = Some(r.read_string(bytes)?.to_owned()),
+                Ok(18) => msg.peers.push(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ControlPruneMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.topic.as_ref().map_or(0, |m| 1 + sizeof_len((m).len()))
+        + self.peers.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        if let Some(ref s) = self.topic { w.write_with_tag(10, |w| w.write_string(&**s))?; }
+        for s in &self.peers { w.write_with_tag(18, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct ControlIDontWantMeta {
+    pub messageIDs: Vec<Vec<u8>>,
+}
+
+impl<'a> MessageRead<'a> for ControlIDontWantMeta {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.messageIDs.push(r.read_bytes(bytes)?.to_owned()),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for ControlIDontWantMeta {
+    fn get_size(&self) -> usize {
+        0
+        + self.messageIDs.iter().map(|s| 1 + sizeof_len((s).len())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.messageIDs { w.write_with_tag(10, |w| w.write_bytes(&**s))?; }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Type {
+    PUBLISH_MESSAGE = 0,
+    REJECT_MESSAGE = 1,
+    DUPLICATE_MESSAGE = 2,
+    DELIVER_MESSAGE = 3,
+    ADD_PEER = 4,
+    REMOVE_PEER = 5,
+    RECV_RPC = 6,
+    SEND_RPC = 7,
+    DROP_RPC = 8,
+    JOIN = 9,
+    LEAVE = 10,
+    GRAFT = 11,
+    PRUNE = 12,
+}
+
+impl Default for Type {
+    fn default() -> Self {
+        Type::PUBLISH_MESSAGE
+    }
+}
+
+impl From<i32> for Type {
+    fn from(i: i32) -> Self {
+        match i {
+            0 => Type::PUBLISH_MESSAGE,
+            1 => Type::REJECT_MESSAGE,
+            2 => Type::DUPLICATE_MESSAGE,
+            3 => Type::DELIVER_MESSAGE,
+            4 => Type::ADD_PEER,
+            5 => Type::REMOVE_PEER,
+            6 => Type::RECV_RPC,
+            7 => Type::SEND_RPC,
+            8 => Type::DROP_RPC,
+            9 => Type::JOIN,
+            10 => Type::LEAVE,
+            11 => Type::GRAFT,
+            12 => Type::PRUNE,
+            _ => Self::default(),
+        }
+    }
+}
+
+impl<'a> From<&'a str> for Type {
+    fn from(s: &'a str) -> Self {
+        match s {
+            "PUBLISH_MESSAGE" => Type::PUBLISH_MESSAGE,
+            "REJECT_MESSAGE" => Type::REJECT_MESSAGE,
+            "DUPLICATE_MESSAGE" => Type::DUPLICATE_MESSAGE,
+            "DELIVER_MESSAGE" => Type::DELIVER_MESSAGE,
+            "ADD_PEER" => Type::ADD_PEER,
+            "REMOVE_PEER" => Type::REMOVE_PEER,
+            "RECV_RPC" => Type::RECV_RPC,
+            "SEND_RPC" => Type::SEND_RPC,
+            "DROP_RPC" => Type::DROP_RPC,
+            "JOIN" => Type::JOIN,
+            "LEAVE" => Type::LEAVE,
+            "GRAFT" => Type::GRAFT,
+            "PRUNE" => Type::PRUNE,
+            _ => Self::default(),
+        }
+    }
+}
+
+}
+
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Debug, Default, PartialEq, Clone)]
+pub struct TraceEventBatch {
+    pub batch: Vec<trace::pb::TraceEvent>,
+}
+
+impl<'a> MessageRead<'a> for TraceEventBatch {
+    fn from_reader(r: &mut BytesReader, bytes: &'a [u8]) -> Result<Self> {
+        let mut msg = Self::default();
+        while !r.is_eof() {
+            match r.next_tag(bytes) {
+                Ok(10) => msg.batch.push(r.read_message::<trace::pb::TraceEvent>(bytes)?),
+                Ok(t) => { r.read_unknown(bytes, t)?; }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(msg)
+    }
+}
+
+impl MessageWrite for TraceEventBatch {
+    fn get_size(&self) -> usize {
+        0
+        + self.batch.iter().map(|s| 1 + sizeof_len((s).get_size())).sum::<usize>()
+    }
+
+    fn write_message<W: WriterBackend>(&self, w: &mut Writer<W>) -> Result<()> {
+        for s in &self.batch { w.write_with_tag(10, |w| w.write_message(s))?; }
+        Ok(())
+    }
+}
+
@@ -0,0 +1,689 @@
+// Copyright 2020 Sigma Prime Pty Ltd.
+//
+// Permission is hereby granted, free of charge, to any person obtaining a
+// copy of this software and associated documentation files (the "Software"),
+// to deal in the Software without restriction, including without limitation
+// the rights to use, copy, modify, merge, publish, distribute, sublicense,
+// and/or sell copies of the Software, and to permit persons to whom the
+// Software is furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in
+// all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS
+// OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING
+// FROM, OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER
+// DEALINGS IN THE SOFTWARE.
+
+//! An opt-in tracer which records gossipsub events to rotating files for offline analysis.
+//!
+//! Events are encoded as `TraceEvent`s of the go-libp2p-pubsub tracer schema and written as
+//! length-delimited protobufs, the format of its `PBTracer`, so that existing tooling can read
+//! the files. Events are written by a dedicated thread; if it falls behind, events are dropped
+//! rather than blocking the swarm.
+//!
+//! Message IDs are not known for the messages contained in RPCs without decoding them again, so
+//! the message metadata of `RecvRPC`, `SendRPC` and `DropRPC` events contains only the topic.
+
+use crate::peer_score::RejectReason;
+use crate::rpc_proto::proto::trace::pb::mod_TraceEvent::{
+    AddPeer, ControlGraftMeta, ControlIDontWantMeta, ControlIHaveMeta, ControlIWantMeta,
+    ControlMeta, ControlPruneMeta, DeliverMessage, DropRPC, DuplicateMessage, Graft, Join, Leave,
+    MessageMeta, Prune, PublishMessage, RPCMeta, RecvRPC, RejectMessage, RemovePeer, SendRPC,
+    SubMeta, Type,
+};
+use crate::rpc_proto::proto::trace::pb::TraceEvent;
+use crate::topic::TopicHash;
+use crate::types::{
+    self, ControlAction, IDontWant, IHave, IWant, MessageId, PeerKind, RawMessage, Rpc, RpcOut,
+    SubscriptionAction,
+};
+use crate::ValidationError;
+use libp2p::identity::PeerId;
+use quick_protobuf::Writer;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender, TrySendError};
+use std::sync::Arc;
+use std::time::Duration;
+use web_time::SystemTime;
+
+/// The prefix of the names of trace files.
+const FILE_PREFIX: &str = "gossipsub-";
+/// The extension of the names of trace files.
+const FILE_EXTENSION: &str = "pb";
+/// The interval at which buffered events are flushed to the current file, if no events arrive.
+const FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration for the gossipsub tracer.
+#[derive(Debug, Clone)]
+pub struct TraceConfig {
+    /// The directory to write trace files to.
+    pub dir: PathBuf,
+    /// The size in bytes after which a new trace file is started.
+    pub max_file_size: u64,
+    /// The number of trace files to keep. The oldest files are deleted.
+    pub max_files: usize,
+    /// The number of events which may be waiting to be written before events are dropped.
+    pub queue_len: usize,
+}
+
+impl TraceConfig {
+    /// Creates a configuration which writes trace files to `dir`, keeping up to 1 GiB of traces.
+    pub fn new(dir: PathBuf) -> Self {
+        Self {
+            dir,
+            max_file_size: 128 * 1024 * 1024,
+            max_files: 8,
+            queue_len: 65_536,
+        }
+    }
+}
+
+/// Records gossipsub events, sending them to be written by the tracer thread.
+#[derive(Debug, Clone)]
+pub(crate) struct Tracer {
+    local_peer_id: Vec<u8>,
+    sender: SyncSender<TraceEvent>,
+    dropped: Arc<AtomicU64>,
+}
+
+impl Tracer {
+    /// Opens the first trace file and starts the thread writing to it. The thread exits once all
+    /// clones of the tracer have been dropped.
+    pub(crate) fn new(config: TraceConfig, local_peer_id: PeerId) -> io::Result<Self> {
+        fs::create_dir_all(&config.dir)?;
+        let mut writer = TraceWriter {
+            file: None,
+            file_size: 0,
+            config,
+        };
+        writer.rotate()?;
+
+        let (sender, receiver) = mpsc::sync_channel(writer.config.queue_len);
+        let dropped = Arc::new(AtomicU64::new(0));
+        let thread_dropped = dropped.clone();
+        std::thread::Builder::new()
+            .name("gossipsub-tracer".into())
+            .spawn(move || loop {
+                match receiver.recv_timeout(FLUSH_INTERVAL) {
+                    Ok(event) => writer.write(&event),
+                    Err(RecvTimeoutError::Timeout) => writer.flush(),
+                    Err(RecvTimeoutError::Disconnected) => {
+                        writer.flush();
+                        let dropped = thread_dropped.load(Ordering::Relaxed);
+                        if dropped > 0 {
+                            tracing::warn!(dropped, "Gossipsub tracer dropped events");
+                        }
+                        break;
+                    }
+                }
+            })?;
+
+        Ok(Self {
+            local_peer_id: local_peer_id.to_bytes(),
+            sender,
+            dropped,
+        })
+    }
+
+    fn send(&self, type_pb: Type, mut event: TraceEvent) {
+        event.type_pb = Some(type_pb);
+        event.peerID = Some(self.local_peer_id.clone());
+        event.timestamp = Some(unix_nanos());
+        match self.sender.try_send(event) {
+            Ok(()) => {}
+            Err(TrySendError::Full(_)) => {
+                self.dropped.fetch_add(1, Ordering::Relaxed);
+            }
+            Err(TrySendError::Disconnected(_)) => {
+                tracing::debug!("Gossipsub tracer thread has stopped");
+            }
+        }
+    }
+
+    pub(crate) fn publish_message(&self, msg_id: &MessageId, topic: &TopicHash) {
+        self.send(
+            Type::PUBLISH_MESSAGE,
+            TraceEvent {
+                publishMessage: Some(PublishMessage {
+                    messageID: Some(msg_id.0.clone()),
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    /// Records the rejection of a message, whose ID is not known if it could not be decoded.
+    pub(crate) fn reject_message(
+        &self,
+        msg_id: Option<&MessageId>,
+        received_from: &PeerId,
+        topic: &TopicHash,
+        reason: RejectReason,
+    ) {
+        self.send(
+            Type::REJECT_MESSAGE,
+            TraceEvent {
+                rejectMessage: Some(RejectMessage {
+                    messageID: msg_id.map(|msg_id| msg_id.0.clone()),
+                    receivedFrom: Some(received_from.to_bytes()),
+                    reason: Some(reject_reason(reason).into()),
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn duplicate_message(
+        &self,
+        msg_id: &MessageId,
+        received_from: &PeerId,
+        topic: &TopicHash,
+    ) {
+        self.send(
+            Type::DUPLICATE_MESSAGE,
+            TraceEvent {
+                duplicateMessage: Some(DuplicateMessage {
+                    messageID: Some(msg_id.0.clone()),
+                    receivedFrom: Some(received_from.to_bytes()),
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    /// Records the delivery of a validated message.
+    pub(crate) fn deliver_message(
+        &self,
+        msg_id: &MessageId,
+        received_from: &PeerId,
+        topic: &TopicHash,
+    ) {
+        self.send(
+            Type::DELIVER_MESSAGE,
+            TraceEvent {
+                deliverMessage: Some(DeliverMessage {
+                    messageID: Some(msg_id.0.clone()),
+                    topic: Some(topic.to_string()),
+                    receivedFrom: Some(received_from.to_bytes()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn add_peer(&self, peer_id: &PeerId, kind: &PeerKind) {
+        self.send(
+            Type::ADD_PEER,
+            TraceEvent {
+                addPeer: Some(AddPeer {
+                    peerID: Some(peer_id.to_bytes()),
+                    proto: Some(kind.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn remove_peer(&self, peer_id: &PeerId) {
+        self.send(
+            Type::REMOVE_PEER,
+            TraceEvent {
+                removePeer: Some(RemovePeer {
+                    peerID: Some(peer_id.to_bytes()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn recv_rpc(&self, received_from: &PeerId, rpc: &Rpc) {
+        self.send(
+            Type::RECV_RPC,
+            TraceEvent {
+                recvRPC: Some(RecvRPC {
+                    receivedFrom: Some(received_from.to_bytes()),
+                    meta: Some(rpc_meta(rpc)),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn send_rpc(&self, send_to: &PeerId, meta: RPCMeta) {
+        self.send(
+            Type::SEND_RPC,
+            TraceEvent {
+                sendRPC: Some(SendRPC {
+                    sendTo: Some(send_to.to_bytes()),
+                    meta: Some(meta),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    /// Records an RPC which was dropped because the peer's queue was full or it timed out.
+    pub(crate) fn drop_rpc(&self, send_to: &PeerId, meta: RPCMeta) {
+        self.send(
+            Type::DROP_RPC,
+            TraceEvent {
+                dropRPC: Some(DropRPC {
+                    sendTo: Some(send_to.to_bytes()),
+                    meta: Some(meta),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn join(&self, topic: &TopicHash) {
+        self.send(
+            Type::JOIN,
+            TraceEvent {
+                join: Some(Join {
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    pub(crate) fn leave(&self, topic: &TopicHash) {
+        self.send(
+            Type::LEAVE,
+            TraceEvent {
+                leave: Some(Leave {
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    /// Records the addition of a peer to our mesh for a topic.
+    pub(crate) fn graft(&self, peer_id: &PeerId, topic: &TopicHash) {
+        self.send(
+            Type::GRAFT,
+            TraceEvent {
+                graft: Some(Graft {
+                    peerID: Some(peer_id.to_bytes()),
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+
+    /// Records the removal of a peer from our mesh for a topic.
+    pub(crate) fn prune(&self, peer_id: &PeerId, topic: &TopicHash) {
+        self.send(
+            Type::PRUNE,
+            TraceEvent {
+                prune: Some(Prune {
+                    peerID: Some(peer_id.to_bytes()),
+                    topic: Some(topic.to_string()),
+                }),
+                ..TraceEvent::default()
+            },
+        );
+    }
+}
+
+/// Writes events to the current trace file, starting a new one once it is full.
+struct TraceWriter {
+    file: Option<BufWriter<File>>,
+    file_size: u64,
+    config: TraceConfig,
+}
+
+impl TraceWriter {
+    fn write(&mut self, event: &TraceEvent) {
+        let mut bytes = vec![];
+        if let Err(e) = Writer::new(&mut bytes).write_message(event) {
+            tracing::warn!(error = %e, "Failed to encode gossipsub trace event");
+            return;
+        }
+
+        if self.file.is_none() || self.file_size >= self.config.max_file_size {
+            if let Err(e) = self.rotate() {
+                tracing::warn!(error = %e, "Failed to create gossipsub trace file");
+                return;
+            }
+        }
+        let Some(file) = self.file.as_mut() else {
+            return;
+        };
+        match file.write_all(&bytes) {
+            Ok(()) => self.file_size += bytes.len() as u64,
+            Err(e) => {
+                tracing::warn!(error = %e, "Failed to write gossipsub trace event");
+                // Start a new file with the next event.
+                self.file = None;
+            }
+        }
+    }
+
+    fn flush(&mut self) {
+        if let Some(file) = self.file.as_mut() {
+            if let Err(e) = file.flush() {
+                tracing::warn!(error = %e, "Failed to flush gossipsub trace file");
+            }
+        }
+    }
+
+    /// Starts a new trace file, deleting the oldest files if there are too many.
+    fn rotate(&mut self) -> io::Result<()> {
+        self.flush();
+        let path = self.config.dir.join(format!(
+            "{FILE_PREFIX}{:020}.{FILE_EXTENSION}",
+            unix_nanos()
+        ));
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        self.file_size = file.metadata()?.len();
+        self.file = Some(BufWriter::new(file));
+
+        let mut files = trace_files(&self.config.dir)?;
+        let excess = files.len().saturating_sub(self.config.max_files.max(1));
+        files.sort();
+        for old in files.into_iter().take(excess) {
+            if let Err(e) = fs::remove_file(&old) {
+                tracing::warn!(error = %e, file = %old.display(), "Failed to remove gossipsub trace file");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Returns the paths of the trace files in `dir`.
+fn trace_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == FILE_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(FILE_PREFIX))
+        })
+        .collect())
+}
+
+fn unix_nanos() -> i64 {
+    SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as i64)
+}
+
+/// Returns the reason for a rejection, as used by go-libp2p-pubsub where it has an equivalent.
+fn reject_reason(reason: RejectReason) -> &'static str {
+    match reason {
+        RejectReason::ValidationError(error) => match error {
+            ValidationError::InvalidSignature => "invalid signature",
+            ValidationError::EmptySequenceNumber => "missing sequence number",
+            ValidationError::InvalidSequenceNumber => "invalid sequence number",
+            ValidationError::InvalidPeerId => "invalid peer id",
+            ValidationError::SignaturePresent => "unexpected signature",
+            ValidationError::SequenceNumberPresent | ValidationError::MessageSourcePresent => {
+                "unexpected auth info"
+            }
+            ValidationError::TransformFailed => "transform failed",
+        },
+        RejectReason::SelfOrigin => "self originated message",
+        RejectReason::BlackListedPeer => "blacklisted peer",
+        RejectReason::BlackListedSource => "blacklisted source",
+        RejectReason::ValidationIgnored => "validation ignored",
+        RejectReason::ValidationFailed => "validation failed",
+    }
+}
+
+fn message_meta(message: &RawMessage) -> MessageMeta {
+    MessageMeta {
+        messageID: None,
+        topic: Some(message.topic.to_string()),
+    }
+}
+
+fn sub_meta(subscribe: bool, topic: &TopicHash) -> SubMeta {
+    SubMeta {
+        subscribe: Some(subscribe),
+        topic: Some(topic.to_string()),
+    }
+}
+
+fn message_ids(message_ids: &[MessageId]) -> Vec<Vec<u8>> {
+    message_ids.iter().map(|msg_id| msg_id.0.clone()).collect()
+}
+
+fn ihave_meta(ihave: &IHave) -> ControlIHaveMeta {
+    ControlIHaveMeta {
+        topic: Some(ihave.topic_hash.to_string()),
+        messageIDs: message_ids(&ihave.message_ids),
+    }
+}
+
+fn iwant_meta(iwant: &IWant) -> ControlIWantMeta {
+    ControlIWantMeta {
+        messageIDs: message_ids(&iwant.message_ids),
+    }
+}
+
+fn graft_meta(graft: &types::Graft) -> ControlGraftMeta {
+    ControlGraftMeta {
+        topic: Some(graft.topic_hash.to_string()),
+    }
+}
+
+fn prune_meta(prune: &types::Prune) -> ControlPruneMeta {
+    ControlPruneMeta {
+        topic: Some(prune.topic_hash.to_string()),
+        peers: prune
+            .peers
+            .iter()
+            .filter_map(|info| info.peer_id.map(|peer_id| peer_id.to_bytes()))
+            .collect(),
+    }
+}
+
+fn idontwant_meta(idontwant: &IDontWant) -> ControlIDontWantMeta {
+    ControlIDontWantMeta {
+        messageIDs: message_ids(&idontwant.message_ids),
+    }
+}
+
+fn rpc_meta(rpc: &Rpc) -> RPCMeta {
+    let mut control = ControlMeta::default();
+    for action in &rpc.control_msgs {
+        match action {
+            ControlAction::IHave(ihave) => control.ihave.push(ihave_meta(ihave)),
+            ControlAction::IWant(iwant) => control.iwant.push(iwant_meta(iwant)),
+            ControlAction::Graft(graft) => control.graft.push(graft_meta(graft)),
+            ControlAction::Prune(prune) => control.prune.push(prune_meta(prune)),
+            ControlAction::IDontWant(idontwant) => {
+                control.idontwant.push(idontwant_meta(idontwant))
+            }
+        }
+    }
+    RPCMeta {
+        messages: rpc.messages.iter().map(message_meta).collect(),
+        subscription: rpc
+            .subscriptions
+            .iter()
+            .map(|sub| sub_meta(sub.action == SubscriptionAction::Subscribe, &sub.topic_hash))
+            .collect(),
+        control: (control != ControlMeta::default()).then_some(control),
+    }
+}
+
+/// Returns the metadata of an RPC to be sent, for `Tracer::send_rpc` and `Tracer::drop_rpc`.
+pub(crate) fn rpc_out_meta(rpc: &RpcOut) -> RPCMeta {
+    let mut meta = RPCMeta::default();
+    let mut control = ControlMeta::default();
+    match rpc {
+        RpcOut::Publish { message, .. } | RpcOut::Forward { message, .. } => {
+            return publish_meta(message)
+        }
+        RpcOut::Subscribe(topic) => meta.subscription.push(sub_meta(true, topic)),
+        RpcOut::Unsubscribe(topic) => meta.subscription.push(sub_meta(false, topic)),
+        RpcOut::Graft(graft) => control.graft.push(graft_meta(graft)),
+        RpcOut::Prune(prune) => control.prune.push(prune_meta(prune)),
+        RpcOut::IHave(ihave) => control.ihave.push(ihave_meta(ihave)),
+        RpcOut::IWant(iwant) => control.iwant.push(iwant_meta(iwant)),
+        RpcOut::IDontWant(idontwant) => control.idontwant.push(idontwant_meta(idontwant)),
+    }
+    meta.control = (control != ControlMeta::default()).then_some(control);
+    meta
+}
+
+/// Returns the metadata of an RPC publishing or forwarding `message`, without constructing the RPC.
+pub(crate) fn publish_meta(message: &RawMessage) -> RPCMeta {
+    RPCMeta {
+        messages: vec![message_meta(message)],
+        ..RPCMeta::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quick_protobuf::{BytesReader, MessageRead};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "gossipsub-tracer-{name}-{}-{}",
+            std::process::id(),
+            unix_nanos()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn read_events(dir: &Path) -> Vec<TraceEvent> {
+        let mut files = trace_files(dir).unwrap();
+        files.sort();
+        let mut events = vec![];
+        for path in files {
+            let bytes = fs::read(path).unwrap();
+            let mut reader = BytesReader::from_bytes(&bytes);
+            while !reader.is_eof() {
+                events.push(reader.read_message::<TraceEvent>(&bytes).unwrap());
+            }
+        }
+        events
+    }
+
+    fn writer(config: TraceConfig) -> TraceWriter {
+        TraceWriter {
+            file: None,
+            file_size: 0,
+            config,
+        }
+    }
+
+    #[test]
+    fn creates_trace_file() {
+        let dir = temp_dir("create").join("traces");
+        let tracer = Tracer::new(TraceConfig::new(dir.clone()), PeerId::random()).unwrap();
+        assert_eq!(trace_files(&dir).unwrap().len(), 1);
+
+        drop(tracer);
+        fs::remove_dir_all(dir.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn events() {
+        let dir = temp_dir("events");
+        let local_peer_id = PeerId::random();
+        let peer_id = PeerId::random();
+        let topic = TopicHash::from_raw("topic");
+        let msg_id = MessageId::new(b"message");
+
+        let (sender, receiver) = mpsc::sync_channel(2);
+        let tracer = Tracer {
+            local_peer_id: local_peer_id.to_bytes(),
+            sender,
+            dropped: Arc::new(AtomicU64::new(0)),
+        };
+        tracer.publish_message(&msg_id, &topic);
+        tracer.send_rpc(
+            &peer_id,
+            rpc_out_meta(&RpcOut::IHave(IHave {
+                topic_hash: topic.clone(),
+                message_ids: vec![msg_id.clone()],
+            })),
+        );
+        // The queue is full.
+        tracer.reject_message(None, &peer_id, &topic, RejectReason::ValidationFailed);
+        assert_eq!(tracer.dropped.load(Ordering::Relaxed), 1);
+
+        let events = receiver.try_iter().collect::<Vec<_>>();
+        assert_eq!(events.len(), 2);
+        assert!(events
+            .iter()
+            .all(|event| event.peerID == Some(local_peer_id.to_bytes())));
+        assert_eq!(events[0].type_pb, Some(Type::PUBLISH_MESSAGE));
+        assert_eq!(
+            events[0].publishMessage,
+            Some(PublishMessage {
+                messageID: Some(msg_id.0.clone()),
+                topic: Some("topic".into()),
+            })
+        );
+        assert_eq!(events[1].type_pb, Some(Type::SEND_RPC));
+        let meta = events[1].sendRPC.as_ref().unwrap().meta.as_ref().unwrap();
+        assert_eq!(
+            meta.control.as_ref().unwrap().ihave,
+            vec![ControlIHaveMeta {
+                topic: Some("topic".into()),
+                messageIDs: vec![msg_id.0],
+            }]
+        );
+
+        // Events are written length-delimited.
+        let mut writer = writer(TraceConfig::new(dir.clone()));
+        for event in &events {
+            writer.write(event);
+        }
+        writer.flush();
+        assert_eq!(read_events(&dir), events);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn rotation() {
+        let dir = temp_dir("rotation");
+        let mut writer = writer(TraceConfig {
+            max_file_size: 1,
+            max_files: 3,
+            ..TraceConfig::new(dir.clone())
+        });
+        let event = TraceEvent {
+            type_pb: Some(Type::JOIN),
+            join: Some(Join {
+                topic: Some("topic".into()),
+            }),
+            ..TraceEvent::default()
+        };
+        for _ in 0..10 {
+            writer.write(&event);
+        }
+        writer.flush();
+
+        // Each event is written to a new file, and only the newest files are kept.
+        assert_eq!(trace_files(&dir).unwrap().len(), 3);
+        assert_eq!(read_events(&dir), vec![event; 3]);
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -20,6 +20,7 @@
 
 //! A collection of types using the Gossipsub system.
 use crate::metrics::Metrics;
+use crate::tracer::{self, Tracer};
 use crate::TopicHash;
 use async_channel::{Receiver, Sender};
 use futures::stream::Peekable;
@@ -649,6 +650,8 @@ pub(crate) struct RpcSender {
     pub(crate) non_priority_sender: Sender<RpcOut>,
     priority_receiver: Receiver<RpcOut>,
     non_priority_receiver: Receiver<RpcOut>,
+    /// The peer the RPCs are sent to and the tracer recording them, if tracing is enabled.
+    tracer: Option<(PeerId, Tracer)>,
 }
 
 impl RpcSender {
@@ -664,6 +667,32 @@ impl RpcSender {
             non_priority_sender,
             priority_receiver,
             non_priority_receiver,
+            tracer: None,
+        }
+    }
+
+    /// Records the RPCs sent to `peer_id` with `tracer`, if tracing is enabled.
+    pub(crate) fn with_tracer(mut self, peer_id: PeerId, tracer: Option<Tracer>) -> RpcSender {
+        self.tracer = tracer.map(|tracer| (peer_id, tracer));
+        self
+    }
+
+    /// Sends `rpc` on `sender`, tracing whether it was sent or dropped.
+    #[allow(clippy::result_large_err)]
+    fn try_send(&self, sender: &Sender<RpcOut>, rpc: RpcOut) -> Result<(), RpcOut> {
+        let Some((peer_id, tracer)) = &self.tracer else {
+            return sender.try_send(rpc).map_err(|err| err.into_inner());
+        };
+        let meta = tracer::rpc_out_meta(&rpc);
+        match sender.try_send(rpc) {
+            Ok(()) => {
+                tracer.send_rpc(peer_id, meta);
+                Ok(())
+            }
+            Err(err) => {
+                tracer.drop_rpc(peer_id, meta);
+                Err(err.into_inner())
+            }
         }
     }
 
@@ -679,16 +708,14 @@ impl RpcSender {
     /// Send a `RpcOut::Graft` message to the `RpcReceiver`
     /// this is high priority.
     pub(crate) fn graft(&mut self, graft: Graft) {
-        self.priority_sender
-            .try_send(RpcOut::Graft(graft))
+        self.try_send(&self.priority_sender, RpcOut::Graft(graft))
             .expect("Channel is unbounded and should always be open");
     }
 
     /// Send a `RpcOut::Prune` message to the `RpcReceiver`
     /// this is high priority.
     pub(crate) fn prune(&mut self, prune: Prune) {
-        self.priority_sender
-            .try_send(RpcOut::Prune(prune))
+        self.try_send(&self.priority_sender, RpcOut::Prune(prune))
             .expect("Channel is unbounded and should always be open");
     }
 
@@ -696,42 +723,34 @@ impl RpcSender {
     /// this is low priority, if the queue is full an Err is returned.
     #[allow(clippy::result_large_err)]
     pub(crate) fn ihave(&mut self, ihave: IHave) -> Result<(), RpcOut> {
-        self.non_priority_sender
-            .try_send(RpcOut::IHave(ihave))
-            .map_err(|err| err.into_inner())
+        self.try_send(&self.non_priority_sender, RpcOut::IHave(ihave))
     }
 
     /// Send a `RpcOut::IHave` message to the `RpcReceiver`
     /// this is low priority, if the queue is full an Err is returned.
     #[allow(clippy::result_large_err)]
     pub(crate) fn iwant(&mut self, iwant: IWant) -> Result<(), RpcOut> {
-        self.non_priority_sender
-            .try_send(RpcOut::IWant(iwant))
-            .map_err(|err| err.into_inner())
+        self.try_send(&self.non_priority_sender, RpcOut::IWant(iwant))
     }
 
     /// Send a `RpcOut::IWant` message to the `RpcReceiver`
     /// this is low priority, if the queue is full an Err is returned.
     #[allow(clippy::result_large_err)]
     pub(crate) fn idontwant(&mut self, idontwant: IDontWant) -> Result<(), RpcOut> {
-        self.non_priority_sender
-            .try_send(RpcOut::IDontWant(idontwant))
-            .map_err(|err| err.into_inner())
+        self.try_send(&self.non_priority_sender, RpcOut::IDontWant(idontwant))
     }
 
     /// Send a `RpcOut::Subscribe` message to the `RpcReceiver`
     /// this is high priority.
     pub(crate) fn subscribe(&mut self, topic: TopicHash) {
-        self.priority_sender
-            .try_send(RpcOut::Subscribe(topic))
+        self.try_send(&self.priority_sender, RpcOut::Subscribe(topic))
             .expect("Channel is unbounded and should always be open");
     }
 
     /// Send a `RpcOut::Unsubscribe` message to the `RpcReceiver`
     /// this is high priority.
     pub(crate) fn unsubscribe(&mut self, topic: TopicHash) {
-        self.priority_sender
-            .try_send(RpcOut::Unsubscribe(topic))
+        self.try_send(&self.priority_sender, RpcOut::Unsubscribe(topic))
             .expect("Channel is unbounded and should always be open");
     }
 
@@ -744,20 +763,27 @@ impl RpcSender {
         metrics: Option<&mut Metrics>,
     ) -> Result<(), ()> {
         if self.len.load(Ordering::Relaxed) >= self.cap {
+            if let Some((peer_id, tracer)) = &self.tracer {
+                tracer.drop_rpc(peer_id, tracer::publish_meta(&message));
+            }
             return Err(());
         }
-        self.priority_sender
-            .try_send(RpcOut::Publish {
-                message: message.clone(),
-                timeout: Delay::new(timeout),
-            })
-            .expect("Channel is unbounded and should always be open");
-        self.len.fetch_add(1, Ordering::Relaxed);
 
+        // The message is moved into the channel, so it is recorded in the metrics first.
         if let Some(m) = metrics {
             m.msg_sent(&message.topic, message.raw_protobuf_len());
         }
 
+        self.try_send(
+            &self.priority_sender,
+            RpcOut::Publish {
+                message,
+                timeout: Delay::new(timeout),
+            },
+        )
+        .expect("Channel is unbounded and should always be open");
+        self.len.fetch_add(1, Ordering::Relaxed);
+
         Ok(())
     }
 
@@ -769,12 +795,14 @@ impl RpcSender {
         timeout: Duration,
         metrics: Option<&mut Metrics>,
     ) -> Result<(), ()> {
-        self.non_priority_sender
-            .try_send(RpcOut::Forward {
+        self.try_send(
+            &self.non_priority_sender,
+            RpcOut::Forward {
                 message: message.clone(),
                 timeout: Delay::new(timeout),
-            })
-            .map_err(|_| ())?;
+            },
+        )
+        .map_err(|_| ())?;
 
         if let Some(m) = metrics {
             m.msg_sent(&message.topic, message.raw_protobuf_len());
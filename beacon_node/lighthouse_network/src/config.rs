@@ -8,6 +8,7 @@ use directory::{
 use libp2p::Multiaddr;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::net::{Ipv4Addr, Ipv6Addr};
 use std::num::NonZeroU16;
 use std::path::PathBuf;
@@ -140,6 +141,13 @@ pub struct Config {
     /// Configures if/where invalid blocks should be stored.
     pub invalid_block_storage: Option<PathBuf>,
 
+    /// The directory to write gossipsub traces to, if gossipsub tracing is enabled.
+    pub gossipsub_trace_dir: Option<PathBuf>,
+
+    /// The number of bytes which may be received per hour on each gossip topic kind (e.g.
+    /// `data_column_sidecar`) before the mesh degree of its topics is reduced.
+    pub gossip_bandwidth_caps: HashMap<String, u64>,
+
     /// Configuration for the inbound rate limiter (requests received by this node).
     pub inbound_rate_limiter_config: Option<InboundRateLimiterConfig>,
 
@@ -356,6 +364,8 @@ impl Default for Config {
             enable_light_client_server: false,
             outbound_rate_limiter_config: None,
             invalid_block_storage: None,
+            gossipsub_trace_dir: None,
+            gossip_bandwidth_caps: HashMap::new(),
             inbound_rate_limiter_config: None,
             idontwant_message_size_threshold: DEFAULT_IDONTWANT_MESSAGE_SIZE_THRESHOLD,
         }
@@ -101,8 +101,8 @@ impl<'a> std::fmt::Display for ClearDialError<'a> {
 }
 
 pub use crate::types::{
-    error, Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals, PubsubMessage, Subnet,
-    SubnetDiscovery,
+    error, BandwidthReport, Enr, EnrSyncCommitteeBitfield, GossipTopic, NetworkGlobals,
+    PubsubMessage, Subnet, SubnetDiscovery,
 };
 
 pub use prometheus_client;
@@ -116,7 +116,9 @@ pub use libp2p::{core::ConnectedPoint, PeerId, Swarm};
 pub use libp2p::{multiaddr, Multiaddr};
 pub use metrics::scrape_discovery_metrics;
 pub use peer_manager::{
+    peer_groups::{PeerGroup, PeerGroupMember},
     peerdb::client::Client,
+    peerdb::reputation::{PeerReputation, SyncRequests},
     peerdb::score::{PeerAction, ReportSource},
     peerdb::PeerDB,
     ConnectionDirection, PeerConnectionStatus, PeerInfo, PeerManager, SyncInfo, SyncStatus,
@@ -164,6 +164,28 @@ pub static GOSSIP_FAILED_LATE_PUBLISH_PER_TOPIC_KIND: LazyLock<Result<IntCounter
             &["topic_kind"],
         )
     });
+pub static GOSSIP_BYTES_PER_TOPIC_KIND: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "gossipsub_bytes_per_topic_kind_total",
+        "Uncompressed bytes of gossip messages received and published per topic kind",
+        &["topic_kind", "direction"],
+    )
+});
+pub static GOSSIP_CAPPED_MESSAGES_PER_TOPIC_KIND: LazyLock<Result<IntCounterVec>> =
+    LazyLock::new(|| {
+        try_create_int_counter_vec(
+            "gossipsub_capped_messages_per_topic_kind_total",
+            "Gossip messages received while their topic kind exceeded its bandwidth cap",
+            &["topic_kind"],
+        )
+    });
+pub static RPC_BYTES_PER_PROTOCOL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "libp2p_rpc_bytes_per_protocol_total",
+        "Uncompressed bytes of RPC responses received and sent per protocol",
+        &["protocol", "direction"],
+    )
+});
 pub static PEER_SCORE_DISTRIBUTION: LazyLock<Result<IntGaugeVec>> = LazyLock::new(|| {
     try_create_int_gauge_vec(
         "peer_score_distribution",
@@ -22,6 +22,7 @@ use types::{DataColumnSubnetId, EthSpec, SyncSubnetId};
 pub use libp2p::core::Multiaddr;
 pub use libp2p::identity::Keypair;
 
+pub mod peer_groups;
 pub mod peerdb;
 
 use crate::peer_manager::peerdb::client::ClientKind;
@@ -80,6 +81,10 @@ pub struct PeerManager<E: EthSpec> {
     target_peers: usize,
     /// Peers queued to be dialed.
     peers_to_dial: Vec<Enr>,
+    /// Peers of peer groups queued to be dialed at their configured addresses.
+    group_peers_to_dial: Vec<PeerId>,
+    /// Peers of peer groups awaiting their redial backoff.
+    group_peers_to_redial: HashSetDelay<PeerId>,
     /// The number of temporarily banned peers. This is used to prevent instantaneous
     /// reconnection.
     // NOTE: This just prevents re-connections. The state of the peer is otherwise unaffected. A
@@ -162,6 +167,8 @@ impl<E: EthSpec> PeerManager<E> {
             network_globals,
             events: SmallVec::new(),
             peers_to_dial: Default::default(),
+            group_peers_to_dial: Default::default(),
+            group_peers_to_redial: HashSetDelay::new(peer_groups::MIN_REDIAL_BACKOFF),
             inbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_inbound)),
             outbound_ping_peers: HashSetDelay::new(Duration::from_secs(ping_interval_outbound)),
             status_peers: HashSetDelay::new(Duration::from_secs(status_interval)),
@@ -436,6 +443,23 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// Updates the peers which joined or left a peer group. Peers joining a group are trusted and
+    /// dialed, peers leaving all groups are no longer trusted.
+    pub fn peer_groups_updated(&mut self, joined: &[PeerId], left: &[PeerId]) {
+        let mut peerdb = self.network_globals.peers.write();
+        for peer_id in joined {
+            peerdb.set_trusted(peer_id, true);
+            if peerdb.should_dial(peer_id) {
+                self.group_peers_to_dial.push(*peer_id);
+            }
+        }
+        for peer_id in left {
+            peerdb.set_trusted(peer_id, false);
+            self.group_peers_to_dial.retain(|queued| queued != peer_id);
+            self.group_peers_to_redial.remove(peer_id);
+        }
+    }
+
     /// Reports if a peer is banned or not.
     ///
     /// This is used to determine if we should accept incoming connections.
@@ -471,6 +495,17 @@ impl<E: EthSpec> PeerManager<E> {
         }
     }
 
+    /// A sync request we made to a peer has been responded to in full.
+    pub fn sync_request_completed(&mut self, peer_id: &PeerId) {
+        self.record_sync_request(peer_id, true);
+    }
+
+    fn record_sync_request(&mut self, peer_id: &PeerId, completed: bool) {
+        if let Some(info) = self.network_globals.peers.write().peer_info_mut(peer_id) {
+            info.record_sync_request(completed);
+        }
+    }
+
     /// An error has occurred in the RPC.
     ///
     /// This adjusts a peer's score based on the error.
@@ -485,6 +520,12 @@ impl<E: EthSpec> PeerManager<E> {
         let score = self.network_globals.peers.read().score(peer_id);
         debug!(self.log, "RPC Error"; "protocol" => %protocol, "err" => %err, "client" => %client,
             "peer_id" => %peer_id, "score" => %score, "direction" => ?direction);
+        if matches!(direction, ConnectionDirection::Outgoing)
+            && protocol.is_sync()
+            && !matches!(err, RPCError::InternalError(_))
+        {
+            self.record_sync_request(peer_id, false);
+        }
         metrics::inc_counter_vec(
             &metrics::TOTAL_RPC_ERRORS_PER_CLIENT,
             &[
@@ -808,6 +849,44 @@ impl<E: EthSpec> PeerManager<E> {
                 .into_iter()
                 .map(|(peer_id, unbanned_ips)| PeerManagerEvent::UnBanned(peer_id, unbanned_ips)),
         );
+
+        // Redial the peers of peer groups.
+        self.schedule_group_peer_redial(peer_id);
+    }
+
+    /// Schedules a redial of a peer group member after its backoff, unless one is scheduled.
+    fn schedule_group_peer_redial(&mut self, peer_id: &PeerId) {
+        if self.group_peers_to_redial.contains_key(peer_id) {
+            return;
+        }
+        if let Some(backoff) = self
+            .network_globals
+            .peer_groups
+            .write()
+            .next_redial_backoff(peer_id)
+        {
+            debug!(self.log, "Scheduling peer group member redial"; "peer_id" => %peer_id, "backoff" => ?backoff);
+            self.group_peers_to_redial.insert_at(*peer_id, backoff);
+        }
+    }
+
+    /// Schedules redials of the peer group members we are neither connected to nor dialing, such as
+    /// those whose ban has expired.
+    fn maintain_group_peers(&mut self) {
+        let peer_ids = self
+            .network_globals
+            .peer_groups
+            .read()
+            .peer_ids()
+            .copied()
+            .collect::<Vec<_>>();
+        for peer_id in peer_ids {
+            if self.network_globals.peers.read().should_dial(&peer_id)
+                && !self.group_peers_to_dial.contains(&peer_id)
+            {
+                self.schedule_group_peer_redial(&peer_id);
+            }
+        }
     }
 
     /// Registers a peer as connected. The `ingoing` parameter determines if the peer is being
@@ -850,6 +929,13 @@ impl<E: EthSpec> PeerManager<E> {
         // start a ping and status timer for the peer
         self.status_peers.insert(*peer_id);
 
+        // A peer group member is redialed promptly the next time it disconnects.
+        self.network_globals
+            .peer_groups
+            .write()
+            .reset_redial_backoff(peer_id);
+        self.group_peers_to_redial.remove(peer_id);
+
         true
     }
 
@@ -908,8 +994,10 @@ impl<E: EthSpec> PeerManager<E> {
     fn maintain_peer_count(&mut self, dialing_peers: usize) {
         // Check if we need to do a discovery lookup
         if self.discovery_enabled {
-            let peer_count = self.network_globals.connected_or_dialing_peers();
-            let outbound_only_peer_count = self.network_globals.connected_outbound_only_peers();
+            let peer_count = self.network_globals.connected_or_dialing_ungrouped_peers();
+            let outbound_only_peer_count = self
+                .network_globals
+                .connected_outbound_only_ungrouped_peers();
             let wanted_peers = if peer_count < self.target_peers.saturating_sub(dialing_peers) {
                 // We need more peers in general.
                 self.max_peers().saturating_sub(dialing_peers) - peer_count
@@ -956,6 +1044,7 @@ impl<E: EthSpec> PeerManager<E> {
     ///     MIN_SYNC_COMMITTEE_PEERS
     ///     number should be set low as an absolute lower bound to maintain peers on the sync
     ///     committees.
+    /// - Peers of peer groups are trusted and do not count towards the target peer count.
     /// - Do not prune trusted peers. NOTE: This means if a user has more trusted peers than the
     ///     excess peer limit, all of the following logic is subverted as we will not prune any peers.
     ///     Also, the more trusted peers a user has, the less room Lighthouse has to efficiently manage
@@ -969,7 +1058,7 @@ impl<E: EthSpec> PeerManager<E> {
     ///
     fn prune_excess_peers(&mut self) {
         // The current number of connected peers.
-        let connected_peer_count = self.network_globals.connected_peers();
+        let connected_peer_count = self.network_globals.connected_ungrouped_peers();
         if connected_peer_count <= self.target_peers {
             // No need to prune peers
             return;
@@ -977,7 +1066,9 @@ impl<E: EthSpec> PeerManager<E> {
 
         // Keep a list of peers we are pruning.
         let mut peers_to_prune = std::collections::HashSet::new();
-        let connected_outbound_peer_count = self.network_globals.connected_outbound_only_peers();
+        let connected_outbound_peer_count = self
+            .network_globals
+            .connected_outbound_only_ungrouped_peers();
 
         // Keep track of the number of outbound peers we are pruning.
         let mut outbound_peers_pruned = 0;
@@ -1214,6 +1305,9 @@ impl<E: EthSpec> PeerManager<E> {
         // Maintain minimum count for sync committee peers.
         self.maintain_sync_committee_peers();
 
+        // Redial any peer group members we are not connected to.
+        self.maintain_group_peers();
+
         // Prune any excess peers back to our target in such a way that incentivises good scores and
         // a uniform distribution of subnets.
         self.prune_excess_peers();
@@ -1230,6 +1324,7 @@ impl<E: EthSpec> PeerManager<E> {
         self.inbound_ping_peers.shrink_to(5);
         self.outbound_ping_peers.shrink_to(5);
         self.status_peers.shrink_to(5);
+        self.group_peers_to_redial.shrink_to(5);
         self.temporary_banned_peers.shrink_to_fit();
         self.sync_committee_subnets.shrink_to_fit();
     }
@@ -1571,6 +1666,67 @@ mod tests {
         assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
     }
 
+    #[tokio::test]
+    async fn test_peer_manager_peer_groups() {
+        // Connect 2 peers and a peer group member with a target of 2. The group member does not
+        // count towards the target, so no peer is pruned.
+        let peer0 = PeerId::random();
+        let peer1 = PeerId::random();
+        let sentry = PeerId::random();
+        let mut peer_manager = build_peer_manager(2).await;
+
+        let (joined, left) = peer_manager
+            .network_globals
+            .peer_groups
+            .write()
+            .set_group(
+                "sentries".to_string(),
+                vec![format!("/ip4/10.0.0.1/tcp/9000/p2p/{sentry}")
+                    .parse()
+                    .unwrap()],
+            )
+            .unwrap();
+        peer_manager.peer_groups_updated(&joined, &left);
+        assert!(peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&sentry)
+            .unwrap()
+            .is_trusted());
+        assert_eq!(peer_manager.group_peers_to_dial, vec![sentry]);
+
+        peer_manager.inject_connect_ingoing(&peer0, "/ip4/0.0.0.0".parse().unwrap(), None);
+        peer_manager.inject_connect_ingoing(&peer1, "/ip4/0.0.0.0".parse().unwrap(), None);
+        peer_manager.inject_connect_ingoing(&sentry, "/ip4/0.0.0.0".parse().unwrap(), None);
+        assert_eq!(peer_manager.network_globals.connected_peers(), 3);
+        assert_eq!(peer_manager.network_globals.connected_ungrouped_peers(), 2);
+
+        peer_manager.heartbeat();
+        assert_eq!(peer_manager.network_globals.connected_or_dialing_peers(), 3);
+
+        // The group member is redialed once it disconnects.
+        peer_manager.inject_disconnect(&sentry);
+        assert!(peer_manager.group_peers_to_redial.contains_key(&sentry));
+
+        // A peer which leaves its groups is no longer trusted or redialed.
+        let (joined, left) = peer_manager
+            .network_globals
+            .peer_groups
+            .write()
+            .set_group("sentries".to_string(), vec![])
+            .unwrap();
+        peer_manager.peer_groups_updated(&joined, &left);
+        assert!(!peer_manager
+            .network_globals
+            .peers
+            .read()
+            .peer_info(&sentry)
+            .unwrap()
+            .is_trusted());
+        assert!(!peer_manager.group_peers_to_redial.contains_key(&sentry));
+    }
+
     #[tokio::test]
     async fn test_peer_manager_not_enough_outbound_peers_no_panic_during_heartbeat() {
         let mut peer_manager = build_peer_manager(20).await;
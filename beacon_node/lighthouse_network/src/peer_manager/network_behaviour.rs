@@ -68,6 +68,20 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             }
         }
 
+        loop {
+            match self.group_peers_to_redial.poll_next_unpin(cx) {
+                Poll::Ready(Some(Ok(peer_id))) => {
+                    if self.network_globals.peers.read().should_dial(&peer_id) {
+                        self.group_peers_to_dial.push(peer_id);
+                    }
+                }
+                Poll::Ready(Some(Err(e))) => {
+                    error!(self.log, "Failed to check for peer group members to redial"; "error" => e.to_string())
+                }
+                Poll::Ready(None) | Poll::Pending => break,
+            }
+        }
+
         if !matches!(
             self.network_globals.sync_state(),
             SyncState::SyncingFinalized { .. } | SyncState::SyncingHead { .. }
@@ -113,6 +127,23 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             });
         }
 
+        while let Some(peer_id) = self.group_peers_to_dial.pop() {
+            // The peer may have left its groups since it was queued.
+            let addresses = self.network_globals.peer_groups.read().addresses(&peer_id);
+            if addresses.is_empty() {
+                continue;
+            }
+            self.inject_peer_connection(&peer_id, ConnectingType::Dialing, None);
+
+            debug!(self.log, "Dialing peer group member"; "peer_id" => %peer_id, "multiaddrs" => ?addresses);
+            return Poll::Ready(ToSwarm::Dial {
+                opts: DialOpts::peer_id(peer_id)
+                    .condition(PeerCondition::Disconnected)
+                    .addresses(addresses)
+                    .build(),
+            });
+        }
+
         Poll::Pending
     }
 
@@ -193,8 +224,11 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             ));
         }
 
-        // Check the connection limits
-        if self.network_globals.connected_or_dialing_peers() >= self.max_peers()
+        // Check the connection limits. Peers of peer groups are always accepted. The peer groups
+        // are checked first, as they must not be locked while `peers` is.
+        let is_group_peer = self.network_globals.peer_groups.read().contains(&peer_id);
+        if !is_group_peer
+            && self.network_globals.connected_or_dialing_ungrouped_peers() >= self.max_peers()
             && self
                 .network_globals
                 .peers
@@ -224,8 +258,11 @@ impl<E: EthSpec> NetworkBehaviour for PeerManager<E> {
             return Err(ConnectionDenied::new(cause));
         }
 
-        // Check the connection limits
-        if self.network_globals.connected_peers() >= self.max_outbound_dialing_peers()
+        // Check the connection limits. Peers of peer groups are always accepted. The peer groups
+        // are checked first, as they must not be locked while `peers` is.
+        let is_group_peer = self.network_globals.peer_groups.read().contains(&peer_id);
+        if !is_group_peer
+            && self.network_globals.connected_ungrouped_peers() >= self.max_outbound_dialing_peers()
             && self
                 .network_globals
                 .peers
@@ -0,0 +1,259 @@
+//! Named groups of peers, such as a node's own sentries, which are configured at runtime.
+//!
+//! The peers in a group are trusted, always redialed when they disconnect, direct peers of
+//! gossipsub and do not count towards the target peer count.
+
+use libp2p::multiaddr::Protocol;
+use libp2p::{Multiaddr, PeerId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::time::Duration;
+
+/// The delay before the first redial of a group peer which has disconnected.
+pub const MIN_REDIAL_BACKOFF: Duration = Duration::from_secs(5);
+/// The maximum delay between redials of a group peer which cannot be reached.
+pub const MAX_REDIAL_BACKOFF: Duration = Duration::from_secs(300);
+
+/// A peer of a group, as reported by the HTTP API.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerGroupMember {
+    pub peer_id: String,
+    pub addresses: Vec<String>,
+    pub connected: bool,
+}
+
+/// A group of peers, as reported by the HTTP API.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerGroup {
+    pub name: String,
+    pub peers: Vec<PeerGroupMember>,
+}
+
+#[derive(Debug)]
+struct GroupPeer {
+    /// The groups the peer is a member of.
+    groups: BTreeSet<String>,
+    /// The delay before the peer is next redialed.
+    redial_backoff: Duration,
+}
+
+/// The peer groups, keyed by name.
+#[derive(Debug, Default)]
+pub struct PeerGroups {
+    /// The addresses of the peers of each group, as configured.
+    groups: BTreeMap<String, Vec<Multiaddr>>,
+    peers: HashMap<PeerId, GroupPeer>,
+}
+
+/// Returns the peer id of an address ending with a `/p2p/<peer_id>` component.
+pub fn peer_id_from_multiaddr(multiaddr: &Multiaddr) -> Option<PeerId> {
+    match multiaddr.iter().last() {
+        Some(Protocol::P2p(peer_id)) => Some(peer_id),
+        _ => None,
+    }
+}
+
+impl PeerGroups {
+    /// Sets the peers of the group `name`, replacing any existing group of the same name. An empty
+    /// list of addresses removes the group.
+    ///
+    /// Returns the peers which joined a group and those which left all groups.
+    pub fn set_group(
+        &mut self,
+        name: String,
+        addresses: Vec<Multiaddr>,
+    ) -> Result<(Vec<PeerId>, Vec<PeerId>), String> {
+        let members = addresses
+            .iter()
+            .map(|multiaddr| {
+                peer_id_from_multiaddr(multiaddr)
+                    .ok_or_else(|| format!("address {multiaddr} does not end with a peer id"))
+            })
+            .collect::<Result<HashSet<_>, _>>()?;
+
+        let mut removed = Vec::new();
+        if let Some(previous) = self.groups.remove(&name) {
+            for peer_id in previous.iter().filter_map(peer_id_from_multiaddr) {
+                let Some(peer) = self.peers.get_mut(&peer_id) else {
+                    continue;
+                };
+                peer.groups.remove(&name);
+                if peer.groups.is_empty() && !members.contains(&peer_id) {
+                    self.peers.remove(&peer_id);
+                    removed.push(peer_id);
+                }
+            }
+        }
+
+        let mut added = Vec::new();
+        for peer_id in members {
+            let peer = self.peers.entry(peer_id).or_insert_with(|| {
+                added.push(peer_id);
+                GroupPeer {
+                    groups: BTreeSet::new(),
+                    redial_backoff: MIN_REDIAL_BACKOFF,
+                }
+            });
+            peer.groups.insert(name.clone());
+        }
+
+        if !addresses.is_empty() {
+            self.groups.insert(name, addresses);
+        }
+        Ok((added, removed))
+    }
+
+    /// Returns whether the peer is a member of any group.
+    pub fn contains(&self, peer_id: &PeerId) -> bool {
+        self.peers.contains_key(peer_id)
+    }
+
+    /// Returns the peers of all groups.
+    pub fn peer_ids(&self) -> impl Iterator<Item = &PeerId> {
+        self.peers.keys()
+    }
+
+    /// Returns the addresses of a group peer in all of its groups.
+    pub fn addresses(&self, peer_id: &PeerId) -> Vec<Multiaddr> {
+        let mut addresses = Vec::new();
+        for multiaddr in self.groups.values().flatten() {
+            if peer_id_from_multiaddr(multiaddr).as_ref() == Some(peer_id)
+                && !addresses.contains(multiaddr)
+            {
+                addresses.push(multiaddr.clone());
+            }
+        }
+        addresses
+    }
+
+    /// Returns the delay before a group peer should be redialed, doubling the delay for the next
+    /// redial.
+    pub fn next_redial_backoff(&mut self, peer_id: &PeerId) -> Option<Duration> {
+        let peer = self.peers.get_mut(peer_id)?;
+        let backoff = peer.redial_backoff;
+        peer.redial_backoff = std::cmp::min(backoff * 2, MAX_REDIAL_BACKOFF);
+        Some(backoff)
+    }
+
+    /// Resets the redial delay of a group peer once it has connected.
+    pub fn reset_redial_backoff(&mut self, peer_id: &PeerId) {
+        if let Some(peer) = self.peers.get_mut(peer_id) {
+            peer.redial_backoff = MIN_REDIAL_BACKOFF;
+        }
+    }
+
+    /// Returns the groups and their peers, given whether a peer is connected.
+    pub fn groups(&self, is_connected: impl Fn(&PeerId) -> bool) -> Vec<PeerGroup> {
+        self.groups
+            .keys()
+            .map(|name| {
+                let mut peers = self
+                    .peers
+                    .iter()
+                    .filter(|(_, peer)| peer.groups.contains(name))
+                    .map(|(peer_id, _)| PeerGroupMember {
+                        peer_id: peer_id.to_string(),
+                        addresses: self
+                            .addresses(peer_id)
+                            .iter()
+                            .map(|multiaddr| multiaddr.to_string())
+                            .collect(),
+                        connected: is_connected(peer_id),
+                    })
+                    .collect::<Vec<_>>();
+                peers.sort_by(|a, b| a.peer_id.cmp(&b.peer_id));
+                PeerGroup {
+                    name: name.clone(),
+                    peers,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(peer_id: &PeerId, port: u16) -> Multiaddr {
+        format!("/ip4/10.0.0.1/tcp/{port}/p2p/{peer_id}")
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn set_and_remove_groups() {
+        let mut groups = PeerGroups::default();
+        let sentry = PeerId::random();
+        let other_sentry = PeerId::random();
+
+        let (added, removed) = groups
+            .set_group(
+                "sentries".to_string(),
+                vec![address(&sentry, 9000), address(&other_sentry, 9000)],
+            )
+            .unwrap();
+        assert_eq!(added.len(), 2);
+        assert!(removed.is_empty());
+
+        // A peer may be a member of several groups, with the addresses of all of them.
+        let (added, _) = groups
+            .set_group("backup".to_string(), vec![address(&sentry, 9001)])
+            .unwrap();
+        assert!(added.is_empty());
+        assert_eq!(groups.addresses(&sentry).len(), 2);
+
+        // Removing a group only removes the peers which are not in another group.
+        let (_, removed) = groups.set_group("sentries".to_string(), vec![]).unwrap();
+        assert_eq!(removed, vec![other_sentry]);
+        assert!(groups.contains(&sentry));
+        assert!(!groups.contains(&other_sentry));
+
+        let reported = groups.groups(|peer_id| *peer_id == sentry);
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].name, "backup");
+        assert!(reported[0].peers[0].connected);
+    }
+
+    #[test]
+    fn addresses_require_peer_id() {
+        let mut groups = PeerGroups::default();
+        let address = "/ip4/10.0.0.1/tcp/9000".parse().unwrap();
+        assert!(groups
+            .set_group("sentries".to_string(), vec![address])
+            .is_err());
+        assert!(groups.groups(|_| false).is_empty());
+    }
+
+    #[test]
+    fn redial_backoff() {
+        let mut groups = PeerGroups::default();
+        let peer_id = PeerId::random();
+        groups
+            .set_group("sentries".to_string(), vec![address(&peer_id, 9000)])
+            .unwrap();
+
+        assert_eq!(
+            groups.next_redial_backoff(&peer_id),
+            Some(MIN_REDIAL_BACKOFF)
+        );
+        assert_eq!(
+            groups.next_redial_backoff(&peer_id),
+            Some(MIN_REDIAL_BACKOFF * 2)
+        );
+        for _ in 0..10 {
+            groups.next_redial_backoff(&peer_id);
+        }
+        assert_eq!(
+            groups.next_redial_backoff(&peer_id),
+            Some(MAX_REDIAL_BACKOFF)
+        );
+
+        groups.reset_redial_backoff(&peer_id);
+        assert_eq!(
+            groups.next_redial_backoff(&peer_id),
+            Some(MIN_REDIAL_BACKOFF)
+        );
+        assert_eq!(groups.next_redial_backoff(&PeerId::random()), None);
+    }
+}
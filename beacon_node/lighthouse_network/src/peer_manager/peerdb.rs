@@ -1,12 +1,14 @@
 use crate::discovery::enr::PEERDAS_CUSTODY_SUBNET_COUNT_ENR_KEY;
 use crate::discovery::{peer_id_to_node_id, CombinedKey};
 use crate::{metrics, multiaddr::Multiaddr, types::Subnet, Enr, EnrExt, Gossipsub, PeerId};
+use client::Client;
 use itertools::Itertools;
 use peer_info::{ConnectionDirection, PeerConnectionStatus, PeerInfo};
+use reputation::PeerReputation;
 use score::{PeerAction, ReportSource, Score, ScoreState};
 use slog::{crit, debug, error, trace, warn};
 use std::net::IpAddr;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::{cmp::Ordering, fmt::Display};
 use std::{
     collections::{HashMap, HashSet},
@@ -17,6 +19,7 @@ use types::{ChainSpec, DataColumnSubnetId, EthSpec};
 
 pub mod client;
 pub mod peer_info;
+pub mod reputation;
 pub mod score;
 pub mod sync_status;
 
@@ -24,6 +27,8 @@ pub mod sync_status;
 const MAX_DC_PEERS: usize = 500;
 /// The maximum number of banned nodes to remember.
 pub const MAX_BANNED_PEERS: usize = 1000;
+/// The maximum number of peer reputations to persist.
+pub const MAX_PEER_REPUTATIONS: usize = MAX_DC_PEERS + MAX_BANNED_PEERS;
 /// We ban an IP if there are more than `BANNED_PEERS_PER_IP_THRESHOLD` banned peers with this IP.
 const BANNED_PEERS_PER_IP_THRESHOLD: usize = 5;
 /// Relative factor of peers that are allowed to have a negative gossipsub score without penalizing
@@ -95,6 +100,19 @@ impl<E: EthSpec> PeerDB<E> {
         self.peers.get_mut(peer_id)
     }
 
+    /// Sets whether a peer is trusted, adding it to the db if it is unknown and now trusted.
+    pub(super) fn set_trusted(&mut self, peer_id: &PeerId, trusted: bool) {
+        // All peers are trusted when scoring is disabled.
+        let trusted = trusted || self.disable_peer_scoring;
+        match self.peers.get_mut(peer_id) {
+            Some(info) => info.set_trusted(trusted),
+            None if trusted => {
+                self.peers.insert(*peer_id, PeerInfo::trusted_peer_info());
+            }
+            None => {}
+        }
+    }
+
     /// Returns if the peer is already connected.
     pub fn is_connected(&self, peer_id: &PeerId) -> bool {
         matches!(
@@ -367,6 +385,111 @@ impl<E: EthSpec> PeerDB<E> {
         Some(info.update_sync_status(sync_status))
     }
 
+    /// Returns the reputations of up to `MAX_PEER_REPUTATIONS` known peers, banned peers first and
+    /// then the most recently seen. Trusted peers are not included.
+    pub fn reputations(&self) -> Vec<PeerReputation> {
+        let now = Instant::now();
+        let unix_now = reputation::unix_now();
+        let mut reputations = self
+            .peers
+            .iter()
+            .filter_map(|(peer_id, info)| {
+                let (score, decay_start) = info.score().lighthouse_score()?;
+                let last_seen = match info.connection_status() {
+                    PeerConnectionStatus::Disconnected { since }
+                    | PeerConnectionStatus::Banned { since }
+                    | PeerConnectionStatus::Dialing { since } => *since,
+                    PeerConnectionStatus::Connected { .. }
+                    | PeerConnectionStatus::Disconnecting { .. }
+                    | PeerConnectionStatus::Unknown => now,
+                };
+                let is_banned = info.score_is_banned();
+                let reputation = PeerReputation {
+                    peer_id: peer_id.to_string(),
+                    score,
+                    banned_until: (is_banned && decay_start > now)
+                        .then(|| reputation::instant_to_unix(decay_start, now, unix_now)),
+                    client: info.client().kind,
+                    sync_requests: *info.sync_requests(),
+                    last_seen: reputation::instant_to_unix(last_seen, now, unix_now),
+                };
+                Some((is_banned, reputation))
+            })
+            .collect::<Vec<_>>();
+
+        reputations.sort_unstable_by(|(banned_a, a), (banned_b, b)| {
+            banned_b
+                .cmp(banned_a)
+                .then_with(|| b.last_seen.cmp(&a.last_seen))
+        });
+        reputations
+            .into_iter()
+            .take(MAX_PEER_REPUTATIONS)
+            .map(|(_, reputation)| reputation)
+            .collect()
+    }
+
+    /// Restores the reputations persisted by a previous run which stopped `offline` ago, so that
+    /// banned peers remain banned. Peers which are already known, such as trusted peers, are
+    /// skipped, as are peers in excess of the limits on disconnected and banned peers.
+    ///
+    /// Returns the number of peers restored.
+    pub fn restore_reputations(
+        &mut self,
+        reputations: Vec<PeerReputation>,
+        offline: Duration,
+    ) -> usize {
+        if self.disable_peer_scoring {
+            return 0;
+        }
+
+        let now = Instant::now();
+        let unix_now = reputation::unix_now();
+        let mut restored = 0;
+        for reputation in reputations {
+            let Ok(peer_id) = reputation.peer_id.parse::<PeerId>() else {
+                debug!(self.log, "Ignoring invalid persisted peer"; "peer_id" => reputation.peer_id);
+                continue;
+            };
+            if self.peers.contains_key(&peer_id) {
+                continue;
+            }
+
+            // A banned peer's score does not decay until its ban ends, which may have happened
+            // while we were offline. Other scores decay as if we had remained online.
+            let decay_start = match reputation.banned_until {
+                Some(banned_until) => reputation::unix_to_instant(banned_until, now, unix_now),
+                None => now.checked_sub(offline).unwrap_or(now),
+            };
+            let score = Score::restore(reputation.score, decay_start);
+            let since = reputation::unix_to_instant(reputation.last_seen, now, unix_now);
+            let connection_status = if score.state() == ScoreState::Banned {
+                if self.banned_peers_count.banned_peers() >= MAX_BANNED_PEERS {
+                    continue;
+                }
+                self.banned_peers_count.add_banned_peer(std::iter::empty());
+                PeerConnectionStatus::Banned { since }
+            } else {
+                if self.disconnected_peers >= MAX_DC_PEERS {
+                    continue;
+                }
+                self.disconnected_peers += 1;
+                PeerConnectionStatus::Disconnected { since }
+            };
+
+            let mut info = PeerInfo::default();
+            info.restore_reputation(score, reputation.sync_requests);
+            info.set_client(Client {
+                kind: reputation.client,
+                ..Client::default()
+            });
+            info.set_connection_status(connection_status);
+            self.peers.insert(peer_id, info);
+            restored += 1;
+        }
+        restored
+    }
+
     /// Updates the scores of known peers according to their connection status and the time that
     /// has passed. This function returns a list of peers that have been unbanned.
     /// NOTE: Peer scores cannot be penalized during the update, they can only increase. Therefore
@@ -1512,6 +1635,63 @@ mod tests {
         assert_eq!(pdb.banned_peers_count.banned_peers(), MAX_BANNED_PEERS);
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_restore_reputations() {
+        let mut pdb = get_db();
+
+        let banned_peer = PeerId::random();
+        let disconnected_peer = PeerId::random();
+        let connected_peer = PeerId::random();
+        for peer in [&banned_peer, &disconnected_peer, &connected_peer] {
+            pdb.connect_ingoing(peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        }
+        let _ = pdb.report_peer(
+            &banned_peer,
+            PeerAction::Fatal,
+            ReportSource::PeerManager,
+            "",
+        );
+        pdb.inject_disconnect(&banned_peer);
+        add_score(&mut pdb, &disconnected_peer, -10.0);
+        pdb.inject_disconnect(&disconnected_peer);
+        pdb.peer_info_mut(&connected_peer)
+            .unwrap()
+            .record_sync_request(true);
+
+        let reputations = pdb.reputations();
+        assert_eq!(reputations.len(), 3);
+        assert_eq!(reputations[0].peer_id, banned_peer.to_string());
+        assert!(reputations[0].banned_until.is_some());
+        assert!(reputations[1..].iter().all(|r| r.banned_until.is_none()));
+
+        let mut restored_pdb = get_db();
+        assert_eq!(
+            restored_pdb.restore_reputations(reputations.clone(), Duration::ZERO),
+            3
+        );
+        // Known peers are not overwritten.
+        assert_eq!(
+            restored_pdb.restore_reputations(reputations, Duration::ZERO),
+            0
+        );
+
+        assert!(restored_pdb.peer_info(&banned_peer).unwrap().is_banned());
+        assert!(matches!(
+            restored_pdb.ban_status(&banned_peer),
+            Some(BanResult::BadScore)
+        ));
+        assert_eq!(restored_pdb.banned_peers_count.banned_peers(), 1);
+
+        let info = restored_pdb.peer_info(&disconnected_peer).unwrap();
+        assert!(info.is_disconnected());
+        assert_eq!(info.score().score(), -10.0);
+        let info = restored_pdb.peer_info(&connected_peer).unwrap();
+        assert!(info.is_disconnected());
+        assert_eq!(info.sync_requests().completed, 1);
+        assert_eq!(restored_pdb.disconnected_peers, 2);
+    }
+
     #[test]
     fn test_best_peers() {
         let mut pdb = get_db();
@@ -2064,6 +2244,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_set_trusted() {
+        let peer = PeerId::random();
+        let unknown_peer = PeerId::random();
+        let log = build_log(slog::Level::Debug, false);
+        let mut pdb: PeerDB<M> = PeerDB::new(vec![], false, &log);
+
+        pdb.connect_ingoing(&peer, "/ip4/0.0.0.0".parse().unwrap(), None);
+        add_score(&mut pdb, &peer, -10.0);
+
+        // Trusting a peer gives it the maximum score.
+        pdb.set_trusted(&peer, true);
+        assert!(pdb.peer_info(&peer).unwrap().is_trusted());
+        assert_eq!(
+            pdb.peer_info(&peer).unwrap().score().score(),
+            Score::max_score().score()
+        );
+
+        // A peer which is no longer trusted starts over from the default score.
+        pdb.set_trusted(&peer, false);
+        assert!(!pdb.peer_info(&peer).unwrap().is_trusted());
+        assert_eq!(pdb.score(&peer), 0.0);
+
+        // Unknown peers are only added if they are trusted.
+        pdb.set_trusted(&unknown_peer, false);
+        assert!(pdb.peer_info(&unknown_peer).is_none());
+        pdb.set_trusted(&unknown_peer, true);
+        assert!(pdb.peer_info(&unknown_peer).unwrap().is_trusted());
+    }
+
     #[test]
     fn test_disable_peer_scoring() {
         let peer = PeerId::random();
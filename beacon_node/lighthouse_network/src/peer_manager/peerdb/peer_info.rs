@@ -1,4 +1,5 @@
 use super::client::Client;
+use super::reputation::SyncRequests;
 use super::score::{PeerAction, Score, ScoreState};
 use super::sync_status::SyncStatus;
 use crate::discovery::Eth2Enr;
@@ -35,6 +36,8 @@ pub struct PeerInfo<E: EthSpec> {
     /// The current syncing state of the peer. The state may be determined after it's initial
     /// connection.
     sync_status: SyncStatus,
+    /// The outcome of the sync requests we have made to the peer.
+    sync_requests: SyncRequests,
     /// The ENR subnet bitfield of the peer. This may be determined after it's initial
     /// connection.
     meta_data: Option<MetaData<E>>,
@@ -69,6 +72,7 @@ impl<E: EthSpec> Default for PeerInfo<E> {
             subnets: HashSet::new(),
             custody_subnets: HashSet::new(),
             sync_status: SyncStatus::Unknown,
+            sync_requests: SyncRequests::default(),
             meta_data: None,
             min_ttl: None,
             is_trusted: false,
@@ -127,6 +131,11 @@ impl<E: EthSpec> PeerInfo<E> {
         &self.sync_status
     }
 
+    /// Returns the outcome of the sync requests we have made to the peer.
+    pub fn sync_requests(&self) -> &SyncRequests {
+        &self.sync_requests
+    }
+
     /// Returns the metadata for the peer if currently known.
     pub fn meta_data(&self) -> Option<&MetaData<E>> {
         self.meta_data.as_ref()
@@ -373,6 +382,34 @@ impl<E: EthSpec> PeerInfo<E> {
         self.custody_subnets = custody_subnets
     }
 
+    /// Records the outcome of a sync request made to the peer.
+    pub(in crate::peer_manager) fn record_sync_request(&mut self, completed: bool) {
+        if completed {
+            self.sync_requests.completed = self.sync_requests.completed.saturating_add(1);
+        } else {
+            self.sync_requests.failed = self.sync_requests.failed.saturating_add(1);
+        }
+    }
+
+    /// Restores the reputation of the peer from a previous run.
+    pub(super) fn restore_reputation(&mut self, score: Score, sync_requests: SyncRequests) {
+        self.score = score;
+        self.sync_requests = sync_requests;
+    }
+
+    /// Sets whether the peer is trusted. Trusted peers have the maximum score, which is reset when
+    /// they stop being trusted.
+    pub(super) fn set_trusted(&mut self, trusted: bool) {
+        if self.is_trusted != trusted {
+            self.is_trusted = trusted;
+            self.score = if trusted {
+                Score::max_score()
+            } else {
+                Score::default()
+            };
+        }
+    }
+
     /// Sets the ENR of the peer if one is known.
     pub(super) fn set_enr(&mut self, enr: Enr) {
         self.enr = Some(enr)
@@ -0,0 +1,94 @@
+//! The parts of a peer's reputation which are persisted across restarts, so that peers we have
+//! banned are not re-dialed and peers which served us well are remembered.
+
+use super::client::ClientKind;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// The outcome of the sync requests (blocks, blobs and data columns by range or root) we have
+/// made to a peer.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncRequests {
+    /// The number of requests the peer responded to in full.
+    pub completed: u64,
+    /// The number of requests which failed with an error or timed out.
+    pub failed: u64,
+}
+
+impl SyncRequests {
+    /// Returns the fraction of requests which the peer completed, or `None` if no requests have
+    /// been made.
+    pub fn reliability(&self) -> Option<f64> {
+        let total = self.completed.saturating_add(self.failed);
+        (total > 0).then(|| self.completed as f64 / total as f64)
+    }
+}
+
+/// A summary of a peer's reputation.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PeerReputation {
+    pub peer_id: String,
+    /// The Lighthouse component of the peer's score. The gossipsub component is not persisted, as
+    /// it is recomputed by gossipsub.
+    pub score: f64,
+    /// The Unix timestamp in seconds until which the peer is banned, if its ban has not begun to
+    /// decay. A banned peer whose ban is decaying has a score below the ban threshold instead.
+    pub banned_until: Option<u64>,
+    pub client: ClientKind,
+    pub sync_requests: SyncRequests,
+    /// The Unix timestamp in seconds at which we were last connected to the peer, or at which its
+    /// connection state last changed.
+    pub last_seen: u64,
+}
+
+/// Returns the current Unix timestamp in seconds.
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+/// Converts `instant` to a Unix timestamp in seconds, given the current `Instant` and Unix time.
+pub(crate) fn instant_to_unix(instant: Instant, now: Instant, unix_now: u64) -> u64 {
+    if instant >= now {
+        unix_now.saturating_add(instant.duration_since(now).as_secs())
+    } else {
+        unix_now.saturating_sub(now.duration_since(instant).as_secs())
+    }
+}
+
+/// Converts the Unix timestamp `unix` in seconds to an `Instant`, given the current `Instant` and
+/// Unix time. Timestamps too far in the past to be represented are clamped to `now`.
+pub(crate) fn unix_to_instant(unix: u64, now: Instant, unix_now: u64) -> Instant {
+    if unix >= unix_now {
+        now + Duration::from_secs(unix - unix_now)
+    } else {
+        now.checked_sub(Duration::from_secs(unix_now - unix))
+            .unwrap_or(now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sync_reliability() {
+        assert_eq!(SyncRequests::default().reliability(), None);
+        let sync_requests = SyncRequests {
+            completed: 3,
+            failed: 1,
+        };
+        assert_eq!(sync_requests.reliability(), Some(0.75));
+    }
+
+    #[test]
+    fn instant_conversion() {
+        let now = Instant::now();
+        let unix_now = 1_700_000_000;
+        let later = now + Duration::from_secs(60);
+        assert_eq!(instant_to_unix(later, now, unix_now), unix_now + 60);
+        assert_eq!(unix_to_instant(unix_now + 60, now, unix_now), later);
+        assert_eq!(unix_to_instant(unix_now, now, unix_now), now);
+    }
+}
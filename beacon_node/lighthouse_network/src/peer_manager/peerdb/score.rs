@@ -153,6 +153,19 @@ impl Default for RealScore {
 }
 
 impl RealScore {
+    /// Restores a persisted Lighthouse score. The score begins to decay at `decay_start`, which is
+    /// in the future if the peer is banned, or in the past to account for time spent offline.
+    fn restore(lighthouse_score: f64, decay_start: Instant) -> Self {
+        let mut score = RealScore {
+            lighthouse_score: lighthouse_score.clamp(MIN_SCORE, MAX_SCORE),
+            last_updated: decay_start,
+            ..RealScore::default()
+        };
+        // Not `update_state`, which would begin a new ban.
+        score.recompute_score();
+        score
+    }
+
     /// Access to the underlying score.
     fn recompute_score(&mut self) {
         self.score = self.lighthouse_score;
@@ -309,6 +322,20 @@ impl Score {
         Self::Max
     }
 
+    /// Restores a persisted Lighthouse score which begins to decay at `decay_start`.
+    pub(crate) fn restore(lighthouse_score: f64, decay_start: Instant) -> Self {
+        Self::Real(RealScore::restore(lighthouse_score, decay_start))
+    }
+
+    /// Returns the Lighthouse component of the score and the time at which it begins to decay, or
+    /// `None` for the maximum score.
+    pub(crate) fn lighthouse_score(&self) -> Option<(f64, Instant)> {
+        match self {
+            Self::Max => None,
+            Self::Real(score) => Some((score.lighthouse_score, score.last_updated)),
+        }
+    }
+
     /// Returns the expected state of the peer given it's score.
     pub(crate) fn state(&self) -> ScoreState {
         match self.score() {
@@ -403,6 +430,25 @@ mod tests {
         assert!(score.score() > MIN_SCORE_BEFORE_BAN);
     }
 
+    #[test]
+    #[allow(clippy::float_cmp)]
+    fn test_restore_banned_score() {
+        let now = Instant::now();
+        let decay_start = now + Duration::from_secs(60);
+        let mut score = Score::restore(MIN_SCORE, decay_start);
+        assert_eq!(score.state(), ScoreState::Banned);
+        assert_eq!(score.lighthouse_score(), Some((MIN_SCORE, decay_start)));
+
+        // The restored ban is not extended.
+        let Score::Real(real_score) = &mut score else {
+            panic!("expected a real score");
+        };
+        real_score.update_at(decay_start);
+        assert_eq!(real_score.score(), MIN_SCORE);
+        real_score.update_at(decay_start + Duration::from_secs(SCORE_HALFLIFE as u64 * 2));
+        assert!(real_score.score() > MIN_SCORE_BEFORE_BAN);
+    }
+
     #[test]
     fn test_very_negative_gossipsub_score() {
         let mut score = Score::default();
@@ -577,6 +577,20 @@ pub enum ResponseTermination {
     LightClientUpdatesByRange,
 }
 
+impl ResponseTermination {
+    pub fn as_protocol(&self) -> Protocol {
+        match self {
+            ResponseTermination::BlocksByRange => Protocol::BlocksByRange,
+            ResponseTermination::BlocksByRoot => Protocol::BlocksByRoot,
+            ResponseTermination::BlobsByRange => Protocol::BlobsByRange,
+            ResponseTermination::BlobsByRoot => Protocol::BlobsByRoot,
+            ResponseTermination::DataColumnsByRoot => Protocol::DataColumnsByRoot,
+            ResponseTermination::DataColumnsByRange => Protocol::DataColumnsByRange,
+            ResponseTermination::LightClientUpdatesByRange => Protocol::LightClientUpdatesByRange,
+        }
+    }
+}
+
 /// The structured response containing a result/code indicating success or failure
 /// and the contents of the response
 #[derive(Debug, Clone)]
@@ -677,6 +691,29 @@ impl<E: EthSpec> RpcSuccessResponse<E> {
             RpcSuccessResponse::LightClientUpdatesByRange(_) => Protocol::LightClientUpdatesByRange,
         }
     }
+
+    /// Returns the length of the response's SSZ encoding, before compression.
+    pub fn ssz_bytes_len(&self) -> usize {
+        match self {
+            RpcSuccessResponse::Status(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::BlocksByRange(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::BlocksByRoot(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::BlobsByRange(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::BlobsByRoot(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::DataColumnsByRoot(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::DataColumnsByRange(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::LightClientBootstrap(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::LightClientOptimisticUpdate(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::LightClientFinalityUpdate(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::LightClientUpdatesByRange(res) => res.ssz_bytes_len(),
+            RpcSuccessResponse::Pong(res) => res.data.ssz_bytes_len(),
+            RpcSuccessResponse::MetaData(res) => match res {
+                MetaData::V1(metadata) => metadata.ssz_bytes_len(),
+                MetaData::V2(metadata) => metadata.ssz_bytes_len(),
+                MetaData::V3(metadata) => metadata.ssz_bytes_len(),
+            },
+        }
+    }
 }
 
 impl std::fmt::Display for RpcErrorResponse {
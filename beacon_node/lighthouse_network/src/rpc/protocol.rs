@@ -338,6 +338,27 @@ impl Protocol {
             Protocol::LightClientUpdatesByRange => None,
         }
     }
+
+    /// Returns whether the protocol is used by sync. The outcomes of our requests using these
+    /// protocols are recorded in the sync request history of the peer.
+    pub fn is_sync(self) -> bool {
+        match self {
+            Protocol::BlocksByRange
+            | Protocol::BlocksByRoot
+            | Protocol::BlobsByRange
+            | Protocol::BlobsByRoot
+            | Protocol::DataColumnsByRoot
+            | Protocol::DataColumnsByRange => true,
+            Protocol::Status
+            | Protocol::Goodbye
+            | Protocol::Ping
+            | Protocol::MetaData
+            | Protocol::LightClientBootstrap
+            | Protocol::LightClientOptimisticUpdate
+            | Protocol::LightClientFinalityUpdate
+            | Protocol::LightClientUpdatesByRange => false,
+        }
+    }
 }
 
 /// RPC Encondings supported.
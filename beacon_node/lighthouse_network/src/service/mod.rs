@@ -25,7 +25,7 @@ use api_types::{AppRequestId, PeerRequestId, RequestId, Response};
 use futures::stream::StreamExt;
 use gossipsub::{
     IdentTopic as Topic, MessageAcceptance, MessageAuthenticity, MessageId, PublishError,
-    TopicScoreParams,
+    TopicScoreParams, TraceConfig,
 };
 use gossipsub_scoring_parameters::{lighthouse_gossip_thresholds, PeerScoreSettings};
 use libp2p::multiaddr::{self, Multiaddr, Protocol as MProtocol};
@@ -56,6 +56,10 @@ pub const TARGET_SUBNET_PEERS: usize = 3;
 
 const MAX_IDENTIFY_ADDRESSES: usize = 10;
 
+/// The mesh degree of the topics of a kind which has exceeded its bandwidth cap. Every message is
+/// still received in full from at least one mesh peer, plus any which publish it to us directly.
+const CAPPED_MESH_N: usize = 2;
+
 /// The types of events than can be obtained from polling the behaviour.
 #[derive(Debug)]
 pub enum NetworkEvent<E: EthSpec> {
@@ -332,6 +336,11 @@ impl<E: EthSpec> Network<E> {
                 .with_peer_score(params, thresholds)
                 .expect("Valid score params and thresholds");
 
+            if let Some(dir) = &config.gossipsub_trace_dir {
+                gossipsub.with_tracer(TraceConfig::new(dir.clone()), local_peer_id)?;
+                info!(log, "Gossipsub tracing enabled"; "dir" => %dir.display());
+            }
+
             // Mark trusted peers as explicit.
             for explicit_peer in config.trusted_peers.iter() {
                 gossipsub.add_explicit_peer(&PeerId::from(explicit_peer.clone()));
@@ -788,8 +797,19 @@ impl<E: EthSpec> Network<E> {
             .write()
             .insert(topic.clone());
 
+        let capped = self
+            .network_globals
+            .bandwidth
+            .read()
+            .is_capped(topic.kind());
         let topic: Topic = topic.into();
 
+        // Topics of a kind which has exceeded its bandwidth cap are joined with a reduced mesh.
+        if capped {
+            self.gossipsub_mut()
+                .set_topic_mesh_n(&topic.hash(), Some(CAPPED_MESH_N));
+        }
+
         match self.gossipsub_mut().subscribe(&topic) {
             Err(e) => {
                 warn!(self.log, "Failed to subscribe to topic"; "topic" => %topic, "error" => ?e);
@@ -802,6 +822,26 @@ impl<E: EthSpec> Network<E> {
         }
     }
 
+    /// Reduces the mesh degree of the subscribed topics whose kind has exceeded its bandwidth cap,
+    /// and restores that of the others.
+    fn update_capped_meshes(&mut self) {
+        let bandwidth = self.network_globals.bandwidth.read();
+        let topics = self
+            .gossipsub()
+            .topics()
+            .filter_map(|topic_hash| {
+                let topic = GossipTopic::decode(topic_hash.as_str()).ok()?;
+                Some((topic_hash.clone(), bandwidth.is_capped(topic.kind())))
+            })
+            .collect::<Vec<_>>();
+        drop(bandwidth);
+
+        for (topic_hash, capped) in topics {
+            self.gossipsub_mut()
+                .set_topic_mesh_n(&topic_hash, capped.then_some(CAPPED_MESH_N));
+        }
+    }
+
     /// Unsubscribe from a gossipsub topic.
     pub fn unsubscribe(&mut self, topic: GossipTopic) -> bool {
         // update the network globals
@@ -831,6 +871,10 @@ impl<E: EthSpec> Network<E> {
         for message in messages {
             for topic in message.topics(GossipEncoding::default(), self.enr_fork_id.fork_digest) {
                 let message_data = message.encode(GossipEncoding::default());
+                self.network_globals
+                    .bandwidth
+                    .write()
+                    .gossip_published(topic.kind(), message_data.len());
                 if let Err(e) = self
                     .gossipsub_mut()
                     .publish(Topic::from(topic.clone()), message_data.clone())
@@ -986,8 +1030,16 @@ impl<E: EthSpec> Network<E> {
         request_id: rpc::RequestId,
         response: Response<E>,
     ) {
+        let response: RpcResponse<E> = response.into();
+        if let RpcResponse::Success(response) = &response {
+            self.network_globals.bandwidth.write().rpc_sent(
+                response.protocol(),
+                &peer_id,
+                response.ssz_bytes_len(),
+            );
+        }
         self.eth2_rpc_mut()
-            .send_response(peer_id, id, request_id, response.into())
+            .send_response(peer_id, id, request_id, response)
     }
 
     /// Inform the peer that their request produced an error.
@@ -1033,6 +1085,44 @@ impl<E: EthSpec> Network<E> {
             .goodbye_peer(peer_id, reason, source);
     }
 
+    /// Sets the peers of the peer group `name` to those at `addresses`, which must end with a
+    /// peer id. An empty list of addresses removes the group.
+    ///
+    /// The peers of a group are trusted, kept connected and are explicit peers of gossipsub.
+    pub fn set_peer_group(
+        &mut self,
+        name: String,
+        addresses: Vec<Multiaddr>,
+    ) -> Result<(), String> {
+        let (joined, left) = self
+            .network_globals
+            .peer_groups
+            .write()
+            .set_group(name.clone(), addresses)?;
+
+        // Peers configured with `--trusted-peers` remain trusted explicit peers.
+        let trusted_peers = &self.network_globals.config.trusted_peers;
+        let left = left
+            .into_iter()
+            .filter(|peer_id| {
+                !trusted_peers
+                    .iter()
+                    .any(|trusted| PeerId::from(trusted.clone()) == *peer_id)
+            })
+            .collect::<Vec<_>>();
+
+        for peer_id in &joined {
+            self.gossipsub_mut().add_explicit_peer(peer_id);
+        }
+        for peer_id in &left {
+            self.gossipsub_mut().remove_explicit_peer(peer_id);
+        }
+        self.peer_manager_mut().peer_groups_updated(&joined, &left);
+
+        info!(self.log, "Updated peer group"; "name" => name, "joined" => joined.len(), "left" => left.len());
+        Ok(())
+    }
+
     /// Hard (ungraceful) disconnect for testing purposes only
     /// Use goodbye_peer for disconnections, do not use this function.
     pub fn __hard_disconnect_testing_only(&mut self, peer_id: PeerId) {
@@ -1248,6 +1338,16 @@ impl<E: EthSpec> Network<E> {
             } => {
                 // Note: We are keeping track here of the peer that sent us the message, not the
                 // peer that originally published the message.
+                if let Ok(topic) = GossipTopic::decode(gs_msg.topic.as_str()) {
+                    let caps_changed = self.network_globals.bandwidth.write().gossip_received(
+                        topic.kind(),
+                        &propagation_source,
+                        gs_msg.data.len(),
+                    );
+                    if caps_changed {
+                        self.update_capped_meshes();
+                    }
+                }
                 match PubsubMessage::decode(&gs_msg.topic, &gs_msg.data, &self.fork_context) {
                     Err(e) => {
                         debug!(self.log, "Could not decode gossipsub message"; "topic" => ?gs_msg.topic,"error" => e);
@@ -1593,6 +1693,11 @@ impl<E: EthSpec> Network<E> {
                 }
             }
             Ok(RPCReceived::Response(id, resp)) => {
+                self.network_globals.bandwidth.write().rpc_received(
+                    resp.protocol(),
+                    &peer_id,
+                    resp.ssz_bytes_len(),
+                );
                 match resp {
                     /* Behaviour managed protocols */
                     RpcSuccessResponse::Pong(ping) => {
@@ -1651,6 +1756,9 @@ impl<E: EthSpec> Network<E> {
                 }
             }
             Ok(RPCReceived::EndOfStream(id, termination)) => {
+                if termination.as_protocol().is_sync() {
+                    self.peer_manager_mut().sync_request_completed(&peer_id);
+                }
                 let response = match termination {
                     ResponseTermination::BlocksByRange => Response::BlocksByRange(None),
                     ResponseTermination::BlocksByRoot => Response::BlocksByRoot(None),
@@ -1702,6 +1810,7 @@ impl<E: EthSpec> Network<E> {
                 Some(NetworkEvent::PeerConnectedOutgoing(peer_id))
             }
             PeerManagerEvent::PeerDisconnected(peer_id) => {
+                self.network_globals.bandwidth.write().remove_peer(&peer_id);
                 Some(NetworkEvent::PeerDisconnected(peer_id))
             }
             PeerManagerEvent::Banned(peer_id, associated_ips) => {
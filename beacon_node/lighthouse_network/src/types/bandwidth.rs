@@ -0,0 +1,342 @@
+//! Accounting of the bandwidth used per gossip topic kind, RPC protocol and peer, and the soft caps
+//! which bound the gossip bandwidth of a topic kind.
+//!
+//! Once a topic kind exceeds its cap, the gossipsub mesh degree of its topics is reduced until the
+//! end of the window. Fewer full copies of each message are then received, while every message is
+//! still received and forwarded to the remaining mesh peers.
+//!
+//! Sizes are those of the SSZ-encoded messages. Gossipsub and the RPC compress messages with
+//! snappy below the layers at which they are accounted, so the bytes on the wire are fewer.
+
+use crate::rpc::Protocol;
+use crate::types::GossipKind;
+use crate::{metrics, PeerId};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::time::{Duration, Instant};
+
+/// The period over which the bytes received on a topic kind are compared to its cap.
+pub const BANDWIDTH_CAP_WINDOW: Duration = Duration::from_secs(60 * 60);
+
+/// The names of the gossip topic kinds, which identify a topic irrespective of its subnet.
+pub const TOPIC_KINDS: [&str; 13] = [
+    "beacon_block",
+    "beacon_aggregate_and_proof",
+    "blob_sidecar",
+    "data_column_sidecar",
+    "beacon_attestation",
+    "voluntary_exit",
+    "proposer_slashing",
+    "attester_slashing",
+    "signed_contribution_and_proof",
+    "sync_committee",
+    "bls_to_execution_change",
+    "light_client_finality_update",
+    "light_client_optimistic_update",
+];
+
+/// The bytes received from and sent to peers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ByteCounts {
+    pub inbound: u64,
+    pub outbound: u64,
+}
+
+/// The state of the bandwidth cap of a topic kind in the current window.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BandwidthCap {
+    pub topic_kind: String,
+    /// The number of bytes which may be received on the topic kind per window.
+    pub limit: u64,
+    /// The number of bytes received on the topic kind in the current window.
+    pub used: u64,
+    /// The number of seconds until the current window ends.
+    pub resets_in: u64,
+}
+
+/// A breakdown of the bandwidth used since the node started.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BandwidthReport {
+    /// Gossip bytes per topic kind. Outbound bytes are those of the messages we published; the
+    /// messages forwarded by gossipsub are not accounted.
+    pub gossip: BTreeMap<String, ByteCounts>,
+    /// RPC response bytes per protocol.
+    pub rpc: BTreeMap<String, ByteCounts>,
+    /// Gossip and RPC bytes per connected peer, since the peer connected.
+    pub peers: BTreeMap<String, ByteCounts>,
+    pub caps: Vec<BandwidthCap>,
+}
+
+#[derive(Debug)]
+struct Cap {
+    limit: u64,
+    used: u64,
+}
+
+impl Cap {
+    fn is_exceeded(&self) -> bool {
+        self.used > self.limit
+    }
+}
+
+/// Tracks the bandwidth used per gossip topic kind, RPC protocol and peer.
+#[derive(Debug)]
+pub struct BandwidthTracker {
+    gossip: HashMap<String, ByteCounts>,
+    rpc: HashMap<Protocol, ByteCounts>,
+    peers: HashMap<PeerId, ByteCounts>,
+    /// The bandwidth caps, keyed by topic kind.
+    caps: HashMap<String, Cap>,
+    /// The start of the current cap window.
+    window_start: Instant,
+}
+
+impl BandwidthTracker {
+    /// Creates a tracker which caps the bytes received per `BANDWIDTH_CAP_WINDOW` on the topic
+    /// kinds in `caps`.
+    pub fn new(caps: &HashMap<String, u64>) -> Self {
+        BandwidthTracker {
+            gossip: HashMap::new(),
+            rpc: HashMap::new(),
+            peers: HashMap::new(),
+            caps: caps
+                .iter()
+                .map(|(kind, limit)| {
+                    (
+                        kind.clone(),
+                        Cap {
+                            limit: *limit,
+                            used: 0,
+                        },
+                    )
+                })
+                .collect(),
+            window_start: Instant::now(),
+        }
+    }
+
+    /// Records a gossip message received from `peer_id`. Returns `true` if the set of topic kinds
+    /// which have exceeded their cap has changed, either because the message's topic kind has
+    /// exceeded its cap or because a new window has started.
+    pub fn gossip_received(&mut self, kind: &GossipKind, peer_id: &PeerId, bytes: usize) -> bool {
+        self.gossip_received_at(kind, peer_id, bytes, Instant::now())
+    }
+
+    fn gossip_received_at(
+        &mut self,
+        kind: &GossipKind,
+        peer_id: &PeerId,
+        bytes: usize,
+        now: Instant,
+    ) -> bool {
+        let bytes = bytes as u64;
+        let kind: &str = kind.as_ref();
+        gossip_counts(&mut self.gossip, kind).inbound += bytes;
+        self.peers.entry(*peer_id).or_default().inbound += bytes;
+        metrics::inc_counter_vec_by(
+            &metrics::GOSSIP_BYTES_PER_TOPIC_KIND,
+            &[kind, "inbound"],
+            bytes,
+        );
+
+        let mut changed = false;
+        if now.duration_since(self.window_start) >= BANDWIDTH_CAP_WINDOW {
+            self.window_start = now;
+            for cap in self.caps.values_mut() {
+                changed |= cap.is_exceeded();
+                cap.used = 0;
+            }
+        }
+        let Some(cap) = self.caps.get_mut(kind) else {
+            return changed;
+        };
+        let was_exceeded = cap.is_exceeded();
+        cap.used = cap.used.saturating_add(bytes);
+        if cap.is_exceeded() {
+            metrics::inc_counter_vec(&metrics::GOSSIP_CAPPED_MESSAGES_PER_TOPIC_KIND, &[kind]);
+        }
+        changed || was_exceeded != cap.is_exceeded()
+    }
+
+    /// Returns whether the topic kind `kind` has exceeded its cap in the current window.
+    pub fn is_capped(&self, kind: &GossipKind) -> bool {
+        let kind: &str = kind.as_ref();
+        self.caps.get(kind).is_some_and(Cap::is_exceeded)
+    }
+
+    /// Records a gossip message we published.
+    pub fn gossip_published(&mut self, kind: &GossipKind, bytes: usize) {
+        let bytes = bytes as u64;
+        let kind: &str = kind.as_ref();
+        gossip_counts(&mut self.gossip, kind).outbound += bytes;
+        metrics::inc_counter_vec_by(
+            &metrics::GOSSIP_BYTES_PER_TOPIC_KIND,
+            &[kind, "outbound"],
+            bytes,
+        );
+    }
+
+    /// Records an RPC response received from `peer_id`.
+    pub fn rpc_received(&mut self, protocol: Protocol, peer_id: &PeerId, bytes: usize) {
+        let bytes = bytes as u64;
+        self.rpc.entry(protocol).or_default().inbound += bytes;
+        self.peers.entry(*peer_id).or_default().inbound += bytes;
+        metrics::inc_counter_vec_by(
+            &metrics::RPC_BYTES_PER_PROTOCOL,
+            &[protocol.as_ref(), "inbound"],
+            bytes,
+        );
+    }
+
+    /// Records an RPC response sent to `peer_id`.
+    pub fn rpc_sent(&mut self, protocol: Protocol, peer_id: &PeerId, bytes: usize) {
+        let bytes = bytes as u64;
+        self.rpc.entry(protocol).or_default().outbound += bytes;
+        self.peers.entry(*peer_id).or_default().outbound += bytes;
+        metrics::inc_counter_vec_by(
+            &metrics::RPC_BYTES_PER_PROTOCOL,
+            &[protocol.as_ref(), "outbound"],
+            bytes,
+        );
+    }
+
+    /// Forgets the bandwidth used by a disconnected peer.
+    pub fn remove_peer(&mut self, peer_id: &PeerId) {
+        self.peers.remove(peer_id);
+    }
+
+    /// Returns the bandwidth used so far and the state of the caps.
+    pub fn report(&self) -> BandwidthReport {
+        let resets_in = BANDWIDTH_CAP_WINDOW
+            .saturating_sub(self.window_start.elapsed())
+            .as_secs();
+        let mut caps = self
+            .caps
+            .iter()
+            .map(|(kind, cap)| BandwidthCap {
+                topic_kind: kind.clone(),
+                limit: cap.limit,
+                used: cap.used,
+                resets_in,
+            })
+            .collect::<Vec<_>>();
+        caps.sort_by(|a, b| a.topic_kind.cmp(&b.topic_kind));
+
+        BandwidthReport {
+            gossip: self
+                .gossip
+                .iter()
+                .map(|(kind, counts)| (kind.clone(), *counts))
+                .collect(),
+            rpc: self
+                .rpc
+                .iter()
+                .map(|(protocol, counts)| (protocol.to_string(), *counts))
+                .collect(),
+            peers: self
+                .peers
+                .iter()
+                .map(|(peer_id, counts)| (peer_id.to_string(), *counts))
+                .collect(),
+            caps,
+        }
+    }
+}
+
+/// Returns the counts of `kind`, avoiding an allocation if it has been seen before.
+fn gossip_counts<'a>(
+    gossip: &'a mut HashMap<String, ByteCounts>,
+    kind: &str,
+) -> &'a mut ByteCounts {
+    if !gossip.contains_key(kind) {
+        gossip.insert(kind.to_string(), ByteCounts::default());
+    }
+    gossip.get_mut(kind).expect("inserted above")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::{DataColumnSubnetId, SubnetId, SyncSubnetId};
+
+    #[test]
+    fn topic_kinds() {
+        let kinds = [
+            GossipKind::BeaconBlock,
+            GossipKind::BeaconAggregateAndProof,
+            GossipKind::BlobSidecar(0),
+            GossipKind::DataColumnSidecar(DataColumnSubnetId::new(0)),
+            GossipKind::Attestation(SubnetId::new(0)),
+            GossipKind::VoluntaryExit,
+            GossipKind::ProposerSlashing,
+            GossipKind::AttesterSlashing,
+            GossipKind::SignedContributionAndProof,
+            GossipKind::SyncCommitteeMessage(SyncSubnetId::new(0)),
+            GossipKind::BlsToExecutionChange,
+            GossipKind::LightClientFinalityUpdate,
+            GossipKind::LightClientOptimisticUpdate,
+        ];
+        let names = kinds
+            .iter()
+            .map(|kind| AsRef::<str>::as_ref(kind))
+            .collect::<Vec<_>>();
+        assert_eq!(names, TOPIC_KINDS);
+    }
+
+    #[test]
+    fn accounting() {
+        let mut tracker = BandwidthTracker::new(&HashMap::new());
+        let peer_id = PeerId::random();
+        let other_peer_id = PeerId::random();
+
+        assert!(!tracker.gossip_received(&GossipKind::BeaconBlock, &peer_id, 100));
+        tracker.gossip_published(&GossipKind::BeaconBlock, 50);
+        tracker.rpc_received(Protocol::BlocksByRange, &peer_id, 1000);
+        tracker.rpc_sent(Protocol::BlocksByRoot, &other_peer_id, 10);
+
+        let report = tracker.report();
+        assert_eq!(
+            report.gossip["beacon_block"],
+            ByteCounts {
+                inbound: 100,
+                outbound: 50
+            }
+        );
+        assert_eq!(report.rpc["beacon_blocks_by_range"].inbound, 1000);
+        assert_eq!(report.rpc["beacon_blocks_by_root"].outbound, 10);
+        assert_eq!(report.peers[&peer_id.to_string()].inbound, 1100);
+        assert_eq!(report.peers[&other_peer_id.to_string()].outbound, 10);
+        assert!(report.caps.is_empty());
+
+        tracker.remove_peer(&peer_id);
+        assert_eq!(tracker.report().peers.len(), 1);
+    }
+
+    #[test]
+    fn caps() {
+        let caps = HashMap::from([("data_column_sidecar".to_string(), 150)]);
+        let mut tracker = BandwidthTracker::new(&caps);
+        let peer_id = PeerId::random();
+        let kind = GossipKind::DataColumnSidecar(DataColumnSubnetId::new(3));
+        let now = tracker.window_start;
+
+        // The cap is shared by all subnets of the topic kind and does not apply to other kinds.
+        assert!(!tracker.gossip_received_at(&kind, &peer_id, 100, now));
+        assert!(!tracker.gossip_received_at(&GossipKind::BeaconBlock, &peer_id, 1000, now));
+        assert!(!tracker.is_capped(&kind));
+        assert!(tracker.gossip_received_at(&kind, &peer_id, 100, now));
+        assert_eq!(tracker.report().caps[0].used, 200);
+        assert!(tracker.is_capped(&GossipKind::DataColumnSidecar(DataColumnSubnetId::new(4))));
+        assert!(!tracker.is_capped(&GossipKind::BeaconBlock));
+
+        // Only exceeding the cap is reported as a change.
+        assert!(!tracker.gossip_received_at(&kind, &peer_id, 100, now));
+
+        // The cap is reset once the window has passed, by a message of any kind.
+        let later = now + BANDWIDTH_CAP_WINDOW;
+        assert!(tracker.gossip_received_at(&GossipKind::BeaconBlock, &peer_id, 100, later));
+        assert!(!tracker.is_capped(&kind));
+        assert!(!tracker.gossip_received_at(&kind, &peer_id, 100, later));
+        assert_eq!(tracker.report().caps[0].used, 100);
+    }
+}
@@ -1,8 +1,9 @@
 //! A collection of variables that are accessible outside of the network thread itself.
+use crate::peer_manager::peer_groups::PeerGroups;
 use crate::peer_manager::peerdb::PeerDB;
 use crate::rpc::{MetaData, MetaDataV3};
-use crate::types::{BackFillState, SyncState};
-use crate::{Client, Enr, EnrExt, GossipTopic, Multiaddr, NetworkConfig, PeerId};
+use crate::types::{BackFillState, BandwidthTracker, SyncState};
+use crate::{Client, Enr, EnrExt, GossipTopic, Multiaddr, NetworkConfig, PeerId, Subnet};
 use itertools::Itertools;
 use parking_lot::RwLock;
 use std::collections::HashSet;
@@ -18,6 +19,9 @@ pub struct NetworkGlobals<E: EthSpec> {
     pub listen_multiaddrs: RwLock<Vec<Multiaddr>>,
     /// The collection of known peers.
     pub peers: RwLock<PeerDB<E>>,
+    /// The named groups of peers which are kept connected. To avoid deadlocks, this must not be
+    /// locked while a lock on `peers` is held.
+    pub peer_groups: RwLock<PeerGroups>,
     // The local meta data of our node.
     pub local_metadata: RwLock<MetaData<E>>,
     /// The current gossipsub topic subscriptions.
@@ -26,6 +30,11 @@ pub struct NetworkGlobals<E: EthSpec> {
     pub sync_state: RwLock<SyncState>,
     /// The current state of the backfill sync.
     pub backfill_state: RwLock<BackFillState>,
+    /// The bandwidth used per gossip topic kind, RPC protocol and peer.
+    pub bandwidth: RwLock<BandwidthTracker>,
+    /// The subnets subscribed to via the HTTP API, which the subnet services do not unsubscribe
+    /// from.
+    pub pinned_subnets: RwLock<HashSet<Subnet>>,
     /// The computed sampling subnets and columns is stored to avoid re-computing.
     pub sampling_subnets: Vec<DataColumnSubnetId>,
     pub sampling_columns: Vec<ColumnIndex>,
@@ -80,9 +89,12 @@ impl<E: EthSpec> NetworkGlobals<E> {
             listen_multiaddrs: RwLock::new(Vec::new()),
             local_metadata: RwLock::new(local_metadata),
             peers: RwLock::new(PeerDB::new(trusted_peers, disable_peer_scoring, log)),
+            peer_groups: RwLock::new(PeerGroups::default()),
             gossipsub_subscriptions: RwLock::new(HashSet::new()),
             sync_state: RwLock::new(SyncState::Stalled),
             backfill_state: RwLock::new(BackFillState::NotRequired),
+            bandwidth: RwLock::new(BandwidthTracker::new(&config.gossip_bandwidth_caps)),
+            pinned_subnets: RwLock::new(HashSet::new()),
             sampling_subnets,
             sampling_columns,
             config,
@@ -121,6 +133,39 @@ impl<E: EthSpec> NetworkGlobals<E> {
         self.peers.read().connected_or_dialing_peers().count()
     }
 
+    /// Returns the number of connected peers which are not in a peer group. Only these count
+    /// towards the target peer count.
+    pub fn connected_ungrouped_peers(&self) -> usize {
+        let peer_groups = self.peer_groups.read();
+        self.peers
+            .read()
+            .connected_peer_ids()
+            .filter(|peer_id| !peer_groups.contains(peer_id))
+            .count()
+    }
+
+    /// Returns the number of connected peers with outbound-only connections which are not in a
+    /// peer group.
+    pub fn connected_outbound_only_ungrouped_peers(&self) -> usize {
+        let peer_groups = self.peer_groups.read();
+        self.peers
+            .read()
+            .connected_outbound_only_peers()
+            .filter(|peer_id| !peer_groups.contains(peer_id))
+            .count()
+    }
+
+    /// Returns the number of peers that are either connected or being dialed which are not in a
+    /// peer group.
+    pub fn connected_or_dialing_ungrouped_peers(&self) -> usize {
+        let peer_groups = self.peer_groups.read();
+        self.peers
+            .read()
+            .connected_or_dialing_peers()
+            .filter(|peer_id| !peer_groups.contains(peer_id))
+            .count()
+    }
+
     /// Returns in the node is syncing.
     pub fn is_syncing(&self) -> bool {
         self.sync_state.read().is_syncing()
@@ -1,3 +1,4 @@
+pub mod bandwidth;
 pub mod error;
 mod globals;
 mod pubsub;
@@ -12,6 +13,7 @@ pub type EnrSyncCommitteeBitfield<E> = BitVector<<E as EthSpec>::SyncCommitteeSu
 
 pub type Enr = discv5::enr::Enr<discv5::enr::CombinedKey>;
 
+pub use bandwidth::{BandwidthReport, BandwidthTracker};
 pub use globals::NetworkGlobals;
 pub use pubsub::{PubsubMessage, SnappyTransform};
 pub use subnet::{Subnet, SubnetDiscovery};
@@ -0,0 +1,166 @@
+use lighthouse_network::peer_manager::peerdb::client::ClientKind;
+use lighthouse_network::{PeerReputation, SyncRequests};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::sync::Arc;
+use store::{DBColumn, Error as StoreError, HotColdDB, ItemStore, StoreItem};
+use strum::IntoEnumIterator;
+use types::{EthSpec, Hash256};
+
+/// 32-byte key for accessing the `PeerReputation`. All zero because `PeerReputation` has its own
+/// column.
+pub const PEER_REPUTATION_DB_KEY: Hash256 = Hash256::ZERO;
+
+pub fn load_peer_reputations<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+) -> Option<PersistedPeerReputations> {
+    store.get_item(&PEER_REPUTATION_DB_KEY).ok().flatten()
+}
+
+/// Attempt to persist the reputations of known peers to `store`.
+pub fn persist_peer_reputations<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
+    store: Arc<HotColdDB<E, Hot, Cold>>,
+    saved_at: u64,
+    reputations: Vec<PeerReputation>,
+) -> Result<(), store::Error> {
+    store.put_item(
+        &PEER_REPUTATION_DB_KEY,
+        &PersistedPeerReputations {
+            saved_at,
+            reputations,
+        },
+    )
+}
+
+/// Wrapper around the peer reputations for persistence to disk.
+#[derive(Debug, PartialEq)]
+pub struct PersistedPeerReputations {
+    /// The Unix timestamp in seconds at which the reputations were saved.
+    pub saved_at: u64,
+    pub reputations: Vec<PeerReputation>,
+}
+
+/// The SSZ encoding of `PersistedPeerReputations`.
+#[derive(Encode, Decode)]
+struct SszPersistedPeerReputations {
+    saved_at: u64,
+    reputations: Vec<SszPeerReputation>,
+}
+
+/// The SSZ encoding of a `PeerReputation`. The client is stored by name, so that its encoding does
+/// not depend on the order of the variants of `ClientKind`.
+#[derive(Encode, Decode)]
+struct SszPeerReputation {
+    peer_id: Vec<u8>,
+    /// The bits of the `f64` score.
+    score: u64,
+    banned_until: Option<u64>,
+    client: Vec<u8>,
+    sync_requests_completed: u64,
+    sync_requests_failed: u64,
+    last_seen: u64,
+}
+
+impl From<&PeerReputation> for SszPeerReputation {
+    fn from(reputation: &PeerReputation) -> Self {
+        Self {
+            peer_id: reputation.peer_id.as_bytes().to_vec(),
+            score: reputation.score.to_bits(),
+            banned_until: reputation.banned_until,
+            client: reputation.client.as_ref().as_bytes().to_vec(),
+            sync_requests_completed: reputation.sync_requests.completed,
+            sync_requests_failed: reputation.sync_requests.failed,
+            last_seen: reputation.last_seen,
+        }
+    }
+}
+
+impl TryFrom<SszPeerReputation> for PeerReputation {
+    type Error = StoreError;
+
+    fn try_from(reputation: SszPeerReputation) -> Result<Self, StoreError> {
+        let client = ClientKind::iter()
+            .find(|client| client.as_ref().as_bytes() == reputation.client)
+            .unwrap_or(ClientKind::Unknown);
+        Ok(Self {
+            peer_id: String::from_utf8(reputation.peer_id).map_err(|_| StoreError::InvalidBytes)?,
+            score: f64::from_bits(reputation.score),
+            banned_until: reputation.banned_until,
+            client,
+            sync_requests: SyncRequests {
+                completed: reputation.sync_requests_completed,
+                failed: reputation.sync_requests_failed,
+            },
+            last_seen: reputation.last_seen,
+        })
+    }
+}
+
+impl StoreItem for PersistedPeerReputations {
+    fn db_column() -> DBColumn {
+        DBColumn::PeerReputation
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        SszPersistedPeerReputations {
+            saved_at: self.saved_at,
+            reputations: self.reputations.iter().map(Into::into).collect(),
+        }
+        .as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        let persisted = SszPersistedPeerReputations::from_ssz_bytes(bytes)?;
+        Ok(Self {
+            saved_at: persisted.saved_at,
+            reputations: persisted
+                .reputations
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighthouse_network::PeerId;
+    use sloggers::{null::NullLoggerBuilder, Build};
+    use store::config::StoreConfig;
+    use store::MemoryStore;
+    use types::{ChainSpec, MinimalEthSpec};
+
+    #[test]
+    fn test_persisted_peer_reputations() {
+        let log = NullLoggerBuilder.build().unwrap();
+        let store: HotColdDB<
+            MinimalEthSpec,
+            MemoryStore<MinimalEthSpec>,
+            MemoryStore<MinimalEthSpec>,
+        > = HotColdDB::open_ephemeral(StoreConfig::default(), ChainSpec::minimal().into(), log)
+            .unwrap();
+        let store = Arc::new(store);
+        assert_eq!(load_peer_reputations(store.clone()), None);
+
+        let reputations = vec![PeerReputation {
+            peer_id: PeerId::random().to_string(),
+            score: -42.5,
+            banned_until: Some(1_700_000_100),
+            client: ClientKind::Teku,
+            sync_requests: SyncRequests {
+                completed: 7,
+                failed: 2,
+            },
+            last_seen: 1_700_000_000,
+        }];
+        persist_peer_reputations(store.clone(), 1_700_000_050, reputations.clone()).unwrap();
+        assert_eq!(
+            load_peer_reputations(store),
+            Some(PersistedPeerReputations {
+                saved_at: 1_700_000_050,
+                reputations,
+            })
+        );
+    }
+}
@@ -1,7 +1,9 @@
 use crate::nat;
 use crate::network_beacon_processor::InvalidBlockStorage;
 use crate::persisted_dht::{clear_dht, load_dht, persist_dht};
+use crate::persisted_peers::{load_peer_reputations, persist_peer_reputations};
 use crate::router::{Router, RouterMessage};
+use crate::subnet_service::attestation_subnets::SubscriptionKind;
 use crate::subnet_service::SyncCommitteeService;
 use crate::{error, metrics};
 use crate::{
@@ -21,14 +23,16 @@ use lighthouse_network::{prometheus_client::registry::Registry, MessageAcceptanc
 use lighthouse_network::{
     rpc::{GoodbyeReason, RpcErrorResponse},
     Context, PeerAction, PeerRequestId, PubsubMessage, ReportSource, Response, Subnet,
+    SubnetDiscovery,
 };
 use lighthouse_network::{
     service::api_types::AppRequestId,
     types::{core_topics_to_subscribe, GossipEncoding, GossipTopic},
-    MessageId, NetworkEvent, NetworkGlobals, PeerId,
+    MessageId, Multiaddr, NetworkEvent, NetworkGlobals, PeerId,
 };
 use slog::{crit, debug, error, info, o, trace, warn};
 use std::collections::BTreeSet;
+use std::time::{SystemTime, UNIX_EPOCH};
 use std::{collections::HashSet, pin::Pin, sync::Arc, time::Duration};
 use store::HotColdDB;
 use strum::IntoStaticStr;
@@ -104,6 +108,17 @@ pub enum NetworkMessage<E: EthSpec> {
         reason: GoodbyeReason,
         source: ReportSource,
     },
+    /// Pins or unpins long-lived subscriptions to subnets, as requested via the HTTP API.
+    UpdatePinnedSubnets {
+        subscribe: Vec<Subnet>,
+        unsubscribe: Vec<Subnet>,
+    },
+    /// Sets the peers of a named peer group, as requested via the HTTP API. An empty list of
+    /// addresses removes the group.
+    SetPeerGroup {
+        name: String,
+        addresses: Vec<Multiaddr>,
+    },
 }
 
 /// Messages triggered by validators that may trigger a subscription to a subnet.
@@ -297,6 +312,24 @@ impl<T: BeaconChainTypes> NetworkService<T> {
             }
         }
 
+        // Restore the reputations of the peers known before the last shutdown, so that banned
+        // peers remain banned.
+        if let Some(persisted) =
+            load_peer_reputations::<T::EthSpec, T::HotStore, T::ColdStore>(store.clone())
+        {
+            let offline = Duration::from_secs(unix_now().saturating_sub(persisted.saved_at));
+            let restored = network_globals
+                .peers
+                .write()
+                .restore_reputations(persisted.reputations, offline);
+            debug!(
+                network_log,
+                "Restored peer reputations";
+                "peers" => restored,
+                "offline_secs" => offline.as_secs(),
+            );
+        }
+
         let invalid_block_storage = config
             .invalid_block_storage
             .clone()
@@ -682,6 +715,22 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 reason,
                 source,
             } => self.libp2p.goodbye_peer(&peer_id, reason, source),
+            NetworkMessage::UpdatePinnedSubnets {
+                subscribe,
+                unsubscribe,
+            } => {
+                for subnet in subscribe {
+                    self.pin_subnet(subnet);
+                }
+                for subnet in unsubscribe {
+                    self.unpin_subnet(subnet);
+                }
+            }
+            NetworkMessage::SetPeerGroup { name, addresses } => {
+                if let Err(e) = self.libp2p.set_peer_group(name, addresses) {
+                    warn!(self.log, "Could not update peer group"; "error" => e);
+                }
+            }
             NetworkMessage::SubscribeCoreTopics => {
                 if self.subscribed_core_topics() {
                     return;
@@ -881,6 +930,79 @@ impl<T: BeaconChainTypes> NetworkService<T> {
         }
     }
 
+    /// Subscribes to `subnet` and advertises it in our ENR until it is unpinned, regardless of the
+    /// validators' duties, and searches for peers on it.
+    fn pin_subnet(&mut self, subnet: Subnet) {
+        if !self.network_globals.pinned_subnets.write().insert(subnet) {
+            return;
+        }
+
+        self.libp2p.update_enr_subnet(subnet, true);
+        for fork_digest in self.required_gossip_fork_digests() {
+            let topic = GossipTopic::new(subnet.into(), GossipEncoding::default(), fork_digest);
+            if !self.libp2p.subscribe(topic.clone()) {
+                warn!(self.log, "Could not subscribe to topic"; "topic" => %topic);
+            }
+        }
+        // Like long-lived subscriptions, the peers found are not required for any duty.
+        self.libp2p.discover_subnet_peers(vec![SubnetDiscovery {
+            subnet,
+            min_ttl: None,
+        }]);
+        info!(self.log, "Pinned subnet subscription"; "subnet" => ?subnet);
+    }
+
+    /// Removes the pin on `subnet`, unsubscribing from it and removing it from our ENR unless
+    /// it is still required otherwise.
+    fn unpin_subnet(&mut self, subnet: Subnet) {
+        if !self.network_globals.pinned_subnets.write().remove(&subnet) {
+            return;
+        }
+
+        // Whether the subnet is still required by the validators' duties or the node's
+        // configuration, and whether it would be advertised in our ENR had it not been pinned.
+        let (subscribed, advertised) = match subnet {
+            Subnet::Attestation(subnet_id) => {
+                let long_lived = self.subscribe_all_subnets
+                    || self
+                        .attestation_service
+                        .is_subscribed(&subnet_id, SubscriptionKind::LongLived);
+                let short_lived = self
+                    .attestation_service
+                    .is_subscribed(&subnet_id, SubscriptionKind::ShortLived);
+                (long_lived || short_lived, long_lived)
+            }
+            Subnet::SyncCommittee(subnet_id) => {
+                let subscribed = self.subscribe_all_subnets
+                    || self.sync_committee_service.is_subscribed(&subnet_id);
+                (subscribed, subscribed)
+            }
+            Subnet::DataColumn(subnet_id) => {
+                let subscribed = self.subscribe_all_data_column_subnets
+                    || self.network_globals.sampling_subnets.contains(&subnet_id);
+                // Data column subnets are not advertised in the ENR.
+                (subscribed, true)
+            }
+        };
+
+        if !advertised {
+            self.libp2p.update_enr_subnet(subnet, false);
+        }
+        if !subscribed {
+            for fork_digest in self.required_gossip_fork_digests() {
+                let topic = GossipTopic::new(subnet.into(), GossipEncoding::default(), fork_digest);
+                self.libp2p.unsubscribe(topic);
+            }
+        }
+        info!(self.log, "Unpinned subnet subscription"; "subnet" => ?subnet);
+    }
+
+    /// Returns whether `subnet` has been pinned via the HTTP API, in which case the subnet
+    /// services must not unsubscribe from it or remove it from our ENR.
+    fn is_pinned(&self, subnet: &Subnet) -> bool {
+        self.network_globals.pinned_subnets.read().contains(subnet)
+    }
+
     fn on_attestation_service_msg(&mut self, msg: SubnetServiceMessage) {
         match msg {
             SubnetServiceMessage::Subscribe(subnet) => {
@@ -891,6 +1013,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 }
             }
             SubnetServiceMessage::Unsubscribe(subnet) => {
+                if self.is_pinned(&subnet) {
+                    return;
+                }
                 for fork_digest in self.required_gossip_fork_digests() {
                     let topic =
                         GossipTopic::new(subnet.into(), GossipEncoding::default(), fork_digest);
@@ -901,6 +1026,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 self.libp2p.update_enr_subnet(subnet, true);
             }
             SubnetServiceMessage::EnrRemove(subnet) => {
+                if self.is_pinned(&subnet) {
+                    return;
+                }
                 self.libp2p.update_enr_subnet(subnet, false);
             }
             SubnetServiceMessage::DiscoverPeers(subnets_to_discover) => {
@@ -919,6 +1047,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 }
             }
             SubnetServiceMessage::Unsubscribe(subnet) => {
+                if self.is_pinned(&subnet) {
+                    return;
+                }
                 for fork_digest in self.required_gossip_fork_digests() {
                     let topic =
                         GossipTopic::new(subnet.into(), GossipEncoding::default(), fork_digest);
@@ -929,6 +1060,9 @@ impl<T: BeaconChainTypes> NetworkService<T> {
                 self.libp2p.update_enr_subnet(subnet, true);
             }
             SubnetServiceMessage::EnrRemove(subnet) => {
+                if self.is_pinned(&subnet) {
+                    return;
+                }
                 self.libp2p.update_enr_subnet(subnet, false);
             }
             SubnetServiceMessage::DiscoverPeers(subnets_to_discover) => {
@@ -1038,6 +1172,35 @@ impl<T: BeaconChainTypes> Drop for NetworkService<T> {
                 "Saved DHT state";
             ),
         }
+
+        let reputations = self.network_globals.peers.read().reputations();
+        debug!(
+            self.log,
+            "Persisting peer reputations to store";
+            "Number of peers" => reputations.len(),
+        );
+        match persist_peer_reputations::<T::EthSpec, T::HotStore, T::ColdStore>(
+            self.store.clone(),
+            unix_now(),
+            reputations,
+        ) {
+            Err(e) => error!(
+                self.log,
+                "Failed to persist peer reputations on drop";
+                "error" => ?e
+            ),
+            Ok(_) => info!(
+                self.log,
+                "Saved peer reputations";
+            ),
+        }
         info!(self.log, "Network service shutdown");
     }
 }
+
+/// Returns the current Unix timestamp in seconds.
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
@@ -2,13 +2,14 @@
 #[cfg(test)]
 mod tests {
     use crate::persisted_dht::load_dht;
+    use crate::subnet_service::SubnetServiceMessage;
     use crate::{NetworkConfig, NetworkService};
-    use beacon_chain::test_utils::BeaconChainHarness;
-    use beacon_chain::BeaconChainTypes;
+    use beacon_chain::test_utils::{BeaconChainHarness, EphemeralHarnessType};
+    use beacon_chain::{BeaconChain, BeaconChainTypes};
     use beacon_processor::{BeaconProcessorChannels, BeaconProcessorConfig};
     use futures::StreamExt;
     use lighthouse_network::types::{GossipEncoding, GossipKind};
-    use lighthouse_network::{Enr, GossipTopic};
+    use lighthouse_network::{Enr, Eth2Enr, GossipTopic, NetworkGlobals, Subnet};
     use slog::{o, Drain, Level, Logger};
     use sloggers::{null::NullLoggerBuilder, Build};
     use std::str::FromStr;
@@ -22,6 +23,83 @@ mod tests {
         }
     }
 
+    /// Builds a network service which is not started, listening on `port` and `port + 1`.
+    fn build_network_service(
+        runtime: &Arc<Runtime>,
+        port: u16,
+    ) -> (
+        Arc<BeaconChain<EphemeralHarnessType<MinimalEthSpec>>>,
+        NetworkService<EphemeralHarnessType<MinimalEthSpec>>,
+        Arc<NetworkGlobals<MinimalEthSpec>>,
+    ) {
+        let beacon_chain = BeaconChainHarness::builder(MinimalEthSpec)
+            .default_spec()
+            .deterministic_keypairs(8)
+            .fresh_ephemeral_store()
+            .build()
+            .chain;
+
+        let (network_service, network_globals, _network_senders) = runtime.block_on(async {
+            let (_, exit) = async_channel::bounded(1);
+            let (shutdown_tx, _) = futures::channel::mpsc::channel(1);
+            let executor = task_executor::TaskExecutor::new(
+                Arc::downgrade(runtime),
+                exit,
+                get_logger(false),
+                shutdown_tx,
+            );
+
+            let mut config = NetworkConfig::default();
+            config.set_ipv4_listening_address(
+                std::net::Ipv4Addr::UNSPECIFIED,
+                port,
+                port,
+                port + 1,
+            );
+            config.discv5_config.table_filter = |_| true; // Do not ignore local IPs
+            config.upnp_enabled = false;
+            let config = Arc::new(config);
+
+            let beacon_processor_channels =
+                BeaconProcessorChannels::new(&BeaconProcessorConfig::default());
+            NetworkService::build(
+                beacon_chain.clone(),
+                config,
+                executor.clone(),
+                None,
+                beacon_processor_channels.beacon_processor_tx,
+                beacon_processor_channels.work_reprocessing_tx,
+            )
+            .await
+            .unwrap()
+        });
+
+        (beacon_chain, network_service, network_globals)
+    }
+
+    fn attestation_topic(
+        beacon_chain: &BeaconChain<EphemeralHarnessType<MinimalEthSpec>>,
+        subnet_id: SubnetId,
+    ) -> GossipTopic {
+        GossipTopic::new(
+            GossipKind::Attestation(subnet_id),
+            GossipEncoding::SSZSnappy,
+            beacon_chain.enr_fork_id().fork_digest,
+        )
+    }
+
+    fn is_advertised(
+        network_globals: &NetworkGlobals<MinimalEthSpec>,
+        subnet_id: SubnetId,
+    ) -> bool {
+        network_globals
+            .local_enr()
+            .attestation_bitfield::<MinimalEthSpec>()
+            .unwrap()
+            .get(*subnet_id as usize)
+            .unwrap()
+    }
+
     fn get_logger(actual_log: bool) -> Logger {
         if actual_log {
             let drain = {
@@ -235,4 +313,104 @@ mod tests {
             .expect("topic score params");
         assert_eq!(0.0, old_topic_params2.topic_weight);
     }
+
+    // Test that a pinned subnet is kept regardless of the subnet services.
+    #[test]
+    fn test_pin_subnet() {
+        let runtime = Arc::new(Runtime::new().unwrap());
+        let (beacon_chain, mut network_service, network_globals) =
+            build_network_service(&runtime, 21216);
+
+        // A subnet which is not one of our long-lived subnets.
+        let long_lived_subnets = SubnetId::compute_subnets_for_epoch::<MinimalEthSpec>(
+            network_globals.local_enr().node_id().raw(),
+            beacon_chain.epoch().unwrap(),
+            &beacon_chain.spec,
+        )
+        .unwrap()
+        .0
+        .collect::<Vec<_>>();
+        let subnet_id = (0..beacon_chain.spec.attestation_subnet_count)
+            .map(SubnetId::new)
+            .find(|subnet_id| !long_lived_subnets.contains(subnet_id))
+            .unwrap();
+        let subnet = Subnet::Attestation(subnet_id);
+        let topic = attestation_topic(&beacon_chain, subnet_id);
+
+        runtime.block_on(async {
+            network_service.pin_subnet(subnet);
+        });
+        assert!(network_globals.pinned_subnets.read().contains(&subnet));
+        assert!(network_globals
+            .gossipsub_subscriptions
+            .read()
+            .contains(&topic));
+        assert!(is_advertised(&network_globals, subnet_id));
+
+        // The subnet services can't unsubscribe from a pinned subnet or remove it from the ENR.
+        runtime.block_on(async {
+            network_service.on_attestation_service_msg(SubnetServiceMessage::Unsubscribe(subnet));
+            network_service.on_attestation_service_msg(SubnetServiceMessage::EnrRemove(subnet));
+        });
+        assert!(network_globals
+            .gossipsub_subscriptions
+            .read()
+            .contains(&topic));
+        assert!(is_advertised(&network_globals, subnet_id));
+
+        // Without any duties, unpinning unsubscribes from the subnet and removes it from the ENR.
+        runtime.block_on(async {
+            network_service.unpin_subnet(subnet);
+        });
+        assert!(network_globals.pinned_subnets.read().is_empty());
+        assert!(!network_globals
+            .gossipsub_subscriptions
+            .read()
+            .contains(&topic));
+        assert!(!is_advertised(&network_globals, subnet_id));
+    }
+
+    // Test that unpinning a subnet keeps the subscriptions required by the subnet services.
+    #[test]
+    fn test_unpin_subnet_keeps_duty_subscriptions() {
+        let runtime = Arc::new(Runtime::new().unwrap());
+        let (beacon_chain, mut network_service, network_globals) =
+            build_network_service(&runtime, 21218);
+
+        // Subscribe to the long-lived subnets.
+        runtime.block_on(async {
+            while network_globals.gossipsub_subscriptions.read().len() < 2 {
+                if let Some(msg) = network_service.attestation_service.next().await {
+                    network_service.on_attestation_service_msg(msg);
+                }
+            }
+        });
+        let subnet_id = SubnetId::compute_subnets_for_epoch::<MinimalEthSpec>(
+            network_globals.local_enr().node_id().raw(),
+            beacon_chain.epoch().unwrap(),
+            &beacon_chain.spec,
+        )
+        .unwrap()
+        .0
+        .next()
+        .unwrap();
+        let subnet = Subnet::Attestation(subnet_id);
+        let topic = attestation_topic(&beacon_chain, subnet_id);
+        assert!(network_globals
+            .gossipsub_subscriptions
+            .read()
+            .contains(&topic));
+        assert!(is_advertised(&network_globals, subnet_id));
+
+        runtime.block_on(async {
+            network_service.pin_subnet(subnet);
+            network_service.unpin_subnet(subnet);
+        });
+        assert!(network_globals.pinned_subnets.read().is_empty());
+        assert!(network_globals
+            .gossipsub_subscriptions
+            .read()
+            .contains(&topic));
+        assert!(is_advertised(&network_globals, subnet_id));
+    }
 }
@@ -170,8 +170,7 @@ impl<T: BeaconChainTypes> AttestationService<T> {
         }
     }
 
-    /// Returns whether we are subscribed to a subnet for testing purposes.
-    #[cfg(test)]
+    /// Returns whether we are subscribed to a subnet with the given kind of subscription.
     pub(crate) fn is_subscribed(
         &self,
         subnet_id: &SubnetId,
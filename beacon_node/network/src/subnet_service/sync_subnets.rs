@@ -90,6 +90,11 @@ impl<T: BeaconChainTypes> SyncCommitteeService<T> {
         }
     }
 
+    /// Returns whether a validator's duties require us to be subscribed to the subnet.
+    pub(crate) fn is_subscribed(&self, subnet_id: &SyncSubnetId) -> bool {
+        self.subscriptions.contains_key(subnet_id)
+    }
+
     /// Return count of all currently subscribed subnets.
     #[cfg(test)]
     pub fn subscription_count(&self) -> usize {
@@ -52,6 +52,18 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("restore-from-snapshot")
+                .long("restore-from-snapshot")
+                .value_name("DIR")
+                .help("Restore the database from a snapshot taken with the \
+                       `/lighthouse/database/snapshot` HTTP API before starting. The snapshot's \
+                       schema version is checked first. The database directories must be empty, \
+                       unless they were already restored from a snapshot, in which case the \
+                       restore is skipped.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         /*
          * Network parameters.
          */
@@ -385,6 +397,19 @@ pub fn cli_app() -> Command {
                 .display_order(0)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("gossip-bandwidth-caps")
+                .long("gossip-bandwidth-caps")
+                .value_name("TOPIC_KIND=MIB")
+                .help("One or more comma-delimited soft caps on the MiB of gossip received per hour \
+                       on a topic kind, e.g. data_column_sidecar=4096,blob_sidecar=2048. Once a \
+                       topic kind exceeds its cap, its topics are meshed with fewer peers until \
+                       the hour is over, so that fewer duplicate copies of each message are \
+                       received. Every message is still received, processed and forwarded, and no \
+                       subnets are unsubscribed from. Messages may arrive later while capped.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("genesis-backfill")
                 .long("genesis-backfill")
@@ -773,6 +798,63 @@ pub fn cli_app() -> Command {
                 .action(ArgAction::Set)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("hierarchy-exponents")
+                .long("hierarchy-exponents")
+                .value_name("EXPONENTS")
+                .help("Enable hierarchical state diffs in the freezer database, using the given \
+                       comma-separated list of strictly increasing exponents. A state is stored \
+                       every 2^N slots for each exponent N, with the largest exponent storing \
+                       full snapshots and smaller exponents storing diffs. Cannot be changed \
+                       after initialization without running `lighthouse db migrate`. \
+                       [example: 5,9,11,13,16,18,21]")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("hdiff-buffer-cache-size")
+                .long("hdiff-buffer-cache-size")
+                .value_name("SIZE")
+                .help("Specifies how many hierarchical state diff buffers from the freezer \
+                       database should be cached in memory")
+                .default_value("16")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("historic-state-retention-epochs")
+                .long("historic-state-retention-epochs")
+                .value_name("EPOCHS")
+                .help("Prune historic states from the freezer database, retaining all states \
+                       from the given number of epochs prior to finalization. Older restore \
+                       points are deleted unless retained by \
+                       --historic-state-retention-interval. Cannot be used with hierarchical \
+                       state diffs or state reconstruction.")
+                .conflicts_with_all(["hierarchy-exponents", "reconstruct-historic-states"])
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("historic-state-retention-interval")
+                .long("historic-state-retention-interval")
+                .value_name("N")
+                .help("When pruning historic states, also retain every Nth restore point prior \
+                       to the retention window [default: 0]")
+                .requires("historic-state-retention-epochs")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
+        .arg(
+            Arg::new("beacon-node-backend")
+                .long("beacon-node-backend")
+                .value_name("DATABASE")
+                .value_parser(store::DatabaseBackend::available())
+                .help("Set the database backend to be used by the beacon node. Cannot be changed \
+                       after initialization. The redb backend is only available in builds with \
+                       the `beacon-node-redb` feature.")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("state-cache-size")
                 .long("state-cache-size")
@@ -929,6 +1011,16 @@ pub fn cli_app() -> Command {
                 .help("If present, the chain database will be deleted without confirmation. Use with caution.")
                 .display_order(0)
         )
+        .arg(
+            Arg::new("database-compression")
+                .long("database-compression")
+                .action(ArgAction::SetTrue)
+                .help_heading(FLAG_HEADER)
+                .help("If present, compress blocks and freezer restore points with zstd. Values \
+                       written without compression remain readable, and vice versa. Use \
+                       `lighthouse db compress` to compress an existing database.")
+                .display_order(0)
+        )
         .arg(
             Arg::new("compact-db")
                 .long("compact-db")
@@ -1220,6 +1312,18 @@ pub fn cli_app() -> Command {
                 .help_heading(FLAG_HEADER)
                 .display_order(0)
         )
+        .arg(
+            Arg::new("reconstruction-threads")
+                .long("reconstruction-threads")
+                .value_name("N")
+                .help("Number of threads to use for historic state reconstruction. Blocks are \
+                       replayed on a single thread, while the remaining threads encode restore \
+                       points in parallel.")
+                .default_value("1")
+                .requires("reconstruct-historic-states")
+                .action(ArgAction::Set)
+                .display_order(0)
+        )
         .arg(
             Arg::new("validator-monitor-auto")
                 .long("validator-monitor-auto")
@@ -1485,6 +1589,18 @@ pub fn cli_app() -> Command {
                     filling up their disks.")
             .display_order(0)
         )
+        .arg(
+            Arg::new("gossipsub-trace-dir")
+            .action(ArgAction::Set)
+            .long("gossipsub-trace-dir")
+            .value_name("DIR")
+            .help("Record gossipsub events (published, delivered, duplicate and rejected \
+                    messages, GRAFT/PRUNE and IHAVE/IWANT/IDONTWANT control messages) to files \
+                    in this directory, in the format of the libp2p pubsub tracer. Up to 8 files \
+                    of 128 MiB are kept, with the oldest deleted first. This feature is only \
+                    recommended for developers.")
+            .display_order(0)
+        )
         .arg(
             Arg::new("beacon-processor-max-workers")
                 .long("beacon-processor-max-workers")
@@ -5,6 +5,7 @@ use beacon_chain::chain_config::{
     DEFAULT_RE_ORG_PARENT_THRESHOLD,
 };
 use beacon_chain::graffiti_calculator::GraffitiOrigin;
+use beacon_chain::store::StateRetention;
 use beacon_chain::TrustedSetup;
 use clap::{parser::ValueSource, ArgMatches, Id};
 use clap_utils::flags::DISABLE_MALLOC_TUNING_FLAG;
@@ -15,6 +16,7 @@ use environment::RuntimeContext;
 use execution_layer::DEFAULT_JWT_FILE;
 use genesis::Eth1Endpoint;
 use http_api::TlsConfig;
+use lighthouse_network::types::bandwidth::TOPIC_KINDS;
 use lighthouse_network::ListenAddress;
 use lighthouse_network::{multiaddr::Protocol, Enr, Multiaddr, NetworkConfig, PeerIdSerialized};
 use sensitive_url::SensitiveUrl;
@@ -400,6 +402,10 @@ pub fn get_config<E: EthSpec>(
         client_config.blobs_db_path = Some(PathBuf::from(blobs_db_dir));
     }
 
+    if let Some(snapshot_dir) = cli_args.get_one::<String>("restore-from-snapshot") {
+        client_config.restore_from_snapshot = Some(PathBuf::from(snapshot_dir));
+    }
+
     let (sprp, sprp_explicit) = get_slots_per_restore_point::<E>(clap_utils::parse_optional(
         cli_args,
         "slots-per-restore-point",
@@ -426,7 +432,38 @@ pub fn get_config<E: EthSpec>(
             .map_err(|_| "historic-state-cache-size is not a valid integer".to_string())?;
     }
 
+    client_config.store.hierarchy_config =
+        clap_utils::parse_optional(cli_args, "hierarchy-exponents")?;
+
+    if let Some(reconstruction_threads) = cli_args.get_one::<String>("reconstruction-threads") {
+        client_config.store.reconstruction_threads = reconstruction_threads
+            .parse()
+            .map_err(|_| "reconstruction-threads is not a valid non-zero integer".to_string())?;
+    }
+
+    if let Some(hdiff_buffer_cache_size) = cli_args.get_one::<String>("hdiff-buffer-cache-size") {
+        client_config.store.hdiff_buffer_cache_size = hdiff_buffer_cache_size
+            .parse()
+            .map_err(|_| "hdiff-buffer-cache-size is not a valid integer".to_string())?;
+    }
+
+    if let Some(epochs) = clap_utils::parse_optional(cli_args, "historic-state-retention-epochs")? {
+        client_config.store.state_retention = Some(StateRetention {
+            epochs,
+            restore_point_interval: clap_utils::parse_optional(
+                cli_args,
+                "historic-state-retention-interval",
+            )?
+            .unwrap_or(0),
+        });
+    }
+
+    if let Some(backend) = clap_utils::parse_optional(cli_args, "beacon-node-backend")? {
+        client_config.store.backend = backend;
+    }
+
     client_config.store.compact_on_init = cli_args.get_flag("compact-db");
+    client_config.store.compression = cli_args.get_flag("database-compression");
     if let Some(compact_on_prune) = cli_args.get_one::<String>("auto-compact-db") {
         client_config.store.compact_on_prune = compact_on_prune
             .parse()
@@ -862,6 +899,10 @@ pub fn get_config<E: EthSpec>(
         client_config.network.invalid_block_storage = Some(path);
     }
 
+    if let Some(path) = clap_utils::parse_optional(cli_args, "gossipsub-trace-dir")? {
+        client_config.network.gossipsub_trace_dir = Some(path);
+    }
+
     if let Some(max_workers) = clap_utils::parse_optional(cli_args, "beacon-processor-max-workers")?
     {
         client_config.beacon_processor.max_workers = max_workers;
@@ -1211,6 +1252,27 @@ pub fn set_network_config(
         }
     }
 
+    if let Some(caps_str) = cli_args.get_one::<String>("gossip-bandwidth-caps") {
+        for cap in caps_str.split(',') {
+            let (kind, mib) = cap
+                .split_once('=')
+                .ok_or_else(|| format!("Invalid gossip bandwidth cap: {}", cap))?;
+            if !TOPIC_KINDS.contains(&kind) {
+                return Err(format!(
+                    "Invalid gossip topic kind: {}, expected one of {}",
+                    kind,
+                    TOPIC_KINDS.join(", ")
+                ));
+            }
+            let mib = mib
+                .parse::<u64>()
+                .map_err(|_| format!("Invalid gossip bandwidth cap: {}", cap))?;
+            config
+                .gossip_bandwidth_caps
+                .insert(kind.to_string(), mib.saturating_mul(1024 * 1024));
+        }
+    }
+
     if let Some(enr_udp_port_str) = cli_args.get_one::<String>("enr-udp-port") {
         config.enr_udp4_port = Some(
             enr_udp_port_str
@@ -2,7 +2,8 @@ mod cli;
 mod config;
 
 pub use beacon_chain;
-use beacon_chain::store::LevelDB;
+use beacon_chain::store::snapshot::{is_snapshot_restored, restore_snapshot};
+use beacon_chain::store::BeaconNodeBackend;
 use beacon_chain::{
     builder::Witness, eth1_chain::CachingEth1Backend, slot_clock::SystemTimeSlotClock,
 };
@@ -19,8 +20,15 @@ use std::sync::Arc;
 use types::{ChainSpec, Epoch, EthSpec, ForkName};
 
 /// A type-alias to the tighten the definition of a production-intended `Client`.
-pub type ProductionClient<E> =
-    Client<Witness<SystemTimeSlotClock, CachingEth1Backend<E>, E, LevelDB<E>, LevelDB<E>>>;
+pub type ProductionClient<E> = Client<
+    Witness<
+        SystemTimeSlotClock,
+        CachingEth1Backend<E>,
+        E,
+        BeaconNodeBackend<E>,
+        BeaconNodeBackend<E>,
+    >,
+>;
 
 /// The beacon node `Client` that will be used in production.
 ///
@@ -82,6 +90,33 @@ impl<E: EthSpec> ProductionBeaconNode<E> {
             );
         }
 
+        if let Some(snapshot_dir) = &client_config.restore_from_snapshot {
+            if is_snapshot_restored(&db_path) {
+                info!(
+                    log,
+                    "Skipping restore of database snapshot";
+                    "msg" => "the database has already been restored from a snapshot, the \
+                        --restore-from-snapshot flag can be removed",
+                    "path" => ?snapshot_dir,
+                );
+            } else {
+                let schema_version = restore_snapshot::<E>(
+                    snapshot_dir,
+                    &store_config,
+                    &db_path,
+                    &freezer_db_path,
+                    &blobs_db_path,
+                )
+                .map_err(|e| format!("Unable to restore database snapshot: {e:?}"))?;
+                info!(
+                    log,
+                    "Restored database from snapshot";
+                    "path" => ?snapshot_dir,
+                    "schema_version" => schema_version.as_u64(),
+                );
+            }
+        }
+
         let builder = ClientBuilder::new(context.eth_spec_instance.clone())
             .runtime_context(context)
             .chain_spec(spec.clone())
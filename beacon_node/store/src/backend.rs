@@ -0,0 +1,150 @@
+//! Runtime selection of the on-disk database backend used by the beacon node.
+use crate::config::DatabaseBackend;
+#[cfg(feature = "leveldb")]
+use crate::leveldb_store::LevelDB;
+#[cfg(feature = "redb")]
+use crate::redb_store::Redb;
+use crate::{
+    ColumnIter, ColumnKeyIter, DBColumn, Error, ItemStore, Key, KeyValueStore, KeyValueStoreOp,
+    RawEntryIter, RawKeyIter, StoreConfig,
+};
+use parking_lot::MutexGuard;
+use std::convert::Infallible;
+use std::marker::PhantomData;
+use std::path::Path;
+use types::EthSpec;
+
+/// Name of the file holding a redb database, within the database directory.
+pub const REDB_DATA_FILENAME: &str = "database.redb";
+
+/// Name of a file present in every LevelDB database directory.
+#[cfg(feature = "redb")]
+const LEVELDB_CURRENT_FILENAME: &str = "CURRENT";
+
+/// A disk-backed key-value store, using the backend selected in the `StoreConfig`.
+pub enum BeaconNodeBackend<E: EthSpec> {
+    #[cfg(feature = "leveldb")]
+    LevelDb(LevelDB<E>),
+    #[cfg(feature = "redb")]
+    Redb(Redb<E>),
+    /// Placeholder for builds without a backend, which can never be constructed.
+    Disabled(Infallible, PhantomData<E>),
+}
+
+impl<E: EthSpec> BeaconNodeBackend<E> {
+    /// Open the database at `path` using `config.backend`.
+    pub fn open(config: &StoreConfig, path: &Path) -> Result<Self, Error> {
+        match config.backend {
+            #[cfg(feature = "leveldb")]
+            DatabaseBackend::LevelDb => LevelDB::open(path).map(Self::LevelDb),
+            #[cfg(feature = "redb")]
+            DatabaseBackend::Redb => Redb::open(path).map(Self::Redb),
+            DatabaseBackend::Disabled => Err(Error::DatabaseBackendDisabled),
+        }
+    }
+
+    /// The backend of this database.
+    pub fn backend(&self) -> DatabaseBackend {
+        match self {
+            #[cfg(feature = "leveldb")]
+            Self::LevelDb(_) => DatabaseBackend::LevelDb,
+            #[cfg(feature = "redb")]
+            Self::Redb(_) => DatabaseBackend::Redb,
+            Self::Disabled(never, _) => match *never {},
+        }
+    }
+}
+
+/// Dispatch a method call to the underlying database.
+macro_rules! dispatch {
+    ($self:ident, $db:ident => $call:expr) => {
+        match $self {
+            #[cfg(feature = "leveldb")]
+            BeaconNodeBackend::LevelDb($db) => $call,
+            #[cfg(feature = "redb")]
+            BeaconNodeBackend::Redb($db) => $call,
+            BeaconNodeBackend::Disabled(never, _) => match *never {},
+        }
+    };
+}
+
+impl<E: EthSpec> KeyValueStore<E> for BeaconNodeBackend<E> {
+    fn get_bytes(&self, column: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        dispatch!(self, db => KeyValueStore::<E>::get_bytes(db, column, key))
+    }
+
+    fn put_bytes(&self, column: &str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::put_bytes(db, column, key, value))
+    }
+
+    fn put_bytes_sync(&self, column: &str, key: &[u8], value: &[u8]) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::put_bytes_sync(db, column, key, value))
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::sync(db))
+    }
+
+    fn key_exists(&self, column: &str, key: &[u8]) -> Result<bool, Error> {
+        dispatch!(self, db => KeyValueStore::<E>::key_exists(db, column, key))
+    }
+
+    fn key_delete(&self, column: &str, key: &[u8]) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::key_delete(db, column, key))
+    }
+
+    fn do_atomically(&self, batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::do_atomically(db, batch))
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        dispatch!(self, db => KeyValueStore::<E>::begin_rw_transaction(db))
+    }
+
+    fn compact_column(&self, column: DBColumn) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::compact_column(db, column))
+    }
+
+    fn compact(&self) -> Result<(), Error> {
+        dispatch!(self, db => KeyValueStore::<E>::compact(db))
+    }
+
+    fn iter_column_from<K: Key>(&self, column: DBColumn, from: &[u8]) -> ColumnIter<K> {
+        dispatch!(self, db => KeyValueStore::<E>::iter_column_from(db, column, from))
+    }
+
+    fn iter_raw_entries(&self, column: DBColumn, prefix: &[u8]) -> RawEntryIter {
+        dispatch!(self, db => KeyValueStore::<E>::iter_raw_entries(db, column, prefix))
+    }
+
+    fn iter_raw_keys(&self, column: DBColumn, prefix: &[u8]) -> RawKeyIter {
+        dispatch!(self, db => KeyValueStore::<E>::iter_raw_keys(db, column, prefix))
+    }
+
+    fn iter_column_keys<K: Key>(&self, column: DBColumn) -> ColumnKeyIter<K> {
+        dispatch!(self, db => KeyValueStore::<E>::iter_column_keys(db, column))
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for BeaconNodeBackend<E> {}
+
+/// Check that the database at `path` was not created by a backend other than `backend`.
+///
+/// Opening a database with the wrong backend would otherwise silently create a second, empty
+/// database alongside the existing one.
+pub(crate) fn check_database_backend(path: &Path, backend: DatabaseBackend) -> Result<(), Error> {
+    let other_backend_file = match backend {
+        #[cfg(feature = "leveldb")]
+        DatabaseBackend::LevelDb => REDB_DATA_FILENAME,
+        #[cfg(feature = "redb")]
+        DatabaseBackend::Redb => LEVELDB_CURRENT_FILENAME,
+        DatabaseBackend::Disabled => return Err(Error::DatabaseBackendDisabled),
+    };
+    if path.join(other_backend_file).exists() {
+        return Err(Error::MismatchedDatabaseBackend {
+            path: path.to_path_buf(),
+            configured: backend,
+        });
+    }
+    Ok(())
+}
@@ -0,0 +1,356 @@
+//! Optional zstd compression of blocks and freezer states.
+//!
+//! Compressed values are prefixed with a version byte followed by a zstd frame, which begins with
+//! the zstd magic number and carries a checksum. Values without this prefix, or which fail to
+//! decompress, are returned unchanged. This allows compressed and uncompressed values to be mixed
+//! within a single database, e.g. after compression is enabled on an existing node.
+//!
+//! The chunked vector columns (block roots, state roots, historical roots, historical summaries
+//! and randao mixes) are deliberately left uncompressed, as their values are hashes which zstd
+//! cannot shrink.
+use crate::hdiff::ZSTD_COMPRESSION_LEVEL;
+use crate::hot_cold_store::HotColdDB;
+use crate::metadata::COMPRESSION_DICTIONARY_KEY;
+use crate::{
+    get_key_for_col, DBColumn, Error as StoreError, ItemStore, KeyValueStore, KeyValueStoreOp,
+    StoreItem,
+};
+use slog::info;
+use std::borrow::Cow;
+use std::io::{Read, Write};
+use types::EthSpec;
+use zstd::dict::{DecoderDictionary, EncoderDictionary};
+
+/// Version byte of values compressed without a dictionary.
+pub const VERSION_ZSTD: u8 = 0x01;
+/// Version byte of values compressed with the database's trained dictionary.
+pub const VERSION_ZSTD_DICTIONARY: u8 = 0x02;
+
+/// Magic number at the start of every zstd frame.
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Maximum size of a trained dictionary, matching the zstd CLI default.
+pub const DICTIONARY_SIZE: usize = 112_640;
+/// Maximum number of values sampled to train a dictionary.
+pub const DICTIONARY_MAX_SAMPLES: usize = 10_000;
+/// Number of values to rewrite in each write batch when compressing an existing database.
+const COMPRESSION_BATCH_SIZE: usize = 1024;
+
+#[derive(Debug)]
+pub enum Error {
+    Compression(std::io::Error),
+    Training(std::io::Error),
+    /// A value was compressed with a dictionary, but no dictionary is stored in the database.
+    MissingDictionary,
+    /// A dictionary has already been trained. Replacing it would make existing values unreadable.
+    DictionaryExists,
+}
+
+/// A zstd dictionary trained on the values of the database.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressionDictionary(pub Vec<u8>);
+
+impl CompressionDictionary {
+    /// Train a dictionary from sample values.
+    pub fn train<S: AsRef<[u8]>>(samples: &[S]) -> Result<Self, Error> {
+        zstd::dict::from_samples(samples, DICTIONARY_SIZE)
+            .map(Self)
+            .map_err(Error::Training)
+    }
+}
+
+impl StoreItem for CompressionDictionary {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.0.clone()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, StoreError> {
+        Ok(Self(bytes.to_vec()))
+    }
+}
+
+/// Compresses values on write (if enabled) and decompresses them on read.
+pub struct ValueCompressor {
+    enabled: bool,
+    dictionary: Option<(EncoderDictionary<'static>, DecoderDictionary<'static>)>,
+}
+
+impl ValueCompressor {
+    pub fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            dictionary: None,
+        }
+    }
+
+    pub fn set_dictionary(&mut self, dictionary: &CompressionDictionary) {
+        self.dictionary = Some((
+            EncoderDictionary::copy(&dictionary.0, ZSTD_COMPRESSION_LEVEL),
+            DecoderDictionary::copy(&dictionary.0),
+        ));
+    }
+
+    /// Compress `bytes`, or return them unchanged if compression is disabled or ineffective.
+    pub fn compress(&self, bytes: Vec<u8>) -> Vec<u8> {
+        if !self.enabled {
+            return bytes;
+        }
+        match self.try_compress(&bytes) {
+            Ok(compressed) if compressed.len() < bytes.len() => compressed,
+            _ => bytes,
+        }
+    }
+
+    /// Compress `bytes` regardless of whether compression is enabled.
+    pub fn try_compress(&self, bytes: &[u8]) -> Result<Vec<u8>, Error> {
+        let (version, encoder) = match &self.dictionary {
+            Some((dictionary, _)) => (
+                VERSION_ZSTD_DICTIONARY,
+                zstd::stream::Encoder::with_prepared_dictionary(vec![], dictionary),
+            ),
+            None => (
+                VERSION_ZSTD,
+                zstd::stream::Encoder::new(vec![], ZSTD_COMPRESSION_LEVEL),
+            ),
+        };
+        let mut encoder = encoder.map_err(Error::Compression)?;
+        encoder.include_checksum(true).map_err(Error::Compression)?;
+        encoder.write_all(bytes).map_err(Error::Compression)?;
+
+        let mut output = vec![version];
+        output.extend(encoder.finish().map_err(Error::Compression)?);
+        Ok(output)
+    }
+
+    /// Decompress `bytes` if they were written by `compress`.
+    pub fn decompress<'a>(&self, bytes: &'a [u8]) -> Result<Cow<'a, [u8]>, Error> {
+        let Some((&version, frame)) = bytes.split_first() else {
+            return Ok(Cow::Borrowed(bytes));
+        };
+        if !frame.starts_with(&ZSTD_MAGIC) {
+            return Ok(Cow::Borrowed(bytes));
+        }
+
+        let mut output = vec![];
+        let result = match version {
+            VERSION_ZSTD => zstd::stream::Decoder::new(frame)
+                .and_then(|mut decoder| decoder.read_to_end(&mut output)),
+            VERSION_ZSTD_DICTIONARY => {
+                let (_, dictionary) = self.dictionary.as_ref().ok_or(Error::MissingDictionary)?;
+                zstd::stream::Decoder::with_prepared_dictionary(frame, dictionary)
+                    .and_then(|mut decoder| decoder.read_to_end(&mut output))
+            }
+            _ => return Ok(Cow::Borrowed(bytes)),
+        };
+
+        // An uncompressed value may coincidentally begin with a valid header, in which case the
+        // frame checksum will not match.
+        match result {
+            Ok(_) => Ok(Cow::Owned(output)),
+            Err(_) => Ok(Cow::Borrowed(bytes)),
+        }
+    }
+}
+
+/// The result of compressing the existing values of a database.
+#[derive(Debug, Default)]
+pub struct CompressionReport {
+    /// Number of values which were rewritten in compressed form.
+    pub compressed: usize,
+    /// Total size of the rewritten values before compression.
+    pub bytes_before: u64,
+    /// Total size of the rewritten values after compression.
+    pub bytes_after: u64,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Train a compression dictionary on a sample of the blocks in the database and store it.
+    ///
+    /// The dictionary can't be replaced once it has been used, so this errors if a dictionary
+    /// already exists. Returns the number of blocks sampled.
+    pub fn train_compression_dictionary(&self) -> Result<usize, StoreError> {
+        if self.load_compression_dictionary()?.is_some() {
+            return Err(Error::DictionaryExists.into());
+        }
+
+        let samples = {
+            let compressor = self.compressor.read();
+            self.hot_db
+                .iter_raw_entries(DBColumn::BeaconBlock, &[])
+                .take(DICTIONARY_MAX_SAMPLES)
+                .map(|res| -> Result<_, StoreError> {
+                    let (_, value) = res?;
+                    Ok(compressor.decompress(&value)?.into_owned())
+                })
+                .collect::<Result<Vec<_>, StoreError>>()?
+        };
+
+        let dictionary = CompressionDictionary::train(&samples)?;
+        self.hot_db.put(&COMPRESSION_DICTIONARY_KEY, &dictionary)?;
+        self.compressor.write().set_dictionary(&dictionary);
+
+        info!(
+            self.log,
+            "Trained compression dictionary";
+            "samples" => samples.len(),
+            "dictionary_size" => dictionary.0.len(),
+        );
+        Ok(samples.len())
+    }
+
+    /// Compress every uncompressed block and restore point state in the database.
+    ///
+    /// Values are only rewritten if compression makes them smaller. This function should only
+    /// be used while the beacon node is stopped.
+    pub fn compress_existing_values(&self) -> Result<CompressionReport, StoreError> {
+        let mut report = CompressionReport::default();
+        compress_column(
+            &self.hot_db,
+            DBColumn::BeaconBlock,
+            &self.compressor.read(),
+            &mut report,
+        )?;
+        compress_column(
+            &self.cold_db,
+            DBColumn::BeaconState,
+            &self.compressor.read(),
+            &mut report,
+        )?;
+
+        info!(
+            self.log,
+            "Compressed existing database values";
+            "compressed" => report.compressed,
+            "bytes_before" => report.bytes_before,
+            "bytes_after" => report.bytes_after,
+        );
+        Ok(report)
+    }
+}
+
+/// Rewrite the uncompressed values of `column` in compressed form.
+fn compress_column<E: EthSpec, S: KeyValueStore<E>>(
+    db: &S,
+    column: DBColumn,
+    compressor: &ValueCompressor,
+    report: &mut CompressionReport,
+) -> Result<(), StoreError> {
+    let mut ops = vec![];
+    for res in db.iter_raw_entries(column, &[]) {
+        let (key, value) = res?;
+        if matches!(compressor.decompress(&value)?, Cow::Owned(_)) {
+            continue;
+        }
+        let compressed = compressor.try_compress(&value)?;
+        if compressed.len() >= value.len() {
+            continue;
+        }
+
+        report.compressed += 1;
+        report.bytes_before += value.len() as u64;
+        report.bytes_after += compressed.len() as u64;
+        ops.push(KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(column.into(), &key),
+            compressed,
+        ));
+
+        if ops.len() >= COMPRESSION_BATCH_SIZE {
+            db.do_atomically(std::mem::take(&mut ops))?;
+        }
+    }
+    db.do_atomically(ops)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values sharing a common structure, each with some unique pseudo-random content.
+    fn sample_values() -> Vec<Vec<u8>> {
+        let mut seed = 0x5eed_u64;
+        (0..2000u64)
+            .map(|i| {
+                let mut value = b"signed beacon block with a shared structure; ".repeat(4);
+                value.extend_from_slice(&i.to_le_bytes());
+                for _ in 0..4 {
+                    seed = seed
+                        .wrapping_mul(6364136223846793005)
+                        .wrapping_add(1442695040888963407);
+                    value.extend_from_slice(&seed.to_le_bytes());
+                }
+                value
+            })
+            .collect()
+    }
+
+    #[test]
+    fn round_trip_without_dictionary() {
+        let compressor = ValueCompressor::new(true);
+        let value = vec![42u8; 4096];
+        let compressed = compressor.compress(value.clone());
+        assert_eq!(compressed[0], VERSION_ZSTD);
+        assert!(compressed.len() < value.len());
+        assert_eq!(
+            compressor.decompress(&compressed).unwrap(),
+            value.as_slice()
+        );
+    }
+
+    #[test]
+    fn round_trip_with_dictionary() {
+        let samples = sample_values();
+        let dictionary = CompressionDictionary::train(&samples).unwrap();
+        let mut compressor = ValueCompressor::new(true);
+        compressor.set_dictionary(&dictionary);
+
+        let value = samples[500].clone();
+        let compressed = compressor.compress(value.clone());
+        assert_eq!(compressed[0], VERSION_ZSTD_DICTIONARY);
+        assert_eq!(
+            compressor.decompress(&compressed).unwrap(),
+            value.as_slice()
+        );
+
+        // The dictionary is required to read the value.
+        assert!(matches!(
+            ValueCompressor::new(true).decompress(&compressed),
+            Err(Error::MissingDictionary)
+        ));
+    }
+
+    #[test]
+    fn uncompressed_values_unchanged() {
+        let compressor = ValueCompressor::new(false);
+        let value = vec![42u8; 4096];
+        assert_eq!(compressor.compress(value.clone()), value);
+        assert!(matches!(
+            compressor.decompress(&value).unwrap(),
+            Cow::Borrowed(_)
+        ));
+
+        // A raw value with a compression header but an invalid frame is returned as-is.
+        let mut lookalike = vec![VERSION_ZSTD];
+        lookalike.extend_from_slice(&ZSTD_MAGIC);
+        lookalike.extend_from_slice(&[0u8; 32]);
+        assert_eq!(
+            compressor.decompress(&lookalike).unwrap(),
+            lookalike.as_slice()
+        );
+    }
+
+    #[test]
+    fn incompressible_values_unchanged() {
+        let compressor = ValueCompressor::new(true);
+        let value = vec![7u8];
+        assert_eq!(compressor.compress(value.clone()), value);
+    }
+}
@@ -1,8 +1,10 @@
+use crate::hdiff::HierarchyConfig;
 use crate::{DBColumn, Error, StoreItem};
 use serde::{Deserialize, Serialize};
 use ssz::{Decode, Encode};
 use ssz_derive::{Decode, Encode};
 use std::num::NonZeroUsize;
+use strum::{Display, EnumString, EnumVariantNames, VariantNames};
 use types::non_zero_usize::new_non_zero_usize;
 use types::{EthSpec, MinimalEthSpec};
 
@@ -11,8 +13,17 @@ pub const DEFAULT_SLOTS_PER_RESTORE_POINT: u64 = 8192;
 pub const DEFAULT_BLOCK_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(5);
 pub const DEFAULT_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(128);
 pub const DEFAULT_HISTORIC_STATE_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(1);
+pub const DEFAULT_HDIFF_BUFFER_CACHE_SIZE: NonZeroUsize = new_non_zero_usize(16);
 pub const DEFAULT_EPOCHS_PER_BLOB_PRUNE: u64 = 1;
 pub const DEFAULT_BLOB_PUNE_MARGIN_EPOCHS: u64 = 0;
+pub const DEFAULT_RECONSTRUCTION_THREADS: NonZeroUsize = new_non_zero_usize(1);
+
+#[cfg(feature = "leveldb")]
+pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::LevelDb;
+#[cfg(all(feature = "redb", not(feature = "leveldb")))]
+pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Redb;
+#[cfg(not(any(feature = "leveldb", feature = "redb")))]
+pub const DEFAULT_BACKEND: DatabaseBackend = DatabaseBackend::Disabled;
 
 /// Database configuration parameters.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -27,6 +38,12 @@ pub struct StoreConfig {
     pub state_cache_size: NonZeroUsize,
     /// Maximum number of states from freezer database to store in the in-memory state cache.
     pub historic_state_cache_size: NonZeroUsize,
+    /// Layout of hierarchical state diffs in the freezer database.
+    ///
+    /// If `None`, the freezer stores full restore points every `slots_per_restore_point` slots.
+    pub hierarchy_config: Option<HierarchyConfig>,
+    /// Maximum number of hierarchical diff buffers to keep in memory.
+    pub hdiff_buffer_cache_size: NonZeroUsize,
     /// Whether to compact the database on initialization.
     pub compact_on_init: bool,
     /// Whether to compact the database during database pruning.
@@ -40,17 +57,97 @@ pub struct StoreConfig {
     /// The margin for blob pruning in epochs. The oldest blobs are pruned up until
     /// data_availability_boundary - blob_prune_margin_epochs. Default: 0.
     pub blob_prune_margin_epochs: u64,
+    /// Key-value store implementation used for the hot, cold and blobs databases.
+    pub backend: DatabaseBackend,
+    /// Policy for pruning historic states from the freezer database.
+    ///
+    /// If `None`, all historic states are retained.
+    pub state_retention: Option<StateRetention>,
+    /// Whether to compress blocks and freezer states with zstd.
+    ///
+    /// Values written while compression was disabled remain readable, and vice versa. The chunked
+    /// vector columns of the freezer are never compressed.
+    pub compression: bool,
+    /// Number of threads used for historic state reconstruction.
+    ///
+    /// Blocks are always replayed on a single thread. Additional threads encode and compress
+    /// restore points in parallel with block replay.
+    pub reconstruction_threads: NonZeroUsize,
+}
+
+/// Retention policy for historic states in the freezer database.
+///
+/// All states from the last `epochs` epochs prior to the split are retained. Older restore points
+/// are pruned, except for every `restore_point_interval`th restore point and the genesis state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StateRetention {
+    pub epochs: u64,
+    /// Interval between the restore points retained beyond the window. `0` retains none.
+    pub restore_point_interval: u64,
+}
+
+impl StateRetention {
+    /// Whether the restore point with the given index is retained beyond the retention window.
+    pub fn retains_restore_point(&self, restore_point_index: u64) -> bool {
+        restore_point_index == 0
+            || (self.restore_point_interval > 0
+                && restore_point_index % self.restore_point_interval == 0)
+    }
+}
+
+/// Key-value store implementations available to the beacon node, subject to cargo features.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Display, EnumString, EnumVariantNames,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum DatabaseBackend {
+    #[cfg(feature = "leveldb")]
+    LevelDb,
+    #[cfg(feature = "redb")]
+    Redb,
+    /// Placeholder for builds without a backend, in which the beacon node can't open a database.
+    Disabled,
+}
+
+impl DatabaseBackend {
+    /// Returns the names of the backends compiled into this build, which can be selected.
+    pub fn available() -> Vec<&'static str> {
+        Self::VARIANTS
+            .iter()
+            .copied()
+            .filter(|name| *name != "disabled")
+            .collect()
+    }
 }
 
 /// Variant of `StoreConfig` that gets written to disk. Contains immutable configuration params.
-#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OnDiskStoreConfig {
     pub slots_per_restore_point: u64,
+    pub hierarchy_config: Option<HierarchyConfig>,
+}
+
+/// On-disk representation of an `OnDiskStoreConfig` using hierarchical state diffs.
+///
+/// Databases without a hierarchy config keep using the bare `u64` encoding so that they remain
+/// readable by older versions of Lighthouse.
+#[derive(Encode, Decode)]
+struct OnDiskStoreConfigV2 {
+    slots_per_restore_point: u64,
+    hierarchy_config: HierarchyConfig,
 }
 
 #[derive(Debug, Clone)]
 pub enum StoreConfigError {
-    MismatchedSlotsPerRestorePoint { config: u64, on_disk: u64 },
+    MismatchedSlotsPerRestorePoint {
+        config: u64,
+        on_disk: u64,
+    },
+    MismatchedHierarchyConfig {
+        config: Option<HierarchyConfig>,
+        on_disk: Option<HierarchyConfig>,
+    },
 }
 
 impl Default for StoreConfig {
@@ -62,12 +159,18 @@ impl Default for StoreConfig {
             block_cache_size: DEFAULT_BLOCK_CACHE_SIZE,
             state_cache_size: DEFAULT_STATE_CACHE_SIZE,
             historic_state_cache_size: DEFAULT_HISTORIC_STATE_CACHE_SIZE,
+            hierarchy_config: None,
+            hdiff_buffer_cache_size: DEFAULT_HDIFF_BUFFER_CACHE_SIZE,
             compact_on_init: false,
             compact_on_prune: true,
             prune_payloads: true,
             prune_blobs: true,
             epochs_per_blob_prune: DEFAULT_EPOCHS_PER_BLOB_PRUNE,
             blob_prune_margin_epochs: DEFAULT_BLOB_PUNE_MARGIN_EPOCHS,
+            backend: DEFAULT_BACKEND,
+            state_retention: None,
+            compression: false,
+            reconstruction_threads: DEFAULT_RECONSTRUCTION_THREADS,
         }
     }
 }
@@ -76,6 +179,7 @@ impl StoreConfig {
     pub fn as_disk_config(&self) -> OnDiskStoreConfig {
         OnDiskStoreConfig {
             slots_per_restore_point: self.slots_per_restore_point,
+            hierarchy_config: self.hierarchy_config.clone(),
         }
     }
 
@@ -89,6 +193,12 @@ impl StoreConfig {
                 on_disk: on_disk_config.slots_per_restore_point,
             });
         }
+        if self.hierarchy_config != on_disk_config.hierarchy_config {
+            return Err(StoreConfigError::MismatchedHierarchyConfig {
+                config: self.hierarchy_config.clone(),
+                on_disk: on_disk_config.hierarchy_config.clone(),
+            });
+        }
         Ok(())
     }
 }
@@ -99,10 +209,54 @@ impl StoreItem for OnDiskStoreConfig {
     }
 
     fn as_store_bytes(&self) -> Vec<u8> {
-        self.as_ssz_bytes()
+        match &self.hierarchy_config {
+            None => self.slots_per_restore_point.as_ssz_bytes(),
+            Some(hierarchy_config) => OnDiskStoreConfigV2 {
+                slots_per_restore_point: self.slots_per_restore_point,
+                hierarchy_config: hierarchy_config.clone(),
+            }
+            .as_ssz_bytes(),
+        }
     }
 
     fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
-        Ok(Self::from_ssz_bytes(bytes)?)
+        if bytes.len() == <u64 as Encode>::ssz_fixed_len() {
+            Ok(Self {
+                slots_per_restore_point: u64::from_ssz_bytes(bytes)?,
+                hierarchy_config: None,
+            })
+        } else {
+            let config = OnDiskStoreConfigV2::from_ssz_bytes(bytes)?;
+            Ok(Self {
+                slots_per_restore_point: config.slots_per_restore_point,
+                hierarchy_config: Some(config.hierarchy_config),
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn on_disk_config_legacy_encoding() {
+        let config = OnDiskStoreConfig {
+            slots_per_restore_point: 8192,
+            hierarchy_config: None,
+        };
+        let bytes = config.as_store_bytes();
+        assert_eq!(bytes, 8192u64.as_ssz_bytes());
+        assert_eq!(OnDiskStoreConfig::from_store_bytes(&bytes).unwrap(), config);
+    }
+
+    #[test]
+    fn on_disk_config_hierarchy_round_trip() {
+        let config = OnDiskStoreConfig {
+            slots_per_restore_point: 32,
+            hierarchy_config: Some(HierarchyConfig::default()),
+        };
+        let bytes = config.as_store_bytes();
+        assert_eq!(OnDiskStoreConfig::from_store_bytes(&bytes).unwrap(), config);
     }
 }
@@ -0,0 +1,520 @@
+//! Export and import of finalized history as era files.
+//!
+//! An era file holds the blocks of one `SLOTS_PER_HISTORICAL_ROOT` period followed by the state
+//! at the end of the period, stored in the e2store format:
+//!
+//! https://github.com/status-im/nimbus-eth2/blob/stable/docs/e2store.md
+//!
+//! Era `N` contains the blocks for slots `(N - 1) * SLOTS_PER_HISTORICAL_ROOT` up to (but not
+//! including) `N * SLOTS_PER_HISTORICAL_ROOT`, and the state at `N * SLOTS_PER_HISTORICAL_ROOT`.
+//! Era 0 contains only the genesis state.
+use crate::chunked_vector::BlockRoots;
+use crate::hot_cold_store::HotColdDB;
+use crate::{AnchorInfo, ChunkWriter, Error as StoreError, ItemStore};
+use slog::{debug, info};
+use ssz::{Decode, Encode};
+use state_processing::{
+    per_block_processing::ParallelSignatureSets,
+    signature_sets::{block_proposal_signature_set_from_parts, Error as SignatureSetError},
+};
+use std::borrow::Cow;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use tree_hash::TreeHash;
+use types::{
+    BeaconState, EthSpec, FixedBytesExtended, Hash256, HistoricalSummary, SignedBeaconBlock, Slot,
+};
+
+/// Size of the header preceding every e2store entry.
+const HEADER_SIZE: usize = 8;
+
+const VERSION: [u8; 2] = [0x65, 0x32];
+const COMPRESSED_SIGNED_BEACON_BLOCK: [u8; 2] = [0x01, 0x00];
+const COMPRESSED_BEACON_STATE: [u8; 2] = [0x02, 0x00];
+const SLOT_INDEX: [u8; 2] = [0x69, 0x32];
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// An entry header or its data extends past the end of the file.
+    TruncatedEntry {
+        offset: usize,
+    },
+    /// The reserved bytes of an entry header are non-zero.
+    InvalidEntryHeader {
+        offset: usize,
+    },
+    /// The file does not start with a version entry.
+    MissingVersion,
+    MissingState,
+    MultipleStates,
+    UnknownEntryType([u8; 2]),
+    /// The state in an era file is not at the end of an era.
+    InvalidEraSlot(Slot),
+    /// The state at the end of the era has not been migrated to the freezer yet.
+    EraNotFinalized {
+        era: u64,
+        split_slot: Slot,
+    },
+    MissingEraState(Slot),
+    MissingBlock {
+        slot: Slot,
+        block_root: Hash256,
+    },
+    /// A block is not part of the history described by the era state.
+    MismatchedBlockRoot {
+        slot: Slot,
+        block_root: Hash256,
+        expected_block_root: Hash256,
+    },
+    /// The blocks in the era file do not connect to the oldest block in the database.
+    MismatchedParentRoot {
+        block_root: Hash256,
+        expected_block_root: Hash256,
+    },
+    SignatureSet(SignatureSetError),
+    InvalidSignature,
+    /// Historic blocks can only be imported into a database that was checkpoint synced.
+    NoAnchorInfo,
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+/// Compute the name of the file for `era`, following the era file naming convention.
+///
+/// The state at the end of the era is used to compute the short historical root.
+pub fn era_file_name<E: EthSpec>(config_name: &str, era: u64, state: &BeaconState<E>) -> String {
+    let root = if era == 0 {
+        state.genesis_validators_root()
+    } else {
+        HistoricalSummary::new(state).tree_hash_root()
+    };
+    let short_root = root.as_slice()[..4]
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect::<String>();
+    format!("{config_name}-{era:05}-{short_root}.era")
+}
+
+fn compress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encoder = snap::write::FrameEncoder::new(vec![]);
+    encoder.write_all(bytes)?;
+    encoder.into_inner().map_err(|e| Error::Io(e.into_error()))
+}
+
+fn decompress(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut decompressed = vec![];
+    snap::read::FrameDecoder::new(bytes).read_to_end(&mut decompressed)?;
+    Ok(decompressed)
+}
+
+/// Writer for e2store entries, which tracks the offset of each entry within the file.
+struct E2StoreWriter<W: Write> {
+    writer: W,
+    position: u64,
+}
+
+impl<W: Write> E2StoreWriter<W> {
+    fn new(writer: W) -> Self {
+        Self {
+            writer,
+            position: 0,
+        }
+    }
+
+    /// Write an entry, returning its offset within the file.
+    fn write_entry(&mut self, entry_type: [u8; 2], data: &[u8]) -> Result<u64, Error> {
+        let offset = self.position;
+        self.writer.write_all(&entry_type)?;
+        self.writer.write_all(&(data.len() as u32).to_le_bytes())?;
+        self.writer.write_all(&[0; 2])?;
+        self.writer.write_all(data)?;
+        self.position += (HEADER_SIZE + data.len()) as u64;
+        Ok(offset)
+    }
+
+    /// Write a slot index for the entries at `offsets`, the first of which is for `start_slot`.
+    ///
+    /// Offsets in the index are relative to the start of the index entry, with 0 denoting a slot
+    /// without an entry.
+    fn write_slot_index(&mut self, start_slot: Slot, offsets: &[Option<u64>]) -> Result<(), Error> {
+        let index_position = self.position as i64;
+        let mut data = Vec::with_capacity((offsets.len() + 2) * 8);
+        data.extend_from_slice(&(start_slot.as_u64() as i64).to_le_bytes());
+        for offset in offsets {
+            let relative_offset = offset.map_or(0, |offset| offset as i64 - index_position);
+            data.extend_from_slice(&relative_offset.to_le_bytes());
+        }
+        data.extend_from_slice(&(offsets.len() as i64).to_le_bytes());
+        self.write_entry(SLOT_INDEX, &data)?;
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<W, Error> {
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+/// The compressed contents of an era file.
+struct EraFile<'a> {
+    blocks: Vec<&'a [u8]>,
+    state: &'a [u8],
+}
+
+impl<'a> EraFile<'a> {
+    fn parse(bytes: &'a [u8]) -> Result<Self, Error> {
+        let mut offset = 0;
+        let mut blocks = vec![];
+        let mut state = None;
+
+        while offset < bytes.len() {
+            let header = bytes
+                .get(offset..offset + HEADER_SIZE)
+                .ok_or(Error::TruncatedEntry { offset })?;
+            let entry_type = [header[0], header[1]];
+            let length = u32::from_le_bytes([header[2], header[3], header[4], header[5]]) as usize;
+            if header[6..] != [0, 0] {
+                return Err(Error::InvalidEntryHeader { offset });
+            }
+            let data_start = offset + HEADER_SIZE;
+            let data = bytes
+                .get(data_start..data_start + length)
+                .ok_or(Error::TruncatedEntry { offset })?;
+
+            match entry_type {
+                VERSION if offset == 0 => (),
+                _ if offset == 0 => return Err(Error::MissingVersion),
+                COMPRESSED_SIGNED_BEACON_BLOCK => blocks.push(data),
+                COMPRESSED_BEACON_STATE if state.is_none() => state = Some(data),
+                COMPRESSED_BEACON_STATE => return Err(Error::MultipleStates),
+                // The indices are only needed for random access.
+                SLOT_INDEX => (),
+                other => return Err(Error::UnknownEntryType(other)),
+            }
+            offset = data_start + length;
+        }
+
+        Ok(Self {
+            blocks,
+            state: state.ok_or(Error::MissingState)?,
+        })
+    }
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Write the blocks and state of `era` to an era file in `output_dir`.
+    ///
+    /// Blocks are exported with their execution payloads, so the payloads for the era must not
+    /// have been pruned. Return the path of the new file.
+    pub fn export_era(
+        &self,
+        era: u64,
+        config_name: &str,
+        output_dir: &Path,
+    ) -> Result<PathBuf, StoreError> {
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let era_slot = Slot::new(era * slots_per_era);
+        let split_slot = self.get_split_slot();
+        if era_slot >= split_slot {
+            return Err(Error::EraNotFinalized { era, split_slot }.into());
+        }
+
+        let state = self
+            .load_cold_state_by_slot(era_slot)?
+            .ok_or(Error::MissingEraState(era_slot))?;
+
+        let path = output_dir.join(era_file_name(config_name, era, &state));
+        let tmp_path = path.with_extension("era.tmp");
+        let file = File::create(&tmp_path).map_err(Error::Io)?;
+        let mut writer = E2StoreWriter::new(BufWriter::new(file));
+        writer.write_entry(VERSION, &[])?;
+
+        let mut block_offsets = vec![];
+        if era > 0 {
+            let start_slot = era_slot - slots_per_era;
+            // The genesis block is unsigned, and not included in era files.
+            let oldest_block_slot = self.get_oldest_block_slot();
+            if oldest_block_slot > std::cmp::max(start_slot, Slot::new(1)) {
+                return Err(StoreError::MissingHistoricBlocks { oldest_block_slot });
+            }
+
+            let mut prev_block_root = None;
+            for slot in start_slot.as_u64()..era_slot.as_u64() {
+                let slot = Slot::new(slot);
+                let block_root = *state.get_block_root(slot)?;
+                if slot == 0 || prev_block_root == Some(block_root) {
+                    block_offsets.push(None);
+                    continue;
+                }
+                prev_block_root = Some(block_root);
+
+                let block = self
+                    .get_full_block(&block_root)?
+                    .ok_or(Error::MissingBlock { slot, block_root })?;
+                // The first slot of the era may be a skip slot.
+                if block.slot() != slot {
+                    block_offsets.push(None);
+                    continue;
+                }
+
+                let offset = writer.write_entry(
+                    COMPRESSED_SIGNED_BEACON_BLOCK,
+                    &compress(&block.as_ssz_bytes())?,
+                )?;
+                block_offsets.push(Some(offset));
+            }
+        }
+
+        let state_offset =
+            writer.write_entry(COMPRESSED_BEACON_STATE, &compress(&state.as_ssz_bytes())?)?;
+        if era > 0 {
+            writer.write_slot_index(era_slot - slots_per_era, &block_offsets)?;
+        }
+        writer.write_slot_index(era_slot, &[Some(state_offset)])?;
+        writer.finish()?;
+
+        fs::rename(&tmp_path, &path).map_err(Error::Io)?;
+
+        debug!(
+            self.log,
+            "Exported era file";
+            "era" => era,
+            "blocks" => block_offsets.iter().flatten().count(),
+            "path" => ?path,
+        );
+        Ok(path)
+    }
+
+    /// Import the blocks from the era file at `path` as historic blocks.
+    ///
+    /// Like backfill sync, the blocks must connect to the oldest block in the database, so era
+    /// files should be imported in descending order. Every block is checked against the block
+    /// roots of the era state, and its proposer signature is verified using the validators of
+    /// the era state. Blocks that are already known are ignored.
+    ///
+    /// Return the number of blocks imported.
+    pub fn import_era(&self, path: &Path) -> Result<usize, StoreError> {
+        let bytes = fs::read(path).map_err(Error::Io)?;
+        let era_file = EraFile::parse(&bytes)?;
+
+        let slots_per_era = E::slots_per_historical_root() as u64;
+        let state = BeaconState::<E>::from_ssz_bytes(&decompress(era_file.state)?, &self.spec)?;
+        if state.slot() % slots_per_era != 0 {
+            return Err(Error::InvalidEraSlot(state.slot()).into());
+        }
+
+        let anchor_info = self.get_anchor_info().ok_or(Error::NoAnchorInfo)?;
+
+        let mut blocks = vec![];
+        for block_bytes in era_file.blocks {
+            let block =
+                SignedBeaconBlock::<E>::from_ssz_bytes(&decompress(block_bytes)?, &self.spec)?;
+            if block.slot() >= anchor_info.oldest_block_slot {
+                continue;
+            }
+            let block_root = block.canonical_root();
+            let expected_block_root = *state.get_block_root(block.slot())?;
+            if block_root != expected_block_root {
+                return Err(Error::MismatchedBlockRoot {
+                    slot: block.slot(),
+                    block_root,
+                    expected_block_root,
+                }
+                .into());
+            }
+            blocks.push((block_root, block));
+        }
+
+        if blocks.is_empty() {
+            return Ok(0);
+        }
+
+        // The era state only knows the genesis block root if it is the state for era 1.
+        let genesis_block_root = state.get_block_root(Slot::new(0)).ok().copied();
+
+        let mut expected_block_root = anchor_info.oldest_block_parent;
+        let mut prev_block_slot = anchor_info.oldest_block_slot;
+        let mut chunk_writer =
+            ChunkWriter::<BlockRoots, _, _>::new(&self.cold_db, prev_block_slot.as_usize())?;
+        let mut cold_batch = Vec::with_capacity(blocks.len());
+        let mut hot_batch = Vec::with_capacity(blocks.len());
+        let mut num_imported = 0;
+
+        for (block_root, block) in blocks.iter().rev() {
+            if *block_root != expected_block_root {
+                return Err(Error::MismatchedParentRoot {
+                    block_root: *block_root,
+                    expected_block_root,
+                }
+                .into());
+            }
+
+            self.blinded_block_as_kv_store_ops(
+                block_root,
+                &block.clone_as_blinded(),
+                &mut hot_batch,
+            );
+
+            // Store block roots, including at all skip slots in the freezer DB.
+            for slot in (block.slot().as_usize()..prev_block_slot.as_usize()).rev() {
+                chunk_writer.set(slot, *block_root, &mut cold_batch)?;
+            }
+
+            prev_block_slot = block.slot();
+            expected_block_root = block.message().parent_root();
+            num_imported += 1;
+
+            // If we've reached genesis, store the genesis block root for all slots prior to the
+            // first block, and set the anchor slot to 0 to indicate completion.
+            if Some(expected_block_root) == genesis_block_root {
+                for slot in 0..prev_block_slot.as_usize() {
+                    chunk_writer.set(slot, expected_block_root, &mut cold_batch)?;
+                }
+                prev_block_slot = Slot::new(0);
+                expected_block_root = Hash256::zero();
+                break;
+            }
+        }
+        chunk_writer.write(&mut cold_batch)?;
+
+        // Blocks prior to the first block after genesis are not imported. This can only be the
+        // genesis block itself, which is already stored and is unsigned.
+        let blocks = &blocks[blocks.len() - num_imported..];
+
+        // Verify the proposer signatures. Only the proposers' public keys are decompressed, as
+        // decompressing every key in the registry would be far slower.
+        let validators = state.validators();
+        let signature_set = blocks
+            .iter()
+            .map(|(block_root, block)| {
+                block_proposal_signature_set_from_parts(
+                    block,
+                    Some(*block_root),
+                    block.message().proposer_index(),
+                    &self.spec.fork_at_epoch(block.message().epoch()),
+                    state.genesis_validators_root(),
+                    |validator_index| {
+                        validators
+                            .get(validator_index)
+                            .and_then(|validator| validator.pubkey.decompress().ok())
+                            .map(Cow::Owned)
+                    },
+                    &self.spec,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(Error::SignatureSet)
+            .map(ParallelSignatureSets::from)?;
+        if !signature_set.verify() {
+            return Err(Error::InvalidSignature.into());
+        }
+
+        // Write the blocks before the block roots, as it's better for the hot DB to contain extra
+        // blocks than for the cold DB to point to blocks that do not exist.
+        self.hot_db.do_atomically(hot_batch)?;
+        self.cold_db.do_atomically(cold_batch)?;
+
+        let new_anchor = AnchorInfo {
+            oldest_block_slot: prev_block_slot,
+            oldest_block_parent: expected_block_root,
+            ..anchor_info.clone()
+        };
+        self.compare_and_set_anchor_info_with_write(Some(anchor_info), Some(new_anchor))?;
+
+        info!(
+            self.log,
+            "Imported era file";
+            "blocks" => blocks.len(),
+            "oldest_block_slot" => prev_block_slot,
+            "path" => ?path,
+        );
+        Ok(blocks.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn e2store_round_trip() {
+        let block = vec![1; 100];
+        let state = vec![2; 1000];
+
+        let mut writer = E2StoreWriter::new(vec![]);
+        writer.write_entry(VERSION, &[]).unwrap();
+        let block_offset = writer
+            .write_entry(COMPRESSED_SIGNED_BEACON_BLOCK, &compress(&block).unwrap())
+            .unwrap();
+        let state_offset = writer
+            .write_entry(COMPRESSED_BEACON_STATE, &compress(&state).unwrap())
+            .unwrap();
+        writer
+            .write_slot_index(Slot::new(0), &[None, Some(block_offset)])
+            .unwrap();
+        writer
+            .write_slot_index(Slot::new(2), &[Some(state_offset)])
+            .unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let era_file = EraFile::parse(&bytes).unwrap();
+        assert_eq!(era_file.blocks.len(), 1);
+        assert_eq!(decompress(era_file.blocks[0]).unwrap(), block);
+        assert_eq!(decompress(era_file.state).unwrap(), state);
+    }
+
+    #[test]
+    fn slot_index_offsets_are_relative() {
+        let mut writer = E2StoreWriter::new(vec![]);
+        writer.write_entry(VERSION, &[]).unwrap();
+        let offset = writer
+            .write_entry(COMPRESSED_BEACON_STATE, &[0; 4])
+            .unwrap();
+        let index_position = writer.position as usize;
+        writer
+            .write_slot_index(Slot::new(8192), &[Some(offset)])
+            .unwrap();
+        let bytes = writer.finish().unwrap();
+
+        let data = &bytes[index_position + HEADER_SIZE..];
+        let read_i64 = |i: usize| i64::from_le_bytes(data[i * 8..(i + 1) * 8].try_into().unwrap());
+        assert_eq!(read_i64(0), 8192);
+        assert_eq!(read_i64(1), offset as i64 - index_position as i64);
+        assert_eq!(read_i64(2), 1);
+    }
+
+    #[test]
+    fn parse_rejects_missing_version() {
+        let mut writer = E2StoreWriter::new(vec![]);
+        writer.write_entry(COMPRESSED_BEACON_STATE, &[]).unwrap();
+        let bytes = writer.finish().unwrap();
+        assert!(matches!(EraFile::parse(&bytes), Err(Error::MissingVersion)));
+    }
+
+    #[test]
+    fn parse_rejects_truncated_entry() {
+        let mut writer = E2StoreWriter::new(vec![]);
+        writer.write_entry(VERSION, &[]).unwrap();
+        writer
+            .write_entry(COMPRESSED_BEACON_STATE, &[0; 16])
+            .unwrap();
+        let bytes = writer.finish().unwrap();
+        assert!(matches!(
+            EraFile::parse(&bytes[..bytes.len() - 1]),
+            Err(Error::TruncatedEntry {
+                offset: HEADER_SIZE
+            })
+        ));
+    }
+}
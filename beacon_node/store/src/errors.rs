@@ -1,8 +1,13 @@
 use crate::chunked_vector::ChunkError;
-use crate::config::StoreConfigError;
+use crate::compression;
+use crate::config::{DatabaseBackend, StoreConfigError};
+use crate::era;
+use crate::hdiff;
 use crate::hot_cold_store::HotColdDBError;
+use crate::snapshot;
 use ssz::DecodeError;
 use state_processing::BlockReplayError;
+use std::path::PathBuf;
 use types::{BeaconStateError, EpochCacheError, Hash256, InconsistentFork, Slot};
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -39,6 +44,8 @@ pub enum Error {
     ///
     /// This should never happen (it's a logic error).
     StateReconstructionDidNotComplete,
+    /// A thread encoding restore points during state reconstruction panicked.
+    StateReconstructionWorkerPanicked,
     StateReconstructionRootMismatch {
         slot: Slot,
         expected: Hash256,
@@ -60,6 +67,17 @@ pub enum Error {
         slot: Slot,
     },
     ArithError(safe_arith::ArithError),
+    Hdiff(hdiff::Error),
+    Era(era::Error),
+    Compression(compression::Error),
+    Snapshot(snapshot::Error),
+    /// The database at `path` was created by a different backend than the one configured.
+    MismatchedDatabaseBackend {
+        path: PathBuf,
+        configured: DatabaseBackend,
+    },
+    /// No database backend was enabled at compile time.
+    DatabaseBackendDisabled,
 }
 
 pub trait HandleUnavailable<T> {
@@ -136,6 +154,30 @@ impl From<safe_arith::ArithError> for Error {
     }
 }
 
+impl From<hdiff::Error> for Error {
+    fn from(e: hdiff::Error) -> Error {
+        Error::Hdiff(e)
+    }
+}
+
+impl From<era::Error> for Error {
+    fn from(e: era::Error) -> Error {
+        Error::Era(e)
+    }
+}
+
+impl From<compression::Error> for Error {
+    fn from(e: compression::Error) -> Error {
+        Error::Compression(e)
+    }
+}
+
+impl From<snapshot::Error> for Error {
+    fn from(e: snapshot::Error) -> Error {
+        Error::Snapshot(e)
+    }
+}
+
 #[derive(Debug)]
 pub struct DBError {
     pub message: String,
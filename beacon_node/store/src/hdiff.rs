@@ -0,0 +1,447 @@
+//! Hierarchical state diffs for the freezer database.
+//!
+//! Rather than storing a full state every `slots_per_restore_point` slots and replaying blocks in
+//! between, the hierarchical scheme arranges freezer states into layers. The coarsest layer stores
+//! full snapshots, while each finer layer stores a diff relative to the nearest state of the layer
+//! above it. Loading any state therefore requires at most one snapshot, one diff per layer, and a
+//! replay of fewer than `2^exponents[0]` slots of blocks.
+//!
+//! ## Example
+//!
+//! With exponents `[5, 9, 11]`:
+//!
+//! - Every 2^11 = 2048 slots a full snapshot is stored.
+//! - Every 2^9 = 512 slots a diff against the previous 2048-slot snapshot is stored.
+//! - Every 2^5 = 32 slots a diff against the previous 512-slot state is stored.
+//! - All other slots are reconstructed by replaying blocks from the previous 32-slot state.
+use serde::{Deserialize, Serialize};
+use ssz::{Decode, Encode};
+use ssz_derive::{Decode, Encode};
+use std::fmt;
+use std::str::FromStr;
+use types::{BeaconState, ChainSpec, EthSpec, List, Slot, Validator};
+
+/// Compression level used for snapshots and diffs.
+///
+/// Level 1 is fast and still eliminates the long runs of zeroes produced by XOR deltas.
+pub const ZSTD_COMPRESSION_LEVEL: i32 = 1;
+
+/// Exponents for an archive node with a mix of fast recent and compact distant history.
+pub const DEFAULT_HIERARCHY_EXPONENTS: [u8; 7] = [5, 9, 11, 13, 16, 18, 21];
+
+#[derive(Debug)]
+pub enum Error {
+    InvalidHierarchy,
+    Compression(std::io::Error),
+    Decompression(std::io::Error),
+    InvalidSszState(ssz::DecodeError),
+    InvalidSszValidators(ssz::DecodeError),
+    InvalidSszSnapshot(ssz::DecodeError),
+    InvalidSszDiff(ssz::DecodeError),
+    InvalidBalancesLength,
+    Milhouse(types::milhouse::Error),
+}
+
+/// Configuration of the layers of the hierarchical diff scheme.
+///
+/// Each exponent `e` denotes a layer with a state stored every `2^e` slots. The last (largest)
+/// exponent is the snapshot layer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Encode, Decode)]
+pub struct HierarchyConfig {
+    pub exponents: Vec<u8>,
+}
+
+impl Default for HierarchyConfig {
+    fn default() -> Self {
+        Self {
+            exponents: DEFAULT_HIERARCHY_EXPONENTS.to_vec(),
+        }
+    }
+}
+
+impl FromStr for HierarchyConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        let exponents = s
+            .split(',')
+            .map(|s| {
+                s.trim()
+                    .parse()
+                    .map_err(|e| format!("invalid hierarchy exponent {s:?}: {e:?}"))
+            })
+            .collect::<Result<Vec<u8>, _>>()?;
+
+        if exponents.windows(2).any(|w| w[0] >= w[1]) {
+            return Err("hierarchy exponents must be strictly increasing".to_string());
+        }
+
+        Ok(HierarchyConfig { exponents })
+    }
+}
+
+impl fmt::Display for HierarchyConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let exponents = self
+            .exponents
+            .iter()
+            .map(|e| e.to_string())
+            .collect::<Vec<_>>();
+        write!(f, "{}", exponents.join(","))
+    }
+}
+
+impl HierarchyConfig {
+    /// Convert the exponents into moduli, checking that they are suitable for the given spec.
+    ///
+    /// The finest layer must lie on epoch boundaries so that its states are present as full
+    /// states in the hot database at the time of migration, and must not exceed
+    /// `slots_per_historical_root` so that the frozen state roots remain contiguous.
+    pub fn to_moduli<E: EthSpec>(&self) -> Result<HierarchyModuli, Error> {
+        let Some(&first_exponent) = self.exponents.first() else {
+            return Err(Error::InvalidHierarchy);
+        };
+        let strictly_increasing = self.exponents.windows(2).all(|w| w[0] < w[1]);
+        let in_range = self.exponents.iter().all(|&e| e < 64);
+
+        if !strictly_increasing || !in_range {
+            return Err(Error::InvalidHierarchy);
+        }
+
+        let first_modulus = 1u64 << first_exponent;
+        if first_modulus % E::slots_per_epoch() != 0
+            || first_modulus > E::slots_per_historical_root() as u64
+        {
+            return Err(Error::InvalidHierarchy);
+        }
+
+        Ok(HierarchyModuli {
+            moduli: self.exponents.iter().map(|&e| 1 << e).collect(),
+        })
+    }
+}
+
+/// The storage strategy for a state at a given slot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageStrategy {
+    /// Store the full state.
+    Snapshot,
+    /// Store a diff against the state at the given slot.
+    DiffFrom(Slot),
+    /// Store nothing, and replay blocks on top of the state at the given slot.
+    ReplayFrom(Slot),
+}
+
+/// The layer moduli of a validated `HierarchyConfig`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HierarchyModuli {
+    moduli: Vec<u64>,
+}
+
+impl HierarchyModuli {
+    /// The number of slots between states stored in the finest layer.
+    pub fn first_modulus(&self) -> u64 {
+        // The moduli are non-empty, this is checked by `HierarchyConfig::to_moduli`.
+        self.moduli.first().copied().unwrap_or(1)
+    }
+
+    /// Return `true` if a snapshot or diff is stored at `slot`.
+    pub fn is_storage_slot(&self, slot: Slot) -> bool {
+        slot % self.first_modulus() == 0
+    }
+
+    /// Return the storage strategy for the state at `slot`.
+    ///
+    /// The `start_slot` is the first slot from which states are retained. It is always stored as
+    /// a snapshot, and no diff is ever taken against a slot prior to it. This supports
+    /// checkpoint-synced nodes whose historic states begin at an arbitrary aligned slot.
+    pub fn storage_strategy(&self, slot: Slot, start_slot: Slot) -> StorageStrategy {
+        if slot == start_slot {
+            return StorageStrategy::Snapshot;
+        }
+
+        let first_modulus = self.first_modulus();
+        if slot % first_modulus != 0 {
+            let replay_from = std::cmp::max(slot / first_modulus * first_modulus, start_slot);
+            return StorageStrategy::ReplayFrom(replay_from);
+        }
+
+        // Find the coarsest layer that this slot belongs to, and diff against the layer above.
+        let layer = self
+            .moduli
+            .iter()
+            .rposition(|modulus| slot % *modulus == 0)
+            .unwrap_or(0);
+
+        match self.moduli.get(layer + 1) {
+            Some(&next_modulus) => {
+                let diff_from = std::cmp::max(slot / next_modulus * next_modulus, start_slot);
+                StorageStrategy::DiffFrom(diff_from)
+            }
+            None => StorageStrategy::Snapshot,
+        }
+    }
+}
+
+/// An uncompressed representation of a `BeaconState` against which diffs can be applied.
+///
+/// The validator registry and balances are held separately from the rest of the state so that
+/// their positions are stable between states, which keeps the XOR deltas sparse.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct HDiffBuffer {
+    state: Vec<u8>,
+    validators: Vec<u8>,
+    balances: Vec<u64>,
+}
+
+impl HDiffBuffer {
+    pub fn from_state<E: EthSpec>(mut beacon_state: BeaconState<E>) -> Self {
+        let validators = std::mem::take(beacon_state.validators_mut());
+        let balances = std::mem::take(beacon_state.balances_mut());
+
+        HDiffBuffer {
+            state: beacon_state.as_ssz_bytes(),
+            validators: validators.as_ssz_bytes(),
+            balances: balances.iter().copied().collect(),
+        }
+    }
+
+    pub fn as_state<E: EthSpec>(&self, spec: &ChainSpec) -> Result<BeaconState<E>, Error> {
+        let mut state =
+            BeaconState::from_ssz_bytes(&self.state, spec).map_err(Error::InvalidSszState)?;
+        *state.validators_mut() =
+            List::<Validator, E::ValidatorRegistryLimit>::from_ssz_bytes(&self.validators)
+                .map_err(Error::InvalidSszValidators)?;
+        *state.balances_mut() = List::new(self.balances.clone()).map_err(Error::Milhouse)?;
+        Ok(state)
+    }
+
+    /// Encode this buffer as a compressed snapshot.
+    pub fn to_snapshot_bytes(&self) -> Result<Vec<u8>, Error> {
+        zstd::encode_all(self.as_ssz_bytes().as_slice(), ZSTD_COMPRESSION_LEVEL)
+            .map_err(Error::Compression)
+    }
+
+    /// Decode a buffer from a compressed snapshot.
+    pub fn from_snapshot_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let ssz_bytes = zstd::decode_all(bytes).map_err(Error::Decompression)?;
+        Self::from_ssz_bytes(&ssz_bytes).map_err(Error::InvalidSszSnapshot)
+    }
+}
+
+/// A diff between two states, to be applied to the state at `base_slot`.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct HDiff {
+    /// The slot of the state that this diff must be applied to.
+    ///
+    /// This is recorded rather than recomputed from the hierarchy so that diffs remain valid if
+    /// the retained state range changes, e.g. after historic state reconstruction.
+    pub base_slot: Slot,
+    state_diff: BytesDiff,
+    validators_diff: BytesDiff,
+    balances_diff: CompressedU64Diff,
+}
+
+impl HDiff {
+    pub fn compute(
+        base_slot: Slot,
+        source: &HDiffBuffer,
+        target: &HDiffBuffer,
+    ) -> Result<Self, Error> {
+        Ok(Self {
+            base_slot,
+            state_diff: BytesDiff::compute(&source.state, &target.state)?,
+            validators_diff: BytesDiff::compute(&source.validators, &target.validators)?,
+            balances_diff: CompressedU64Diff::compute(&source.balances, &target.balances)?,
+        })
+    }
+
+    /// Transform `source` (the state at `base_slot`) into the target state.
+    pub fn apply(&self, source: &mut HDiffBuffer) -> Result<(), Error> {
+        self.state_diff.apply(&mut source.state)?;
+        self.validators_diff.apply(&mut source.validators)?;
+        self.balances_diff.apply(&mut source.balances)
+    }
+
+    /// The total size of the compressed diff data, in bytes.
+    pub fn size(&self) -> usize {
+        self.state_diff.bytes.len()
+            + self.validators_diff.bytes.len()
+            + self.balances_diff.bytes.len()
+    }
+}
+
+/// Compressed XOR delta between two byte strings.
+///
+/// The source is zero-padded or truncated to the length of the target, so the delta of an
+/// append-only list is just the compressed new suffix.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct BytesDiff {
+    bytes: Vec<u8>,
+}
+
+impl BytesDiff {
+    pub fn compute(source: &[u8], target: &[u8]) -> Result<Self, Error> {
+        let xor = target
+            .iter()
+            .enumerate()
+            .map(|(i, byte)| byte ^ source.get(i).copied().unwrap_or(0))
+            .collect::<Vec<u8>>();
+        let bytes =
+            zstd::encode_all(xor.as_slice(), ZSTD_COMPRESSION_LEVEL).map_err(Error::Compression)?;
+        Ok(Self { bytes })
+    }
+
+    pub fn apply(&self, source: &mut Vec<u8>) -> Result<(), Error> {
+        let xor = zstd::decode_all(self.bytes.as_slice()).map_err(Error::Decompression)?;
+        source.resize(xor.len(), 0);
+        for (byte, delta) in source.iter_mut().zip(xor) {
+            *byte ^= delta;
+        }
+        Ok(())
+    }
+}
+
+/// Compressed XOR delta between two lists of `u64`s, used for validator balances.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct CompressedU64Diff {
+    bytes: Vec<u8>,
+}
+
+impl CompressedU64Diff {
+    pub fn compute(source: &[u64], target: &[u64]) -> Result<Self, Error> {
+        let xor = target
+            .iter()
+            .enumerate()
+            .flat_map(|(i, value)| (value ^ source.get(i).copied().unwrap_or(0)).to_le_bytes())
+            .collect::<Vec<u8>>();
+        let bytes =
+            zstd::encode_all(xor.as_slice(), ZSTD_COMPRESSION_LEVEL).map_err(Error::Compression)?;
+        Ok(Self { bytes })
+    }
+
+    pub fn apply(&self, source: &mut Vec<u64>) -> Result<(), Error> {
+        let xor = zstd::decode_all(self.bytes.as_slice()).map_err(Error::Decompression)?;
+        if xor.len() % 8 != 0 {
+            return Err(Error::InvalidBalancesLength);
+        }
+        source.resize(xor.len() / 8, 0);
+        for (value, delta) in source.iter_mut().zip(xor.chunks_exact(8)) {
+            let mut delta_bytes = [0; 8];
+            delta_bytes.copy_from_slice(delta);
+            *value ^= u64::from_le_bytes(delta_bytes);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::MinimalEthSpec;
+
+    fn moduli(exponents: &[u8]) -> HierarchyModuli {
+        HierarchyConfig {
+            exponents: exponents.to_vec(),
+        }
+        .to_moduli::<MinimalEthSpec>()
+        .unwrap()
+    }
+
+    #[test]
+    fn parse_and_display_config() {
+        let config = HierarchyConfig::from_str("5, 9,11").unwrap();
+        assert_eq!(config.exponents, vec![5, 9, 11]);
+        assert_eq!(config.to_string(), "5,9,11");
+
+        assert!(HierarchyConfig::from_str("9,5").is_err());
+        assert!(HierarchyConfig::from_str("5,5").is_err());
+        assert!(HierarchyConfig::from_str("5,x").is_err());
+    }
+
+    #[test]
+    fn invalid_moduli() {
+        let to_moduli =
+            |exponents: Vec<u8>| HierarchyConfig { exponents }.to_moduli::<MinimalEthSpec>();
+        // Empty.
+        assert!(to_moduli(vec![]).is_err());
+        // Not epoch aligned (minimal spec has 8 slots per epoch).
+        assert!(to_moduli(vec![2, 9]).is_err());
+        // Larger than `slots_per_historical_root` (64 for the minimal spec).
+        assert!(to_moduli(vec![7, 9]).is_err());
+        // Overflowing.
+        assert!(to_moduli(vec![3, 64]).is_err());
+    }
+
+    #[test]
+    fn storage_strategy() {
+        let moduli = moduli(&[3, 5, 7]);
+        let genesis = Slot::new(0);
+
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(0), genesis),
+            StorageStrategy::Snapshot
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(128), genesis),
+            StorageStrategy::Snapshot
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(160), genesis),
+            StorageStrategy::DiffFrom(Slot::new(128))
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(168), genesis),
+            StorageStrategy::DiffFrom(Slot::new(160))
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(170), genesis),
+            StorageStrategy::ReplayFrom(Slot::new(168))
+        );
+
+        // Nothing is ever diffed against a slot prior to the start slot.
+        let start_slot = Slot::new(136);
+        assert_eq!(
+            moduli.storage_strategy(start_slot, start_slot),
+            StorageStrategy::Snapshot
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(144), start_slot),
+            StorageStrategy::DiffFrom(start_slot)
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(192), start_slot),
+            StorageStrategy::DiffFrom(start_slot)
+        );
+        assert_eq!(
+            moduli.storage_strategy(Slot::new(256), start_slot),
+            StorageStrategy::Snapshot
+        );
+    }
+
+    #[test]
+    fn bytes_diff_round_trip() {
+        let source = vec![1, 2, 3, 4, 5];
+        for target in [
+            vec![1, 2, 3, 4, 5, 6, 7],
+            vec![1, 9, 3],
+            vec![],
+            vec![0; 64],
+        ] {
+            let diff = BytesDiff::compute(&source, &target).unwrap();
+            let mut buffer = source.clone();
+            diff.apply(&mut buffer).unwrap();
+            assert_eq!(buffer, target);
+        }
+    }
+
+    #[test]
+    fn balances_diff_round_trip() {
+        let source = vec![32_000_000_000, 31_999_999_999, 16_000_000_000];
+        let target = vec![32_000_000_010, 31_999_999_999, 0, 32_000_000_000];
+        let diff = CompressedU64Diff::compute(&source, &target).unwrap();
+        let mut buffer = source.clone();
+        diff.apply(&mut buffer).unwrap();
+        assert_eq!(buffer, target);
+    }
+}
@@ -1,30 +1,32 @@
 use crate::chunked_vector::{
     store_updated_vector, BlockRoots, HistoricalRoots, HistoricalSummaries, RandaoMixes, StateRoots,
 };
+use crate::compression::{CompressionDictionary, ValueCompressor};
 use crate::config::{
     OnDiskStoreConfig, StoreConfig, DEFAULT_SLOTS_PER_RESTORE_POINT,
     PREV_DEFAULT_SLOTS_PER_RESTORE_POINT,
 };
 use crate::forwards_iter::{HybridForwardsBlockRootsIterator, HybridForwardsStateRootsIterator};
+use crate::hdiff::{self, HDiff, HDiffBuffer, HierarchyConfig, HierarchyModuli, StorageStrategy};
 use crate::impls::beacon_state::{get_full_state, store_full_state};
 use crate::iter::{BlockRootsIterator, ParentRootBlockIterator, RootsIterator};
-use crate::leveldb_store::BytesKey;
-use crate::leveldb_store::LevelDB;
 use crate::memory_store::MemoryStore;
 use crate::metadata::{
     AnchorInfo, BlobInfo, CompactionTimestamp, DataColumnInfo, PruningCheckpoint, SchemaVersion,
-    ANCHOR_INFO_KEY, BLOB_INFO_KEY, COMPACTION_TIMESTAMP_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION,
-    DATA_COLUMN_INFO_KEY, PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY,
+    StateRetentionInfo, ANCHOR_INFO_KEY, BLOB_INFO_KEY, COMPACTION_TIMESTAMP_KEY,
+    COMPRESSION_DICTIONARY_KEY, CONFIG_KEY, CURRENT_SCHEMA_VERSION, DATA_COLUMN_INFO_KEY,
+    PRUNING_CHECKPOINT_KEY, SCHEMA_VERSION_KEY, SPLIT_KEY, STATE_RETENTION_INFO_KEY,
     STATE_UPPER_LIMIT_NO_RETAIN,
 };
+use crate::reconstruct::ReconstructionProgress;
 use crate::state_cache::{PutStateOutcome, StateCache};
 use crate::{
-    get_data_column_key, get_key_for_col, ChunkWriter, DBColumn, DatabaseBlock, Error, ItemStore,
-    KeyValueStoreOp, PartialBeaconState, StoreItem, StoreOp,
+    get_data_column_key, get_key_for_col, BeaconNodeBackend, BytesKey, ChunkWriter, DBColumn,
+    DatabaseBlock, Error, ItemStore, KeyValueStore, KeyValueStoreOp, PartialBeaconState, StoreItem,
+    StoreOp,
 };
 use crate::{metrics, parse_data_column_key};
 use itertools::process_results;
-use leveldb::iterator::LevelDBIterator;
 use lru::LruCache;
 use parking_lot::{Mutex, RwLock};
 use safe_arith::SafeArith;
@@ -81,6 +83,14 @@ pub struct HotColdDB<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> {
     state_cache: Mutex<StateCache<E>>,
     /// LRU cache of replayed states.
     historic_state_cache: Mutex<LruCache<Slot, BeaconState<E>>>,
+    /// Layer moduli for hierarchical state diffs, if enabled.
+    pub(crate) hierarchy: Option<HierarchyModuli>,
+    /// LRU cache of hierarchical diff buffers for frozen states, keyed by slot.
+    diff_buffer_cache: Mutex<LruCache<Slot, HDiffBuffer>>,
+    /// Compressor for blocks and restore point states, see `StoreConfig::compression`.
+    pub(crate) compressor: RwLock<ValueCompressor>,
+    /// Progress of historic state reconstruction, if it is running.
+    pub(crate) reconstruction_progress: RwLock<Option<ReconstructionProgress>>,
     /// Chain spec.
     pub(crate) spec: Arc<ChainSpec>,
     /// Logger.
@@ -158,6 +168,18 @@ pub enum HotColdDBError {
     MissingStateToFreeze(Hash256),
     MissingRestorePointHash(u64),
     MissingRestorePoint(Hash256),
+    MissingHDiffBuffer(Slot),
+    InvalidHDiffBaseSlot {
+        slot: Slot,
+        base_slot: Slot,
+    },
+    InvalidHierarchyConfig(HierarchyConfig),
+    FreezerAlreadyHierarchical,
+    HierarchyConversionUnsupported {
+        slots_per_restore_point: u64,
+        first_modulus: u64,
+    },
+    StateRetentionWithHierarchy,
     MissingColdStateSummary(Hash256),
     MissingHotStateSummary(Hash256),
     MissingEpochBoundaryState(Hash256),
@@ -198,6 +220,7 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
         log: Logger,
     ) -> Result<HotColdDB<E, MemoryStore<E>, MemoryStore<E>>, Error> {
         Self::verify_config(&config)?;
+        let hierarchy = Self::hierarchy_moduli(&config)?;
 
         let db = HotColdDB {
             split: RwLock::new(Split::default()),
@@ -210,6 +233,10 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
             historic_state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            hierarchy,
+            diff_buffer_cache: Mutex::new(LruCache::new(config.hdiff_buffer_cache_size)),
+            compressor: RwLock::new(ValueCompressor::new(config.compression)),
+            reconstruction_progress: RwLock::new(None),
             config,
             spec,
             log,
@@ -220,7 +247,7 @@ impl<E: EthSpec> HotColdDB<E, MemoryStore<E>, MemoryStore<E>> {
     }
 }
 
-impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
+impl<E: EthSpec> HotColdDB<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>> {
     /// Open a new or existing database, with the given paths to the hot and cold DBs.
     ///
     /// The `migrate_schema` function is passed in so that the parent `BeaconChain` can provide
@@ -235,18 +262,24 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
         log: Logger,
     ) -> Result<Arc<Self>, Error> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
+        Self::verify_state_retention(&config)?;
+        let hierarchy = Self::hierarchy_moduli(&config)?;
 
         let mut db = HotColdDB {
             split: RwLock::new(Split::default()),
             anchor_info: RwLock::new(None),
             blob_info: RwLock::new(BlobInfo::default()),
             data_column_info: RwLock::new(DataColumnInfo::default()),
-            cold_db: LevelDB::open(cold_path)?,
-            blobs_db: LevelDB::open(blobs_db_path)?,
-            hot_db: LevelDB::open(hot_path)?,
+            cold_db: BeaconNodeBackend::open(&config, cold_path)?,
+            blobs_db: BeaconNodeBackend::open(&config, blobs_db_path)?,
+            hot_db: BeaconNodeBackend::open(&config, hot_path)?,
             block_cache: Mutex::new(BlockCache::new(config.block_cache_size)),
             state_cache: Mutex::new(StateCache::new(config.state_cache_size)),
             historic_state_cache: Mutex::new(LruCache::new(config.historic_state_cache_size)),
+            hierarchy,
+            diff_buffer_cache: Mutex::new(LruCache::new(config.hdiff_buffer_cache_size)),
+            compressor: RwLock::new(ValueCompressor::new(config.compression)),
+            reconstruction_progress: RwLock::new(None),
             config,
             spec,
             log,
@@ -288,6 +321,11 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
             );
         }
 
+        // Values compressed with a dictionary can only be read once it is loaded.
+        if let Some(dictionary) = db.load_compression_dictionary()? {
+            db.compressor.get_mut().set_dictionary(&dictionary);
+        }
+
         // Open separate blobs directory if configured and same configuration was used on previous
         // run.
         let blob_info = db.load_blob_info()?;
@@ -390,23 +428,8 @@ impl<E: EthSpec> HotColdDB<E, LevelDB<E>, LevelDB<E>> {
 
     /// Return an iterator over the state roots of all temporary states.
     pub fn iter_temporary_state_roots(&self) -> impl Iterator<Item = Result<Hash256, Error>> + '_ {
-        let column = DBColumn::BeaconStateTemporary;
-        let start_key =
-            BytesKey::from_vec(get_key_for_col(column.into(), Hash256::zero().as_slice()));
-
-        let keys_iter = self.hot_db.keys_iter();
-        keys_iter.seek(&start_key);
-
-        keys_iter
-            .take_while(move |key| key.matches_column(column))
-            .map(move |bytes_key| {
-                bytes_key.remove_column(column).ok_or_else(|| {
-                    HotColdDBError::IterationError {
-                        unexpected_key: bytes_key,
-                    }
-                    .into()
-                })
-            })
+        self.hot_db
+            .iter_column_keys::<Hash256>(DBColumn::BeaconStateTemporary)
     }
 }
 
@@ -476,10 +499,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         ops: &mut Vec<KeyValueStoreOp>,
     ) {
         let db_key = get_key_for_col(DBColumn::BeaconBlock.into(), key.as_slice());
-        ops.push(KeyValueStoreOp::PutKeyValue(
-            db_key,
-            blinded_block.as_ssz_bytes(),
-        ));
+        let block_bytes = self
+            .compressor
+            .read()
+            .compress(blinded_block.as_ssz_bytes());
+        ops.push(KeyValueStoreOp::PutKeyValue(db_key, block_bytes));
     }
 
     pub fn try_get_full_block(
@@ -600,9 +624,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     ) -> Result<Option<SignedBeaconBlock<E, Payload>>, Error> {
         self.hot_db
             .get_bytes(DBColumn::BeaconBlock.into(), block_root.as_slice())?
-            .map(|block_bytes| decoder(&block_bytes))
+            .map(|block_bytes| -> Result<_, Error> {
+                let block_bytes = self.compressor.read().decompress(&block_bytes)?;
+                Ok(decoder(&block_bytes)?)
+            })
             .transpose()
-            .map_err(|e| e.into())
     }
 
     /// Load the execution payload for a block from disk.
@@ -1540,13 +1566,57 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         state_root: &Hash256,
         state: &BeaconState<E>,
         ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
+        self.store_cold_state_without_restore_point(state_root, state, ops)?;
+
+        if self.hierarchy.is_none() && state.slot() % self.slots_per_restore_point() == 0 {
+            self.store_restore_point(state_root, state, ops);
+        }
+        Ok(())
+    }
+
+    /// Store a pre-finalization state in the freezer database, except for its restore point.
+    ///
+    /// If the state lies on a restore point boundary then the restore point must be stored
+    /// separately using `store_restore_point`. This allows the restore point, which is costly to
+    /// encode, to be prepared on another thread.
+    pub(crate) fn store_cold_state_without_restore_point(
+        &self,
+        state_root: &Hash256,
+        state: &BeaconState<E>,
+        ops: &mut Vec<KeyValueStoreOp>,
     ) -> Result<(), Error> {
         ops.push(ColdStateSummary { slot: state.slot() }.as_kv_store_op(*state_root));
 
-        if state.slot() % self.config.slots_per_restore_point != 0 {
+        if state.slot() % self.slots_per_restore_point() != 0 {
             return Ok(());
         }
 
+        if let Some(hierarchy) = &self.hierarchy {
+            return self.store_cold_state_as_hdiff(state, hierarchy, ops);
+        }
+
+        // Store updated vector entries.
+        // Block roots need to be written here as well as by the `ChunkWriter` in `migrate_db`
+        // because states may require older block roots, and the writer only stores block roots
+        // between the previous split point and the new split point.
+        let db = &self.cold_db;
+        store_updated_vector(BlockRoots, db, state, &self.spec, ops)?;
+        store_updated_vector(StateRoots, db, state, &self.spec, ops)?;
+        store_updated_vector(HistoricalRoots, db, state, &self.spec, ops)?;
+        store_updated_vector(RandaoMixes, db, state, &self.spec, ops)?;
+        store_updated_vector(HistoricalSummaries, db, state, &self.spec, ops)?;
+
+        Ok(())
+    }
+
+    /// Store the restore point for a state which lies on a restore point boundary.
+    pub(crate) fn store_restore_point(
+        &self,
+        state_root: &Hash256,
+        state: &BeaconState<E>,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) {
         trace!(
             self.log,
             "Creating restore point";
@@ -1556,13 +1626,37 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
         // 1. Convert to PartialBeaconState and store that in the DB.
         let partial_state = PartialBeaconState::from_state_forgetful(state);
-        let op = partial_state.as_kv_store_op(*state_root);
-        ops.push(op);
+        let db_key = get_key_for_col(DBColumn::BeaconState.into(), state_root.as_slice());
+        let partial_state_bytes = self
+            .compressor
+            .read()
+            .compress(partial_state.as_ssz_bytes());
+        ops.push(KeyValueStoreOp::PutKeyValue(db_key, partial_state_bytes));
 
-        // 2. Store updated vector entries.
-        // Block roots need to be written here as well as by the `ChunkWriter` in `migrate_db`
-        // because states may require older block roots, and the writer only stores block roots
-        // between the previous split point and the new split point.
+        // 2. Store restore point.
+        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
+        self.store_restore_point_hash(restore_point_index, *state_root, ops);
+    }
+
+    /// Store a frozen state which lies on a storage slot of the diff hierarchy.
+    ///
+    /// The state is stored either as a compressed snapshot, or as a diff against a state from a
+    /// coarser layer, which must already have been written to the freezer.
+    fn store_cold_state_as_hdiff(
+        &self,
+        state: &BeaconState<E>,
+        hierarchy: &HierarchyModuli,
+        ops: &mut Vec<KeyValueStoreOp>,
+    ) -> Result<(), Error> {
+        let slot = state.slot();
+
+        trace!(
+            self.log,
+            "Storing hierarchical state";
+            "slot" => slot,
+        );
+
+        // The vector fields are still required by the forwards iterators.
         let db = &self.cold_db;
         store_updated_vector(BlockRoots, db, state, &self.spec, ops)?;
         store_updated_vector(StateRoots, db, state, &self.spec, ops)?;
@@ -1570,13 +1664,158 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         store_updated_vector(RandaoMixes, db, state, &self.spec, ops)?;
         store_updated_vector(HistoricalSummaries, db, state, &self.spec, ops)?;
 
-        // 3. Store restore point.
-        let restore_point_index = state.slot().as_u64() / self.config.slots_per_restore_point;
-        self.store_restore_point_hash(restore_point_index, *state_root, ops);
+        let buffer = HDiffBuffer::from_state(state.clone());
+        let (column, bytes) = match hierarchy.storage_strategy(slot, self.hdiff_start_slot(slot)) {
+            StorageStrategy::Snapshot => {
+                (DBColumn::BeaconStateSnapshot, buffer.to_snapshot_bytes()?)
+            }
+            StorageStrategy::DiffFrom(base_slot) => {
+                let base_buffer = self.load_hdiff_buffer(base_slot)?;
+                let diff = HDiff::compute(base_slot, &base_buffer, &buffer)?;
+                (DBColumn::BeaconStateDiff, diff.as_ssz_bytes())
+            }
+            // Unreachable for aligned slots, but storing nothing is the correct behaviour.
+            StorageStrategy::ReplayFrom(_) => return Ok(()),
+        };
+
+        metrics::inc_counter_by(&metrics::BEACON_HDIFF_WRITE_BYTES, bytes.len() as u64);
+        ops.push(KeyValueStoreOp::PutKeyValue(
+            get_key_for_col(column.into(), &slot.as_u64().to_be_bytes()),
+            bytes,
+        ));
+        self.diff_buffer_cache.lock().put(slot, buffer);
 
         Ok(())
     }
 
+    /// Return the slot from which hierarchical diffs covering `slot` are computed.
+    ///
+    /// For checkpoint-synced nodes the states from the state upper limit onwards form an
+    /// independent hierarchy, as the states prior to it may not (yet) exist.
+    fn hdiff_start_slot(&self, slot: Slot) -> Slot {
+        match self.get_anchor_info() {
+            Some(anchor) if slot >= anchor.state_upper_limit => anchor.state_upper_limit,
+            _ => self.spec.genesis_slot,
+        }
+    }
+
+    /// Load the diff buffer for the frozen state at `slot`, which must be a storage slot.
+    ///
+    /// Diffs are applied on top of the nearest snapshot or cached buffer, and every intermediate
+    /// buffer is cached.
+    fn load_hdiff_buffer(&self, slot: Slot) -> Result<HDiffBuffer, Error> {
+        if let Some(buffer) = self.diff_buffer_cache.lock().get(&slot) {
+            metrics::inc_counter(&metrics::BEACON_HDIFF_BUFFER_CACHE_HIT_COUNT);
+            return Ok(buffer.clone());
+        }
+
+        let _timer = metrics::start_timer(&metrics::BEACON_HDIFF_BUFFER_LOAD_TIMES);
+
+        // Walk up the hierarchy collecting diffs until a snapshot or a cached buffer is found.
+        let mut diffs = vec![];
+        let mut current_slot = slot;
+        let mut buffer = loop {
+            if let Some(buffer) = self.diff_buffer_cache.lock().get(&current_slot) {
+                break buffer.clone();
+            }
+
+            let key = current_slot.as_u64().to_be_bytes();
+            if let Some(bytes) = self
+                .cold_db
+                .get_bytes(DBColumn::BeaconStateSnapshot.into(), &key)?
+            {
+                break HDiffBuffer::from_snapshot_bytes(&bytes)?;
+            }
+
+            let bytes = self
+                .cold_db
+                .get_bytes(DBColumn::BeaconStateDiff.into(), &key)?
+                .ok_or(HotColdDBError::MissingHDiffBuffer(current_slot))?;
+            let diff = HDiff::from_ssz_bytes(&bytes).map_err(hdiff::Error::InvalidSszDiff)?;
+
+            if diff.base_slot >= current_slot {
+                return Err(HotColdDBError::InvalidHDiffBaseSlot {
+                    slot: current_slot,
+                    base_slot: diff.base_slot,
+                }
+                .into());
+            }
+
+            let base_slot = diff.base_slot;
+            diffs.push((current_slot, diff));
+            current_slot = base_slot;
+        };
+
+        // Apply the diffs from the coarsest layer down to the target.
+        for (diff_slot, diff) in diffs.into_iter().rev() {
+            diff.apply(&mut buffer)?;
+            self.diff_buffer_cache.lock().put(diff_slot, buffer.clone());
+        }
+
+        Ok(buffer)
+    }
+
+    /// Load a frozen state using the hierarchical diff scheme.
+    ///
+    /// States at storage slots are built directly from their diff buffer, while other states are
+    /// reconstructed by replaying blocks from the previous storage slot.
+    fn load_hdiff_state(&self, slot: Slot) -> Result<BeaconState<E>, Error> {
+        if let Some(state) = self.historic_state_cache.lock().get(&slot) {
+            return Ok(state.clone());
+        }
+
+        let slots_per_restore_point = self.slots_per_restore_point();
+        let base_slot = slot / slots_per_restore_point * slots_per_restore_point;
+        let base_state = self.load_hdiff_buffer(base_slot)?.as_state(&self.spec)?;
+
+        if base_slot == slot {
+            return Ok(base_state);
+        }
+
+        // Acquire the read lock, so that the split can't change while this is happening.
+        let split = self.split.read_recursive();
+
+        // The split state is only required if `slot` lies beyond the frozen vector fields.
+        let block_root = self
+            .forwards_block_roots_iterator_until(
+                slot,
+                slot,
+                || Ok((self.get_split_state(&split)?, split.block_root)),
+                &self.spec,
+            )?
+            .next()
+            .transpose()?
+            .map(|(block_root, _)| block_root)
+            .ok_or(HotColdDBError::MissingHDiffBuffer(slot))?;
+
+        let blocks = self.load_blocks_to_replay(base_slot, slot, block_root)?;
+
+        let state_root_iter = self.forwards_state_roots_iterator_until(
+            base_slot,
+            slot,
+            || Ok((self.get_split_state(&split)?, split.state_root)),
+            &self.spec,
+        )?;
+
+        let mut state =
+            self.replay_blocks(base_state, blocks, slot, Some(state_root_iter), None)?;
+        state.apply_pending_mutations()?;
+
+        self.historic_state_cache.lock().put(slot, state.clone());
+
+        Ok(state)
+    }
+
+    /// Load the state at the split point.
+    fn get_split_state(&self, split: &Split) -> Result<BeaconState<E>, Error> {
+        self.get_state(&split.state_root, Some(split.slot))?
+            .ok_or(HotColdDBError::MissingSplitState(
+                split.state_root,
+                split.slot,
+            ))
+            .map_err(Into::into)
+    }
+
     /// Try to load a pre-finalization state from the freezer database.
     ///
     /// Return `None` if no state with `state_root` lies in the freezer.
@@ -1597,13 +1836,30 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         let (lower_limit, upper_limit) = self.get_historic_state_limits();
 
         if slot <= lower_limit || slot >= upper_limit {
-            if slot % self.config.slots_per_restore_point == 0 {
+            if self.hierarchy.is_some() {
+                self.load_hdiff_state(slot)
+            } else if slot % self.config.slots_per_restore_point == 0 {
                 let restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
                 self.load_restore_point_by_index(restore_point_idx)
             } else {
                 self.load_cold_intermediate_state(slot)
             }
             .map(Some)
+        } else if slot % self.config.slots_per_restore_point == 0
+            && self.config.state_retention.is_some_and(|retention| {
+                retention.retains_restore_point(slot.as_u64() / self.config.slots_per_restore_point)
+            })
+        {
+            // Restore points beyond the retention window may have been retained. They are absent
+            // if they were never stored, e.g. due to checkpoint sync.
+            let restore_point_idx = slot.as_u64() / self.config.slots_per_restore_point;
+            match self
+                .cold_db
+                .get::<RestorePointHash>(&Self::restore_point_key(restore_point_idx))?
+            {
+                Some(restore_point) => self.load_restore_point(&restore_point.state_root).map(Some),
+                None => Ok(None),
+            }
         } else {
             Ok(None)
         }
@@ -1615,6 +1871,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             .cold_db
             .get_bytes(DBColumn::BeaconState.into(), state_root.as_slice())?
             .ok_or(HotColdDBError::MissingRestorePoint(*state_root))?;
+        let partial_state_bytes = self.compressor.read().decompress(&partial_state_bytes)?;
         let mut partial_state: PartialBeaconState<E> =
             PartialBeaconState::from_ssz_bytes(&partial_state_bytes, &self.spec)?;
 
@@ -1708,13 +1965,11 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         restore_point_idx: u64,
         split: &Split,
     ) -> Result<BeaconState<E>, Error> {
-        if restore_point_idx * self.config.slots_per_restore_point >= split.slot.as_u64() {
-            self.get_state(&split.state_root, Some(split.slot))?
-                .ok_or(HotColdDBError::MissingSplitState(
-                    split.state_root,
-                    split.slot,
-                ))
-                .map_err(Into::into)
+        let slot = Slot::new(restore_point_idx * self.slots_per_restore_point());
+        if slot >= split.slot {
+            self.get_split_state(split)
+        } else if self.hierarchy.is_some() {
+            self.load_hdiff_state(slot)
         } else {
             self.load_restore_point_by_index(restore_point_idx)
         }
@@ -1921,13 +2176,22 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         {
             Some(lower_limit)
         } else {
-            Some(
-                (split_slot - 1) / self.config.slots_per_restore_point
-                    * self.config.slots_per_restore_point,
-            )
+            Some((split_slot - 1) / self.slots_per_restore_point() * self.slots_per_restore_point())
         }
     }
 
+    /// Return the number of slots between frozen states which are stored on disk.
+    ///
+    /// With hierarchical state diffs enabled this is the modulus of the finest layer, otherwise
+    /// it is the configured `slots_per_restore_point`.
+    pub fn slots_per_restore_point(&self) -> u64 {
+        self.hierarchy
+            .as_ref()
+            .map_or(self.config.slots_per_restore_point, |hierarchy| {
+                hierarchy.first_modulus()
+            })
+    }
+
     /// Load the database schema version from disk.
     fn load_schema_version(&self) -> Result<Option<SchemaVersion>, Error> {
         self.hot_db.get(&SCHEMA_VERSION_KEY)
@@ -1960,7 +2224,7 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         retain_historic_states: bool,
     ) -> Result<KeyValueStoreOp, Error> {
         let anchor_slot = block.slot();
-        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let slots_per_restore_point = self.slots_per_restore_point();
 
         let state_upper_limit = if !retain_historic_states {
             STATE_UPPER_LIMIT_NO_RETAIN
@@ -2030,6 +2294,18 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         self.hot_db.get(&ANCHOR_INFO_KEY)
     }
 
+    /// Load the record of states pruned by the state retention policy, if any have been.
+    pub fn load_state_retention_info(&self) -> Result<Option<StateRetentionInfo>, Error> {
+        self.hot_db.get(&STATE_RETENTION_INFO_KEY)
+    }
+
+    /// Load the compression dictionary from disk, if one has been trained.
+    pub(crate) fn load_compression_dictionary(
+        &self,
+    ) -> Result<Option<CompressionDictionary>, Error> {
+        self.hot_db.get(&COMPRESSION_DICTIONARY_KEY)
+    }
+
     /// Store the given `anchor_info` to disk.
     ///
     /// The argument is intended to be `self.anchor_info`, but is passed manually to avoid issues
@@ -2321,7 +2597,31 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// Verify that a parsed config is valid.
     fn verify_config(config: &StoreConfig) -> Result<(), HotColdDBError> {
         Self::verify_slots_per_restore_point(config.slots_per_restore_point)?;
-        Self::verify_epochs_per_blob_prune(config.epochs_per_blob_prune)
+        Self::verify_epochs_per_blob_prune(config.epochs_per_blob_prune)?;
+        Self::verify_state_retention(config)
+    }
+
+    /// Check that the state retention policy can be applied to the freezer layout.
+    ///
+    /// Retention is defined in terms of restore points, which aren't used by the hierarchy.
+    fn verify_state_retention(config: &StoreConfig) -> Result<(), HotColdDBError> {
+        if config.state_retention.is_some() && config.hierarchy_config.is_some() {
+            return Err(HotColdDBError::StateRetentionWithHierarchy);
+        }
+        Ok(())
+    }
+
+    /// Compute the hierarchy moduli from the config, if hierarchical state diffs are enabled.
+    fn hierarchy_moduli(config: &StoreConfig) -> Result<Option<HierarchyModuli>, HotColdDBError> {
+        config
+            .hierarchy_config
+            .as_ref()
+            .map(|hierarchy_config| {
+                hierarchy_config
+                    .to_moduli::<E>()
+                    .map_err(|_| HotColdDBError::InvalidHierarchyConfig(hierarchy_config.clone()))
+            })
+            .transpose()
     }
 
     /// Check that the restore point frequency is valid.
@@ -2538,20 +2838,27 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
 
     /// Try to prune blobs, approximating the current epoch from the split slot.
     pub fn try_prune_most_blobs(&self, force: bool) -> Result<(), Error> {
-        let Some(deneb_fork_epoch) = self.spec.deneb_fork_epoch else {
+        let Some(min_data_availability_boundary) = self.min_data_availability_boundary() else {
             debug!(self.log, "Deneb fork is disabled");
             return Ok(());
         };
+
+        self.try_prune_blobs(force, min_data_availability_boundary)
+    }
+
+    /// Approximate the data availability boundary using the split slot.
+    ///
+    /// Return `None` if the Deneb fork is disabled.
+    pub fn min_data_availability_boundary(&self) -> Option<Epoch> {
+        let deneb_fork_epoch = self.spec.deneb_fork_epoch?;
         // The current epoch is >= split_epoch + 2. It could be greater if the database is
         // configured to delay updating the split or finalization has ceased. In this instance we
         // choose to also delay the pruning of blobs (we never prune without finalization anyway).
         let min_current_epoch = self.get_split_slot().epoch(E::slots_per_epoch()) + 2;
-        let min_data_availability_boundary = std::cmp::max(
+        Some(std::cmp::max(
             deneb_fork_epoch,
             min_current_epoch.saturating_sub(self.spec.min_epochs_for_blob_sidecars_requests),
-        );
-
-        self.try_prune_blobs(force, min_data_availability_boundary)
+        ))
     }
 
     /// Try to prune blobs older than the data availability boundary.
@@ -2730,8 +3037,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
     /// slot, if any.
     pub fn heal_freezer_block_roots_at_split(&self) -> Result<(), Error> {
         let split = self.get_split_info();
-        let last_restore_point_slot = (split.slot - 1) / self.config.slots_per_restore_point
-            * self.config.slots_per_restore_point;
+        let last_restore_point_slot =
+            (split.slot - 1) / self.slots_per_restore_point() * self.slots_per_restore_point();
 
         // Load split state (which has access to block roots).
         let (_, split_state) = self
@@ -2849,6 +3156,8 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
             DBColumn::BeaconState,
             DBColumn::BeaconStateSummary,
             DBColumn::BeaconRestorePoint,
+            DBColumn::BeaconStateSnapshot,
+            DBColumn::BeaconStateDiff,
             DBColumn::BeaconStateRoots,
             DBColumn::BeaconHistoricalRoots,
             DBColumn::BeaconRandaoMixes,
@@ -2894,6 +3203,190 @@ impl<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>> HotColdDB<E, Hot, Cold>
         Ok(())
     }
 
+    /// Prune historic states which lie outside of the configured `StateRetention` window.
+    ///
+    /// Restore points prior to the window are deleted unless they are retained by the policy, and
+    /// the anchor's `state_upper_limit` is advanced to the start of the window so that the pruned
+    /// states are treated as unavailable. The new limit is also recorded in the `StateRetentionInfo`
+    /// so that reconstruction doesn't regenerate the pruned states. Nothing is pruned while
+    /// historic states are still being reconstructed.
+    pub fn prune_historic_states_outside_retention(&self) -> Result<(), Error> {
+        let Some(retention) = self.config.state_retention else {
+            return Ok(());
+        };
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+
+        let window_start_slot = self
+            .get_split_slot()
+            .saturating_sub(retention.epochs.saturating_mul(E::slots_per_epoch()));
+        let new_upper_limit = Slot::new(
+            window_start_slot.as_u64() / slots_per_restore_point * slots_per_restore_point,
+        );
+
+        // States below the current upper limit have already been pruned, or were never stored.
+        let old_anchor = self.get_anchor_info();
+        let prune_start_slot = match &old_anchor {
+            None => Slot::new(slots_per_restore_point),
+            Some(anchor) if anchor.state_lower_limit == 0 => {
+                std::cmp::max(anchor.state_upper_limit, Slot::new(slots_per_restore_point))
+            }
+            Some(anchor) => {
+                debug!(
+                    self.log,
+                    "Historic state pruning deferred until reconstruction completes";
+                    "state_lower_limit" => anchor.state_lower_limit,
+                );
+                return Ok(());
+            }
+        };
+        if new_upper_limit <= prune_start_slot {
+            return Ok(());
+        }
+
+        let mut cold_ops = vec![];
+        let start_idx = prune_start_slot.as_u64() / slots_per_restore_point;
+        let end_idx = new_upper_limit.as_u64() / slots_per_restore_point;
+        for restore_point_idx in start_idx..end_idx {
+            if retention.retains_restore_point(restore_point_idx) {
+                continue;
+            }
+            let key = Self::restore_point_key(restore_point_idx);
+            if let Some(restore_point) = self.cold_db.get::<RestorePointHash>(&key)? {
+                cold_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    DBColumn::BeaconState.into(),
+                    restore_point.state_root.as_slice(),
+                )));
+                cold_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    DBColumn::BeaconRestorePoint.into(),
+                    key.as_slice(),
+                )));
+            }
+        }
+
+        let new_anchor = match old_anchor.clone() {
+            Some(anchor) => AnchorInfo {
+                state_upper_limit: new_upper_limit,
+                ..anchor
+            },
+            None => AnchorInfo {
+                anchor_slot: Slot::new(0),
+                oldest_block_slot: Slot::new(0),
+                oldest_block_parent: Hash256::zero(),
+                state_upper_limit: new_upper_limit,
+                state_lower_limit: Slot::new(0),
+            },
+        };
+
+        // Commit the anchor change first so that states are never served after their deletion.
+        let anchor_op = self.compare_and_set_anchor_info(old_anchor, Some(new_anchor))?;
+        let retention_info = StateRetentionInfo {
+            pruned_state_upper_limit: new_upper_limit,
+        };
+        self.hot_db.do_atomically(vec![
+            anchor_op,
+            retention_info.as_kv_store_op(STATE_RETENTION_INFO_KEY),
+        ])?;
+
+        info!(
+            self.log,
+            "Pruning historic states outside retention window";
+            "num_restore_points" => cold_ops.len() / 2,
+            "state_upper_limit" => new_upper_limit,
+        );
+        self.cold_db.do_atomically(cold_ops)?;
+
+        Ok(())
+    }
+
+    /// Convert a freezer database which stores restore points to hierarchical state diffs.
+    ///
+    /// The database must have been opened without a hierarchy config. Every retained state at a
+    /// storage slot of the new hierarchy is rewritten as a snapshot or diff, after which the
+    /// restore points are deleted and the new config is written to disk. The database must then
+    /// be re-opened with the same `hierarchy_config`.
+    ///
+    /// The restore point interval must be a multiple of the finest layer of the hierarchy, so that
+    /// the limits of the retained state range remain aligned.
+    pub fn convert_freezer_to_hierarchy(
+        &self,
+        hierarchy_config: &HierarchyConfig,
+    ) -> Result<(), Error> {
+        if self.hierarchy.is_some() {
+            return Err(HotColdDBError::FreezerAlreadyHierarchical.into());
+        }
+
+        let hierarchy = hierarchy_config
+            .to_moduli::<E>()
+            .map_err(|_| HotColdDBError::InvalidHierarchyConfig(hierarchy_config.clone()))?;
+
+        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let first_modulus = hierarchy.first_modulus();
+        if slots_per_restore_point % first_modulus != 0 {
+            return Err(HotColdDBError::HierarchyConversionUnsupported {
+                slots_per_restore_point,
+                first_modulus,
+            }
+            .into());
+        }
+
+        let split_slot = self.get_split_slot();
+        let (lower_limit, upper_limit) = self.get_historic_state_limits();
+
+        info!(
+            self.log,
+            "Converting freezer to hierarchical state diffs";
+            "exponents" => %hierarchy_config,
+            "split_slot" => split_slot,
+        );
+
+        // Diffs are computed against previously written states, so states are converted in
+        // slot-ascending order and each one is committed before moving on.
+        let storage_slots = (0..split_slot.as_u64())
+            .step_by(first_modulus as usize)
+            .map(Slot::new)
+            .filter(|slot| *slot <= lower_limit || *slot >= upper_limit);
+
+        for slot in storage_slots {
+            let state = self
+                .load_cold_state_by_slot(slot)?
+                .ok_or(HotColdDBError::MissingHDiffBuffer(slot))?;
+
+            let mut cold_db_ops = vec![];
+            self.store_cold_state_as_hdiff(&state, &hierarchy, &mut cold_db_ops)?;
+            self.cold_db.do_atomically(cold_db_ops)?;
+
+            if slot % slots_per_restore_point == 0 {
+                debug!(self.log, "Freezer conversion in progress"; "slot" => slot);
+            }
+        }
+
+        // Delete the restore points, which are superseded by the snapshots and diffs.
+        let mut cold_db_ops = vec![];
+        for column in [DBColumn::BeaconState, DBColumn::BeaconRestorePoint] {
+            for res in self.cold_db.iter_column_keys::<Vec<u8>>(column) {
+                let key = res?;
+                cold_db_ops.push(KeyValueStoreOp::DeleteKey(get_key_for_col(
+                    column.as_str(),
+                    &key,
+                )));
+            }
+        }
+        self.cold_db.do_atomically(cold_db_ops)?;
+
+        let disk_config = OnDiskStoreConfig {
+            slots_per_restore_point,
+            hierarchy_config: Some(hierarchy_config.clone()),
+        };
+        self.hot_db.put(&CONFIG_KEY, &disk_config)?;
+
+        self.diff_buffer_cache.lock().clear();
+        self.cold_db.compact()?;
+
+        info!(self.log, "Freezer conversion complete");
+
+        Ok(())
+    }
+
     /// Prune states from the hot database which are prior to the split.
     ///
     /// This routine is important for cleaning up advanced states which are stored in the database
@@ -2984,6 +3477,7 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     let mut cold_db_ops = vec![];
     let mut epoch_boundary_blocks = HashSet::new();
     let mut non_checkpoint_block_roots = HashSet::new();
+    let mut hdiff_state_roots = vec![];
 
     // Chunk writer for the linear block roots in the freezer DB.
     // Start at the new upper limit because we iterate backwards.
@@ -3052,7 +3546,14 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
         let op = cold_state_summary.as_kv_store_op(state_root);
         cold_db_ops.push(op);
 
-        if slot % store.config.slots_per_restore_point == 0 {
+        if slot % store.slots_per_restore_point() == 0 {
+            // Hierarchical diffs are computed against previously stored states, so they must be
+            // stored in slot-ascending order once iteration is complete.
+            if store.hierarchy.is_some() {
+                hdiff_state_roots.push(state_root);
+                continue;
+            }
+
             let state: BeaconState<E> = get_full_state(&store.hot_db, &state_root, &store.spec)?
                 .ok_or(HotColdDBError::MissingStateToFreeze(state_root))?;
 
@@ -3083,6 +3584,15 @@ pub fn migrate_database<E: EthSpec, Hot: ItemStore<E>, Cold: ItemStore<E>>(
     block_root_writer.write(&mut cold_db_ops)?;
     store.cold_db.do_atomically(cold_db_ops)?;
 
+    for state_root in hdiff_state_roots.into_iter().rev() {
+        let state: BeaconState<E> = get_full_state(&store.hot_db, &state_root, &store.spec)?
+            .ok_or(HotColdDBError::MissingStateToFreeze(state_root))?;
+
+        let mut cold_db_ops = vec![];
+        store.store_cold_state(&state_root, &state, &mut cold_db_ops)?;
+        store.cold_db.do_atomically(cold_db_ops)?;
+    }
+
     // Warning: Critical section.  We have to take care not to put any of the two databases in an
     //          inconsistent state if the OS process dies at any point during the freezing
     //          procedure.
@@ -3191,7 +3701,7 @@ fn no_state_root_iter() -> Option<std::iter::Empty<Result<(Hash256, Slot), Error
 pub struct HotStateSummary {
     pub slot: Slot,
     pub latest_block_root: Hash256,
-    epoch_boundary_state_root: Hash256,
+    pub epoch_boundary_state_root: Hash256,
 }
 
 impl StoreItem for HotStateSummary {
@@ -1,4 +1,6 @@
 use super::*;
+use crate::backend::check_database_backend;
+use crate::config::DatabaseBackend;
 use crate::hot_cold_store::HotColdDBError;
 use leveldb::compaction::Compaction;
 use leveldb::database::batch::{Batch, Writebatch};
@@ -22,6 +24,8 @@ pub struct LevelDB<E: EthSpec> {
 impl<E: EthSpec> LevelDB<E> {
     /// Open a database at `path`, creating a new database if one does not already exist.
     pub fn open(path: &Path) -> Result<Self, Error> {
+        check_database_backend(path, DatabaseBackend::LevelDb)?;
+
         let mut options = Options::new();
 
         options.create_if_missing = true;
@@ -251,15 +255,9 @@ impl<E: EthSpec> KeyValueStore<E> for LevelDB<E> {
 
 impl<E: EthSpec> ItemStore<E> for LevelDB<E> {}
 
-/// Used for keying leveldb.
-#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
-pub struct BytesKey {
-    key: Vec<u8>,
-}
-
 impl db_key::Key for BytesKey {
     fn from_u8(key: &[u8]) -> Self {
-        Self { key: key.to_vec() }
+        Self::from_vec(key.to_vec())
     }
 
     fn as_slice<T, F: Fn(&[u8]) -> T>(&self, f: F) -> T {
@@ -267,40 +265,6 @@ impl db_key::Key for BytesKey {
     }
 }
 
-impl BytesKey {
-    pub fn starts_with(&self, prefix: &Self) -> bool {
-        self.key.starts_with(&prefix.key)
-    }
-
-    /// Return `true` iff this `BytesKey` was created with the given `column`.
-    pub fn matches_column(&self, column: DBColumn) -> bool {
-        self.key.starts_with(column.as_bytes())
-    }
-
-    /// Remove the column from a 32 byte key, yielding the `Hash256` key.
-    pub fn remove_column(&self, column: DBColumn) -> Option<Hash256> {
-        let key = self.remove_column_variable(column)?;
-        (column.key_size() == 32).then(|| Hash256::from_slice(key))
-    }
-
-    /// Remove the column from a key.
-    ///
-    /// Will return `None` if the value doesn't match the column or has the wrong length.
-    pub fn remove_column_variable(&self, column: DBColumn) -> Option<&[u8]> {
-        if self.matches_column(column) {
-            let subkey = &self.key[column.as_bytes().len()..];
-            if subkey.len() == column.key_size() {
-                return Some(subkey);
-            }
-        }
-        None
-    }
-
-    pub fn from_vec(key: Vec<u8>) -> Self {
-        Self { key }
-    }
-}
-
 impl From<LevelDBError> for Error {
     fn from(e: LevelDBError) -> Error {
         Error::DBError {
@@ -2,38 +2,57 @@
 //!
 //! Provides the following stores:
 //!
-//! - `HotColdDB`: an on-disk store backed by leveldb. Used in production.
+//! - `HotColdDB`: an on-disk store backed by leveldb or redb. Used in production.
 //! - `MemoryStore`: an in-memory store backed by a hash-map. Used for testing.
 //!
 //! Provides a simple API for storing/retrieving all types that sometimes needs type-hints. See
 //! tests for implementation examples.
+//!
+//! The on-disk backends are enabled by the `leveldb` (default) and `redb` features. Crates which
+//! only use the store's types may depend on it without either, in which case opening a database on
+//! disk fails with `Error::DatabaseBackendDisabled`.
+
+mod backend;
 mod chunk_writer;
 pub mod chunked_iter;
 pub mod chunked_vector;
+pub mod compression;
 pub mod config;
 pub mod consensus_context;
+pub mod era;
 pub mod errors;
 mod forwards_iter;
 mod garbage_collection;
+pub mod hdiff;
 pub mod hot_cold_store;
 mod impls;
+#[cfg(feature = "leveldb")]
 mod leveldb_store;
 mod memory_store;
 pub mod metadata;
 pub mod metrics;
 mod partial_beacon_state;
 pub mod reconstruct;
+#[cfg(feature = "redb")]
+mod redb_store;
+pub mod snapshot;
 pub mod state_cache;
+pub mod stats;
+pub mod verify;
 
 pub mod iter;
 
+pub use self::backend::BeaconNodeBackend;
 pub use self::chunk_writer::ChunkWriter;
-pub use self::config::StoreConfig;
+pub use self::config::{DatabaseBackend, StateRetention, StoreConfig};
 pub use self::consensus_context::OnDiskConsensusContext;
 pub use self::hot_cold_store::{HotColdDB, HotStateSummary, Split};
+#[cfg(feature = "leveldb")]
 pub use self::leveldb_store::LevelDB;
 pub use self::memory_store::MemoryStore;
 pub use self::partial_beacon_state::PartialBeaconState;
+#[cfg(feature = "redb")]
+pub use self::redb_store::Redb;
 pub use crate::metadata::BlobInfo;
 pub use errors::Error;
 pub use impls::beacon_state::StorageContainer as BeaconStateStorageContainer;
@@ -41,7 +60,7 @@ pub use metadata::AnchorInfo;
 pub use metrics::scrape_for_metrics;
 use parking_lot::MutexGuard;
 use std::sync::Arc;
-use strum::{EnumString, IntoStaticStr};
+use strum::{EnumIter, EnumString, IntoStaticStr};
 pub use types::*;
 
 const DATA_COLUMN_DB_KEY_SIZE: usize = 32 + 8;
@@ -172,6 +191,46 @@ pub fn parse_data_column_key(data: Vec<u8>) -> Result<(Hash256, ColumnIndex), Er
     Ok((block_root, column_index))
 }
 
+/// A database key, consisting of a column prefix followed by the key within that column.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BytesKey {
+    pub(crate) key: Vec<u8>,
+}
+
+impl BytesKey {
+    pub fn starts_with(&self, prefix: &Self) -> bool {
+        self.key.starts_with(&prefix.key)
+    }
+
+    /// Return `true` iff this `BytesKey` was created with the given `column`.
+    pub fn matches_column(&self, column: DBColumn) -> bool {
+        self.key.starts_with(column.as_bytes())
+    }
+
+    /// Remove the column from a 32 byte key, yielding the `Hash256` key.
+    pub fn remove_column(&self, column: DBColumn) -> Option<Hash256> {
+        let key = self.remove_column_variable(column)?;
+        (column.key_size() == 32).then(|| Hash256::from_slice(key))
+    }
+
+    /// Remove the column from a key.
+    ///
+    /// Will return `None` if the value doesn't match the column or has the wrong length.
+    pub fn remove_column_variable(&self, column: DBColumn) -> Option<&[u8]> {
+        if self.matches_column(column) {
+            let subkey = &self.key[column.as_bytes().len()..];
+            if subkey.len() == column.key_size() {
+                return Some(subkey);
+            }
+        }
+        None
+    }
+
+    pub fn from_vec(key: Vec<u8>) -> Self {
+        Self { key }
+    }
+}
+
 #[must_use]
 #[derive(Clone)]
 pub enum KeyValueStoreOp {
@@ -246,7 +305,7 @@ pub enum StoreOp<'a, E: EthSpec> {
 }
 
 /// A unique column identifier.
-#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString)]
+#[derive(Debug, Clone, Copy, PartialEq, IntoStaticStr, EnumString, EnumIter)]
 pub enum DBColumn {
     /// For data related to the database itself.
     #[strum(serialize = "bma")]
@@ -288,12 +347,21 @@ pub enum DBColumn {
     BeaconBlockRoots,
     #[strum(serialize = "bsr")]
     BeaconStateRoots,
+    /// For compressed full states stored at the top layer of the diff hierarchy, keyed by slot.
+    #[strum(serialize = "bsn")]
+    BeaconStateSnapshot,
+    /// For hierarchical state diffs, keyed by slot.
+    #[strum(serialize = "bsd")]
+    BeaconStateDiff,
     #[strum(serialize = "bhr")]
     BeaconHistoricalRoots,
     #[strum(serialize = "brm")]
     BeaconRandaoMixes,
     #[strum(serialize = "dht")]
     DhtEnrs,
+    /// For the reputations of peers known to the peer manager.
+    #[strum(serialize = "prp")]
+    PeerReputation,
     /// For Optimistically Imported Merge Transition Blocks
     #[strum(serialize = "otb")]
     OptimisticTransitionBlock,
@@ -347,12 +415,15 @@ impl DBColumn {
             | Self::PubkeyCache
             | Self::BeaconRestorePoint
             | Self::DhtEnrs
+            | Self::PeerReputation
             | Self::OptimisticTransitionBlock => 32,
             Self::BeaconBlockRoots
             | Self::BeaconStateRoots
             | Self::BeaconHistoricalRoots
             | Self::BeaconHistoricalSummaries
             | Self::BeaconRandaoMixes
+            | Self::BeaconStateSnapshot
+            | Self::BeaconStateDiff
             | Self::SyncCommittee
             | Self::SyncCommitteeBranch
             | Self::LightClientUpdate => 8,
@@ -428,6 +499,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "leveldb")]
     fn simplediskdb() {
         let dir = tempdir().unwrap();
         let path = dir.path();
@@ -436,6 +508,52 @@ mod tests {
         test_impl(store);
     }
 
+    #[test]
+    #[cfg(feature = "redb")]
+    fn redb() {
+        let dir = tempdir().unwrap();
+        let path = dir.path();
+        let store = Redb::open(path).unwrap();
+
+        test_impl(store);
+    }
+
+    #[test]
+    #[cfg(feature = "redb")]
+    fn redb_iter_column() {
+        let dir = tempdir().unwrap();
+        let store = Redb::<MinimalEthSpec>::open(dir.path()).unwrap();
+        let column = DBColumn::BeaconBlockRoots;
+
+        let keys = (0..10u64)
+            .map(|i| i.to_be_bytes().to_vec())
+            .collect::<Vec<_>>();
+        let ops = keys
+            .iter()
+            .map(|key| KeyValueStoreOp::PutKeyValue(get_key_for_col(column.into(), key), vec![1]))
+            .collect();
+        store.do_atomically(ops).unwrap();
+
+        let all_keys = store
+            .iter_column_keys::<Vec<u8>>(column)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(all_keys, keys);
+
+        let from_five = store
+            .iter_column_from::<Vec<u8>>(column, &keys[5])
+            .map(|res| res.map(|(key, _)| key))
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(from_five, keys[5..]);
+
+        let raw_keys = store
+            .iter_raw_keys(column, &[0; 7])
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(raw_keys, keys);
+    }
+
     #[test]
     fn memorydb() {
         let store = MemoryStore::open();
@@ -17,6 +17,8 @@ pub const COMPACTION_TIMESTAMP_KEY: Hash256 = Hash256::repeat_byte(4);
 pub const ANCHOR_INFO_KEY: Hash256 = Hash256::repeat_byte(5);
 pub const BLOB_INFO_KEY: Hash256 = Hash256::repeat_byte(6);
 pub const DATA_COLUMN_INFO_KEY: Hash256 = Hash256::repeat_byte(7);
+pub const COMPRESSION_DICTIONARY_KEY: Hash256 = Hash256::repeat_byte(8);
+pub const STATE_RETENTION_INFO_KEY: Hash256 = Hash256::repeat_byte(9);
 
 /// State upper limit value used to indicate that a node is not storing historic states.
 pub const STATE_UPPER_LIMIT_NO_RETAIN: Slot = Slot::new(u64::MAX);
@@ -85,6 +87,32 @@ impl StoreItem for CompactionTimestamp {
     }
 }
 
+/// The historic states which have been deleted by the state retention policy.
+///
+/// The anchor alone can't distinguish states pruned by the policy from states which have yet to
+/// be reconstructed, so this is recorded separately to stop reconstruction from regenerating them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StateRetentionInfo {
+    /// States prior to this slot were pruned, except for restore points retained by the policy.
+    pub pruned_state_upper_limit: Slot,
+}
+
+impl StoreItem for StateRetentionInfo {
+    fn db_column() -> DBColumn {
+        DBColumn::BeaconMeta
+    }
+
+    fn as_store_bytes(&self) -> Vec<u8> {
+        self.pruned_state_upper_limit.as_ssz_bytes()
+    }
+
+    fn from_store_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(StateRetentionInfo {
+            pruned_state_upper_limit: Slot::from_ssz_bytes(bytes)?,
+        })
+    }
+}
+
 /// Database parameters relevant to weak subjectivity sync.
 #[derive(Debug, PartialEq, Eq, Clone, Encode, Decode, Serialize, Deserialize)]
 pub struct AnchorInfo {
@@ -130,6 +130,57 @@ pub static BEACON_STATE_WRITE_BYTES: LazyLock<Result<IntCounter>> = LazyLock::ne
         "Total number of beacon state bytes written to the DB",
     )
 });
+/*
+ * Hierarchical state diffs
+ */
+pub static BEACON_HDIFF_BUFFER_CACHE_HIT_COUNT: LazyLock<Result<IntCounter>> =
+    LazyLock::new(|| {
+        try_create_int_counter(
+            "store_beacon_hdiff_buffer_cache_hit_total",
+            "Number of hits to the store's hierarchical diff buffer cache",
+        )
+    });
+pub static BEACON_HDIFF_BUFFER_LOAD_TIMES: LazyLock<Result<Histogram>> = LazyLock::new(|| {
+    try_create_histogram(
+        "store_beacon_hdiff_buffer_load_seconds",
+        "Time taken to load a hierarchical diff buffer from the freezer DB",
+    )
+});
+pub static BEACON_HDIFF_WRITE_BYTES: LazyLock<Result<IntCounter>> = LazyLock::new(|| {
+    try_create_int_counter(
+        "store_beacon_hdiff_write_bytes_total",
+        "Total number of compressed snapshot and diff bytes written to the freezer DB",
+    )
+});
+/*
+ * Historic state reconstruction
+ */
+pub static STORE_RECONSTRUCTION_STATE_LOWER_LIMIT: LazyLock<Result<IntGauge>> =
+    LazyLock::new(|| {
+        try_create_int_gauge(
+            "store_reconstruction_state_lower_limit_slot",
+            "Slot up to which historic states have been reconstructed",
+        )
+    });
+pub static STORE_RECONSTRUCTION_REPLAYED_SLOT: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "store_reconstruction_replayed_slot",
+        "Slot up to which blocks have been replayed by historic state reconstruction",
+    )
+});
+pub static STORE_RECONSTRUCTION_END_SLOT: LazyLock<Result<IntGauge>> = LazyLock::new(|| {
+    try_create_int_gauge(
+        "store_reconstruction_end_slot",
+        "Slot at which historic state reconstruction will complete",
+    )
+});
+pub static STORE_RECONSTRUCTION_RESTORE_POINT_ENCODE_TIMES: LazyLock<Result<Histogram>> =
+    LazyLock::new(|| {
+        try_create_histogram(
+            "store_reconstruction_restore_point_encode_seconds",
+            "Time taken to encode a restore point during historic state reconstruction",
+        )
+    });
 /*
  * Beacon Block
  */
@@ -1,14 +1,40 @@
 //! Implementation of historic state reconstruction (given complete block history).
 use crate::hot_cold_store::{HotColdDB, HotColdDBError};
-use crate::{Error, ItemStore};
+use crate::{metrics, AnchorInfo, Error, ItemStore, KeyValueStoreOp};
 use itertools::{process_results, Itertools};
-use slog::info;
+use serde::{Deserialize, Serialize};
+use slog::{debug, info};
 use state_processing::{
     per_block_processing, per_slot_processing, BlockSignatureStrategy, ConsensusContext,
     VerifyBlockRoot,
 };
+use std::collections::VecDeque;
 use std::sync::Arc;
-use types::{EthSpec, Hash256};
+use std::thread::ScopedJoinHandle;
+use types::{EthSpec, Hash256, Slot};
+
+/// Progress of a running historic state reconstruction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReconstructionProgress {
+    /// The state lower limit at which this run of reconstruction started.
+    ///
+    /// Reconstruction resumes from the last completed restore point after a restart.
+    pub start_slot: Slot,
+    /// All states at or below this slot have been reconstructed and written to disk.
+    pub state_lower_limit: Slot,
+    /// The slot up to which blocks have been replayed.
+    pub replayed_slot: Slot,
+    /// The slot at which reconstruction will complete.
+    pub end_slot: Slot,
+    /// Number of threads used for reconstruction.
+    pub threads: usize,
+}
+
+/// A restore point which is being encoded on a worker thread.
+struct PendingRestorePoint<'scope> {
+    slot: Slot,
+    handle: ScopedJoinHandle<'scope, Vec<KeyValueStoreOp>>,
+}
 
 impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
 where
@@ -16,8 +42,13 @@ where
     Hot: ItemStore<E>,
     Cold: ItemStore<E>,
 {
+    /// Get the progress of historic state reconstruction, if it is running.
+    pub fn get_reconstruction_progress(&self) -> Option<ReconstructionProgress> {
+        self.reconstruction_progress.read().clone()
+    }
+
     pub fn reconstruct_historic_states(self: &Arc<Self>) -> Result<(), Error> {
-        let Some(mut anchor) = self.get_anchor_info() else {
+        let Some(anchor) = self.get_anchor_info() else {
             // Nothing to do, history is complete.
             return Ok(());
         };
@@ -29,13 +60,35 @@ where
             });
         }
 
+        // Don't regenerate the states which were deliberately pruned by the retention policy, even
+        // if the policy has since been disabled.
+        if let Some(retention_info) = self.load_state_retention_info()? {
+            if anchor.state_upper_limit <= retention_info.pruned_state_upper_limit {
+                debug!(
+                    self.log,
+                    "Skipping reconstruction of pruned historic states";
+                    "pruned_state_upper_limit" => retention_info.pruned_state_upper_limit,
+                );
+                return Ok(());
+            }
+        }
+
+        let result = self.reconstruct_historic_states_from(anchor);
+        *self.reconstruction_progress.write() = None;
+        result
+    }
+
+    fn reconstruct_historic_states_from(&self, mut anchor: AnchorInfo) -> Result<(), Error> {
+        let threads = self.config.reconstruction_threads.get();
+
         info!(
             self.log,
             "Beginning historic state reconstruction";
             "start_slot" => anchor.state_lower_limit,
+            "threads" => threads,
         );
 
-        let slots_per_restore_point = self.config.slots_per_restore_point;
+        let slots_per_restore_point = self.slots_per_restore_point();
 
         // Iterate blocks from the state lower limit to the upper limit.
         let lower_limit_slot = anchor.state_lower_limit;
@@ -46,6 +99,22 @@ where
         )?;
         let upper_limit_slot = upper_limit_state.slot();
 
+        *self.reconstruction_progress.write() = Some(ReconstructionProgress {
+            start_slot: lower_limit_slot,
+            state_lower_limit: lower_limit_slot,
+            replayed_slot: lower_limit_slot,
+            end_slot: upper_limit_slot,
+            threads,
+        });
+        metrics::set_gauge(
+            &metrics::STORE_RECONSTRUCTION_STATE_LOWER_LIMIT,
+            lower_limit_slot.as_u64() as i64,
+        );
+        metrics::set_gauge(
+            &metrics::STORE_RECONSTRUCTION_END_SLOT,
+            upper_limit_slot.as_u64() as i64,
+        );
+
         // Use a dummy root, as we never read the block for the upper limit state.
         let upper_limit_block_root = Hash256::repeat_byte(0xff);
 
@@ -63,96 +132,147 @@ where
 
         state.build_caches(&self.spec)?;
 
-        process_results(block_root_iter, |iter| -> Result<(), Error> {
-            let mut io_batch = vec![];
-
-            let mut prev_state_root = None;
-
-            for ((prev_block_root, _), (block_root, slot)) in iter.tuple_windows() {
-                let is_skipped_slot = prev_block_root == block_root;
-
-                let block = if is_skipped_slot {
-                    None
-                } else {
-                    Some(
-                        self.get_blinded_block(&block_root)?
-                            .ok_or(Error::BlockNotFound(block_root))?,
-                    )
-                };
-
-                // Advance state to slot.
-                per_slot_processing(&mut state, prev_state_root.take(), &self.spec)
-                    .map_err(HotColdDBError::BlockReplaySlotError)?;
-
-                // Apply block.
-                if let Some(block) = block {
-                    let mut ctxt = ConsensusContext::new(block.slot())
-                        .set_current_block_root(block_root)
-                        .set_proposer_index(block.message().proposer_index());
-
-                    per_block_processing(
-                        &mut state,
-                        &block,
-                        BlockSignatureStrategy::NoVerification,
-                        VerifyBlockRoot::True,
-                        &mut ctxt,
-                        &self.spec,
-                    )
-                    .map_err(HotColdDBError::BlockReplayBlockError)?;
-
-                    prev_state_root = Some(block.state_root());
-                }
+        // Block replay is inherently sequential: the only states available are those at the lower
+        // and upper limits, and the state at the start of any later range is only known once the
+        // range before it has been replayed. Instead, additional threads are used to encode restore
+        // points while replay continues. Hierarchical diffs are computed against previously
+        // written states, so they are always stored by the replaying thread.
+        let max_pending_restore_points = if self.hierarchy.is_none() {
+            threads - 1
+        } else {
+            0
+        };
 
-                let state_root = prev_state_root
-                    .ok_or(())
-                    .or_else(|_| state.update_tree_hash_cache())?;
-
-                // Stage state for storage in freezer DB.
-                self.store_cold_state(&state_root, &state, &mut io_batch)?;
-
-                // If the slot lies on an epoch boundary, commit the batch and update the anchor.
-                if slot % slots_per_restore_point == 0 || slot + 1 == upper_limit_slot {
-                    info!(
-                        self.log,
-                        "State reconstruction in progress";
-                        "slot" => slot,
-                        "remaining" => upper_limit_slot - 1 - slot
-                    );
-
-                    self.cold_db.do_atomically(std::mem::take(&mut io_batch))?;
-
-                    // Update anchor.
-                    let old_anchor = Some(anchor.clone());
-
-                    if slot + 1 == upper_limit_slot {
-                        // The two limits have met in the middle! We're done!
-                        // Perform one last integrity check on the state reached.
-                        let computed_state_root = state.update_tree_hash_cache()?;
-                        if computed_state_root != state_root {
-                            return Err(Error::StateReconstructionRootMismatch {
-                                slot,
-                                expected: state_root,
-                                computed: computed_state_root,
-                            });
-                        }
+        std::thread::scope(|scope| {
+            process_results(block_root_iter, |iter| -> Result<(), Error> {
+                let mut io_batch = vec![];
+                let mut pending_restore_points = VecDeque::new();
 
-                        self.compare_and_set_anchor_info_with_write(old_anchor, None)?;
+                let mut prev_state_root = None;
 
-                        return Ok(());
+                for ((prev_block_root, _), (block_root, slot)) in iter.tuple_windows() {
+                    let is_skipped_slot = prev_block_root == block_root;
+
+                    let block = if is_skipped_slot {
+                        None
                     } else {
-                        // The lower limit has been raised, store it.
-                        anchor.state_lower_limit = slot;
+                        Some(
+                            self.get_blinded_block(&block_root)?
+                                .ok_or(Error::BlockNotFound(block_root))?,
+                        )
+                    };
+
+                    // Advance state to slot.
+                    per_slot_processing(&mut state, prev_state_root.take(), &self.spec)
+                        .map_err(HotColdDBError::BlockReplaySlotError)?;
+
+                    // Apply block.
+                    if let Some(block) = block {
+                        let mut ctxt = ConsensusContext::new(block.slot())
+                            .set_current_block_root(block_root)
+                            .set_proposer_index(block.message().proposer_index());
+
+                        per_block_processing(
+                            &mut state,
+                            &block,
+                            BlockSignatureStrategy::NoVerification,
+                            VerifyBlockRoot::True,
+                            &mut ctxt,
+                            &self.spec,
+                        )
+                        .map_err(HotColdDBError::BlockReplayBlockError)?;
+
+                        prev_state_root = Some(block.state_root());
+                    }
+
+                    let state_root = prev_state_root
+                        .ok_or(())
+                        .or_else(|_| state.update_tree_hash_cache())?;
+
+                    // Stage state for storage in freezer DB. Restore points are staged separately
+                    // so that they can be encoded on a worker thread.
+                    self.store_cold_state_without_restore_point(
+                        &state_root,
+                        &state,
+                        &mut io_batch,
+                    )?;
+                    let is_restore_point =
+                        self.hierarchy.is_none() && slot % slots_per_restore_point == 0;
+                    if is_restore_point && max_pending_restore_points == 0 {
+                        self.store_restore_point(&state_root, &state, &mut io_batch);
+                    } else if is_restore_point {
+                        let restore_point_state = state.clone();
+                        let handle = scope.spawn(move || {
+                            let _timer = metrics::start_timer(
+                                &metrics::STORE_RECONSTRUCTION_RESTORE_POINT_ENCODE_TIMES,
+                            );
+                            let mut ops = vec![];
+                            self.store_restore_point(&state_root, &restore_point_state, &mut ops);
+                            ops
+                        });
+                        pending_restore_points.push_back(PendingRestorePoint { slot, handle });
+                    }
+
+                    // If the slot lies on an epoch boundary, commit the batch and update the anchor.
+                    if slot % slots_per_restore_point == 0 || slot + 1 == upper_limit_slot {
+                        info!(
+                            self.log,
+                            "State reconstruction in progress";
+                            "slot" => slot,
+                            "remaining" => upper_limit_slot - 1 - slot
+                        );
+
+                        // Commit the summaries and vectors up to this slot, which do not need to
+                        // wait for restore points as they are only read within the state limits.
+                        self.cold_db.do_atomically(std::mem::take(&mut io_batch))?;
+                        self.set_reconstruction_replayed_slot(slot);
 
-                        self.compare_and_set_anchor_info_with_write(
-                            old_anchor,
-                            Some(anchor.clone()),
-                        )?;
+                        let is_last_slot = slot + 1 == upper_limit_slot;
+
+                        // Commit restore points in order, raising the lower limit as they
+                        // complete. Replay continues while the others are being encoded.
+                        while pending_restore_points.len() > max_pending_restore_points
+                            || (is_last_slot && !pending_restore_points.is_empty())
+                        {
+                            let Some(pending) = pending_restore_points.pop_front() else {
+                                break;
+                            };
+                            let ops = pending
+                                .handle
+                                .join()
+                                .map_err(|_| Error::StateReconstructionWorkerPanicked)?;
+                            self.cold_db.do_atomically(ops)?;
+                            self.raise_reconstruction_lower_limit(&mut anchor, pending.slot)?;
+                        }
+
+                        if is_last_slot {
+                            // The two limits have met in the middle! We're done!
+                            // Perform one last integrity check on the state reached.
+                            let computed_state_root = state.update_tree_hash_cache()?;
+                            if computed_state_root != state_root {
+                                return Err(Error::StateReconstructionRootMismatch {
+                                    slot,
+                                    expected: state_root,
+                                    computed: computed_state_root,
+                                });
+                            }
+
+                            self.compare_and_set_anchor_info_with_write(
+                                Some(anchor.clone()),
+                                None,
+                            )?;
+
+                            return Ok(());
+                        } else if max_pending_restore_points == 0 {
+                            // The lower limit has been raised, store it.
+                            self.raise_reconstruction_lower_limit(&mut anchor, slot)?;
+                        }
                     }
                 }
-            }
 
-            // Should always reach the `upper_limit_slot` and return early above.
-            Err(Error::StateReconstructionDidNotComplete)
+                // Should always reach the `upper_limit_slot` and return early above.
+                Err(Error::StateReconstructionDidNotComplete)
+            })
         })??;
 
         // Check that the split point wasn't mutated during the state reconstruction process.
@@ -165,4 +285,34 @@ where
 
         Ok(())
     }
+
+    /// Persist a new state lower limit, allowing reconstruction to resume from it.
+    fn raise_reconstruction_lower_limit(
+        &self,
+        anchor: &mut AnchorInfo,
+        slot: Slot,
+    ) -> Result<(), Error> {
+        let old_anchor = Some(anchor.clone());
+        anchor.state_lower_limit = slot;
+        self.compare_and_set_anchor_info_with_write(old_anchor, Some(anchor.clone()))?;
+
+        if let Some(progress) = self.reconstruction_progress.write().as_mut() {
+            progress.state_lower_limit = slot;
+        }
+        metrics::set_gauge(
+            &metrics::STORE_RECONSTRUCTION_STATE_LOWER_LIMIT,
+            slot.as_u64() as i64,
+        );
+        Ok(())
+    }
+
+    fn set_reconstruction_replayed_slot(&self, slot: Slot) {
+        if let Some(progress) = self.reconstruction_progress.write().as_mut() {
+            progress.replayed_slot = slot;
+        }
+        metrics::set_gauge(
+            &metrics::STORE_RECONSTRUCTION_REPLAYED_SLOT,
+            slot.as_u64() as i64,
+        );
+    }
 }
@@ -0,0 +1,312 @@
+use super::*;
+use crate::backend::{check_database_backend, REDB_DATA_FILENAME};
+use crate::config::DatabaseBackend;
+use parking_lot::{Mutex, RwLock};
+use redb::{ReadOnlyTable, ReadableTable, TableDefinition};
+use std::marker::PhantomData;
+use std::path::Path;
+
+/// A table containing the keys and values for a single `DBColumn`.
+type Table<'a> = TableDefinition<'a, &'static [u8], &'static [u8]>;
+
+/// A wrapped redb database.
+///
+/// Each column is stored in its own table, keyed by the column-less key.
+pub struct Redb<E: EthSpec> {
+    /// The database, which is only locked for writing during compaction.
+    db: RwLock<redb::Database>,
+    /// A mutex to synchronise sensitive read-write transactions.
+    transaction_mutex: Mutex<()>,
+    _phantom: PhantomData<E>,
+}
+
+impl<E: EthSpec> Redb<E> {
+    /// Open a database in the directory `path`, creating a new database if one does not already
+    /// exist.
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        check_database_backend(path, DatabaseBackend::Redb)?;
+
+        std::fs::create_dir_all(path).map_err(|e| Error::DBError {
+            message: format!("Unable to create database directory {path:?}: {e:?}"),
+        })?;
+        let db = redb::Database::create(path.join(REDB_DATA_FILENAME))?;
+
+        Ok(Self {
+            db: RwLock::new(db),
+            transaction_mutex: Mutex::new(()),
+            _phantom: PhantomData,
+        })
+    }
+
+    fn write_with_durability(
+        &self,
+        ops: impl IntoIterator<Item = KeyValueStoreOp>,
+        durability: redb::Durability,
+    ) -> Result<(), Error> {
+        let mut txn = self.db.read().begin_write()?;
+        txn.set_durability(durability);
+
+        for op in ops {
+            match op {
+                KeyValueStoreOp::PutKeyValue(column_key, value) => {
+                    let (col, key) = split_column_key(&column_key)?;
+                    metrics::inc_counter_vec(&metrics::DISK_DB_WRITE_COUNT, &[col]);
+                    metrics::inc_counter_vec_by(
+                        &metrics::DISK_DB_WRITE_BYTES,
+                        &[col],
+                        value.len() as u64,
+                    );
+
+                    let mut table = txn.open_table(Table::new(col))?;
+                    table.insert(key, value.as_slice())?;
+                }
+                KeyValueStoreOp::DeleteKey(column_key) => {
+                    let (col, key) = split_column_key(&column_key)?;
+                    metrics::inc_counter_vec(&metrics::DISK_DB_DELETE_COUNT, &[col]);
+
+                    let mut table = txn.open_table(Table::new(col))?;
+                    table.remove(key)?;
+                }
+            }
+        }
+
+        let _timer = metrics::start_timer(&metrics::DISK_DB_WRITE_TIMES);
+        txn.commit()?;
+        Ok(())
+    }
+
+    /// Open the table for `col` in a new read transaction.
+    ///
+    /// Return `None` if nothing has ever been written to the column.
+    fn read_table(
+        &self,
+        col: &str,
+    ) -> Result<Option<ReadOnlyTable<&'static [u8], &'static [u8]>>, Error> {
+        let txn = self.db.read().begin_read()?;
+        match txn.open_table(Table::new(col)) {
+            Ok(table) => Ok(Some(table)),
+            Err(redb::TableError::TableDoesNotExist(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Iterate the entries of `column` with keys greater than or equal to `from`.
+    fn iter_entries_from(
+        &self,
+        column: DBColumn,
+        from: &[u8],
+    ) -> impl Iterator<Item = Result<(Vec<u8>, Vec<u8>), Error>> {
+        match self.read_table(column.as_str()) {
+            Ok(Some(table)) => RedbEntryIter {
+                table: Some(table),
+                next_key: Some(from.to_vec()),
+                error: None,
+            },
+            Ok(None) => RedbEntryIter {
+                table: None,
+                next_key: None,
+                error: None,
+            },
+            Err(e) => RedbEntryIter {
+                table: None,
+                next_key: None,
+                error: Some(e),
+            },
+        }
+    }
+}
+
+impl<E: EthSpec> KeyValueStore<E> for Redb<E> {
+    /// Store some `value` in `column`, indexed with `key`.
+    fn put_bytes(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        let op = KeyValueStoreOp::PutKeyValue(get_key_for_col(col, key), val.to_vec());
+        self.write_with_durability([op], redb::Durability::Eventual)
+    }
+
+    fn put_bytes_sync(&self, col: &str, key: &[u8], val: &[u8]) -> Result<(), Error> {
+        let op = KeyValueStoreOp::PutKeyValue(get_key_for_col(col, key), val.to_vec());
+        self.write_with_durability([op], redb::Durability::Immediate)
+    }
+
+    fn sync(&self) -> Result<(), Error> {
+        // An empty commit with immediate durability persists all previous commits.
+        self.write_with_durability([], redb::Durability::Immediate)
+    }
+
+    /// Retrieve some bytes in `column` with `key`.
+    fn get_bytes(&self, col: &str, key: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+        metrics::inc_counter_vec(&metrics::DISK_DB_READ_COUNT, &[col]);
+        let _timer = metrics::start_timer(&metrics::DISK_DB_READ_TIMES);
+
+        let Some(table) = self.read_table(col)? else {
+            return Ok(None);
+        };
+        let value = table
+            .get(key)?
+            .map(|access_guard| access_guard.value().to_vec());
+
+        if let Some(bytes) = &value {
+            metrics::inc_counter_vec_by(&metrics::DISK_DB_READ_BYTES, &[col], bytes.len() as u64);
+        }
+        Ok(value)
+    }
+
+    /// Return `true` if `key` exists in `column`.
+    fn key_exists(&self, col: &str, key: &[u8]) -> Result<bool, Error> {
+        metrics::inc_counter_vec(&metrics::DISK_DB_EXISTS_COUNT, &[col]);
+
+        let Some(table) = self.read_table(col)? else {
+            return Ok(false);
+        };
+        Ok(table.get(key)?.is_some())
+    }
+
+    /// Removes `key` from `column`.
+    fn key_delete(&self, col: &str, key: &[u8]) -> Result<(), Error> {
+        let op = KeyValueStoreOp::DeleteKey(get_key_for_col(col, key));
+        self.write_with_durability([op], redb::Durability::Eventual)
+    }
+
+    fn do_atomically(&self, ops_batch: Vec<KeyValueStoreOp>) -> Result<(), Error> {
+        self.write_with_durability(ops_batch, redb::Durability::Eventual)
+    }
+
+    fn begin_rw_transaction(&self) -> MutexGuard<()> {
+        self.transaction_mutex.lock()
+    }
+
+    /// Compact the whole database, as redb does not support compacting individual tables.
+    ///
+    /// Compaction is skipped if it would conflict with an in-progress transaction.
+    fn compact_column(&self, _column: DBColumn) -> Result<(), Error> {
+        match self.db.write().compact() {
+            Ok(_) | Err(redb::CompactionError::TransactionInProgress) => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn compact(&self) -> Result<(), Error> {
+        self.compact_column(DBColumn::BeaconState)
+    }
+
+    fn iter_column_from<K: Key>(&self, column: DBColumn, from: &[u8]) -> ColumnIter<K> {
+        Box::new(
+            self.iter_entries_from(column, from)
+                .map(|res| res.and_then(|(key, value)| Ok((K::from_bytes(&key)?, value)))),
+        )
+    }
+
+    fn iter_raw_entries(&self, column: DBColumn, prefix: &[u8]) -> RawEntryIter {
+        let prefix = prefix.to_vec();
+        Box::new(
+            self.iter_entries_from(column, &prefix)
+                .take_while(move |res| {
+                    res.as_ref()
+                        .map_or(true, |(key, _)| key.starts_with(&prefix))
+                }),
+        )
+    }
+
+    fn iter_raw_keys(&self, column: DBColumn, prefix: &[u8]) -> RawKeyIter {
+        Box::new(
+            self.iter_raw_entries(column, prefix)
+                .map(|res| res.map(|(key, _)| key)),
+        )
+    }
+
+    /// Iterate through all keys in a particular column.
+    fn iter_column_keys<K: Key>(&self, column: DBColumn) -> ColumnKeyIter<K> {
+        Box::new(
+            self.iter_column_from::<K>(column, &vec![0; column.key_size()])
+                .map(|res| res.map(|(key, _)| key)),
+        )
+    }
+}
+
+impl<E: EthSpec> ItemStore<E> for Redb<E> {}
+
+/// Split a key created by `get_key_for_col` into its column and the key within the column.
+fn split_column_key(column_key: &[u8]) -> Result<(&str, &[u8]), Error> {
+    // All columns have 3-byte names. The `sync` pseudo-column is never written via a batch.
+    if column_key.len() < 3 {
+        return Err(Error::InvalidKey);
+    }
+    let (col, key) = column_key.split_at(3);
+    let col = std::str::from_utf8(col).map_err(|_| Error::InvalidKey)?;
+    Ok((col, key))
+}
+
+/// Forwards iterator over the entries of a single table.
+///
+/// Each step performs a fresh range query starting just after the previous key, so that the
+/// iterator does not need to borrow from the table.
+struct RedbEntryIter {
+    table: Option<ReadOnlyTable<&'static [u8], &'static [u8]>>,
+    /// The smallest key which has not yet been visited.
+    next_key: Option<Vec<u8>>,
+    /// An error from opening the table, to be yielded by the first call to `next`.
+    error: Option<Error>,
+}
+
+impl RedbEntryIter {
+    fn next_entry(&mut self) -> Result<Option<(Vec<u8>, Vec<u8>)>, Error> {
+        let (Some(table), Some(from)) = (&self.table, self.next_key.take()) else {
+            return Ok(None);
+        };
+
+        let range: std::ops::RangeFrom<&[u8]> = from.as_slice()..;
+        let Some(entry) = table.range(range)?.next() else {
+            return Ok(None);
+        };
+        let (key, value) = entry?;
+        let key = key.value().to_vec();
+        let value = value.value().to_vec();
+
+        // Appending a zero byte yields the immediate successor of `key` in lexicographic order.
+        let mut next_key = key.clone();
+        next_key.push(0);
+        self.next_key = Some(next_key);
+
+        Ok(Some((key, value)))
+    }
+}
+
+impl Iterator for RedbEntryIter {
+    type Item = Result<(Vec<u8>, Vec<u8>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(e) = self.error.take() {
+            return Some(Err(e));
+        }
+        self.next_entry().transpose()
+    }
+}
+
+impl From<redb::Error> for Error {
+    fn from(e: redb::Error) -> Error {
+        Error::DBError {
+            message: format!("{:?}", e),
+        }
+    }
+}
+
+macro_rules! impl_from_redb_error {
+    ($($error:ty),*) => {
+        $(
+            impl From<$error> for Error {
+                fn from(e: $error) -> Error {
+                    redb::Error::from(e).into()
+                }
+            }
+        )*
+    };
+}
+
+impl_from_redb_error!(
+    redb::DatabaseError,
+    redb::TransactionError,
+    redb::TableError,
+    redb::StorageError,
+    redb::CommitError,
+    redb::CompactionError
+);
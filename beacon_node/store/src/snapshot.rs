@@ -0,0 +1,290 @@
+//! Point-in-time snapshots of the database, for backing up a running beacon node.
+//!
+//! A snapshot is a directory containing a copy of the hot, freezer and blobs databases, which can
+//! be used as the data directory of a new node or restored with `--restore-from-snapshot`.
+//! Snapshots are always written within the `snapshots` directory of the beacon node's data
+//! directory, so that API clients can't write to arbitrary locations on the host.
+//!
+//! The migrator must be paused while a snapshot is taken, as it is the only writer which deletes
+//! values or writes to the freezer database. Every other write either adds new values (blocks,
+//! states, blobs) or overwrites the metadata which refers to them. The metadata columns are
+//! therefore copied first, so that every value they refer to is present in the snapshot.
+use crate::hot_cold_store::HotColdDB;
+use crate::metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+use crate::{
+    get_key_for_col, BeaconNodeBackend, DBColumn, Error as StoreError, ItemStore, KeyValueStore,
+    KeyValueStoreOp, StoreConfig,
+};
+use serde::{Deserialize, Serialize};
+use slog::info;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use strum::IntoEnumIterator;
+use types::{EthSpec, Slot};
+
+/// Name of the directory holding the hot database, within a snapshot.
+pub const SNAPSHOT_CHAIN_DB: &str = "chain_db";
+/// Name of the directory holding the freezer database, within a snapshot.
+pub const SNAPSHOT_FREEZER_DB: &str = "freezer_db";
+/// Name of the directory holding the blobs database, within a snapshot.
+pub const SNAPSHOT_BLOBS_DB: &str = "blobs_db";
+/// Name of the directory holding snapshots, within the beacon node's data directory.
+pub const SNAPSHOTS_DIR: &str = "snapshots";
+/// Name of the file written to the hot database directory once a snapshot has been restored.
+pub const RESTORED_SNAPSHOT_FILENAME: &str = "restored_from_snapshot";
+
+/// Columns which refer to values in other columns, and must be copied before them.
+const METADATA_COLUMNS: [DBColumn; 5] = [
+    DBColumn::BeaconMeta,
+    DBColumn::BeaconChain,
+    DBColumn::ForkChoice,
+    DBColumn::OpPool,
+    DBColumn::Eth1Cache,
+];
+
+/// Number of bytes to write in each batch when copying a column.
+const SNAPSHOT_BATCH_SIZE_BYTES: usize = 64 * 1024 * 1024;
+
+#[derive(Debug)]
+pub enum Error {
+    Io(io::Error),
+    /// The snapshot directory already contains files, which would be overwritten.
+    TargetNotEmpty(PathBuf),
+    /// The snapshot name is not a plain directory name.
+    InvalidSnapshotName(String),
+    /// The snapshot is missing one of its databases.
+    MissingDatabase(PathBuf),
+    /// The snapshot's hot database does not record a schema version.
+    MissingSchemaVersion,
+    /// The snapshot was taken by a newer version of Lighthouse.
+    UnsupportedSchemaVersion {
+        snapshot: SchemaVersion,
+        current: SchemaVersion,
+    },
+}
+
+/// Summary of a snapshot which has been written to disk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotReport {
+    /// The directory containing the snapshot.
+    pub path: PathBuf,
+    pub schema_version: u64,
+    /// The split slot of the snapshot, below which states are stored in the freezer database.
+    pub split_slot: Slot,
+    /// Number of keys copied across all databases.
+    pub keys: u64,
+    /// Total size of the copied keys and values.
+    pub bytes: u64,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Write a snapshot of the database to the directory `dir`, which must be empty or not exist.
+    ///
+    /// The snapshot is written using the configured database backend. The caller is responsible
+    /// for pausing the migrator until this function returns.
+    pub fn snapshot(&self, dir: &Path) -> Result<SnapshotReport, StoreError> {
+        if dir.exists() && fs::read_dir(dir).map_err(Error::Io)?.next().is_some() {
+            return Err(Error::TargetNotEmpty(dir.to_path_buf()).into());
+        }
+        fs::create_dir_all(dir).map_err(Error::Io)?;
+
+        let split = self.get_split_info();
+        let mut report = SnapshotReport {
+            path: dir.to_path_buf(),
+            schema_version: CURRENT_SCHEMA_VERSION.as_u64(),
+            split_slot: split.slot,
+            keys: 0,
+            bytes: 0,
+        };
+
+        // The blobs and freezer databases are copied last, as the hot database refers to them.
+        // A running database always has the current schema, which is recorded explicitly as
+        // in-memory databases do not store it.
+        let hot_db = BeaconNodeBackend::open(&self.config, &dir.join(SNAPSHOT_CHAIN_DB))?;
+        hot_db.put(&SCHEMA_VERSION_KEY, &CURRENT_SCHEMA_VERSION)?;
+        copy_database(&self.hot_db, &hot_db, &mut report)?;
+        let blobs_db = BeaconNodeBackend::open(&self.config, &dir.join(SNAPSHOT_BLOBS_DB))?;
+        copy_database(&self.blobs_db, &blobs_db, &mut report)?;
+        let cold_db = BeaconNodeBackend::open(&self.config, &dir.join(SNAPSHOT_FREEZER_DB))?;
+        copy_database(&self.cold_db, &cold_db, &mut report)?;
+
+        info!(
+            self.log,
+            "Database snapshot complete";
+            "path" => ?dir,
+            "split_slot" => split.slot,
+            "keys" => report.keys,
+            "bytes" => report.bytes,
+        );
+        Ok(report)
+    }
+}
+
+/// Copy every column of `source` into `target`, starting with the metadata columns.
+fn copy_database<E: EthSpec, S: KeyValueStore<E>>(
+    source: &S,
+    target: &BeaconNodeBackend<E>,
+    report: &mut SnapshotReport,
+) -> Result<(), StoreError> {
+    let columns = METADATA_COLUMNS
+        .into_iter()
+        .chain(DBColumn::iter().filter(|column| !METADATA_COLUMNS.contains(column)));
+
+    for column in columns {
+        let mut batch = vec![];
+        let mut batch_bytes = 0;
+
+        for res in source.iter_raw_entries(column, &[]) {
+            let (key, value) = res?;
+
+            report.keys += 1;
+            report.bytes += (key.len() + value.len()) as u64;
+            batch_bytes += value.len();
+            batch.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(column.as_str(), &key),
+                value,
+            ));
+
+            if batch_bytes >= SNAPSHOT_BATCH_SIZE_BYTES {
+                target.do_atomically(std::mem::take(&mut batch))?;
+                batch_bytes = 0;
+            }
+        }
+        target.do_atomically(batch)?;
+    }
+
+    target.sync()
+}
+
+/// Return the directory of the snapshot `name` within the beacon node's `data_dir`.
+///
+/// Errors unless `name` consists only of alphanumeric characters, `-`, `_` and `.`, and does not
+/// start with `.`, so that the directory can't lie outside of the snapshots directory.
+pub fn snapshot_dir(data_dir: &Path, name: &str) -> Result<PathBuf, Error> {
+    let is_valid = !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if !is_valid {
+        return Err(Error::InvalidSnapshotName(name.to_string()));
+    }
+    Ok(data_dir.join(SNAPSHOTS_DIR).join(name))
+}
+
+/// Returns whether a snapshot has already been restored to the hot database at `hot_path`.
+pub fn is_snapshot_restored(hot_path: &Path) -> bool {
+    hot_path.join(RESTORED_SNAPSHOT_FILENAME).exists()
+}
+
+/// Read the schema version of the snapshot in `dir`.
+///
+/// Errors if the snapshot is incomplete, or was written with a backend other than
+/// `config.backend`.
+pub fn read_snapshot_schema_version<E: EthSpec>(
+    dir: &Path,
+    config: &StoreConfig,
+) -> Result<SchemaVersion, StoreError> {
+    for name in [SNAPSHOT_CHAIN_DB, SNAPSHOT_FREEZER_DB, SNAPSHOT_BLOBS_DB] {
+        let path = dir.join(name);
+        if !path.is_dir() {
+            return Err(Error::MissingDatabase(path).into());
+        }
+    }
+
+    let hot_db = BeaconNodeBackend::<E>::open(config, &dir.join(SNAPSHOT_CHAIN_DB))?;
+    hot_db
+        .get(&SCHEMA_VERSION_KEY)?
+        .ok_or_else(|| Error::MissingSchemaVersion.into())
+}
+
+/// Restore the snapshot in `dir` to the given database paths, which must be empty or not exist.
+///
+/// The snapshot's schema version is checked before any files are copied. Snapshots from older
+/// versions of Lighthouse are migrated to the current schema when the node starts. Once every
+/// database has been copied, a `RESTORED_SNAPSHOT_FILENAME` file is written to `hot_path`, see
+/// `is_snapshot_restored`.
+pub fn restore_snapshot<E: EthSpec>(
+    dir: &Path,
+    config: &StoreConfig,
+    hot_path: &Path,
+    cold_path: &Path,
+    blobs_path: &Path,
+) -> Result<SchemaVersion, StoreError> {
+    let schema_version = read_snapshot_schema_version::<E>(dir, config)?;
+    if schema_version > CURRENT_SCHEMA_VERSION {
+        return Err(Error::UnsupportedSchemaVersion {
+            snapshot: schema_version,
+            current: CURRENT_SCHEMA_VERSION,
+        }
+        .into());
+    }
+
+    let targets = [
+        (SNAPSHOT_CHAIN_DB, hot_path),
+        (SNAPSHOT_FREEZER_DB, cold_path),
+        (SNAPSHOT_BLOBS_DB, blobs_path),
+    ];
+    for (_, target) in targets {
+        if target.exists() && fs::read_dir(target).map_err(Error::Io)?.next().is_some() {
+            return Err(Error::TargetNotEmpty(target.to_path_buf()).into());
+        }
+    }
+    for (name, target) in targets {
+        copy_dir(&dir.join(name), target).map_err(Error::Io)?;
+    }
+    fs::write(
+        hot_path.join(RESTORED_SNAPSHOT_FILENAME),
+        dir.to_string_lossy().as_bytes(),
+    )
+    .map_err(Error::Io)?;
+
+    Ok(schema_version)
+}
+
+/// Recursively copy the directory `source` to `target`.
+fn copy_dir(source: &Path, target: &Path) -> Result<(), io::Error> {
+    fs::create_dir_all(target)?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        let target = target.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_dir_rejects_paths() {
+        let data_dir = Path::new("/data/beacon");
+        assert_eq!(
+            snapshot_dir(data_dir, "2024-09-01_backup.1").unwrap(),
+            data_dir.join(SNAPSHOTS_DIR).join("2024-09-01_backup.1")
+        );
+        for name in [
+            "",
+            ".",
+            "..",
+            "../chain_db",
+            "/tmp/snapshot",
+            "a/b",
+            ".hidden",
+            "a b",
+        ] {
+            assert!(snapshot_dir(data_dir, name).is_err(), "{name:?}");
+        }
+    }
+}
@@ -0,0 +1,235 @@
+//! Statistics about the disk usage of each database column.
+use crate::chunked_vector::{BlockRoots, Field, StateRoots};
+use crate::hot_cold_store::HotColdDB;
+use crate::{DBColumn, Error, HotStateSummary, ItemStore, KeyValueStore, StoreItem};
+use serde::{Deserialize, Serialize};
+use std::cmp::{max, min};
+use std::collections::{HashMap, HashSet};
+use strum::IntoEnumIterator;
+use types::{Epoch, EthSpec, Hash256, Slot};
+
+/// Statistics for a single column of one of the databases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColumnStats {
+    /// The 3-byte column ID (see `DBColumn`).
+    pub column: String,
+    pub keys: u64,
+    /// Total size of the keys and values in the column, excluding the column prefix.
+    pub bytes: u64,
+    /// Lowest slot covered by the column, for columns with slot-indexed keys.
+    pub min_slot: Option<Slot>,
+    /// Highest slot covered by the column, for columns with slot-indexed keys.
+    pub max_slot: Option<Slot>,
+}
+
+/// Estimates of the number of bytes that could be reclaimed by pruning.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PrunableStats {
+    /// Execution payloads of finalized blocks.
+    pub execution_payload_bytes: u64,
+    /// Blobs and data columns of blocks older than the data availability boundary.
+    pub blob_bytes: u64,
+    /// Hot states and summaries older than the split, and temporary states left over from
+    /// block imports that did not complete.
+    pub non_canonical_state_bytes: u64,
+}
+
+/// Statistics for every non-empty column of the hot, freezer and blobs databases.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseStats {
+    pub hot: Vec<ColumnStats>,
+    pub cold: Vec<ColumnStats>,
+    pub blobs: Vec<ColumnStats>,
+    pub prunable: PrunableStats,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Compute statistics for every column of the database.
+    ///
+    /// Blobs from before `data_availability_boundary` are considered prunable. Every key of
+    /// the database is read, so this can take several minutes on a large database.
+    pub fn database_stats(
+        &self,
+        data_availability_boundary: Option<Epoch>,
+    ) -> Result<DatabaseStats, Error> {
+        Ok(DatabaseStats {
+            hot: all_column_stats::<E, _>(&self.hot_db)?,
+            cold: all_column_stats::<E, _>(&self.cold_db)?,
+            blobs: all_column_stats::<E, _>(&self.blobs_db)?,
+            prunable: self.prunable_stats(data_availability_boundary)?,
+        })
+    }
+
+    fn prunable_stats(
+        &self,
+        data_availability_boundary: Option<Epoch>,
+    ) -> Result<PrunableStats, Error> {
+        let split = self.get_split_info();
+        let mut stats = PrunableStats::default();
+
+        // Hot state summaries newer than the split reference all of the unfinalized blocks.
+        let mut unfinalized_block_roots = HashSet::from([split.block_root]);
+        let mut non_canonical_state_roots = HashSet::new();
+        for res in self
+            .hot_db
+            .iter_raw_entries(DBColumn::BeaconStateSummary, &[])
+        {
+            let (key, value) = res?;
+            let summary = HotStateSummary::from_store_bytes(&value)?;
+            if summary.slot < split.slot {
+                stats.non_canonical_state_bytes += (key.len() + value.len()) as u64;
+                non_canonical_state_roots.insert(key);
+            } else {
+                unfinalized_block_roots.insert(summary.latest_block_root);
+            }
+        }
+        for res in self
+            .hot_db
+            .iter_raw_keys(DBColumn::BeaconStateTemporary, &[])
+        {
+            non_canonical_state_roots.insert(res?);
+        }
+        for res in self.hot_db.iter_raw_entries(DBColumn::BeaconState, &[]) {
+            let (key, value) = res?;
+            if non_canonical_state_roots.contains(&key) {
+                stats.non_canonical_state_bytes += (key.len() + value.len()) as u64;
+            }
+        }
+
+        // The payload of the split block is retained by payload pruning.
+        for res in self.hot_db.iter_raw_entries(DBColumn::ExecPayload, &[]) {
+            let (key, value) = res?;
+            if !unfinalized_block_roots.contains(&Hash256::from_slice(&key)) {
+                stats.execution_payload_bytes += (key.len() + value.len()) as u64;
+            }
+        }
+
+        // Only blobs older than the split can be pruned, and blobs within the margin are kept.
+        if let Some(data_availability_boundary) = data_availability_boundary {
+            let margin_epochs = self.get_config().blob_prune_margin_epochs;
+            let prune_end_slot = min(
+                split.slot,
+                data_availability_boundary
+                    .saturating_sub(margin_epochs)
+                    .start_slot(E::slots_per_epoch()),
+            );
+
+            // Data columns are keyed by block root followed by the column index.
+            let mut block_is_prunable = HashMap::new();
+            for column in [DBColumn::BeaconBlob, DBColumn::BeaconDataColumn] {
+                for res in self.blobs_db.iter_raw_entries(column, &[]) {
+                    let (key, value) = res?;
+                    let Some(block_root) = key.get(..32).map(Hash256::from_slice) else {
+                        continue;
+                    };
+                    let prunable = match block_is_prunable.get(&block_root) {
+                        Some(prunable) => *prunable,
+                        None => {
+                            // Blobs without a block are orphaned and can always be pruned.
+                            let prunable = self
+                                .get_blinded_block(&block_root)?
+                                .map_or(true, |block| block.slot() < prune_end_slot);
+                            block_is_prunable.insert(block_root, prunable);
+                            prunable
+                        }
+                    };
+                    if prunable {
+                        stats.blob_bytes += (key.len() + value.len()) as u64;
+                    }
+                }
+            }
+        }
+
+        Ok(stats)
+    }
+}
+
+/// Compute the statistics of every non-empty column of `db`.
+fn all_column_stats<E: EthSpec, S: KeyValueStore<E>>(db: &S) -> Result<Vec<ColumnStats>, Error> {
+    let mut all_stats = vec![];
+    for column in DBColumn::iter() {
+        let mut stats = ColumnStats {
+            column: column.as_str().to_string(),
+            keys: 0,
+            bytes: 0,
+            min_slot: None,
+            max_slot: None,
+        };
+        for res in db.iter_raw_entries(column, &[]) {
+            let (key, value) = res?;
+            stats.keys += 1;
+            stats.bytes += (key.len() + value.len()) as u64;
+
+            if let Some((start_slot, end_slot)) = key_slot_range::<E>(column, &key) {
+                stats.min_slot = Some(stats.min_slot.map_or(start_slot, |s| min(s, start_slot)));
+                stats.max_slot = Some(stats.max_slot.map_or(end_slot, |s| max(s, end_slot)));
+            }
+        }
+        if stats.keys > 0 {
+            all_stats.push(stats);
+        }
+    }
+    Ok(all_stats)
+}
+
+/// Return the range of slots covered by `key`, if the keys of `column` are slot-indexed.
+fn key_slot_range<E: EthSpec>(column: DBColumn, key: &[u8]) -> Option<(Slot, Slot)> {
+    let index = u64::from_be_bytes(key.try_into().ok()?);
+    let chunk_size = match column {
+        DBColumn::BeaconStateSnapshot | DBColumn::BeaconStateDiff => {
+            return Some((Slot::new(index), Slot::new(index)));
+        }
+        DBColumn::BeaconBlockRoots => <BlockRoots as Field<E>>::chunk_size() as u64,
+        DBColumn::BeaconStateRoots => <StateRoots as Field<E>>::chunk_size() as u64,
+        _ => return None,
+    };
+
+    // Chunk keys are shifted up by 1 to make room for the genesis value at key 0.
+    let Some(chunk_index) = index.checked_sub(1) else {
+        return Some((Slot::new(0), Slot::new(0)));
+    };
+    let start_slot = chunk_index.saturating_mul(chunk_size);
+    Some((
+        Slot::new(start_slot),
+        Slot::new(start_slot.saturating_add(chunk_size - 1)),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunked_vector::chunk_key;
+    use types::MainnetEthSpec;
+
+    type E = MainnetEthSpec;
+
+    #[test]
+    fn slot_ranges() {
+        let slot_key = 8192u64.to_be_bytes();
+        assert_eq!(
+            key_slot_range::<E>(DBColumn::BeaconStateDiff, &slot_key),
+            Some((Slot::new(8192), Slot::new(8192)))
+        );
+        assert_eq!(
+            key_slot_range::<E>(DBColumn::BeaconBlockRoots, &chunk_key(2)),
+            Some((Slot::new(256), Slot::new(383)))
+        );
+        assert_eq!(
+            key_slot_range::<E>(DBColumn::BeaconStateRoots, &0u64.to_be_bytes()),
+            Some((Slot::new(0), Slot::new(0)))
+        );
+        assert_eq!(
+            key_slot_range::<E>(DBColumn::BeaconHistoricalRoots, &slot_key),
+            None
+        );
+        assert_eq!(
+            key_slot_range::<E>(DBColumn::BeaconBlock, Hash256::zero().as_slice()),
+            None
+        );
+    }
+}
@@ -0,0 +1,312 @@
+//! Consistency checks for the hot and cold databases.
+use crate::chunked_vector::BlockRoots;
+use crate::forwards_iter::FrozenForwardsIterator;
+use crate::hot_cold_store::{HotColdDB, Split};
+use crate::{DBColumn, Error, HotStateSummary, ItemStore, KeyValueStore, StoreItem, StoreOp};
+use slog::{info, warn};
+use std::cmp::max;
+use std::collections::{HashMap, HashSet};
+use types::{BlindedPayload, Epoch, EthSpec, Hash256, SignedBeaconBlock, Slot};
+
+/// An inconsistency found by `HotColdDB::verify`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inconsistency {
+    /// A block root in the freezer's `BeaconBlockRoots` vector has no block.
+    MissingFrozenBlock { slot: Slot, block_root: Hash256 },
+    /// A hot state summary is older than the split, or its block does not descend from the split.
+    ///
+    /// These summaries are unreachable and can be safely deleted.
+    DanglingStateSummary { state_root: Hash256, slot: Slot },
+    /// A hot state summary refers to an epoch boundary state that does not exist.
+    MissingEpochBoundaryState {
+        state_root: Hash256,
+        slot: Slot,
+        epoch_boundary_state_root: Hash256,
+    },
+    /// A block within the data availability window has commitments but no blobs or columns.
+    MissingBlobs { slot: Slot, block_root: Hash256 },
+    /// The parent of the oldest block does not match `AnchorInfo::oldest_block_parent`.
+    AnchorParentMismatch {
+        oldest_block_slot: Slot,
+        anchor_parent: Hash256,
+        block_parent: Hash256,
+    },
+    /// The block `AnchorInfo::oldest_block_parent` exists although backfill has not reached it.
+    AnchorBehindBackfill {
+        oldest_block_slot: Slot,
+        oldest_block_parent: Hash256,
+    },
+}
+
+impl Inconsistency {
+    /// Whether the inconsistency can be repaired without losing any usable data.
+    pub fn is_repairable(&self) -> bool {
+        matches!(self, Inconsistency::DanglingStateSummary { .. })
+    }
+}
+
+/// The result of verifying the database.
+#[derive(Debug, Default)]
+pub struct VerificationReport {
+    pub inconsistencies: Vec<Inconsistency>,
+    /// Number of inconsistencies that were repaired.
+    pub repaired: usize,
+}
+
+impl<E, Hot, Cold> HotColdDB<E, Hot, Cold>
+where
+    E: EthSpec,
+    Hot: ItemStore<E>,
+    Cold: ItemStore<E>,
+{
+    /// Check that the database is internally consistent.
+    ///
+    /// Blobs are expected for blocks from `data_availability_boundary` onwards (or from the oldest
+    /// blob slot, if later). If `repair` is set then inconsistencies that can be safely repaired
+    /// are fixed, see `Inconsistency::is_repairable`.
+    ///
+    /// This function assumes that the split is stationary while it runs, so it should only be
+    /// used while the beacon node is stopped.
+    pub fn verify(
+        &self,
+        data_availability_boundary: Option<Epoch>,
+        repair: bool,
+    ) -> Result<VerificationReport, Error> {
+        let split = self.get_split_info();
+        let blob_start_slot = data_availability_boundary.and_then(|boundary| {
+            let oldest_blob_slot = self.get_blob_info().oldest_blob_slot?;
+            Some(max(
+                boundary.start_slot(E::slots_per_epoch()),
+                oldest_blob_slot,
+            ))
+        });
+        let mut report = VerificationReport::default();
+
+        self.verify_frozen_blocks(&split, blob_start_slot, &mut report)?;
+        let unfinalized_block_roots = self.verify_state_summaries(&split, repair, &mut report)?;
+
+        // Blocks prior to the split slot were checked along with the frozen block roots.
+        if let Some(blob_start_slot) = blob_start_slot {
+            for block_root in unfinalized_block_roots {
+                let Some(block) = self.get_blinded_block(&block_root)? else {
+                    continue;
+                };
+                if block.slot() >= max(blob_start_slot, split.slot)
+                    && self.blobs_missing(&block_root, &block)?
+                {
+                    report.inconsistencies.push(Inconsistency::MissingBlobs {
+                        slot: block.slot(),
+                        block_root,
+                    });
+                }
+            }
+        }
+
+        info!(
+            self.log,
+            "Database verification complete";
+            "inconsistencies" => report.inconsistencies.len(),
+            "repaired" => report.repaired,
+        );
+        Ok(report)
+    }
+
+    /// Check the blocks of the freezer's block roots vector and the anchor.
+    fn verify_frozen_blocks(
+        &self,
+        split: &Split,
+        blob_start_slot: Option<Slot>,
+        report: &mut VerificationReport,
+    ) -> Result<(), Error> {
+        let anchor = self.get_anchor_info();
+        let oldest_block_slot = self.get_oldest_block_slot();
+
+        if let Some(anchor) = &anchor {
+            if anchor.oldest_block_slot > 0 && self.block_exists(&anchor.oldest_block_parent)? {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::AnchorBehindBackfill {
+                        oldest_block_slot: anchor.oldest_block_slot,
+                        oldest_block_parent: anchor.oldest_block_parent,
+                    });
+            }
+        }
+
+        info!(
+            self.log,
+            "Verifying frozen blocks";
+            "start_slot" => oldest_block_slot,
+            "end_slot" => split.slot,
+        );
+
+        let block_roots = FrozenForwardsIterator::<E, BlockRoots, Hot, Cold>::new(
+            self,
+            oldest_block_slot,
+            split.slot,
+            &self.spec,
+        );
+        let mut prev_block_root = None;
+        for (block_root, slot) in block_roots {
+            // Skipped slots repeat the root of the previous block.
+            if prev_block_root == Some(block_root) {
+                continue;
+            }
+            prev_block_root = Some(block_root);
+
+            let Some(block) = self.get_blinded_block(&block_root)? else {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::MissingFrozenBlock { slot, block_root });
+                continue;
+            };
+
+            if let Some(anchor) = anchor.as_ref().filter(|anchor| {
+                anchor.oldest_block_slot > 0 && anchor.oldest_block_slot == block.slot()
+            }) {
+                if block.parent_root() != anchor.oldest_block_parent {
+                    report
+                        .inconsistencies
+                        .push(Inconsistency::AnchorParentMismatch {
+                            oldest_block_slot: anchor.oldest_block_slot,
+                            anchor_parent: anchor.oldest_block_parent,
+                            block_parent: block.parent_root(),
+                        });
+                }
+            }
+
+            if blob_start_slot.is_some_and(|blob_start_slot| block.slot() >= blob_start_slot)
+                && self.blobs_missing(&block_root, &block)?
+            {
+                report.inconsistencies.push(Inconsistency::MissingBlobs {
+                    slot: block.slot(),
+                    block_root,
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Check that every hot state summary descends from the split, returning the roots of the
+    /// unfinalized blocks that do.
+    fn verify_state_summaries(
+        &self,
+        split: &Split,
+        repair: bool,
+        report: &mut VerificationReport,
+    ) -> Result<HashSet<Hash256>, Error> {
+        info!(self.log, "Verifying hot state summaries");
+
+        let summaries = self
+            .hot_db
+            .iter_raw_entries(DBColumn::BeaconStateSummary, &[])
+            .map(|res| {
+                let (key, value) = res?;
+                let summary = HotStateSummary::from_store_bytes(&value)?;
+                Ok((Hash256::from_slice(&key), summary))
+            })
+            .collect::<Result<HashMap<_, _>, Error>>()?;
+
+        let mut descends_from_split = HashMap::new();
+        let mut delete_ops = vec![];
+        for (state_root, summary) in &summaries {
+            let dangling = summary.slot < split.slot
+                || !self.block_descends_from_split(
+                    summary.latest_block_root,
+                    split,
+                    &mut descends_from_split,
+                )?;
+
+            if dangling {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::DanglingStateSummary {
+                        state_root: *state_root,
+                        slot: summary.slot,
+                    });
+                if repair {
+                    delete_ops.push(StoreOp::DeleteState(*state_root, Some(summary.slot)));
+                }
+            } else if !summaries.contains_key(&summary.epoch_boundary_state_root) {
+                report
+                    .inconsistencies
+                    .push(Inconsistency::MissingEpochBoundaryState {
+                        state_root: *state_root,
+                        slot: summary.slot,
+                        epoch_boundary_state_root: summary.epoch_boundary_state_root,
+                    });
+            }
+        }
+
+        if !delete_ops.is_empty() {
+            warn!(
+                self.log,
+                "Deleting dangling state summaries";
+                "count" => delete_ops.len(),
+            );
+            report.repaired += delete_ops.len();
+            self.do_atomically_with_block_and_blobs_cache(delete_ops)?;
+        }
+
+        Ok(descends_from_split
+            .into_iter()
+            .filter_map(|(block_root, descends)| descends.then_some(block_root))
+            .chain(std::iter::once(split.block_root))
+            .collect())
+    }
+
+    /// Check whether `block_root` is the split block or one of its descendants.
+    ///
+    /// Results are memoized in `cache` for every block visited.
+    fn block_descends_from_split(
+        &self,
+        block_root: Hash256,
+        split: &Split,
+        cache: &mut HashMap<Hash256, bool>,
+    ) -> Result<bool, Error> {
+        let mut path = vec![];
+        let mut current_root = block_root;
+        let descends = loop {
+            if current_root == split.block_root {
+                break true;
+            }
+            if let Some(descends) = cache.get(&current_root) {
+                break *descends;
+            }
+            path.push(current_root);
+            match self.get_blinded_block(&current_root)? {
+                Some(block) if block.slot() > split.slot => current_root = block.parent_root(),
+                _ => break false,
+            }
+        };
+
+        for root in path {
+            cache.insert(root, descends);
+        }
+        Ok(descends)
+    }
+
+    /// Check whether the blobs or data columns for a block with commitments are missing.
+    fn blobs_missing(
+        &self,
+        block_root: &Hash256,
+        block: &SignedBeaconBlock<E, BlindedPayload<E>>,
+    ) -> Result<bool, Error> {
+        let num_commitments = block
+            .message()
+            .body()
+            .blob_kzg_commitments()
+            .map_or(0, |commitments| commitments.len());
+        if num_commitments == 0 {
+            return Ok(false);
+        }
+
+        if self.spec.is_peer_das_enabled_for_epoch(block.epoch()) {
+            Ok(self.get_data_column_keys(*block_root)?.is_empty())
+        } else {
+            Ok(!self
+                .blobs_db
+                .key_exists(DBColumn::BeaconBlob.into(), block_root.as_slice())?)
+        }
+    }
+}
@@ -82,6 +82,82 @@ pub struct Web3SignerDefinition {
     pub client_identity_password: Option<String>,
 }
 
+/// A share of a validator key, which is split between several validator clients so that any
+/// `threshold` of them are required to produce a signature.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdShareDefinition {
+    /// Path to an EIP-2335 keystore containing this validator client's share of the key.
+    pub share_keystore_path: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_keystore_password_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub share_keystore_password: Option<ZeroizeString>,
+    /// The index of this validator client's share, starting at 1.
+    pub share_index: u64,
+    /// The number of shares required to produce a signature.
+    pub threshold: u64,
+    /// The validator clients holding the other shares of the key.
+    pub peers: Vec<ThresholdPeer>,
+    /// Maximum time to wait for partial signatures from peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_timeout_ms: Option<u64>,
+}
+
+/// A validator client holding a share of a validator key.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdPeer {
+    /// The index of the peer's share.
+    pub share_index: u64,
+    /// The public key of the peer's share, used to verify its partial signatures.
+    pub share_public_key: PublicKey,
+    /// The URL of the peer's validator client HTTP API.
+    pub url: String,
+    /// The API token of the peer's validator client HTTP API.
+    pub api_token: ZeroizeString,
+    /// Path to a .pem file, for peers using a self-signed TLS certificate.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root_certificate_path: Option<PathBuf>,
+}
+
+/// A validator whose secret key is held in a PKCS#11 token, such as a hardware security module.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+pub struct Pkcs11Definition {
+    /// Path to the PKCS#11 module (shared library) provided by the token vendor.
+    pub module_path: PathBuf,
+    /// The label of the token holding the key.
+    pub token_label: String,
+    /// The label of the private key object holding the validator's BLS secret key.
+    pub key_label: String,
+    /// The vendor-defined PKCS#11 mechanism with which the token signs using a BLS12-381 key
+    /// (`CKM_VENDOR_DEFINED` or greater).
+    pub mechanism: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_pin_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_pin: Option<ZeroizeString>,
+}
+
+/// Conditions which must be met before the validator client signs messages for a validator, in
+/// addition to slashing protection.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SigningPolicy {
+    /// Refuse to sign blocks containing a full execution payload which does not pay the
+    /// validator's fee recipient.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub require_fee_recipient: bool,
+    /// Refuse to sign blocks containing a blinded execution payload unless it pays the validator's
+    /// fee recipient or one of these addresses, which identify trusted builders.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub builder_fee_recipients: Option<Vec<Address>>,
+    /// Refuse to sign blocks and validator registrations with a gas limit above this value.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_gas_limit: Option<u64>,
+    /// Refuse to sign voluntary exits unless this file exists.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exit_confirmation_path: Option<PathBuf>,
+}
+
 /// Defines how the validator client should attempt to sign messages for this validator.
 #[derive(Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -100,6 +176,13 @@ pub enum SigningDefinition {
     /// https://github.com/ConsenSys/web3signer
     #[serde(rename = "web3signer")]
     Web3Signer(Web3SignerDefinition),
+    /// A validator whose key is split between several validator clients, which exchange partial
+    /// signatures to produce a signature.
+    #[serde(rename = "threshold_share")]
+    ThresholdShare(ThresholdShareDefinition),
+    /// A validator whose secret key is held in a PKCS#11 token.
+    #[serde(rename = "pkcs11")]
+    Pkcs11(Pkcs11Definition),
 }
 
 impl SigningDefinition {
@@ -121,7 +204,9 @@ impl SigningDefinition {
                 .map(Option::Some)
                 .map_err(Error::UnableToReadKeystorePassword),
             SigningDefinition::LocalKeystore { .. } => Err(Error::KeystoreWithoutPassword),
-            SigningDefinition::Web3Signer(_) => Ok(None),
+            SigningDefinition::Web3Signer(_)
+            | SigningDefinition::ThresholdShare(_)
+            | SigningDefinition::Pkcs11(_) => Ok(None),
         }
     }
 
@@ -130,7 +215,11 @@ impl SigningDefinition {
             SigningDefinition::LocalKeystore {
                 voting_keystore_password_path: Some(path),
                 ..
-            } => Some(path),
+            }
+            | SigningDefinition::ThresholdShare(ThresholdShareDefinition {
+                share_keystore_password_path: Some(path),
+                ..
+            }) => Some(path),
             _ => None,
         }
     }
@@ -163,6 +252,9 @@ pub struct ValidatorDefinition {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub prefer_builder_proposals: Option<bool>,
     #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub signing_policy: Option<SigningPolicy>,
+    #[serde(default)]
     pub description: String,
     #[serde(flatten)]
     pub signing_definition: SigningDefinition,
@@ -207,6 +299,7 @@ impl ValidatorDefinition {
             builder_proposals,
             builder_boost_factor,
             prefer_builder_proposals,
+            signing_policy: None,
             signing_definition: SigningDefinition::LocalKeystore {
                 voting_keystore_path,
                 voting_keystore_password_path,
@@ -283,8 +376,10 @@ impl ValidatorDefinitions {
                     voting_keystore_path,
                     ..
                 } => Some(voting_keystore_path),
-                // A Web3Signer validator does not use a local keystore file.
-                SigningDefinition::Web3Signer { .. } => None,
+                // A key share must not be discovered as a validator in its own right.
+                SigningDefinition::ThresholdShare(share) => Some(&share.share_keystore_path),
+                // Web3Signer and PKCS#11 validators do not use a local keystore file.
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Pkcs11(_) => None,
             })
             .collect();
 
@@ -357,6 +452,7 @@ impl ValidatorDefinitions {
                     builder_proposals: None,
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
+                    signing_policy: None,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path,
                         voting_keystore_password_path,
@@ -687,4 +783,97 @@ mod tests {
         let def: ValidatorDefinition = serde_yaml::from_str(valid_builder_proposals).unwrap();
         assert_eq!(def.builder_proposals, Some(true));
     }
+
+    #[test]
+    fn pkcs11_checks() {
+        let pkcs11 = r#"---
+        description: ""
+        enabled: true
+        type: pkcs11
+        module_path: "/usr/lib/softhsm/libsofthsm2.so"
+        token_label: "validators"
+        key_label: "validator-0"
+        mechanism: 0x80000101
+        user_pin_path: "/secrets/pin"
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(pkcs11).unwrap();
+        match def.signing_definition {
+            SigningDefinition::Pkcs11(pkcs11) => {
+                assert_eq!(
+                    pkcs11.module_path,
+                    PathBuf::from("/usr/lib/softhsm/libsofthsm2.so")
+                );
+                assert_eq!(pkcs11.token_label, "validators");
+                assert_eq!(pkcs11.key_label, "validator-0");
+                assert_eq!(pkcs11.mechanism, 0x8000_0101);
+                assert_eq!(pkcs11.user_pin_path, Some(PathBuf::from("/secrets/pin")));
+                assert!(pkcs11.user_pin.is_none());
+            }
+            _ => panic!("expected a PKCS#11 definition"),
+        }
+
+        let missing_key_label = r#"---
+        description: ""
+        enabled: true
+        type: pkcs11
+        module_path: "/usr/lib/softhsm/libsofthsm2.so"
+        token_label: "validators"
+        mechanism: 0x80000101
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: Result<ValidatorDefinition, _> = serde_yaml::from_str(missing_key_label);
+        assert!(def.is_err());
+    }
+
+    #[test]
+    fn signing_policy_checks() {
+        let no_signing_policy = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(no_signing_policy).unwrap();
+        assert!(def.signing_policy.is_none());
+
+        // Misspelt policies must not be silently ignored.
+        let unknown_policy = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        signing_policy:
+          max_gas_limt: 30000000
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: Result<ValidatorDefinition, _> = serde_yaml::from_str(unknown_policy);
+        assert!(def.is_err());
+
+        let valid_signing_policy = r#"---
+        description: ""
+        enabled: true
+        type: local_keystore
+        signing_policy:
+          require_fee_recipient: true
+          builder_fee_recipients: ["0xa2e334e71511686bcfe38bb3ee1ad8f6babcc03d"]
+          max_gas_limit: 30000000
+        voting_keystore_path: ""
+        voting_public_key: "0xaf3c7ddab7e293834710fca2d39d068f884455ede270e0d0293dc818e4f2f0f975355067e8437955cb29aec674e5c9e7"
+        "#;
+        let def: ValidatorDefinition = serde_yaml::from_str(valid_signing_policy).unwrap();
+        assert_eq!(
+            def.signing_policy,
+            Some(SigningPolicy {
+                require_fee_recipient: true,
+                builder_fee_recipients: Some(vec![Address::from_str(
+                    "0xa2e334e71511686bcfe38bb3ee1ad8f6babcc03d"
+                )
+                .unwrap()]),
+                max_gas_limit: Some(30000000),
+                exit_confirmation_path: None,
+            })
+        );
+    }
 }
@@ -12,6 +12,7 @@ pub mod lighthouse;
 #[cfg(feature = "lighthouse")]
 pub mod lighthouse_vc;
 pub mod mixin;
+pub mod trace_context;
 pub mod types;
 
 use self::mixin::{RequestAccept, ResponseOptional};
@@ -24,7 +25,7 @@ use pretty_reqwest_error::PrettyReqwestError;
 pub use reqwest;
 use reqwest::{
     header::{HeaderMap, HeaderValue},
-    Body, IntoUrl, RequestBuilder, Response,
+    Body, IntoUrl, Method, RequestBuilder, Response,
 };
 pub use reqwest::{StatusCode, Url};
 pub use sensitive_url::{SensitiveError, SensitiveUrl};
@@ -199,6 +200,16 @@ impl BeaconNodeHttpClient {
         }
     }
 
+    /// Returns a `RequestBuilder` for `method` and `url`, including the `traceparent` header of the
+    /// current trace, if any.
+    fn request<U: IntoUrl>(&self, method: Method, url: U) -> RequestBuilder {
+        let builder = self.client.request(method, url);
+        match trace_context::current_traceparent() {
+            Some(traceparent) => builder.header(trace_context::TRACEPARENT_HEADER, traceparent),
+            None => builder,
+        }
+    }
+
     /// Return the path with the standard `/eth/vX` prefix applied.
     fn eth_path(&self, version: EndpointVersion) -> Result<Url, Error> {
         let mut path = self.server.full.clone();
@@ -223,7 +234,7 @@ impl BeaconNodeHttpClient {
         url: U,
         builder: impl FnOnce(RequestBuilder) -> RequestBuilder,
     ) -> Result<Response, Error> {
-        let response = builder(self.client.get(url)).send().await?;
+        let response = builder(self.request(Method::GET, url)).send().await?;
         ok_or_error(response).await
     }
 
@@ -387,7 +398,7 @@ impl BeaconNodeHttpClient {
         body: &T,
         timeout: Option<Duration>,
     ) -> Result<Response, Error> {
-        let mut builder = self.client.post(url);
+        let mut builder = self.request(Method::POST, url);
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
@@ -404,7 +415,7 @@ impl BeaconNodeHttpClient {
         timeout: Option<Duration>,
         fork: ForkName,
     ) -> Result<Response, Error> {
-        let mut builder = self.client.post(url);
+        let mut builder = self.request(Method::POST, url);
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
@@ -422,7 +433,7 @@ impl BeaconNodeHttpClient {
         url: U,
         body: &T,
     ) -> Result<Response, Error> {
-        let builder = self.client.post(url);
+        let builder = self.request(Method::POST, url);
         let mut headers = HeaderMap::new();
 
         headers.insert(
@@ -441,7 +452,7 @@ impl BeaconNodeHttpClient {
         timeout: Option<Duration>,
         fork: ForkName,
     ) -> Result<Response, Error> {
-        let mut builder = self.client.post(url);
+        let mut builder = self.request(Method::POST, url);
         if let Some(timeout) = timeout {
             builder = builder.timeout(timeout);
         }
@@ -1784,7 +1795,7 @@ impl BeaconNodeHttpClient {
             .push("node")
             .push("health");
 
-        let status = self.client.get(path).send().await?.status();
+        let status = self.request(Method::GET, path).send().await?.status();
         if status == StatusCode::OK || status == StatusCode::PARTIAL_CONTENT {
             Ok(status)
         } else {
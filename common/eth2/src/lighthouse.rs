@@ -9,7 +9,8 @@ mod sync_committee_rewards;
 
 use crate::{
     types::{
-        DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock, GenericResponse, ValidatorId,
+        DataColumnSubnetId, DepositTreeSnapshot, Epoch, EthSpec, FinalizedExecutionBlock,
+        GenericResponse, SubnetId, SyncSubnetId, ValidatorId,
     },
     BeaconNodeHttpClient, DepositData, Error, Eth1Data, Hash256, Slot,
 };
@@ -27,8 +28,13 @@ pub use block_packing_efficiency::{
     BlockPackingEfficiency, BlockPackingEfficiencyQuery, ProposerInfo, UniqueAttestation,
 };
 pub use block_rewards::{AttestationRewards, BlockReward, BlockRewardMeta, BlockRewardsQuery};
-pub use lighthouse_network::{types::SyncState, PeerInfo};
+pub use lighthouse_network::{
+    types::SyncState, BandwidthReport, PeerGroup, PeerGroupMember, PeerInfo, PeerReputation, Subnet,
+};
 pub use standard_block_rewards::StandardBlockReward;
+pub use store::reconstruct::ReconstructionProgress;
+pub use store::snapshot::SnapshotReport;
+pub use store::stats::{ColumnStats, DatabaseStats, PrunableStats};
 pub use sync_committee_rewards::SyncCommitteeReward;
 
 // Define "legacy" implementations of `Option<T>` which use four bytes for encoding the union
@@ -363,6 +369,94 @@ pub struct DatabaseInfo {
     pub split: Split,
     pub anchor: Option<AnchorInfo>,
     pub blob_info: BlobInfo,
+    /// Progress of historic state reconstruction, if it is running.
+    pub reconstruction: Option<ReconstructionProgress>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DatabaseSnapshotRequest {
+    /// Name of the snapshot, which is written to the `snapshots` directory within the beacon
+    /// node's data directory. A snapshot of the same name must not already exist.
+    pub name: String,
+}
+
+/// Subnet ids, grouped by the kind of subnet.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubnetIds {
+    #[serde(default, with = "serde_utils::quoted_u64_vec")]
+    pub attestation: Vec<u64>,
+    #[serde(default, with = "serde_utils::quoted_u64_vec")]
+    pub sync_committee: Vec<u64>,
+    #[serde(default, with = "serde_utils::quoted_u64_vec")]
+    pub data_column: Vec<u64>,
+}
+
+impl SubnetIds {
+    /// Groups `subnets` by kind, with the ids of each kind sorted and deduplicated.
+    pub fn from_subnets(subnets: impl IntoIterator<Item = Subnet>) -> Self {
+        let mut ids = SubnetIds::default();
+        for subnet in subnets {
+            match subnet {
+                Subnet::Attestation(id) => ids.attestation.push(*id),
+                Subnet::SyncCommittee(id) => ids.sync_committee.push(*id),
+                Subnet::DataColumn(id) => ids.data_column.push(*id),
+            }
+        }
+        for ids in [
+            &mut ids.attestation,
+            &mut ids.sync_committee,
+            &mut ids.data_column,
+        ] {
+            ids.sort_unstable();
+            ids.dedup();
+        }
+        ids
+    }
+
+    pub fn subnets(&self) -> Vec<Subnet> {
+        let attestation = self
+            .attestation
+            .iter()
+            .map(|id| Subnet::Attestation(SubnetId::new(*id)));
+        let sync_committee = self
+            .sync_committee
+            .iter()
+            .map(|id| Subnet::SyncCommittee(SyncSubnetId::new(*id)));
+        let data_column = self
+            .data_column
+            .iter()
+            .map(|id| Subnet::DataColumn(DataColumnSubnetId::new(*id)));
+        attestation
+            .chain(sync_committee)
+            .chain(data_column)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NetworkSubscriptions {
+    /// The subnets pinned via `POST lighthouse/network/subscriptions`.
+    pub pinned: SubnetIds,
+    /// All the subnets whose gossip topics we are subscribed to, for any reason.
+    pub subscribed: SubnetIds,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpdateNetworkSubscriptionsRequest {
+    /// Subnets to subscribe to and advertise until they are unpinned.
+    #[serde(default)]
+    pub subscribe: SubnetIds,
+    /// Pinned subnets to unpin. The node stays subscribed to those required by its validators.
+    #[serde(default)]
+    pub unsubscribe: SubnetIds,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetPeerGroupRequest {
+    pub name: String,
+    /// The multiaddrs of the group's peers, each ending with `/p2p/<peer_id>`. A peer may have
+    /// several addresses. An empty list removes the group.
+    pub peers: Vec<String>,
 }
 
 impl BeaconNodeHttpClient {
@@ -398,6 +492,96 @@ impl BeaconNodeHttpClient {
      * fairly simply achieved, if desired.
      */
 
+    /// `GET lighthouse/peers/reputation`
+    pub async fn get_lighthouse_peers_reputation(&self) -> Result<Vec<PeerReputation>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("reputation");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/peers/groups`
+    pub async fn get_lighthouse_peers_groups(&self) -> Result<Vec<PeerGroup>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("groups");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/peers/groups`
+    pub async fn post_lighthouse_peers_groups(
+        &self,
+        request: &SetPeerGroupRequest,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("peers")
+            .push("groups");
+
+        self.post(path, request).await?;
+
+        Ok(())
+    }
+
+    /// `GET lighthouse/network/bandwidth`
+    pub async fn get_lighthouse_network_bandwidth(&self) -> Result<BandwidthReport, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("bandwidth");
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/network/subscriptions`
+    pub async fn get_lighthouse_network_subscriptions(
+        &self,
+    ) -> Result<NetworkSubscriptions, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("subscriptions");
+
+        self.get(path).await
+    }
+
+    /// `POST lighthouse/network/subscriptions`
+    pub async fn post_lighthouse_network_subscriptions(
+        &self,
+        request: &UpdateNetworkSubscriptionsRequest,
+    ) -> Result<(), Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("network")
+            .push("subscriptions");
+
+        self.post(path, request).await?;
+
+        Ok(())
+    }
+
     /// `GET lighthouse/proto_array`
     pub async fn get_lighthouse_proto_array(&self) -> Result<GenericResponse<ProtoArray>, Error> {
         let mut path = self.server.full.clone();
@@ -515,6 +699,19 @@ impl BeaconNodeHttpClient {
         self.get(path).await
     }
 
+    /// `GET lighthouse/database/stats`
+    pub async fn get_lighthouse_database_stats(&self) -> Result<DatabaseStats, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("stats");
+
+        self.get(path).await
+    }
+
     /// `POST lighthouse/database/reconstruct`
     pub async fn post_lighthouse_database_reconstruct(&self) -> Result<String, Error> {
         let mut path = self.server.full.clone();
@@ -528,6 +725,22 @@ impl BeaconNodeHttpClient {
         self.post_with_response(path, &()).await
     }
 
+    /// `POST lighthouse/database/snapshot`
+    pub async fn post_lighthouse_database_snapshot(
+        &self,
+        request: &DatabaseSnapshotRequest,
+    ) -> Result<SnapshotReport, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("database")
+            .push("snapshot");
+
+        self.post_with_response(path, request).await
+    }
+
     ///
     /// Analysis endpoints.
     ///
@@ -349,6 +349,38 @@ impl ValidatorClientHttpClient {
         self.get_opt(path).await
     }
 
+    /// `GET lighthouse/validators/reload`
+    ///
+    /// Returns `None` if the validator definitions have not been reloaded since startup.
+    pub async fn get_lighthouse_validators_reload(
+        &self,
+    ) -> Result<Option<GenericResponse<ValidatorDefinitionsReload>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("reload");
+
+        self.get_opt(path).await
+    }
+
+    /// `POST lighthouse/validators/reload`
+    pub async fn post_lighthouse_validators_reload(
+        &self,
+    ) -> Result<GenericResponse<ValidatorDefinitionsReload>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("validators")
+            .push("reload");
+
+        self.post(path, &()).await
+    }
+
     /// `POST lighthouse/validators`
     pub async fn post_lighthouse_validators(
         &self,
@@ -446,6 +478,82 @@ impl ValidatorClientHttpClient {
         .await
     }
 
+    /// `GET lighthouse/threshold/partial_signatures/{validator_pubkey}/{signing_root}`
+    ///
+    /// Returns `None` if the validator client has not signed `signing_root` with its share of the
+    /// key.
+    pub async fn get_lighthouse_threshold_partial_signature(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        signing_root: Hash256,
+    ) -> Result<Option<GenericResponse<ThresholdPartialSignature>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("threshold")
+            .push("partial_signatures")
+            .push(&validator_pubkey.to_string())
+            .push(&format!("{:?}", signing_root));
+
+        self.get_opt(path).await
+    }
+
+    /// Append the parameters of `query` to `path`.
+    fn append_slashing_protection_audit_query(
+        path: &mut Url,
+        query: &SlashingProtectionAuditQuery,
+    ) {
+        let mut pairs = path.query_pairs_mut();
+        if let Some(pubkey) = query.pubkey {
+            pairs.append_pair("pubkey", &pubkey.to_string());
+        }
+        if let Some(start_epoch) = query.start_epoch {
+            pairs.append_pair("start_epoch", &start_epoch.to_string());
+        }
+        if let Some(end_epoch) = query.end_epoch {
+            pairs.append_pair("end_epoch", &end_epoch.to_string());
+        }
+        if let Some(limit) = query.limit {
+            pairs.append_pair("limit", &limit.to_string());
+        }
+    }
+
+    /// `GET lighthouse/slashing_protection/history`
+    pub async fn get_lighthouse_slashing_protection_history(
+        &self,
+        query: &SlashingProtectionAuditQuery,
+    ) -> Result<GenericResponse<Vec<SigningHistory>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slashing_protection")
+            .push("history");
+        Self::append_slashing_protection_audit_query(&mut path, query);
+
+        self.get(path).await
+    }
+
+    /// `GET lighthouse/slashing_protection/refusals`
+    pub async fn get_lighthouse_slashing_protection_refusals(
+        &self,
+        query: &SlashingProtectionAuditQuery,
+    ) -> Result<GenericResponse<Vec<RefusedSigningAttempt>>, Error> {
+        let mut path = self.server.full.clone();
+
+        path.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.server.clone()))?
+            .push("lighthouse")
+            .push("slashing_protection")
+            .push("refusals");
+        Self::append_slashing_protection_audit_query(&mut path, query);
+
+        self.get(path).await
+    }
+
     /// `DELETE eth/v1/keystores`
     pub async fn delete_lighthouse_keystores(
         &self,
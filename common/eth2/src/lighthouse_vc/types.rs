@@ -7,6 +7,7 @@ use std::path::PathBuf;
 pub use crate::lighthouse::Health;
 pub use crate::lighthouse_vc::std_types::*;
 pub use crate::types::{GenericResponse, VersionData};
+pub use slashing_protection::{RefusedSigningAttempt, SigningAttemptKind, SigningHistory};
 pub use types::*;
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -198,3 +199,43 @@ pub struct SingleExportKeystoresResponse {
 pub struct SetGraffitiRequest {
     pub graffiti: GraffitiString,
 }
+
+/// A signature produced by one share of a validator key which is split between validator clients.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdPartialSignature {
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub share_index: u64,
+    pub signature: Signature,
+}
+
+/// Selects the records returned by the `lighthouse/slashing_protection` endpoints.
+///
+/// All validators and epochs are selected by default.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SlashingProtectionAuditQuery {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pubkey: Option<PublicKeyBytes>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_epoch: Option<Epoch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_epoch: Option<Epoch>,
+    /// The maximum number of refused signing attempts to return, most recent first.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<usize>,
+}
+
+/// The outcome of reloading `validator_definitions.yml` from disk.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ValidatorDefinitionsReload {
+    /// Seconds since the UNIX epoch at which the reload took place.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timestamp: u64,
+    pub added: Vec<PublicKeyBytes>,
+    pub removed: Vec<PublicKeyBytes>,
+    pub enabled: Vec<PublicKeyBytes>,
+    pub disabled: Vec<PublicKeyBytes>,
+    pub updated: Vec<PublicKeyBytes>,
+    /// The reason the reload failed, in which case the previous definitions remain in use.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
@@ -0,0 +1,100 @@
+//! Propagation of W3C trace context to the beacon node, so that requests can be correlated with
+//! the traces of the caller. The beacon node logs the trace ID of each request it receives.
+//!
+//! https://www.w3.org/TR/trace-context/#traceparent-header
+
+use std::future::Future;
+
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+
+tokio::task_local! {
+    static TRACEPARENT: String;
+}
+
+/// Run `future`, sending `traceparent` in the `traceparent` header of each request it makes with a
+/// `BeaconNodeHttpClient`. If `traceparent` is `None` the future is run unchanged.
+pub async fn with_traceparent<F: Future>(traceparent: Option<String>, future: F) -> F::Output {
+    match traceparent {
+        Some(traceparent) => TRACEPARENT.scope(traceparent, future).await,
+        None => future.await,
+    }
+}
+
+/// Returns the `traceparent` set by the enclosing `with_traceparent`, if any.
+pub fn current_traceparent() -> Option<String> {
+    TRACEPARENT.try_with(Clone::clone).ok()
+}
+
+/// Returns the trace ID of the `traceparent` header value, or `None` if it is not valid.
+pub fn trace_id(traceparent: &str) -> Option<&str> {
+    let is_hex = |field: &str, len: usize| {
+        field.len() == len
+            && field
+                .bytes()
+                .all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    };
+    let is_zero = |field: &str| field.bytes().all(|b| b == b'0');
+
+    let mut fields = traceparent.split('-');
+    let version = fields.next()?;
+    let trace_id = fields.next()?;
+    let parent_id = fields.next()?;
+    let flags = fields.next()?;
+    // Later versions may append fields, but version 0 has exactly four.
+    let is_valid = is_hex(version, 2)
+        && version != "ff"
+        && (version != "00" || fields.next().is_none())
+        && is_hex(trace_id, 32)
+        && !is_zero(trace_id)
+        && is_hex(parent_id, 16)
+        && !is_zero(parent_id)
+        && is_hex(flags, 2);
+    is_valid.then_some(trace_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn scoped_traceparent() {
+        let traceparent = "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01".to_string();
+
+        assert_eq!(current_traceparent(), None);
+        let inner =
+            with_traceparent(Some(traceparent.clone()), async { current_traceparent() }).await;
+        assert_eq!(inner, Some(traceparent));
+        assert_eq!(current_traceparent(), None);
+        assert_eq!(
+            with_traceparent(None, async { current_traceparent() }).await,
+            None
+        );
+    }
+
+    #[test]
+    fn parse_trace_id() {
+        let trace_id = "0af7651916cd43dd8448eb211c80319c";
+        assert_eq!(
+            super::trace_id(&format!("00-{trace_id}-b7ad6b7169203331-01")),
+            Some(trace_id)
+        );
+        assert_eq!(
+            super::trace_id(&format!("01-{trace_id}-b7ad6b7169203331-00-extra")),
+            Some(trace_id)
+        );
+
+        for invalid in [
+            "",
+            "not a traceparent",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331",
+            "00-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01-extra",
+            "ff-0af7651916cd43dd8448eb211c80319c-b7ad6b7169203331-01",
+            "00-0AF7651916CD43DD8448EB211C80319C-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c8031-b7ad6b7169203331-01",
+            "00-00000000000000000000000000000000-b7ad6b7169203331-01",
+            "00-0af7651916cd43dd8448eb211c80319c-0000000000000000-01",
+        ] {
+            assert_eq!(super::trace_id(invalid), None, "{invalid}");
+        }
+    }
+}
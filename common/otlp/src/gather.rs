@@ -0,0 +1,205 @@
+//! Conversion of the Prometheus metrics registry into OTLP metrics.
+
+use crate::types::*;
+use lighthouse_metrics::{Metric as PrometheusMetric, MetricFamily, MetricType};
+
+/// Convert every metric in the registry into an OTLP metric, reporting `start_time_unix_nano` as
+/// the start of the cumulative counters and histograms.
+pub fn gather_metrics(start_time_unix_nano: u64, time_unix_nano: u64) -> Vec<Metric> {
+    lighthouse_metrics::gather()
+        .iter()
+        .filter_map(|mf| convert_metric_family(mf, start_time_unix_nano, time_unix_nano))
+        .collect()
+}
+
+/// Convert a single Prometheus metric family, returning `None` for summaries, which are not
+/// supported.
+pub fn convert_metric_family(
+    mf: &MetricFamily,
+    start_time_unix_nano: u64,
+    time_unix_nano: u64,
+) -> Option<Metric> {
+    let number_data_points = |value: fn(&PrometheusMetric) -> f64| -> Vec<NumberDataPoint> {
+        mf.get_metric()
+            .iter()
+            .map(|metric| NumberDataPoint {
+                attributes: attributes(metric),
+                start_time_unix_nano,
+                time_unix_nano,
+                as_double: value(metric),
+            })
+            .collect()
+    };
+
+    let data = match mf.get_field_type() {
+        MetricType::COUNTER => MetricData::Sum(Sum {
+            data_points: number_data_points(|metric| metric.get_counter().get_value()),
+            aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+            is_monotonic: true,
+        }),
+        MetricType::GAUGE => MetricData::Gauge(Gauge {
+            data_points: number_data_points(|metric| metric.get_gauge().get_value()),
+        }),
+        MetricType::UNTYPED => MetricData::Gauge(Gauge {
+            data_points: number_data_points(|metric| metric.get_untyped().get_value()),
+        }),
+        MetricType::HISTOGRAM => MetricData::Histogram(Histogram {
+            data_points: mf
+                .get_metric()
+                .iter()
+                .map(|metric| histogram_data_point(metric, start_time_unix_nano, time_unix_nano))
+                .collect(),
+            aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+        }),
+        MetricType::SUMMARY => return None,
+    };
+
+    Some(Metric {
+        name: mf.get_name().to_string(),
+        description: mf.get_help().to_string(),
+        data,
+    })
+}
+
+fn attributes(metric: &PrometheusMetric) -> Vec<KeyValue> {
+    metric
+        .get_label()
+        .iter()
+        .map(|label| KeyValue::new(label.get_name(), label.get_value()))
+        .collect()
+}
+
+fn histogram_data_point(
+    metric: &PrometheusMetric,
+    start_time_unix_nano: u64,
+    time_unix_nano: u64,
+) -> HistogramDataPoint {
+    let histogram = metric.get_histogram();
+    let count = histogram.get_sample_count();
+
+    // Prometheus buckets are cumulative and the `+Inf` bucket is implicit, whereas OTLP counts each
+    // bucket separately and includes the overflow bucket.
+    let mut explicit_bounds = Vec::with_capacity(histogram.get_bucket().len());
+    let mut bucket_counts = Vec::with_capacity(histogram.get_bucket().len() + 1);
+    let mut previous = 0;
+    for bucket in histogram.get_bucket() {
+        if bucket.get_upper_bound().is_infinite() {
+            continue;
+        }
+        let cumulative_count = bucket.get_cumulative_count();
+        explicit_bounds.push(bucket.get_upper_bound());
+        bucket_counts.push(cumulative_count.saturating_sub(previous));
+        previous = cumulative_count;
+    }
+    bucket_counts.push(count.saturating_sub(previous));
+
+    HistogramDataPoint {
+        attributes: attributes(metric),
+        start_time_unix_nano,
+        time_unix_nano,
+        count,
+        sum: histogram.get_sample_sum(),
+        bucket_counts,
+        explicit_bounds,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use lighthouse_metrics::{
+        try_create_histogram_with_buckets, try_create_int_counter_vec, try_create_int_gauge,
+    };
+
+    fn find(name: &str) -> MetricFamily {
+        lighthouse_metrics::gather()
+            .into_iter()
+            .find(|mf| mf.get_name() == name)
+            .unwrap()
+    }
+
+    #[test]
+    fn counter_and_gauge() {
+        let counter =
+            try_create_int_counter_vec("otlp_test_counter", "A counter", &["type"]).unwrap();
+        counter.with_label_values(&["a"]).inc_by(3);
+        let gauge = try_create_int_gauge("otlp_test_gauge", "A gauge").unwrap();
+        gauge.set(-2);
+
+        let metric = convert_metric_family(&find("otlp_test_counter"), 1, 2).unwrap();
+        assert_eq!(metric.description, "A counter");
+        assert_eq!(
+            metric.data,
+            MetricData::Sum(Sum {
+                data_points: vec![NumberDataPoint {
+                    attributes: vec![KeyValue::new("type", "a")],
+                    start_time_unix_nano: 1,
+                    time_unix_nano: 2,
+                    as_double: 3.0,
+                }],
+                aggregation_temporality: AGGREGATION_TEMPORALITY_CUMULATIVE,
+                is_monotonic: true,
+            })
+        );
+
+        let metric = convert_metric_family(&find("otlp_test_gauge"), 1, 2).unwrap();
+        let MetricData::Gauge(gauge) = metric.data else {
+            panic!("expected a gauge");
+        };
+        assert_eq!(gauge.data_points[0].as_double, -2.0);
+    }
+
+    #[test]
+    fn histogram_buckets() {
+        let histogram = try_create_histogram_with_buckets(
+            "otlp_test_histogram",
+            "A histogram",
+            Ok(vec![1.0, 2.0]),
+        )
+        .unwrap();
+        for value in [0.5, 0.5, 1.5, 5.0] {
+            histogram.observe(value);
+        }
+
+        let metric = convert_metric_family(&find("otlp_test_histogram"), 1, 2).unwrap();
+        let MetricData::Histogram(histogram) = metric.data else {
+            panic!("expected a histogram");
+        };
+        let data_point = &histogram.data_points[0];
+        assert_eq!(data_point.count, 4);
+        assert_eq!(data_point.sum, 7.5);
+        assert_eq!(data_point.explicit_bounds, vec![1.0, 2.0]);
+        assert_eq!(data_point.bucket_counts, vec![2, 1, 1]);
+    }
+
+    #[test]
+    fn serialization() {
+        let metric = Metric {
+            name: "otlp_test".to_string(),
+            description: String::new(),
+            data: MetricData::Gauge(Gauge {
+                data_points: vec![NumberDataPoint {
+                    attributes: vec![],
+                    start_time_unix_nano: 1,
+                    time_unix_nano: 2,
+                    as_double: 3.0,
+                }],
+            }),
+        };
+        assert_eq!(
+            serde_json::to_value(&metric).unwrap(),
+            serde_json::json!({
+                "name": "otlp_test",
+                "description": "",
+                "gauge": {
+                    "dataPoints": [{
+                        "attributes": [],
+                        "startTimeUnixNano": 1,
+                        "timeUnixNano": 2,
+                        "asDouble": 3.0,
+                    }]
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Pushes metrics and duty traces to an OpenTelemetry collector using OTLP/HTTP with JSON
+//! encoding, as an alternative to scraping the Prometheus metrics server.
+
+mod gather;
+pub mod trace;
+pub mod types;
+
+use gather::gather_metrics;
+use reqwest::{Response, StatusCode};
+use sensitive_url::SensitiveUrl;
+use serde::{Deserialize, Serialize};
+use slog::{debug, error, info};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use task_executor::TaskExecutor;
+use tokio::time::{interval_at, Instant};
+use types::*;
+
+pub use trace::Span;
+
+/// Duration after which we collect and send metrics and traces to the collector.
+pub const DEFAULT_UPDATE_DURATION: u64 = 10;
+/// Timeout for HTTP requests.
+pub const TIMEOUT_DURATION: u64 = 5;
+/// The name of the instrumentation scope reported to the collector.
+pub const SCOPE_NAME: &str = "lighthouse";
+
+#[derive(Debug)]
+pub enum Error {
+    /// The `reqwest` client raised an error.
+    Reqwest(reqwest::Error),
+    /// The supplied URL is badly formatted. It should look something like `http://127.0.0.1:4318`.
+    InvalidUrl(SensitiveUrl),
+    /// The collector returned an error.
+    StatusCode(StatusCode),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self {
+            Error::Reqwest(e) => write!(f, "Reqwest error: {}", e),
+            // Print the debug value
+            e => write!(f, "{:?}", e),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct Config {
+    /// Base URL of the collector's OTLP/HTTP receiver, e.g. `http://127.0.0.1:4318`.
+    pub otlp_endpoint: String,
+    /// Reported to the collector as the `service.name` resource attribute.
+    pub service_name: String,
+    /// User-defined update period in seconds.
+    pub update_period_secs: Option<u64>,
+}
+
+#[derive(Clone)]
+pub struct OtlpHttpClient {
+    client: reqwest::Client,
+    update_period: Duration,
+    otlp_endpoint: SensitiveUrl,
+    service_name: String,
+    /// The time at which the exporter was created, reported as the start of cumulative metrics.
+    start_time_unix_nano: u64,
+    log: slog::Logger,
+}
+
+impl OtlpHttpClient {
+    pub fn new(config: &Config, log: slog::Logger) -> Result<Self, String> {
+        Ok(Self {
+            client: reqwest::Client::new(),
+            update_period: Duration::from_secs(
+                config.update_period_secs.unwrap_or(DEFAULT_UPDATE_DURATION),
+            ),
+            otlp_endpoint: SensitiveUrl::parse(&config.otlp_endpoint)
+                .map_err(|e| format!("Invalid OTLP endpoint: {:?}", e))?,
+            service_name: config.service_name.clone(),
+            start_time_unix_nano: unix_nanos(SystemTime::now()),
+            log,
+        })
+    }
+
+    /// Perform a HTTP POST request to the `signal` path of the collector, i.e. `v1/{signal}`.
+    async fn post<T: Serialize>(&self, signal: &str, body: &T) -> Result<(), Error> {
+        let mut url = self.otlp_endpoint.full.clone();
+        url.path_segments_mut()
+            .map_err(|()| Error::InvalidUrl(self.otlp_endpoint.clone()))?
+            .pop_if_empty()
+            .push("v1")
+            .push(signal);
+
+        let response = self
+            .client
+            .post(url)
+            .json(body)
+            .timeout(Duration::from_secs(TIMEOUT_DURATION))
+            .send()
+            .await
+            .map_err(Error::Reqwest)?;
+        ok_or_error(response)?;
+        Ok(())
+    }
+
+    /// Creates a task which periodically sends metrics and the spans which have ended since the
+    /// last update to the collector.
+    ///
+    /// Spans are only recorded once this function has been called.
+    pub fn auto_update(self, executor: TaskExecutor) {
+        trace::enable();
+
+        let mut interval = interval_at(Instant::now() + self.update_period, self.update_period);
+
+        info!(
+            self.log,
+            "Starting OTLP exporter";
+            "endpoint" => %self.otlp_endpoint,
+            "update_period" => format!("{}s", self.update_period.as_secs()),
+        );
+
+        let update_future = async move {
+            loop {
+                interval.tick().await;
+                if let Err(e) = self.send_metrics().await {
+                    error!(self.log, "Failed to send metrics to OTLP endpoint"; "error" => %e);
+                }
+                if let Err(e) = self.send_traces().await {
+                    error!(self.log, "Failed to send traces to OTLP endpoint"; "error" => %e);
+                }
+            }
+        };
+
+        executor.spawn(update_future, "otlp_exporter");
+    }
+
+    fn resource(&self) -> Resource {
+        Resource {
+            attributes: vec![
+                KeyValue::new("service.name", self.service_name.as_str()),
+                KeyValue::new("service.version", lighthouse_version::VERSION),
+            ],
+        }
+    }
+
+    fn scope() -> InstrumentationScope {
+        InstrumentationScope {
+            name: SCOPE_NAME.to_string(),
+            version: lighthouse_version::VERSION.to_string(),
+        }
+    }
+
+    /// Send the current value of all metrics to the collector.
+    pub async fn send_metrics(&self) -> Result<(), Error> {
+        let metrics = gather_metrics(self.start_time_unix_nano, unix_nanos(SystemTime::now()));
+        let request = ExportMetricsServiceRequest {
+            resource_metrics: vec![ResourceMetrics {
+                resource: self.resource(),
+                scope_metrics: vec![ScopeMetrics {
+                    scope: Self::scope(),
+                    metrics,
+                }],
+            }],
+        };
+        self.post("metrics", &request).await?;
+        debug!(self.log, "Metrics sent to OTLP endpoint"; "endpoint" => %self.otlp_endpoint);
+        Ok(())
+    }
+
+    /// Send the spans which have ended since the last call to the collector. Spans which fail to
+    /// send are discarded.
+    pub async fn send_traces(&self) -> Result<(), Error> {
+        let spans = trace::take_finished_spans();
+        if spans.is_empty() {
+            return Ok(());
+        }
+        let num_spans = spans.len();
+        let request = ExportTraceServiceRequest {
+            resource_spans: vec![ResourceSpans {
+                resource: self.resource(),
+                scope_spans: vec![ScopeSpans {
+                    scope: Self::scope(),
+                    spans,
+                }],
+            }],
+        };
+        self.post("traces", &request).await?;
+        debug!(
+            self.log,
+            "Traces sent to OTLP endpoint";
+            "endpoint" => %self.otlp_endpoint,
+            "spans" => num_spans,
+        );
+        Ok(())
+    }
+}
+
+/// Returns the number of nanoseconds between the Unix epoch and `time`.
+pub(crate) fn unix_nanos(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_nanos() as u64)
+}
+
+/// Returns `Ok(response)` if the response is successful. Otherwise, returns the status code.
+fn ok_or_error(response: Response) -> Result<Response, Error> {
+    let status = response.status();
+    if status.is_success() {
+        Ok(response)
+    } else {
+        Err(Error::StatusCode(status))
+    }
+}
@@ -0,0 +1,179 @@
+//! Spans recording the duration of each stage of a duty.
+//!
+//! Spans are no-ops until tracing is enabled by starting the exporter. Once enabled, each span is
+//! buffered when it is dropped and sent with the next export.
+
+use crate::types::{self, AnyValue, KeyValue, SPAN_KIND_INTERNAL};
+use crate::unix_nanos;
+use parking_lot::Mutex;
+use rand::RngCore;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::LazyLock;
+use std::time::SystemTime;
+
+/// The maximum number of spans buffered between exports. Further spans are discarded, so that
+/// memory usage is bounded if the collector is unavailable.
+pub const MAX_BUFFERED_SPANS: usize = 16_384;
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static FINISHED_SPANS: LazyLock<Mutex<Vec<types::Span>>> = LazyLock::new(|| Mutex::new(vec![]));
+
+pub(crate) fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Returns all spans which have ended since the last call.
+pub(crate) fn take_finished_spans() -> Vec<types::Span> {
+    std::mem::take(&mut *FINISHED_SPANS.lock())
+}
+
+/// A span which ends when it is dropped.
+pub struct Span {
+    trace_id: [u8; 16],
+    span_id: [u8; 8],
+    parent_span_id: Option<[u8; 8]>,
+    name: &'static str,
+    start: SystemTime,
+    attributes: Vec<KeyValue>,
+    error: Option<String>,
+    enabled: bool,
+}
+
+impl Span {
+    /// Start a span in a new trace.
+    pub fn root(name: &'static str) -> Self {
+        let mut trace_id = [0; 16];
+        if ENABLED.load(Ordering::Relaxed) {
+            rand::thread_rng().fill_bytes(&mut trace_id);
+        }
+        Self::new(name, trace_id, None)
+    }
+
+    /// Start a span in the same trace as `self`, as a child of `self`.
+    pub fn child(&self, name: &'static str) -> Self {
+        let mut child = Self::new(name, self.trace_id, Some(self.span_id));
+        child.enabled &= self.enabled;
+        child
+    }
+
+    fn new(name: &'static str, trace_id: [u8; 16], parent_span_id: Option<[u8; 8]>) -> Self {
+        let enabled = ENABLED.load(Ordering::Relaxed);
+        let mut span_id = [0; 8];
+        if enabled {
+            rand::thread_rng().fill_bytes(&mut span_id);
+        }
+        Self {
+            trace_id,
+            span_id,
+            parent_span_id,
+            name,
+            start: SystemTime::now(),
+            attributes: vec![],
+            error: None,
+            enabled,
+        }
+    }
+
+    /// Returns the value of the `traceparent` header which identifies this span to the beacon
+    /// node, or `None` if tracing is disabled.
+    pub fn traceparent(&self) -> Option<String> {
+        self.enabled.then(|| {
+            format!(
+                "00-{}-{}-01",
+                hex::encode(self.trace_id),
+                hex::encode(self.span_id)
+            )
+        })
+    }
+
+    pub fn set_attribute(&mut self, key: &'static str, value: impl Into<AnyValue>) {
+        if self.enabled {
+            self.attributes.push(KeyValue::new(key, value));
+        }
+    }
+
+    /// Mark the span as failed.
+    pub fn set_error(&mut self, error: impl ToString) {
+        if self.enabled {
+            self.error = Some(error.to_string());
+        }
+    }
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if !self.enabled {
+            return;
+        }
+
+        let span = types::Span {
+            trace_id: hex::encode(self.trace_id),
+            span_id: hex::encode(self.span_id),
+            parent_span_id: self.parent_span_id.map(hex::encode).unwrap_or_default(),
+            name: self.name.to_string(),
+            kind: SPAN_KIND_INTERNAL,
+            start_time_unix_nano: unix_nanos(self.start),
+            end_time_unix_nano: unix_nanos(SystemTime::now()),
+            attributes: std::mem::take(&mut self.attributes),
+            status: match self.error.take() {
+                Some(message) => types::Status {
+                    code: types::STATUS_CODE_ERROR,
+                    message,
+                },
+                None => types::Status {
+                    code: types::STATUS_CODE_UNSET,
+                    message: String::new(),
+                },
+            },
+        };
+
+        let mut finished_spans = FINISHED_SPANS.lock();
+        if finished_spans.len() < MAX_BUFFERED_SPANS {
+            finished_spans.push(span);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spans() {
+        // Spans are discarded until tracing is enabled.
+        let root = Span::root("disabled");
+        assert_eq!(root.traceparent(), None);
+        drop(root);
+        assert!(take_finished_spans().is_empty());
+
+        enable();
+        let mut root = Span::root("root");
+        root.set_attribute("slot", 1_u64);
+        let mut child = root.child("child");
+        child.set_error("failed");
+
+        let traceparent = child.traceparent().unwrap();
+        let parts = traceparent.split('-').collect::<Vec<_>>();
+        assert_eq!(parts.len(), 4);
+        assert_eq!(parts[0], "00");
+        assert_eq!(parts[1], hex::encode(root.trace_id));
+        assert_eq!(parts[2], hex::encode(child.span_id));
+        assert_eq!(parts[3], "01");
+
+        drop(child);
+        drop(root);
+        let spans = take_finished_spans();
+        assert_eq!(spans.len(), 2);
+        let (child, root) = (&spans[0], &spans[1]);
+        assert_eq!(child.name, "child");
+        assert_eq!(child.trace_id, root.trace_id);
+        assert_eq!(child.parent_span_id, root.span_id);
+        assert_eq!(child.status.code, types::STATUS_CODE_ERROR);
+        assert_eq!(child.status.message, "failed");
+        assert_eq!(root.parent_span_id, "");
+        assert_eq!(root.attributes, vec![KeyValue::new("slot", 1_u64)]);
+        assert_eq!(root.status.code, types::STATUS_CODE_UNSET);
+        assert!(root.start_time_unix_nano <= root.end_time_unix_nano);
+        assert!(take_finished_spans().is_empty());
+    }
+}
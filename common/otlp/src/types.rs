@@ -0,0 +1,213 @@
+//! The subset of the OTLP/HTTP JSON encoding used by the exporter.
+//!
+//! https://opentelemetry.io/docs/specs/otlp/#json-protobuf-encoding
+
+use serde::Serialize;
+
+/// `AGGREGATION_TEMPORALITY_CUMULATIVE`, as Prometheus counters and histograms are never reset.
+pub const AGGREGATION_TEMPORALITY_CUMULATIVE: u8 = 2;
+pub const SPAN_KIND_INTERNAL: u8 = 1;
+pub const STATUS_CODE_UNSET: u8 = 0;
+pub const STATUS_CODE_ERROR: u8 = 2;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportMetricsServiceRequest {
+    pub resource_metrics: Vec<ResourceMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceMetrics {
+    pub resource: Resource,
+    pub scope_metrics: Vec<ScopeMetrics>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeMetrics {
+    pub scope: InstrumentationScope,
+    pub metrics: Vec<Metric>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportTraceServiceRequest {
+    pub resource_spans: Vec<ResourceSpans>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSpans {
+    pub resource: Resource,
+    pub scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeSpans {
+    pub scope: InstrumentationScope,
+    pub spans: Vec<Span>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Resource {
+    pub attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstrumentationScope {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AnyValue,
+}
+
+impl KeyValue {
+    pub fn new(key: impl Into<String>, value: impl Into<AnyValue>) -> Self {
+        Self {
+            key: key.into(),
+            value: value.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AnyValue {
+    StringValue(String),
+    BoolValue(bool),
+    IntValue(i64),
+    DoubleValue(f64),
+}
+
+impl From<String> for AnyValue {
+    fn from(value: String) -> Self {
+        AnyValue::StringValue(value)
+    }
+}
+
+impl From<&str> for AnyValue {
+    fn from(value: &str) -> Self {
+        AnyValue::StringValue(value.to_string())
+    }
+}
+
+impl From<bool> for AnyValue {
+    fn from(value: bool) -> Self {
+        AnyValue::BoolValue(value)
+    }
+}
+
+impl From<i64> for AnyValue {
+    fn from(value: i64) -> Self {
+        AnyValue::IntValue(value)
+    }
+}
+
+impl From<u64> for AnyValue {
+    fn from(value: u64) -> Self {
+        AnyValue::IntValue(value as i64)
+    }
+}
+
+impl From<usize> for AnyValue {
+    fn from(value: usize) -> Self {
+        AnyValue::IntValue(value as i64)
+    }
+}
+
+impl From<f64> for AnyValue {
+    fn from(value: f64) -> Self {
+        AnyValue::DoubleValue(value)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Metric {
+    pub name: String,
+    pub description: String,
+    #[serde(flatten)]
+    pub data: MetricData,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MetricData {
+    Gauge(Gauge),
+    Sum(Sum),
+    Histogram(Histogram),
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Gauge {
+    pub data_points: Vec<NumberDataPoint>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sum {
+    pub data_points: Vec<NumberDataPoint>,
+    pub aggregation_temporality: u8,
+    pub is_monotonic: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Histogram {
+    pub data_points: Vec<HistogramDataPoint>,
+    pub aggregation_temporality: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberDataPoint {
+    pub attributes: Vec<KeyValue>,
+    pub start_time_unix_nano: u64,
+    pub time_unix_nano: u64,
+    pub as_double: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistogramDataPoint {
+    pub attributes: Vec<KeyValue>,
+    pub start_time_unix_nano: u64,
+    pub time_unix_nano: u64,
+    pub count: u64,
+    pub sum: f64,
+    /// The number of observations in each bucket, *not* cumulative as in Prometheus. There is one
+    /// more bucket than there are `explicit_bounds`, for observations above the last bound.
+    pub bucket_counts: Vec<u64>,
+    pub explicit_bounds: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    /// Hex encoded.
+    pub trace_id: String,
+    /// Hex encoded.
+    pub span_id: String,
+    /// Hex encoded, or empty for a root span.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub parent_span_id: String,
+    pub name: String,
+    pub kind: u8,
+    pub start_time_unix_nano: u64,
+    pub end_time_unix_nano: u64,
+    pub attributes: Vec<KeyValue>,
+    pub status: Status,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Status {
+    pub code: u8,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
@@ -32,6 +32,8 @@ mod get_withdrawal_credentials;
 mod zeroize_hash;
 
 pub mod impls;
+#[cfg(feature = "supranational")]
+pub mod threshold;
 
 pub use generic_public_key::{
     INFINITY_PUBLIC_KEY, PUBLIC_KEY_BYTES_LEN, PUBLIC_KEY_UNCOMPRESSED_BYTES_LEN,
@@ -60,6 +62,10 @@ pub enum Error {
     InvalidInfinityPublicKey,
     /// The secret key is all zero bytes, which is invalid.
     InvalidZeroSecretKey,
+    /// A threshold must be between 1 and the number of shares.
+    InvalidThreshold { threshold: usize, num_shares: usize },
+    /// Share indices must be non-zero and distinct.
+    InvalidShareIndex(u64),
 }
 
 #[cfg(feature = "supranational")]
@@ -0,0 +1,358 @@
+//! Threshold BLS signatures, using Shamir secret sharing of a secret key.
+//!
+//! A secret key is split into `n` shares, any `t` of which can produce a signature that is
+//! indistinguishable from one produced by the original key. Each share signs the message
+//! independently, and the partial signatures are combined by Lagrange interpolation. Public keys
+//! of the shares can be combined in the same way to recover the original public key.
+//!
+//! Share indices start at 1, as the secret key is the value of the sharing polynomial at 0.
+use crate::{Error, PublicKey, SecretKey, Signature, PUBLIC_KEY_BYTES_LEN, SIGNATURE_BYTES_LEN};
+use blst::{
+    blst_bendian_from_scalar, blst_fr, blst_fr_add, blst_fr_from_scalar, blst_fr_from_uint64,
+    blst_fr_inverse, blst_fr_mul, blst_fr_sub, blst_p1, blst_p1_add_or_double, blst_p1_affine,
+    blst_p1_compress, blst_p1_from_affine, blst_p1_mult, blst_p1_uncompress, blst_p2,
+    blst_p2_add_or_double, blst_p2_affine, blst_p2_compress, blst_p2_from_affine, blst_p2_mult,
+    blst_p2_uncompress, blst_scalar, blst_scalar_from_bendian, blst_scalar_from_fr, BLST_ERROR,
+};
+use std::collections::HashSet;
+use zeroize::Zeroize;
+
+/// Number of bits in a scalar of the BLS12-381 field.
+const SCALAR_BITS: usize = 255;
+
+/// A share of a secret key.
+#[derive(Clone)]
+pub struct SecretKeyShare {
+    /// The index of the share, starting at 1.
+    pub index: u64,
+    pub secret_key: SecretKey,
+}
+
+/// Split `secret_key` into `num_shares` shares, any `threshold` of which can produce a signature.
+pub fn split_secret_key(
+    secret_key: &SecretKey,
+    threshold: usize,
+    num_shares: usize,
+) -> Result<Vec<SecretKeyShare>, Error> {
+    if threshold == 0 || threshold > num_shares {
+        return Err(Error::InvalidThreshold {
+            threshold,
+            num_shares,
+        });
+    }
+
+    // The polynomial `f(x) = a_0 + a_1 x + ... + a_{t-1} x^{t-1}` with `f(0)` equal to the
+    // secret key and random coefficients.
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(fr_from_secret_key(secret_key));
+    for _ in 1..threshold {
+        coefficients.push(fr_from_secret_key(&SecretKey::random()));
+    }
+
+    let shares = (1..=num_shares as u64)
+        .map(|index| {
+            let x = fr_from_u64(index);
+            let mut value = coefficients[threshold - 1];
+            for coefficient in coefficients.iter().rev().skip(1) {
+                value = fr_add(&fr_mul(&value, &x), coefficient);
+            }
+            let secret_key = secret_key_from_fr(&value);
+            value.l.zeroize();
+            Ok(SecretKeyShare {
+                index,
+                secret_key: secret_key?,
+            })
+        })
+        .collect();
+
+    for coefficient in &mut coefficients {
+        coefficient.l.zeroize();
+    }
+    shares
+}
+
+/// Combine partial signatures from at least `threshold` distinct shares into a signature.
+///
+/// Each partial signature should be verified against the public key of its share first, as an
+/// invalid partial signature results in an invalid signature.
+pub fn combine_signatures(shares: &[(u64, Signature)]) -> Result<Signature, Error> {
+    let indices = shares.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    let coefficients = lagrange_coefficients(&indices)?;
+
+    let mut combined = blst_p2::default();
+    for ((_, signature), coefficient) in shares.iter().zip(&coefficients) {
+        let mut affine = blst_p2_affine::default();
+        let result = unsafe { blst_p2_uncompress(&mut affine, signature.serialize().as_ptr()) };
+        if result != BLST_ERROR::BLST_SUCCESS {
+            return Err(Error::BlstError(result));
+        }
+        let mut point = blst_p2::default();
+        let mut term = blst_p2::default();
+        let sum = combined;
+        unsafe {
+            blst_p2_from_affine(&mut point, &affine);
+            blst_p2_mult(&mut term, &point, coefficient.b.as_ptr(), SCALAR_BITS);
+            blst_p2_add_or_double(&mut combined, &sum, &term);
+        }
+    }
+
+    let mut bytes = [0; SIGNATURE_BYTES_LEN];
+    unsafe { blst_p2_compress(bytes.as_mut_ptr(), &combined) };
+    Signature::deserialize(&bytes)
+}
+
+/// Combine the public keys of at least `threshold` distinct shares into the public key of the
+/// secret key which was split.
+pub fn combine_public_keys(shares: &[(u64, PublicKey)]) -> Result<PublicKey, Error> {
+    let indices = shares.iter().map(|(index, _)| *index).collect::<Vec<_>>();
+    let coefficients = lagrange_coefficients(&indices)?;
+
+    let mut combined = blst_p1::default();
+    for ((_, public_key), coefficient) in shares.iter().zip(&coefficients) {
+        let mut affine = blst_p1_affine::default();
+        let result = unsafe { blst_p1_uncompress(&mut affine, public_key.serialize().as_ptr()) };
+        if result != BLST_ERROR::BLST_SUCCESS {
+            return Err(Error::BlstError(result));
+        }
+        let mut point = blst_p1::default();
+        let mut term = blst_p1::default();
+        let sum = combined;
+        unsafe {
+            blst_p1_from_affine(&mut point, &affine);
+            blst_p1_mult(&mut term, &point, coefficient.b.as_ptr(), SCALAR_BITS);
+            blst_p1_add_or_double(&mut combined, &sum, &term);
+        }
+    }
+
+    let mut bytes = [0; PUBLIC_KEY_BYTES_LEN];
+    unsafe { blst_p1_compress(bytes.as_mut_ptr(), &combined) };
+    PublicKey::deserialize(&bytes)
+}
+
+/// Combine the public keys of the first `threshold` shares, and check that every other share lies
+/// on the same sharing polynomial.
+///
+/// Any `threshold` shares determine the polynomial, so each remaining share is combined with the
+/// first `threshold - 1` shares. This only recovers the same public key if the share lies on the
+/// polynomial. Returns the combined public key and the indices of the shares which do not.
+pub fn verify_share_public_keys(
+    shares: &[(u64, PublicKey)],
+    threshold: usize,
+) -> Result<(PublicKey, Vec<u64>), Error> {
+    if threshold == 0 || threshold > shares.len() {
+        return Err(Error::InvalidThreshold {
+            threshold,
+            num_shares: shares.len(),
+        });
+    }
+    let mut seen = HashSet::new();
+    for (index, _) in shares {
+        if !seen.insert(*index) {
+            return Err(Error::InvalidShareIndex(*index));
+        }
+    }
+
+    let (base, rest) = shares.split_at(threshold);
+    let public_key = combine_public_keys(base)?;
+
+    let mut subset = base[..threshold - 1].to_vec();
+    let mut invalid = vec![];
+    for share in rest {
+        subset.push(share.clone());
+        if combine_public_keys(&subset)? != public_key {
+            invalid.push(share.0);
+        }
+        subset.pop();
+    }
+    Ok((public_key, invalid))
+}
+
+/// Compute the Lagrange coefficients for interpolating the value at 0 from `indices`.
+fn lagrange_coefficients(indices: &[u64]) -> Result<Vec<blst_scalar>, Error> {
+    if indices.is_empty() {
+        return Err(Error::InvalidThreshold {
+            threshold: 1,
+            num_shares: 0,
+        });
+    }
+    let mut seen = HashSet::new();
+    for &index in indices {
+        if index == 0 || !seen.insert(index) {
+            return Err(Error::InvalidShareIndex(index));
+        }
+    }
+
+    Ok(indices
+        .iter()
+        .map(|&index| {
+            let x_i = fr_from_u64(index);
+            let mut numerator = fr_from_u64(1);
+            let mut denominator = fr_from_u64(1);
+            for &other in indices.iter().filter(|&&other| other != index) {
+                let x_j = fr_from_u64(other);
+                numerator = fr_mul(&numerator, &x_j);
+                denominator = fr_mul(&denominator, &fr_sub(&x_j, &x_i));
+            }
+
+            let mut inverse = blst_fr::default();
+            let mut scalar = blst_scalar::default();
+            unsafe {
+                blst_fr_inverse(&mut inverse, &denominator);
+                blst_scalar_from_fr(&mut scalar, &fr_mul(&numerator, &inverse));
+            }
+            scalar
+        })
+        .collect())
+}
+
+fn fr_add(a: &blst_fr, b: &blst_fr) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe { blst_fr_add(&mut out, a, b) };
+    out
+}
+
+fn fr_sub(a: &blst_fr, b: &blst_fr) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe { blst_fr_sub(&mut out, a, b) };
+    out
+}
+
+fn fr_mul(a: &blst_fr, b: &blst_fr) -> blst_fr {
+    let mut out = blst_fr::default();
+    unsafe { blst_fr_mul(&mut out, a, b) };
+    out
+}
+
+fn fr_from_u64(value: u64) -> blst_fr {
+    let limbs = [value, 0, 0, 0];
+    let mut fr = blst_fr::default();
+    unsafe { blst_fr_from_uint64(&mut fr, limbs.as_ptr()) };
+    fr
+}
+
+fn fr_from_secret_key(secret_key: &SecretKey) -> blst_fr {
+    let mut scalar = blst_scalar::default();
+    let mut fr = blst_fr::default();
+    unsafe {
+        blst_scalar_from_bendian(&mut scalar, secret_key.serialize().as_bytes().as_ptr());
+        blst_fr_from_scalar(&mut fr, &scalar);
+    }
+    scalar.b.zeroize();
+    fr
+}
+
+fn secret_key_from_fr(fr: &blst_fr) -> Result<SecretKey, Error> {
+    let mut scalar = blst_scalar::default();
+    let mut bytes = [0; 32];
+    unsafe {
+        blst_scalar_from_fr(&mut scalar, fr);
+        blst_bendian_from_scalar(bytes.as_mut_ptr(), &scalar);
+    }
+    let secret_key = SecretKey::deserialize(&bytes);
+    scalar.b.zeroize();
+    bytes.zeroize();
+    secret_key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Hash256;
+
+    fn partial_signatures(shares: &[SecretKeyShare], msg: Hash256) -> Vec<(u64, Signature)> {
+        shares
+            .iter()
+            .map(|share| (share.index, share.secret_key.sign(msg)))
+            .collect()
+    }
+
+    #[test]
+    fn any_threshold_subset_signs() {
+        let secret_key = SecretKey::random();
+        let public_key = secret_key.public_key();
+        let msg = Hash256::repeat_byte(42);
+        let shares = split_secret_key(&secret_key, 3, 5).unwrap();
+        let partials = partial_signatures(&shares, msg);
+
+        for subset in [[0, 1, 2], [0, 2, 4], [1, 3, 4], [4, 3, 2]] {
+            let subset_partials = subset.map(|i| partials[i].clone());
+            let signature = combine_signatures(&subset_partials).unwrap();
+            assert_eq!(signature, secret_key.sign(msg));
+            assert!(signature.verify(&public_key, msg));
+        }
+    }
+
+    #[test]
+    fn public_keys_combine() {
+        let secret_key = SecretKey::random();
+        let shares = split_secret_key(&secret_key, 2, 3).unwrap();
+        let public_keys = shares
+            .iter()
+            .map(|share| (share.index, share.secret_key.public_key()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            combine_public_keys(&public_keys[1..]).unwrap(),
+            secret_key.public_key()
+        );
+    }
+
+    #[test]
+    fn every_share_public_key_verified() {
+        let secret_key = SecretKey::random();
+        let shares = split_secret_key(&secret_key, 2, 4).unwrap();
+        let mut public_keys = shares
+            .iter()
+            .map(|share| (share.index, share.secret_key.public_key()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            verify_share_public_keys(&public_keys, 2).unwrap(),
+            (secret_key.public_key(), vec![])
+        );
+
+        // A share beyond the first `threshold` with the wrong public key is detected.
+        public_keys[3].1 = SecretKey::random().public_key();
+        assert_eq!(
+            verify_share_public_keys(&public_keys, 2).unwrap(),
+            (secret_key.public_key(), vec![4])
+        );
+
+        public_keys[3].0 = 1;
+        assert_eq!(
+            verify_share_public_keys(&public_keys, 2),
+            Err(Error::InvalidShareIndex(1))
+        );
+    }
+
+    #[test]
+    fn below_threshold_fails() {
+        let secret_key = SecretKey::random();
+        let msg = Hash256::repeat_byte(7);
+        let shares = split_secret_key(&secret_key, 3, 5).unwrap();
+        let partials = partial_signatures(&shares, msg);
+
+        let signature = combine_signatures(&partials[..2]).unwrap();
+        assert!(!signature.verify(&secret_key.public_key(), msg));
+    }
+
+    #[test]
+    fn invalid_indices() {
+        let secret_key = SecretKey::random();
+        let msg = Hash256::repeat_byte(1);
+        let signature = secret_key.sign(msg);
+
+        assert_eq!(
+            combine_signatures(&[(1, signature.clone()), (1, signature.clone())]),
+            Err(Error::InvalidShareIndex(1))
+        );
+        assert_eq!(
+            combine_signatures(&[(0, signature)]),
+            Err(Error::InvalidShareIndex(0))
+        );
+        assert!(matches!(
+            split_secret_key(&secret_key, 4, 3),
+            Err(Error::InvalidThreshold { .. })
+        ));
+    }
+}
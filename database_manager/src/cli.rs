@@ -3,6 +3,8 @@ use clap_utils::get_color_style;
 use clap_utils::FLAG_HEADER;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
+use store::hdiff::HierarchyConfig;
+use store::DatabaseBackend;
 
 use crate::InspectTarget;
 
@@ -29,6 +31,15 @@ pub struct DatabaseManager {
     )]
     pub slots_per_restore_point: Option<u64>,
 
+    #[clap(
+        long,
+        value_name = "EXPONENTS",
+        help = "Comma-separated exponents of the hierarchical state diff layout used by the \
+                freezer database. Must match the value the database was initialized with.",
+        display_order = 0
+    )]
+    pub hierarchy_exponents: Option<HierarchyConfig>,
+
     #[clap(
         long,
         value_name = "DIR",
@@ -55,6 +66,15 @@ pub struct DatabaseManager {
     )]
     pub blobs_dir: Option<PathBuf>,
 
+    #[clap(
+        long,
+        value_name = "DATABASE",
+        help = "Set the database backend of the beacon node. Must match the backend the \
+                database was created with. [default: leveldb]",
+        display_order = 0
+    )]
+    pub backend: Option<DatabaseBackend>,
+
     #[clap(
         long,
         global = true,
@@ -75,10 +95,16 @@ pub enum DatabaseManagerSubcommand {
     Migrate(Migrate),
     Inspect(Inspect),
     Version(Version),
+    Stats(Stats),
     PrunePayloads(PrunePayloads),
     PruneBlobs(PruneBlobs),
     PruneStates(PruneStates),
     Compact(Compact),
+    Convert(Convert),
+    ExportEra(ExportEra),
+    ImportEra(ImportEra),
+    Verify(Verify),
+    Compress(Compress),
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -91,6 +117,16 @@ pub struct Migrate {
         display_order = 0
     )]
     pub to: u64,
+
+    #[clap(
+        long,
+        value_name = "EXPONENTS",
+        help = "Convert the freezer database from restore points to hierarchical state diffs \
+                with the given comma-separated exponents. Requires migrating to the latest \
+                schema version.",
+        display_order = 0
+    )]
+    pub to_hierarchy_exponents: Option<HierarchyConfig>,
 }
 
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
@@ -161,6 +197,10 @@ pub struct Inspect {
 #[clap(about = "Display database schema version.", visible_aliases = &["v"])]
 pub struct Version {}
 
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Display the number of keys and bytes stored in each database column.")]
+pub struct Stats {}
+
 #[derive(Parser, Clone, Deserialize, Serialize, Debug)]
 #[clap(
     about = "Prune finalized execution payloads.",
@@ -227,3 +267,109 @@ pub struct Compact {
     )]
     pub output_dir: Option<PathBuf>,
 }
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Copy the database into a new database using a different backend.")]
+pub struct Convert {
+    #[clap(
+        long,
+        value_name = "DATABASE",
+        help = "Backend of the existing database",
+        display_order = 0
+    )]
+    pub from: DatabaseBackend,
+
+    #[clap(
+        long,
+        value_name = "DATABASE",
+        help = "Backend of the converted database",
+        display_order = 0
+    )]
+    pub to: DatabaseBackend,
+
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Data directory for the converted database. The hot, freezer and blobs \
+                databases are written to the same subdirectories as in the beacon node's data \
+                directory. Must not contain an existing database.",
+        display_order = 0
+    )]
+    pub output_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "N",
+        default_value_t = 1000,
+        help = "Verify the hash of every Nth value after copying it",
+        display_order = 0
+    )]
+    pub sample_interval: usize,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Export finalized blocks and states to era files.")]
+pub struct ExportEra {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory to write the era files to",
+        display_order = 0
+    )]
+    pub output_dir: PathBuf,
+
+    #[clap(
+        long,
+        value_name = "ERA",
+        default_value_t = 0,
+        help = "First era to export",
+        display_order = 0
+    )]
+    pub start_era: u64,
+
+    #[clap(
+        long,
+        value_name = "ERA",
+        help = "Last era to export. Defaults to the latest era that has been finalized",
+        display_order = 0
+    )]
+    pub end_era: Option<u64>,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Import historic blocks from era files into a checkpoint synced database.")]
+pub struct ImportEra {
+    #[clap(
+        long,
+        value_name = "DIR",
+        help = "Directory containing the era files to import",
+        display_order = 0
+    )]
+    pub input_dir: PathBuf,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Check that the database is internally consistent.")]
+pub struct Verify {
+    #[clap(
+        long,
+        help = "Repair inconsistencies that can be fixed without losing data, such as dangling \
+                state summaries.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub repair: bool,
+}
+
+#[derive(Parser, Clone, Deserialize, Serialize, Debug)]
+#[clap(about = "Compress the existing blocks and restore point states of the database.")]
+pub struct Compress {
+    #[clap(
+        long,
+        help = "Compress without training a dictionary. Values compress less well without a \
+                dictionary, but a dictionary can still be trained by a later run.",
+        display_order = 0,
+        help_heading = FLAG_HEADER
+    )]
+    pub skip_dictionary: bool,
+}
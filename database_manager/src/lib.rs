@@ -9,20 +9,24 @@ use beacon_chain::{
 use beacon_node::{get_data_dir, get_slots_per_restore_point, ClientConfig};
 use clap::ArgMatches;
 use clap::ValueEnum;
-use cli::{Compact, Inspect};
+use cli::{Compact, Compress, Convert, ExportEra, ImportEra, Inspect, Verify};
 use environment::{Environment, RuntimeContext};
+use ethereum_hashing::hash;
 use serde::{Deserialize, Serialize};
 use slog::{info, warn, Logger};
 use std::fs;
 use std::io::Write;
 use std::path::PathBuf;
+use store::compression;
+use store::hdiff::HierarchyConfig;
 use store::metadata::STATE_UPPER_LIMIT_NO_RETAIN;
 use store::{
     errors::Error,
+    get_key_for_col,
     metadata::{SchemaVersion, CURRENT_SCHEMA_VERSION},
-    DBColumn, HotColdDB, KeyValueStore, LevelDB,
+    BeaconNodeBackend, DBColumn, DatabaseBackend, HotColdDB, KeyValueStore, KeyValueStoreOp,
 };
-use strum::{EnumString, EnumVariantNames};
+use strum::{EnumString, EnumVariantNames, IntoEnumIterator};
 use types::{BeaconState, EthSpec, Slot};
 
 fn parse_client_config<E: EthSpec>(
@@ -46,6 +50,13 @@ fn parse_client_config<E: EthSpec>(
     client_config.store.slots_per_restore_point = sprp;
     client_config.store.slots_per_restore_point_set_explicitly = sprp_explicit;
     client_config.store.blob_prune_margin_epochs = database_manager_config.blob_prune_margin_epochs;
+    client_config
+        .store
+        .hierarchy_config
+        .clone_from(&database_manager_config.hierarchy_exponents);
+    if let Some(backend) = database_manager_config.backend {
+        client_config.store.backend = backend;
+    }
 
     Ok(client_config)
 }
@@ -61,7 +72,7 @@ pub fn display_db_version<E: EthSpec>(
     let blobs_path = client_config.get_blobs_db_path();
 
     let mut version = CURRENT_SCHEMA_VERSION;
-    HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
         &hot_path,
         &cold_path,
         &blobs_path,
@@ -87,6 +98,70 @@ pub fn display_db_version<E: EthSpec>(
     Ok(())
 }
 
+pub fn display_db_stats<E: EthSpec>(
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    info!(log, "Computing database statistics, this may take a while");
+
+    // The current epoch is unknown while the beacon node is offline, so the data availability
+    // boundary is approximated from the split.
+    let stats = db.database_stats(db.min_data_availability_boundary())?;
+
+    let format_slot = |slot: Option<Slot>| slot.map_or("-".to_string(), |slot| slot.to_string());
+    for (name, columns) in [
+        ("Hot", &stats.hot),
+        ("Freezer", &stats.cold),
+        ("Blobs", &stats.blobs),
+    ] {
+        println!("{name} database");
+        println!(
+            "{:<8}{:>14}{:>18}{:>12}{:>12}",
+            "column", "keys", "bytes", "min_slot", "max_slot"
+        );
+        for column in columns {
+            println!(
+                "{:<8}{:>14}{:>18}{:>12}{:>12}",
+                column.column,
+                column.keys,
+                column.bytes,
+                format_slot(column.min_slot),
+                format_slot(column.max_slot),
+            );
+        }
+        println!();
+    }
+
+    println!("Estimated prunable bytes");
+    println!(
+        "execution payloads: {}",
+        stats.prunable.execution_payload_bytes
+    );
+    println!("blobs: {}", stats.prunable.blob_bytes);
+    println!(
+        "non-canonical states: {}",
+        stats.prunable.non_canonical_state_bytes
+    );
+
+    Ok(())
+}
+
 #[derive(
     Debug, PartialEq, Eq, Clone, EnumString, Deserialize, Serialize, EnumVariantNames, ValueEnum,
 )]
@@ -151,11 +226,14 @@ pub fn inspect_db<E: EthSpec>(
     let mut num_keys = 0;
 
     let sub_db = if inspect_config.freezer {
-        LevelDB::<E>::open(&cold_path).map_err(|e| format!("Unable to open freezer DB: {e:?}"))?
+        BeaconNodeBackend::<E>::open(&client_config.store, &cold_path)
+            .map_err(|e| format!("Unable to open freezer DB: {e:?}"))?
     } else if inspect_config.blobs_db {
-        LevelDB::<E>::open(&blobs_path).map_err(|e| format!("Unable to open blobs DB: {e:?}"))?
+        BeaconNodeBackend::<E>::open(&client_config.store, &blobs_path)
+            .map_err(|e| format!("Unable to open blobs DB: {e:?}"))?
     } else {
-        LevelDB::<E>::open(&hot_path).map_err(|e| format!("Unable to open hot DB: {e:?}"))?
+        BeaconNodeBackend::<E>::open(&client_config.store, &hot_path)
+            .map_err(|e| format!("Unable to open hot DB: {e:?}"))?
     };
 
     let skip = inspect_config.skip.unwrap_or(0);
@@ -269,11 +347,20 @@ pub fn compact_db<E: EthSpec>(
     let column = compact_config.column;
 
     let (sub_db, db_name) = if compact_config.freezer {
-        (LevelDB::<E>::open(&cold_path)?, "freezer_db")
+        (
+            BeaconNodeBackend::<E>::open(&client_config.store, &cold_path)?,
+            "freezer_db",
+        )
     } else if compact_config.blobs_db {
-        (LevelDB::<E>::open(&blobs_path)?, "blobs_db")
+        (
+            BeaconNodeBackend::<E>::open(&client_config.store, &blobs_path)?,
+            "blobs_db",
+        )
     } else {
-        (LevelDB::<E>::open(&hot_path)?, "hot_db")
+        (
+            BeaconNodeBackend::<E>::open(&client_config.store, &hot_path)?,
+            "hot_db",
+        )
     };
     info!(
         log,
@@ -287,12 +374,17 @@ pub fn compact_db<E: EthSpec>(
 
 pub struct MigrateConfig {
     to: SchemaVersion,
+    to_hierarchy_config: Option<HierarchyConfig>,
 }
 
 fn parse_migrate_config(migrate_config: &Migrate) -> Result<MigrateConfig, String> {
     let to = SchemaVersion(migrate_config.to);
+    let to_hierarchy_config = migrate_config.to_hierarchy_exponents.clone();
 
-    Ok(MigrateConfig { to })
+    Ok(MigrateConfig {
+        to,
+        to_hierarchy_config,
+    })
 }
 
 pub fn migrate_db<E: EthSpec>(
@@ -308,7 +400,7 @@ pub fn migrate_db<E: EthSpec>(
 
     let mut from = CURRENT_SCHEMA_VERSION;
     let to = migrate_config.to;
-    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
         &hot_path,
         &cold_path,
         &blobs_path,
@@ -328,14 +420,33 @@ pub fn migrate_db<E: EthSpec>(
         "to" => to.as_u64(),
     );
 
+    if migrate_config.to_hierarchy_config.is_some() && to != CURRENT_SCHEMA_VERSION {
+        return Err(Error::SchemaMigrationError(format!(
+            "converting to hierarchical state diffs requires migrating to the latest schema \
+             version ({})",
+            CURRENT_SCHEMA_VERSION.as_u64()
+        )));
+    }
+
     migrate_schema::<Witness<SystemTimeSlotClock, CachingEth1Backend<E>, _, _, _>>(
-        db,
+        db.clone(),
         client_config.eth1.deposit_contract_deploy_block,
         from,
         to,
-        log,
+        log.clone(),
         &spec,
-    )
+    )?;
+
+    if let Some(hierarchy_config) = migrate_config.to_hierarchy_config {
+        info!(
+            log,
+            "Converting freezer database to hierarchical state diffs";
+            "exponents" => %hierarchy_config,
+        );
+        db.convert_freezer_to_hierarchy(&hierarchy_config)?;
+    }
+
+    Ok(())
 }
 
 pub fn prune_payloads<E: EthSpec>(
@@ -348,7 +459,7 @@ pub fn prune_payloads<E: EthSpec>(
     let cold_path = client_config.get_freezer_db_path();
     let blobs_path = client_config.get_blobs_db_path();
 
-    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
         &hot_path,
         &cold_path,
         &blobs_path,
@@ -374,7 +485,7 @@ pub fn prune_blobs<E: EthSpec>(
     let cold_path = client_config.get_freezer_db_path();
     let blobs_path = client_config.get_blobs_db_path();
 
-    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
         &hot_path,
         &cold_path,
         &blobs_path,
@@ -411,7 +522,7 @@ pub fn prune_states<E: EthSpec>(
     let cold_path = client_config.get_freezer_db_path();
     let blobs_path = client_config.get_blobs_db_path();
 
-    let db = HotColdDB::<E, LevelDB<E>, LevelDB<E>>::open(
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
         &hot_path,
         &cold_path,
         &blobs_path,
@@ -473,6 +584,455 @@ pub fn prune_states<E: EthSpec>(
     Ok(())
 }
 
+/// Maximum number of value bytes to write in a single batch when converting a database.
+const CONVERT_BATCH_SIZE_BYTES: usize = 64 * 1024 * 1024;
+
+pub struct ConvertConfig {
+    from: DatabaseBackend,
+    to: DatabaseBackend,
+    output_dir: PathBuf,
+    sample_interval: usize,
+}
+
+fn parse_convert_config(convert_config: &Convert) -> Result<ConvertConfig, String> {
+    if convert_config.from == convert_config.to {
+        return Err(format!(
+            "Unable to convert database: source and target backends are both {}",
+            convert_config.to
+        ));
+    }
+    if convert_config.sample_interval == 0 {
+        return Err("Sample interval must be greater than 0".into());
+    }
+    Ok(ConvertConfig {
+        from: convert_config.from,
+        to: convert_config.to,
+        output_dir: convert_config.output_dir.clone(),
+        sample_interval: convert_config.sample_interval,
+    })
+}
+
+pub fn convert_db<E: EthSpec>(
+    convert_config: ConvertConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = runtime_context.eth2_config.spec.clone();
+
+    let mut source_config = client_config.clone();
+    source_config.store.backend = convert_config.from;
+
+    let mut target_config = client_config;
+    target_config.set_data_dir(convert_config.output_dir);
+    target_config.freezer_db_path = None;
+    target_config.blobs_db_path = None;
+    target_config.store.backend = convert_config.to;
+
+    let sub_dbs = [
+        (
+            "hot_db",
+            source_config.get_db_path(),
+            target_config.get_db_path(),
+        ),
+        (
+            "freezer_db",
+            source_config.get_freezer_db_path(),
+            target_config.get_freezer_db_path(),
+        ),
+        (
+            "blobs_db",
+            source_config.get_blobs_db_path(),
+            target_config.get_blobs_db_path(),
+        ),
+    ];
+
+    for (db_name, source_path, target_path) in &sub_dbs {
+        if !source_path.exists() {
+            return Err(format!("No {db_name} found at {source_path:?}"));
+        }
+        if target_path.exists() {
+            return Err(format!(
+                "Refusing to overwrite existing {db_name} at {target_path:?}"
+            ));
+        }
+    }
+
+    info!(
+        log,
+        "Converting database";
+        "from" => %convert_config.from,
+        "to" => %convert_config.to,
+    );
+
+    for (db_name, source_path, target_path) in &sub_dbs {
+        let source = BeaconNodeBackend::<E>::open(&source_config.store, source_path)
+            .map_err(|e| format!("Unable to open source {db_name}: {e:?}"))?;
+        // LevelDB only creates the database directory itself, and not its parents.
+        fs::create_dir_all(target_path)
+            .map_err(|e| format!("Unable to create {target_path:?}: {e:?}"))?;
+        let target = BeaconNodeBackend::<E>::open(&target_config.store, target_path)
+            .map_err(|e| format!("Unable to open target {db_name}: {e:?}"))?;
+        copy_db(
+            &source,
+            &target,
+            db_name,
+            convert_config.sample_interval,
+            &log,
+        )
+        .map_err(|e| format!("Unable to convert {db_name}: {e:?}"))?;
+    }
+
+    // The schema version and config were copied verbatim. Opening the converted database checks
+    // them against the current config and re-writes the on-disk config using the new backend.
+    HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &target_config.get_db_path(),
+        &target_config.get_freezer_db_path(),
+        &target_config.get_blobs_db_path(),
+        |_, _, _| Ok(()),
+        target_config.store.clone(),
+        spec,
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open converted database: {e:?}"))?;
+
+    info!(
+        log,
+        "Database conversion complete";
+        "output_dir" => ?target_config.get_data_dir(),
+    );
+    Ok(())
+}
+
+/// Copy every column of `source` into `target`.
+///
+/// After copying a column the number of keys in `target` is checked, along with the hash of every
+/// `sample_interval`th value.
+fn copy_db<E: EthSpec>(
+    source: &BeaconNodeBackend<E>,
+    target: &BeaconNodeBackend<E>,
+    db_name: &str,
+    sample_interval: usize,
+    log: &Logger,
+) -> Result<(), Error> {
+    for column in DBColumn::iter() {
+        let mut num_keys = 0;
+        let mut total_bytes = 0;
+        let mut samples = vec![];
+        let mut batch = vec![];
+        let mut batch_bytes = 0;
+
+        for res in source.iter_raw_entries(column, &[]) {
+            let (key, value) = res?;
+
+            if num_keys % sample_interval == 0 {
+                samples.push((key.clone(), hash(&value)));
+            }
+            num_keys += 1;
+            total_bytes += value.len();
+            batch_bytes += value.len();
+            batch.push(KeyValueStoreOp::PutKeyValue(
+                get_key_for_col(column.as_str(), &key),
+                value,
+            ));
+
+            if batch_bytes >= CONVERT_BATCH_SIZE_BYTES {
+                target.do_atomically(std::mem::take(&mut batch))?;
+                batch_bytes = 0;
+            }
+        }
+        target.do_atomically(batch)?;
+
+        if num_keys == 0 {
+            continue;
+        }
+
+        let copied_keys = target
+            .iter_raw_keys(column, &[])
+            .try_fold(0, |count, res| res.map(|_| count + 1))?;
+        if copied_keys != num_keys {
+            return Err(Error::DBError {
+                message: format!(
+                    "{column:?} has {copied_keys} keys after conversion, expected {num_keys}"
+                ),
+            });
+        }
+
+        for (key, expected_hash) in &samples {
+            let copied_hash = target
+                .get_bytes(column.as_str(), key)?
+                .map(|value| hash(&value));
+            if copied_hash.as_ref() != Some(expected_hash) {
+                return Err(Error::DBError {
+                    message: format!(
+                        "{column:?} value for key 0x{} does not match after conversion",
+                        hex::encode(key)
+                    ),
+                });
+            }
+        }
+
+        info!(
+            log,
+            "Copied column";
+            "db" => db_name,
+            "column" => ?column,
+            "keys" => num_keys,
+            "bytes" => total_bytes,
+            "samples_verified" => samples.len(),
+        );
+    }
+
+    target.sync()
+}
+
+pub struct ExportEraConfig {
+    output_dir: PathBuf,
+    start_era: u64,
+    end_era: Option<u64>,
+}
+
+fn parse_export_era_config(export_era_config: &ExportEra) -> Result<ExportEraConfig, String> {
+    Ok(ExportEraConfig {
+        output_dir: export_era_config.output_dir.clone(),
+        start_era: export_era_config.start_era,
+        end_era: export_era_config.end_era,
+    })
+}
+
+pub fn export_era<E: EthSpec>(
+    export_era_config: ExportEraConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec.clone(),
+        log.clone(),
+    )?;
+
+    // The state at the end of an era must be in the freezer.
+    let slots_per_era = E::slots_per_historical_root() as u64;
+    let latest_era = db.get_split_slot().as_u64().saturating_sub(1) / slots_per_era;
+    let end_era = export_era_config.end_era.unwrap_or(latest_era);
+    let config_name = spec.config_name.as_deref().unwrap_or("unknown");
+
+    fs::create_dir_all(&export_era_config.output_dir).map_err(|e| Error::DBError {
+        message: format!("Unable to create output directory: {e:?}"),
+    })?;
+
+    info!(
+        log,
+        "Exporting era files";
+        "start_era" => export_era_config.start_era,
+        "end_era" => end_era,
+    );
+
+    for era in export_era_config.start_era..=end_era {
+        let path = db.export_era(era, config_name, &export_era_config.output_dir)?;
+        info!(log, "Exported era"; "era" => era, "path" => ?path);
+    }
+
+    Ok(())
+}
+
+pub struct ImportEraConfig {
+    input_dir: PathBuf,
+}
+
+fn parse_import_era_config(import_era_config: &ImportEra) -> Result<ImportEraConfig, String> {
+    Ok(ImportEraConfig {
+        input_dir: import_era_config.input_dir.clone(),
+    })
+}
+
+pub fn import_era<E: EthSpec>(
+    import_era_config: ImportEraConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    // Era numbers are zero-padded, so sorting by name orders the files by era. Blocks must be
+    // imported backwards from the oldest known block, so import the newest era first.
+    let mut paths = fs::read_dir(&import_era_config.input_dir)
+        .and_then(|entries| {
+            entries
+                .map(|entry| entry.map(|entry| entry.path()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .map_err(|e| Error::DBError {
+            message: format!("Unable to read input directory: {e:?}"),
+        })?;
+    paths.retain(|path| path.extension().is_some_and(|extension| extension == "era"));
+    paths.sort();
+
+    let mut total_blocks = 0;
+    for path in paths.iter().rev() {
+        if db.get_oldest_block_slot() == 0 {
+            break;
+        }
+        total_blocks += db.import_era(path)?;
+    }
+
+    info!(
+        log,
+        "Finished importing era files";
+        "blocks" => total_blocks,
+        "oldest_block_slot" => db.get_oldest_block_slot(),
+    );
+
+    Ok(())
+}
+
+pub struct VerifyConfig {
+    repair: bool,
+}
+
+fn parse_verify_config(verify_config: &Verify) -> Result<VerifyConfig, String> {
+    Ok(VerifyConfig {
+        repair: verify_config.repair,
+    })
+}
+
+pub fn verify_db<E: EthSpec>(
+    verify_config: VerifyConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), String> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )
+    .map_err(|e| format!("Unable to open database: {e:?}"))?;
+
+    // The current epoch is unknown while the beacon node is offline, so the data availability
+    // boundary is approximated from the split.
+    let report = db
+        .verify(db.min_data_availability_boundary(), verify_config.repair)
+        .map_err(|e| format!("Error verifying database: {e:?}"))?;
+
+    for inconsistency in &report.inconsistencies {
+        warn!(
+            log,
+            "Database inconsistency";
+            "inconsistency" => ?inconsistency,
+            "repairable" => inconsistency.is_repairable(),
+        );
+    }
+
+    let unrepaired = report.inconsistencies.len() - report.repaired;
+    if unrepaired > 0 {
+        if !verify_config.repair
+            && report
+                .inconsistencies
+                .iter()
+                .any(|inconsistency| inconsistency.is_repairable())
+        {
+            warn!(
+                log,
+                "Some inconsistencies can be repaired";
+                "info" => "re-run this command with --repair",
+            );
+        }
+        return Err(format!(
+            "Database verification found {unrepaired} unrepaired inconsistencies"
+        ));
+    }
+
+    info!(log, "Database is consistent"; "repaired" => report.repaired);
+    Ok(())
+}
+
+pub struct CompressConfig {
+    train_dictionary: bool,
+}
+
+fn parse_compress_config(compress_config: &Compress) -> Result<CompressConfig, String> {
+    Ok(CompressConfig {
+        train_dictionary: !compress_config.skip_dictionary,
+    })
+}
+
+pub fn compress_db<E: EthSpec>(
+    compress_config: CompressConfig,
+    client_config: ClientConfig,
+    runtime_context: &RuntimeContext<E>,
+    log: Logger,
+) -> Result<(), Error> {
+    let spec = runtime_context.eth2_config.spec.clone();
+    let hot_path = client_config.get_db_path();
+    let cold_path = client_config.get_freezer_db_path();
+    let blobs_path = client_config.get_blobs_db_path();
+
+    let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+        &hot_path,
+        &cold_path,
+        &blobs_path,
+        |_, _, _| Ok(()),
+        client_config.store,
+        spec,
+        log.clone(),
+    )?;
+
+    if compress_config.train_dictionary {
+        match db.train_compression_dictionary() {
+            Ok(_) => (),
+            Err(Error::Compression(compression::Error::DictionaryExists)) => {
+                info!(log, "Using existing compression dictionary");
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    info!(log, "Compressing database, this may take a while");
+    let report = db.compress_existing_values()?;
+
+    info!(
+        log,
+        "Database compression complete";
+        "compressed" => report.compressed,
+        "bytes_saved" => report.bytes_before.saturating_sub(report.bytes_after),
+        "info" => "run the beacon node with --database-compression to compress new values",
+    );
+    Ok(())
+}
+
 /// Run the database manager, returning an error string if the operation did not succeed.
 pub fn run<E: EthSpec>(
     cli_args: &ArgMatches,
@@ -496,6 +1056,9 @@ pub fn run<E: EthSpec>(
         cli::DatabaseManagerSubcommand::Version(_) => {
             display_db_version(client_config, &context, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::Stats(_) => {
+            display_db_stats(client_config, &context, log).map_err(format_err)
+        }
         cli::DatabaseManagerSubcommand::PrunePayloads(_) => {
             prune_payloads(client_config, &context, log).map_err(format_err)
         }
@@ -530,5 +1093,206 @@ pub fn run<E: EthSpec>(
             let compact_config = parse_compact_config(compact_config)?;
             compact_db::<E>(compact_config, client_config, log).map_err(format_err)
         }
+        cli::DatabaseManagerSubcommand::Convert(convert_config) => {
+            let convert_config = parse_convert_config(convert_config)?;
+            convert_db(convert_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::ExportEra(export_era_config) => {
+            let export_era_config = parse_export_era_config(export_era_config)?;
+            export_era(export_era_config, client_config, &context, log).map_err(format_err)
+        }
+        cli::DatabaseManagerSubcommand::ImportEra(import_era_config) => {
+            let import_era_config = parse_import_era_config(import_era_config)?;
+            import_era(import_era_config, client_config, &context, log).map_err(format_err)
+        }
+        cli::DatabaseManagerSubcommand::Verify(verify_config) => {
+            let verify_config = parse_verify_config(verify_config)?;
+            verify_db(verify_config, client_config, &context, log)
+        }
+        cli::DatabaseManagerSubcommand::Compress(compress_config) => {
+            let compress_config = parse_compress_config(compress_config)?;
+            compress_db(compress_config, client_config, &context, log).map_err(format_err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use environment::EnvironmentBuilder;
+    use std::collections::BTreeMap;
+    use std::path::Path;
+    use tempfile::tempdir;
+    use types::MinimalEthSpec;
+
+    type E = MinimalEthSpec;
+
+    /// The key/value pairs of every column, for each of the hot, cold and blobs databases.
+    type Contents = Vec<BTreeMap<&'static str, Vec<(Vec<u8>, Vec<u8>)>>>;
+
+    fn environment() -> Environment<E> {
+        EnvironmentBuilder::minimal()
+            .test_logger()
+            .unwrap()
+            .multi_threaded_tokio_runtime()
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    fn client_config(data_dir: &Path, backend: DatabaseBackend) -> ClientConfig {
+        let mut config = ClientConfig::default();
+        config.set_data_dir(data_dir.to_path_buf());
+        config.store.backend = backend;
+        config
+    }
+
+    fn convert_config(
+        from: DatabaseBackend,
+        to: DatabaseBackend,
+        output_dir: &Path,
+    ) -> ConvertConfig {
+        ConvertConfig {
+            from,
+            to,
+            output_dir: output_dir.to_path_buf(),
+            sample_interval: 1,
+        }
+    }
+
+    /// Create a LevelDB database in `data_dir`, with several entries in every column of the hot,
+    /// cold and blobs databases.
+    fn create_db(data_dir: &Path, context: &RuntimeContext<E>) {
+        let config = client_config(data_dir, DatabaseBackend::LevelDb);
+        config.create_db_path().unwrap();
+        config.create_freezer_db_path().unwrap();
+        config.create_blobs_db_path().unwrap();
+        let db = HotColdDB::<E, BeaconNodeBackend<E>, BeaconNodeBackend<E>>::open(
+            &config.get_db_path(),
+            &config.get_freezer_db_path(),
+            &config.get_blobs_db_path(),
+            |_, _, _| Ok(()),
+            config.store.clone(),
+            context.eth2_config.spec.clone(),
+            context.log().clone(),
+        )
+        .unwrap();
+
+        for (db_index, kv_store) in [&db.hot_db, &db.cold_db, &db.blobs_db]
+            .into_iter()
+            .enumerate()
+        {
+            for (column_index, column) in DBColumn::iter().enumerate() {
+                let ops = (0..3u8)
+                    .map(|i| {
+                        let mut key = vec![0; column.key_size()];
+                        key[..3].copy_from_slice(&[db_index as u8, column_index as u8, i]);
+                        let value = vec![i; 1000 * i as usize + 1];
+                        KeyValueStoreOp::PutKeyValue(get_key_for_col(column.as_str(), &key), value)
+                    })
+                    .collect();
+                kv_store.do_atomically(ops).unwrap();
+            }
+        }
+    }
+
+    /// Read the contents of the database in `data_dir`.
+    fn read_db(data_dir: &Path, backend: DatabaseBackend) -> Contents {
+        let config = client_config(data_dir, backend);
+        [
+            config.get_db_path(),
+            config.get_freezer_db_path(),
+            config.get_blobs_db_path(),
+        ]
+        .iter()
+        .map(|path| {
+            let kv_store = BeaconNodeBackend::<E>::open(&config.store, path).unwrap();
+            DBColumn::iter()
+                .map(|column| {
+                    let entries = kv_store
+                        .iter_raw_entries(column, &[])
+                        .collect::<Result<_, _>>()
+                        .unwrap();
+                    (column.as_str(), entries)
+                })
+                .collect()
+        })
+        .collect()
+    }
+
+    #[test]
+    fn convert_round_trip() {
+        let env = environment();
+        let context = env.core_context();
+        let leveldb_dir = tempdir().unwrap();
+        let redb_dir = tempdir().unwrap();
+        let round_trip_dir = tempdir().unwrap();
+        create_db(leveldb_dir.path(), &context);
+        let contents = read_db(leveldb_dir.path(), DatabaseBackend::LevelDb);
+        assert!(contents
+            .iter()
+            .all(|columns| columns.values().all(|entries| entries.len() >= 3)));
+
+        convert_db(
+            convert_config(
+                DatabaseBackend::LevelDb,
+                DatabaseBackend::Redb,
+                redb_dir.path(),
+            ),
+            client_config(leveldb_dir.path(), DatabaseBackend::LevelDb),
+            &context,
+            context.log().clone(),
+        )
+        .unwrap();
+        assert_eq!(read_db(redb_dir.path(), DatabaseBackend::Redb), contents);
+
+        convert_db(
+            convert_config(
+                DatabaseBackend::Redb,
+                DatabaseBackend::LevelDb,
+                round_trip_dir.path(),
+            ),
+            client_config(redb_dir.path(), DatabaseBackend::Redb),
+            &context,
+            context.log().clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            read_db(round_trip_dir.path(), DatabaseBackend::LevelDb),
+            contents
+        );
+    }
+
+    #[test]
+    fn convert_refuses_existing_target() {
+        let env = environment();
+        let context = env.core_context();
+        let leveldb_dir = tempdir().unwrap();
+        let redb_dir = tempdir().unwrap();
+        create_db(leveldb_dir.path(), &context);
+
+        // A non-empty freezer database is already present in the target directory.
+        let target_config = client_config(redb_dir.path(), DatabaseBackend::Redb);
+        fs::create_dir_all(target_config.get_freezer_db_path()).unwrap();
+        let existing_file = target_config.get_freezer_db_path().join("existing");
+        fs::write(&existing_file, b"existing").unwrap();
+
+        let result = convert_db(
+            convert_config(
+                DatabaseBackend::LevelDb,
+                DatabaseBackend::Redb,
+                redb_dir.path(),
+            ),
+            client_config(leveldb_dir.path(), DatabaseBackend::LevelDb),
+            &context,
+            context.log().clone(),
+        );
+        assert!(result
+            .unwrap_err()
+            .starts_with("Refusing to overwrite existing freezer_db"));
+
+        // Nothing was written to the target.
+        assert!(!target_config.get_db_path().exists());
+        assert_eq!(fs::read(&existing_file).unwrap(), b"existing");
     }
 }
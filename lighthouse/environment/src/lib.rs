@@ -356,6 +356,7 @@ impl<E: EthSpec> EnvironmentBuilder<E> {
             eth_spec_instance: self.eth_spec_instance,
             eth2_config: self.eth2_config,
             eth2_network_config: self.eth2_network_config.map(Arc::new),
+            shutdown_on_sighup: true,
         })
     }
 }
@@ -375,6 +376,8 @@ pub struct Environment<E: EthSpec> {
     eth_spec_instance: E,
     pub eth2_config: Eth2Config,
     pub eth2_network_config: Option<Arc<Eth2NetworkConfig>>,
+    /// If false, SIGHUP is left for services to handle rather than requesting a shutdown.
+    shutdown_on_sighup: bool,
 }
 
 impl<E: EthSpec> Environment<E> {
@@ -386,6 +389,11 @@ impl<E: EthSpec> Environment<E> {
         &self.runtime
     }
 
+    /// Sets whether receiving SIGHUP should shut down the process, which is the default.
+    pub fn set_shutdown_on_sighup(&mut self, shutdown_on_sighup: bool) {
+        self.shutdown_on_sighup = shutdown_on_sighup;
+    }
+
     /// Returns a `Context` where no "service" has been added to the logger output.
     pub fn core_context(&self) -> RuntimeContext<E> {
         RuntimeContext {
@@ -455,10 +463,13 @@ impl<E: EthSpec> Environment<E> {
 
             // setup for handling a SIGHUP
             match signal(SignalKind::hangup()) {
-                Ok(hup_stream) => {
+                Ok(hup_stream) if self.shutdown_on_sighup => {
                     let hup = SignalFuture::new(hup_stream, "Received SIGHUP");
                     handles.push(hup);
                 }
+                // The handler is still registered so that SIGHUP doesn't terminate the process
+                // before the services handling it have started.
+                Ok(_) => info!(self.log, "SIGHUP will not shut down the process"),
                 Err(e) => error!(self.log, "Could not register SIGHUP handler"; "error" => e),
             }
 
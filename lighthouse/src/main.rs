@@ -744,6 +744,11 @@ fn run<E: EthSpec>(
                 return Ok(());
             }
 
+            // SIGHUP triggers a reload of the validator definitions instead.
+            if config.reload_validator_definitions {
+                environment.set_shutdown_on_sighup(false);
+            }
+
             executor.clone().spawn(
                 async move {
                     if let Err(e) = ProductionValidatorClient::new(context, config)
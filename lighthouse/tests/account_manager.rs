@@ -495,6 +495,7 @@ fn validator_import_launchpad() {
         builder_boost_factor: None,
         prefer_builder_proposals: None,
         voting_public_key: keystore.public_key().unwrap(),
+        signing_policy: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
@@ -619,6 +620,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
         builder_boost_factor: None,
         prefer_builder_proposals: None,
         voting_public_key: keystore.public_key().unwrap(),
+        signing_policy: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
@@ -647,6 +649,7 @@ fn validator_import_launchpad_no_password_then_add_password() {
         builder_boost_factor: None,
         prefer_builder_proposals: None,
         voting_public_key: keystore.public_key().unwrap(),
+        signing_policy: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path: dst_keystore_dir.join(KEYSTORE_NAME),
             voting_keystore_password_path: None,
@@ -750,6 +753,7 @@ fn validator_import_launchpad_password_file() {
         builder_proposals: None,
         builder_boost_factor: None,
         prefer_builder_proposals: None,
+        signing_policy: None,
         signing_definition: SigningDefinition::LocalKeystore {
             voting_keystore_path,
             voting_keystore_password_path: None,
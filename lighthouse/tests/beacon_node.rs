@@ -10,6 +10,7 @@ use beacon_processor::BeaconProcessorConfig;
 use eth1::Eth1Endpoint;
 use lighthouse_network::PeerId;
 use lighthouse_version;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
@@ -307,6 +308,17 @@ fn freezer_dir_flag() {
         .with_config(|config| assert_eq!(config.freezer_db_path, Some(dir.path().to_path_buf())));
 }
 
+#[test]
+fn restore_from_snapshot_flag() {
+    let dir = TempDir::new().expect("Unable to create temporary directory");
+    CommandLineTest::new()
+        .flag("restore-from-snapshot", dir.path().as_os_str().to_str())
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.restore_from_snapshot, Some(dir.path().to_path_buf()))
+        });
+}
+
 #[test]
 fn graffiti_flag() {
     CommandLineTest::new()
@@ -1870,6 +1882,122 @@ fn historic_state_cache_size_default() {
         });
 }
 #[test]
+fn hierarchy_exponents_flag() {
+    use beacon_node::beacon_chain::store::hdiff::HierarchyConfig;
+    CommandLineTest::new()
+        .flag("hierarchy-exponents", Some("5,7,11"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.hierarchy_config,
+                Some(HierarchyConfig {
+                    exponents: vec![5, 7, 11]
+                })
+            )
+        });
+}
+#[test]
+fn hierarchy_exponents_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.hierarchy_config, None));
+}
+#[test]
+fn hdiff_buffer_cache_size_flag() {
+    CommandLineTest::new()
+        .flag("hdiff-buffer-cache-size", Some("2"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(config.store.hdiff_buffer_cache_size, new_non_zero_usize(2))
+        });
+}
+#[test]
+fn hdiff_buffer_cache_size_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_HDIFF_BUFFER_CACHE_SIZE;
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.hdiff_buffer_cache_size,
+                DEFAULT_HDIFF_BUFFER_CACHE_SIZE
+            );
+        });
+}
+#[test]
+fn historic_state_retention_flags() {
+    use beacon_node::beacon_chain::store::StateRetention;
+    CommandLineTest::new()
+        .flag("historic-state-retention-epochs", Some("1024"))
+        .flag("historic-state-retention-interval", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.state_retention,
+                Some(StateRetention {
+                    epochs: 1024,
+                    restore_point_interval: 4,
+                })
+            )
+        });
+}
+#[test]
+fn historic_state_retention_interval_default() {
+    use beacon_node::beacon_chain::store::StateRetention;
+    CommandLineTest::new()
+        .flag("historic-state-retention-epochs", Some("1024"))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.state_retention,
+                Some(StateRetention {
+                    epochs: 1024,
+                    restore_point_interval: 0,
+                })
+            )
+        });
+}
+#[test]
+fn historic_state_retention_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.state_retention, None));
+}
+#[test]
+fn beacon_node_backend_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_BACKEND;
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.backend, DEFAULT_BACKEND));
+}
+#[test]
+fn beacon_node_backend_override() {
+    use beacon_node::beacon_chain::store::DatabaseBackend;
+    CommandLineTest::new()
+        .flag("beacon-node-backend", Some("leveldb"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.backend, DatabaseBackend::LevelDb));
+}
+#[test]
+#[should_panic]
+fn beacon_node_backend_disabled() {
+    CommandLineTest::new()
+        .flag("beacon-node-backend", Some("disabled"))
+        .run_with_zero_port();
+}
+#[test]
+fn database_compression_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(!config.store.compression));
+}
+#[test]
+fn database_compression_flag() {
+    CommandLineTest::new()
+        .flag("database-compression", None)
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.store.compression));
+}
+#[test]
 fn auto_compact_db_flag() {
     CommandLineTest::new()
         .flag("auto-compact-db", Some("false"))
@@ -1950,6 +2078,26 @@ fn no_reconstruct_historic_states_flag() {
         .with_config(|config| assert!(!config.chain.reconstruct_historic_states));
 }
 #[test]
+fn reconstruction_threads_default() {
+    use beacon_node::beacon_chain::store::config::DEFAULT_RECONSTRUCTION_THREADS;
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.store.reconstruction_threads,
+                DEFAULT_RECONSTRUCTION_THREADS
+            )
+        });
+}
+#[test]
+fn reconstruction_threads_flag() {
+    CommandLineTest::new()
+        .flag("reconstruct-historic-states", None)
+        .flag("reconstruction-threads", Some("4"))
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.store.reconstruction_threads.get(), 4));
+}
+#[test]
 fn epochs_per_migration_default() {
     CommandLineTest::new()
         .run_with_zero_port()
@@ -2556,6 +2704,61 @@ fn invalid_gossip_verified_blocks_path() {
         });
 }
 
+#[test]
+fn gossipsub_trace_dir_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert_eq!(config.network.gossipsub_trace_dir, None));
+}
+
+#[test]
+fn gossipsub_trace_dir() {
+    let path = "/tmp/gossipsub-traces";
+    CommandLineTest::new()
+        .flag("gossipsub-trace-dir", Some(path))
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.gossipsub_trace_dir,
+                Some(PathBuf::from(path))
+            )
+        });
+}
+
+#[test]
+fn gossip_bandwidth_caps_default() {
+    CommandLineTest::new()
+        .run_with_zero_port()
+        .with_config(|config| assert!(config.network.gossip_bandwidth_caps.is_empty()));
+}
+
+#[test]
+fn gossip_bandwidth_caps() {
+    CommandLineTest::new()
+        .flag(
+            "gossip-bandwidth-caps",
+            Some("data_column_sidecar=4096,blob_sidecar=2048"),
+        )
+        .run_with_zero_port()
+        .with_config(|config| {
+            assert_eq!(
+                config.network.gossip_bandwidth_caps,
+                HashMap::from([
+                    ("data_column_sidecar".to_string(), 4096 * 1024 * 1024),
+                    ("blob_sidecar".to_string(), 2048 * 1024 * 1024),
+                ])
+            )
+        });
+}
+
+#[test]
+#[should_panic]
+fn gossip_bandwidth_caps_invalid_topic_kind() {
+    CommandLineTest::new()
+        .flag("gossip-bandwidth-caps", Some("data_columns=4096"))
+        .run_with_zero_port();
+}
+
 #[test]
 fn beacon_processor() {
     CommandLineTest::new()
@@ -0,0 +1,426 @@
+//! A mock PKCS#11 module, for testing signing with validator keys held in a PKCS#11 token.
+//!
+//! Software tokens such as SoftHSM do not provide a BLS mechanism, so this module emulates a token
+//! which does. It implements just enough of the PKCS#11 API for the validator client to find a key
+//! and sign with it:
+//!
+//! - A single slot (`0`) containing a token labelled `lighthouse-mock`, with the user PIN `1234`.
+//! - The private keys `validator` and `other`, which are the interop keypairs with indices 0 and 1.
+//! - The mechanism `CKM_VENDOR_DEFINED + 0x424c53`, which signs a 32-byte signing root and returns
+//!   the compressed BLS signature.
+//!
+//! As with a real token, logging in applies to all sessions with the token and lasts until the last
+//! session is closed, and private keys can only be found and used while logged in.
+
+#![allow(non_snake_case)]
+
+use bls::{Hash256, SecretKey, SIGNATURE_BYTES_LEN};
+use cryptoki_sys::*;
+use std::collections::BTreeMap;
+use std::sync::{Mutex, MutexGuard, OnceLock, PoisonError};
+
+const SLOT: CK_SLOT_ID = 0;
+const TOKEN_LABEL: &str = "lighthouse-mock";
+const USER_PIN: &[u8] = b"1234";
+const MECHANISM: CK_MECHANISM_TYPE = CKM_VENDOR_DEFINED + 0x424c53;
+/// The label and interop keypair index of each private key, whose handle is its index plus one.
+const KEYS: [(&str, usize); 2] = [("validator", 0), ("other", 1)];
+
+static TOKEN: Mutex<Token> = Mutex::new(Token {
+    initialized: false,
+    logged_in: false,
+    next_session: 1,
+    sessions: BTreeMap::new(),
+});
+
+static FUNCTION_LIST: OnceLock<CK_FUNCTION_LIST> = OnceLock::new();
+
+struct Token {
+    initialized: bool,
+    logged_in: bool,
+    next_session: CK_SESSION_HANDLE,
+    sessions: BTreeMap<CK_SESSION_HANDLE, Session>,
+}
+
+#[derive(Default)]
+struct Session {
+    /// The handles of the objects found by the active search, which have not been returned yet.
+    found: Option<Vec<CK_OBJECT_HANDLE>>,
+    /// The handle of the key of the active signing operation.
+    signing_key: Option<CK_OBJECT_HANDLE>,
+}
+
+/// Lock the token, and return the session with the handle `session` if the module is initialized.
+fn with_session(session: CK_SESSION_HANDLE, f: impl FnOnce(&mut Session, bool) -> CK_RV) -> CK_RV {
+    let mut token = lock_token();
+    if !token.initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    let logged_in = token.logged_in;
+    match token.sessions.get_mut(&session) {
+        Some(session) => f(session, logged_in),
+        None => CKR_SESSION_HANDLE_INVALID,
+    }
+}
+
+fn lock_token() -> MutexGuard<'static, Token> {
+    TOKEN.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn secret_key(key: CK_OBJECT_HANDLE) -> Option<SecretKey> {
+    let index = usize::try_from(key).ok()?.checked_sub(1)?;
+    let (_, keypair_index) = KEYS.get(index)?;
+    Some(eth2_interop_keypairs::keypair(*keypair_index).sk)
+}
+
+/// Copy `s` into the blank-padded field `field`.
+fn blank_padded(field: &mut [CK_UTF8CHAR], s: &str) {
+    field.fill(b' ');
+    field[..s.len()].copy_from_slice(s.as_bytes());
+}
+
+/// Write `values` to the array `list` of length `*count`, or only their number if `list` is null.
+///
+/// # Safety
+///
+/// `count` must be valid, and `list` must be null or valid for `*count` writes.
+unsafe fn write_list(list: *mut CK_ULONG, count: CK_ULONG_PTR, values: &[CK_ULONG]) -> CK_RV {
+    if count.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let capacity = *count;
+    *count = values.len() as CK_ULONG;
+    if list.is_null() {
+        return CKR_OK;
+    }
+    if capacity < values.len() as CK_ULONG {
+        return CKR_BUFFER_TOO_SMALL;
+    }
+    std::ptr::copy_nonoverlapping(values.as_ptr(), list, values.len());
+    CKR_OK
+}
+
+/// Return the function list of the module. This is the only symbol a PKCS#11 module must export.
+///
+/// # Safety
+///
+/// `ppFunctionList` must be valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn C_GetFunctionList(ppFunctionList: CK_FUNCTION_LIST_PTR_PTR) -> CK_RV {
+    if ppFunctionList.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let list = FUNCTION_LIST.get_or_init(|| {
+        // All the functions which are not implemented are null.
+        let mut list: CK_FUNCTION_LIST = std::mem::zeroed();
+        list.version = CK_VERSION {
+            major: 2,
+            minor: 40,
+        };
+        list.C_Initialize = Some(initialize);
+        list.C_Finalize = Some(finalize);
+        list.C_GetFunctionList = Some(C_GetFunctionList);
+        list.C_GetSlotList = Some(get_slot_list);
+        list.C_GetTokenInfo = Some(get_token_info);
+        list.C_GetMechanismList = Some(get_mechanism_list);
+        list.C_OpenSession = Some(open_session);
+        list.C_CloseSession = Some(close_session);
+        list.C_Login = Some(login);
+        list.C_Logout = Some(logout);
+        list.C_FindObjectsInit = Some(find_objects_init);
+        list.C_FindObjects = Some(find_objects);
+        list.C_FindObjectsFinal = Some(find_objects_final);
+        list.C_SignInit = Some(sign_init);
+        list.C_Sign = Some(sign);
+        list
+    });
+    *ppFunctionList = list as *const CK_FUNCTION_LIST as CK_FUNCTION_LIST_PTR;
+    CKR_OK
+}
+
+unsafe extern "C" fn initialize(_pInitArgs: CK_VOID_PTR) -> CK_RV {
+    let mut token = lock_token();
+    if token.initialized {
+        return CKR_CRYPTOKI_ALREADY_INITIALIZED;
+    }
+    token.initialized = true;
+    CKR_OK
+}
+
+unsafe extern "C" fn finalize(_pReserved: CK_VOID_PTR) -> CK_RV {
+    let mut token = lock_token();
+    if !token.initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    token.initialized = false;
+    token.logged_in = false;
+    token.sessions.clear();
+    CKR_OK
+}
+
+unsafe extern "C" fn get_slot_list(
+    _tokenPresent: CK_BBOOL,
+    pSlotList: CK_SLOT_ID_PTR,
+    pulCount: CK_ULONG_PTR,
+) -> CK_RV {
+    if !lock_token().initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    write_list(pSlotList, pulCount, &[SLOT])
+}
+
+unsafe extern "C" fn get_token_info(slotID: CK_SLOT_ID, pInfo: CK_TOKEN_INFO_PTR) -> CK_RV {
+    if !lock_token().initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    if slotID != SLOT {
+        return CKR_SLOT_ID_INVALID;
+    }
+    if pInfo.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let mut info: CK_TOKEN_INFO = std::mem::zeroed();
+    blank_padded(&mut info.label, TOKEN_LABEL);
+    blank_padded(&mut info.manufacturerID, "Lighthouse");
+    blank_padded(&mut info.model, "mock");
+    blank_padded(&mut info.serialNumber, "0");
+    blank_padded(&mut info.utcTime, "");
+    info.flags = CKF_TOKEN_INITIALIZED | CKF_LOGIN_REQUIRED | CKF_USER_PIN_INITIALIZED;
+    info.ulMaxPinLen = USER_PIN.len() as CK_ULONG;
+    info.ulMinPinLen = USER_PIN.len() as CK_ULONG;
+    *pInfo = info;
+    CKR_OK
+}
+
+unsafe extern "C" fn get_mechanism_list(
+    slotID: CK_SLOT_ID,
+    pMechanismList: CK_MECHANISM_TYPE_PTR,
+    pulCount: CK_ULONG_PTR,
+) -> CK_RV {
+    if !lock_token().initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    if slotID != SLOT {
+        return CKR_SLOT_ID_INVALID;
+    }
+    write_list(pMechanismList, pulCount, &[MECHANISM])
+}
+
+unsafe extern "C" fn open_session(
+    slotID: CK_SLOT_ID,
+    flags: CK_FLAGS,
+    _pApplication: CK_VOID_PTR,
+    _Notify: CK_NOTIFY,
+    phSession: CK_SESSION_HANDLE_PTR,
+) -> CK_RV {
+    let mut token = lock_token();
+    if !token.initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    if slotID != SLOT {
+        return CKR_SLOT_ID_INVALID;
+    }
+    if flags & CKF_SERIAL_SESSION == 0 {
+        return CKR_SESSION_PARALLEL_NOT_SUPPORTED;
+    }
+    if phSession.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let handle = token.next_session;
+    token.next_session += 1;
+    token.sessions.insert(handle, Session::default());
+    *phSession = handle;
+    CKR_OK
+}
+
+unsafe extern "C" fn close_session(hSession: CK_SESSION_HANDLE) -> CK_RV {
+    let mut token = lock_token();
+    if !token.initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    if token.sessions.remove(&hSession).is_none() {
+        return CKR_SESSION_HANDLE_INVALID;
+    }
+    // Closing the last session with a token logs out.
+    if token.sessions.is_empty() {
+        token.logged_in = false;
+    }
+    CKR_OK
+}
+
+unsafe extern "C" fn login(
+    hSession: CK_SESSION_HANDLE,
+    userType: CK_USER_TYPE,
+    pPin: CK_UTF8CHAR_PTR,
+    ulPinLen: CK_ULONG,
+) -> CK_RV {
+    let mut token = lock_token();
+    if !token.initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    if !token.sessions.contains_key(&hSession) {
+        return CKR_SESSION_HANDLE_INVALID;
+    }
+    if userType != CKU_USER {
+        return CKR_USER_TYPE_INVALID;
+    }
+    if pPin.is_null() || std::slice::from_raw_parts(pPin, ulPinLen as usize) != USER_PIN {
+        return CKR_PIN_INCORRECT;
+    }
+    if token.logged_in {
+        return CKR_USER_ALREADY_LOGGED_IN;
+    }
+    token.logged_in = true;
+    CKR_OK
+}
+
+unsafe extern "C" fn logout(hSession: CK_SESSION_HANDLE) -> CK_RV {
+    let mut token = lock_token();
+    if !token.initialized {
+        return CKR_CRYPTOKI_NOT_INITIALIZED;
+    }
+    if !token.sessions.contains_key(&hSession) {
+        return CKR_SESSION_HANDLE_INVALID;
+    }
+    if !token.logged_in {
+        return CKR_USER_NOT_LOGGED_IN;
+    }
+    token.logged_in = false;
+    CKR_OK
+}
+
+unsafe extern "C" fn find_objects_init(
+    hSession: CK_SESSION_HANDLE,
+    pTemplate: CK_ATTRIBUTE_PTR,
+    ulCount: CK_ULONG,
+) -> CK_RV {
+    if pTemplate.is_null() && ulCount > 0 {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let template = if ulCount == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(pTemplate, ulCount as usize)
+    };
+    with_session(hSession, |session, logged_in| {
+        if session.found.is_some() {
+            return CKR_OPERATION_ACTIVE;
+        }
+        // The token only contains private keys, which are only visible once logged in.
+        let found = KEYS
+            .iter()
+            .zip(1..)
+            .filter(|_| logged_in)
+            .filter(|((label, _), _)| {
+                template.iter().all(|attribute| {
+                    let value = std::slice::from_raw_parts(
+                        attribute.pValue as *const u8,
+                        attribute.ulValueLen as usize,
+                    );
+                    match attribute.type_ {
+                        CKA_CLASS => value == CKO_PRIVATE_KEY.to_ne_bytes(),
+                        CKA_LABEL => value == label.as_bytes(),
+                        _ => false,
+                    }
+                })
+            })
+            .map(|(_, handle)| handle)
+            .collect();
+        session.found = Some(found);
+        CKR_OK
+    })
+}
+
+unsafe extern "C" fn find_objects(
+    hSession: CK_SESSION_HANDLE,
+    phObject: CK_OBJECT_HANDLE_PTR,
+    ulMaxObjectCount: CK_ULONG,
+    pulObjectCount: CK_ULONG_PTR,
+) -> CK_RV {
+    if phObject.is_null() || pulObjectCount.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    with_session(hSession, |session, _| {
+        let Some(found) = session.found.as_mut() else {
+            return CKR_OPERATION_NOT_INITIALIZED;
+        };
+        let count = found.len().min(ulMaxObjectCount as usize);
+        for (i, handle) in found.drain(..count).enumerate() {
+            *phObject.add(i) = handle;
+        }
+        *pulObjectCount = count as CK_ULONG;
+        CKR_OK
+    })
+}
+
+unsafe extern "C" fn find_objects_final(hSession: CK_SESSION_HANDLE) -> CK_RV {
+    with_session(hSession, |session, _| match session.found.take() {
+        Some(_) => CKR_OK,
+        None => CKR_OPERATION_NOT_INITIALIZED,
+    })
+}
+
+unsafe extern "C" fn sign_init(
+    hSession: CK_SESSION_HANDLE,
+    pMechanism: CK_MECHANISM_PTR,
+    hKey: CK_OBJECT_HANDLE,
+) -> CK_RV {
+    if pMechanism.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let mechanism = (*pMechanism).mechanism;
+    with_session(hSession, |session, logged_in| {
+        if session.signing_key.is_some() {
+            return CKR_OPERATION_ACTIVE;
+        }
+        if mechanism != MECHANISM {
+            return CKR_MECHANISM_INVALID;
+        }
+        if !logged_in {
+            return CKR_USER_NOT_LOGGED_IN;
+        }
+        if secret_key(hKey).is_none() {
+            return CKR_KEY_HANDLE_INVALID;
+        }
+        session.signing_key = Some(hKey);
+        CKR_OK
+    })
+}
+
+unsafe extern "C" fn sign(
+    hSession: CK_SESSION_HANDLE,
+    pData: CK_BYTE_PTR,
+    ulDataLen: CK_ULONG,
+    pSignature: CK_BYTE_PTR,
+    pulSignatureLen: CK_ULONG_PTR,
+) -> CK_RV {
+    if pData.is_null() || pulSignatureLen.is_null() {
+        return CKR_ARGUMENTS_BAD;
+    }
+    let data = std::slice::from_raw_parts(pData, ulDataLen as usize);
+    with_session(hSession, |session, _| {
+        let Some(key) = session.signing_key else {
+            return CKR_OPERATION_NOT_INITIALIZED;
+        };
+        // A call which only returns the length of the signature does not end the operation.
+        if pSignature.is_null() {
+            *pulSignatureLen = SIGNATURE_BYTES_LEN as CK_ULONG;
+            return CKR_OK;
+        }
+        if *pulSignatureLen < SIGNATURE_BYTES_LEN as CK_ULONG {
+            *pulSignatureLen = SIGNATURE_BYTES_LEN as CK_ULONG;
+            return CKR_BUFFER_TOO_SMALL;
+        }
+        session.signing_key = None;
+        if data.len() != Hash256::len_bytes() {
+            return CKR_DATA_LEN_RANGE;
+        }
+        let Some(secret_key) = secret_key(key) else {
+            return CKR_KEY_HANDLE_INVALID;
+        };
+        let signature = secret_key.sign(Hash256::from_slice(data)).serialize();
+        std::ptr::copy_nonoverlapping(signature.as_ptr(), pSignature, signature.len());
+        *pulSignatureLen = signature.len() as CK_ULONG;
+        CKR_OK
+    })
+}
@@ -361,7 +361,7 @@ mod tests {
 
             let validator_store = ValidatorStore::<_, E>::new(
                 initialized_validators,
-                slashing_protection,
+                Arc::new(slashing_protection),
                 Hash256::repeat_byte(42),
                 spec,
                 None,
@@ -426,6 +426,7 @@ mod tests {
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
                     description: String::default(),
+                    signing_policy: None,
                     signing_definition: SigningDefinition::LocalKeystore {
                         voting_keystore_path: signer_rig.keystore_path.clone(),
                         voting_keystore_password_path: None,
@@ -452,6 +453,7 @@ mod tests {
                     builder_boost_factor: None,
                     prefer_builder_proposals: None,
                     description: String::default(),
+                    signing_policy: None,
                     signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
                         url: signer_rig.url.to_string(),
                         root_certificate_path: Some(root_certificate_path()),
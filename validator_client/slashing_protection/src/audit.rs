@@ -0,0 +1,161 @@
+//! Records of the messages signed by validators, and of the signing attempts refused by the
+//! slashing protection database, for investigating near-misses.
+use crate::interchange::{SignedAttestation, SignedBlock};
+use crate::NotSafe;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+use types::{Epoch, Hash256, PublicKeyBytes, Slot};
+
+/// The largest slot or epoch which can be stored in the database.
+const MAX_STORED_VALUE: u64 = i64::MAX as u64;
+
+/// The kind of message which a validator attempted to sign.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SigningAttemptKind {
+    Block,
+    Attestation,
+}
+
+impl SigningAttemptKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SigningAttemptKind::Block => "block",
+            SigningAttemptKind::Attestation => "attestation",
+        }
+    }
+}
+
+impl std::str::FromStr for SigningAttemptKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "block" => Ok(SigningAttemptKind::Block),
+            "attestation" => Ok(SigningAttemptKind::Attestation),
+            other => Err(format!("invalid signing attempt kind: {}", other)),
+        }
+    }
+}
+
+/// A message which the slashing protection database refused to sign.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RefusedSigningAttempt {
+    pub pubkey: PublicKeyBytes,
+    pub kind: SigningAttemptKind,
+    /// The slot of a block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub slot: Option<Slot>,
+    /// The source epoch of an attestation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_epoch: Option<Epoch>,
+    /// The target epoch of an attestation.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_epoch: Option<Epoch>,
+    pub signing_root: Hash256,
+    /// The `NotSafe` error which caused the message to be refused.
+    pub reason: String,
+    /// Seconds since the UNIX epoch at which the message was refused.
+    #[serde(with = "serde_utils::quoted_u64")]
+    pub timestamp: u64,
+}
+
+impl RefusedSigningAttempt {
+    pub fn block(
+        pubkey: PublicKeyBytes,
+        slot: Slot,
+        signing_root: Hash256,
+        error: &NotSafe,
+    ) -> Self {
+        Self {
+            pubkey,
+            kind: SigningAttemptKind::Block,
+            slot: Some(slot),
+            source_epoch: None,
+            target_epoch: None,
+            signing_root,
+            reason: error.to_string(),
+            timestamp: unix_timestamp(),
+        }
+    }
+
+    pub fn attestation(
+        pubkey: PublicKeyBytes,
+        source_epoch: Epoch,
+        target_epoch: Epoch,
+        signing_root: Hash256,
+        error: &NotSafe,
+    ) -> Self {
+        Self {
+            pubkey,
+            kind: SigningAttemptKind::Attestation,
+            slot: None,
+            source_epoch: Some(source_epoch),
+            target_epoch: Some(target_epoch),
+            signing_root,
+            reason: error.to_string(),
+            timestamp: unix_timestamp(),
+        }
+    }
+}
+
+/// The messages signed by a validator which are still held by the slashing protection database.
+///
+/// Messages are pruned from the database once they are too old to be relevant to slashing, and
+/// data imported from an interchange file is only retained as a single block and attestation
+/// with null signing roots.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SigningHistory {
+    pub pubkey: PublicKeyBytes,
+    pub signed_blocks: Vec<SignedBlock>,
+    pub signed_attestations: Vec<SignedAttestation>,
+}
+
+/// Selects the records returned from the slashing protection database.
+#[derive(Debug, Clone, Copy)]
+pub struct AuditQuery {
+    /// Only return records for this validator, or for all validators if `None`.
+    pub pubkey: Option<PublicKeyBytes>,
+    /// Only return messages from this epoch onwards.
+    pub start_epoch: Epoch,
+    /// Only return messages up to and including this epoch.
+    pub end_epoch: Epoch,
+    /// Used to determine the epoch of blocks.
+    pub slots_per_epoch: u64,
+    /// The maximum number of refused signing attempts to return, most recent first.
+    pub limit: usize,
+}
+
+/// The bounds of the query are limited to the range of values which can be stored in the database.
+impl AuditQuery {
+    pub fn start_epoch(&self) -> Epoch {
+        std::cmp::min(self.start_epoch, Epoch::new(MAX_STORED_VALUE))
+    }
+
+    pub fn end_epoch(&self) -> Epoch {
+        std::cmp::min(self.end_epoch, Epoch::new(MAX_STORED_VALUE))
+    }
+
+    /// The first slot of `start_epoch`.
+    pub fn start_slot(&self) -> Slot {
+        std::cmp::min(
+            self.start_epoch.start_slot(self.slots_per_epoch),
+            Slot::new(MAX_STORED_VALUE),
+        )
+    }
+
+    /// The last slot of `end_epoch`.
+    pub fn end_slot(&self) -> Slot {
+        std::cmp::min(
+            self.end_epoch.end_slot(self.slots_per_epoch),
+            Slot::new(MAX_STORED_VALUE),
+        )
+    }
+}
+
+fn unix_timestamp() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0)
+}
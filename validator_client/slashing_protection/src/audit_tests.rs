@@ -0,0 +1,258 @@
+#![cfg(test)]
+
+use crate::attestation_tests::attestation_data_builder;
+use crate::block_tests::block;
+use crate::test_utils::*;
+use crate::*;
+use tempfile::tempdir;
+use types::{Epoch, FixedBytesExtended, Slot};
+
+const SLOTS_PER_EPOCH: u64 = 32;
+
+fn query(pubkey: Option<PublicKeyBytes>, start_epoch: u64, end_epoch: u64) -> AuditQuery {
+    AuditQuery {
+        pubkey,
+        start_epoch: Epoch::new(start_epoch),
+        end_epoch: Epoch::new(end_epoch),
+        slots_per_epoch: SLOTS_PER_EPOCH,
+        limit: usize::MAX,
+    }
+}
+
+#[test]
+fn refused_block_recorded() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let pk = pubkey(0);
+    slashing_db.register_validator(pk).unwrap();
+
+    slashing_db
+        .check_and_insert_block_proposal(&pk, &block(33), DEFAULT_DOMAIN)
+        .unwrap();
+    let error = slashing_db
+        .check_and_insert_block_proposal(&pk, &block(33), Hash256::from_low_u64_be(1))
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        NotSafe::InvalidBlock(InvalidBlock::DoubleBlockProposal(_))
+    ));
+
+    let refusals = slashing_db
+        .refused_signing_attempts(&query(Some(pk), 1, 1))
+        .unwrap();
+    assert_eq!(refusals.len(), 1);
+    assert_eq!(refusals[0].pubkey, pk);
+    assert_eq!(refusals[0].kind, SigningAttemptKind::Block);
+    assert_eq!(refusals[0].slot, Some(Slot::new(33)));
+    assert_eq!(refusals[0].source_epoch, None);
+    assert_eq!(refusals[0].reason, error.to_string());
+    assert_ne!(refusals[0].signing_root, Hash256::zero());
+
+    // The refusal is outside of other epoch ranges.
+    assert!(slashing_db
+        .refused_signing_attempts(&query(Some(pk), 2, 10))
+        .unwrap()
+        .is_empty());
+
+    // The refused block is not recorded as signed.
+    let history = slashing_db
+        .signing_history(&query(Some(pk), 0, 10))
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].signed_blocks.len(), 1);
+}
+
+#[test]
+fn refused_attestations_recorded() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let pk = pubkey(0);
+    slashing_db.register_validator(pk).unwrap();
+
+    slashing_db
+        .check_and_insert_attestation(&pk, &attestation_data_builder(2, 5), DEFAULT_DOMAIN)
+        .unwrap();
+    // Surrounded by the previous attestation.
+    slashing_db
+        .check_and_insert_attestation(&pk, &attestation_data_builder(3, 4), DEFAULT_DOMAIN)
+        .unwrap_err();
+    // Source exceeds target.
+    slashing_db
+        .check_and_insert_attestation(&pk, &attestation_data_builder(7, 6), DEFAULT_DOMAIN)
+        .unwrap_err();
+
+    // Most recent first.
+    let refusals = slashing_db
+        .refused_signing_attempts(&query(None, 0, 10))
+        .unwrap();
+    assert_eq!(refusals.len(), 2);
+    assert_eq!(refusals[0].kind, SigningAttemptKind::Attestation);
+    assert_eq!(refusals[0].source_epoch, Some(Epoch::new(7)));
+    assert_eq!(refusals[0].target_epoch, Some(Epoch::new(6)));
+    assert_eq!(refusals[1].source_epoch, Some(Epoch::new(3)));
+    assert_eq!(refusals[1].target_epoch, Some(Epoch::new(4)));
+
+    let limited = AuditQuery {
+        limit: 1,
+        ..query(None, 0, 10)
+    };
+    assert_eq!(
+        slashing_db.refused_signing_attempts(&limited).unwrap(),
+        refusals[..1]
+    );
+}
+
+#[test]
+fn refused_unregistered_and_disabled() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let unregistered = pubkey(0);
+    let disabled = pubkey(1);
+    slashing_db.register_validator(disabled).unwrap();
+    slashing_db
+        .export_interchange_info_and_disable(DEFAULT_GENESIS_VALIDATORS_ROOT, &[disabled])
+        .unwrap();
+
+    for pk in [unregistered, disabled] {
+        slashing_db
+            .check_and_insert_block_proposal(&pk, &block(1), DEFAULT_DOMAIN)
+            .unwrap_err();
+        let refusals = slashing_db
+            .refused_signing_attempts(&query(Some(pk), 0, 0))
+            .unwrap();
+        assert_eq!(refusals.len(), 1);
+        assert_eq!(refusals[0].pubkey, pk);
+    }
+
+    assert_eq!(
+        slashing_db.signing_history(&query(Some(unregistered), 0, 0)),
+        Err(NotSafe::UnregisteredValidator(unregistered))
+    );
+}
+
+#[test]
+fn signing_history_range() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let pubkeys = [pubkey(0), pubkey(1)];
+    slashing_db.register_validators(pubkeys.iter()).unwrap();
+
+    for pk in &pubkeys {
+        for epoch in 1..=4 {
+            slashing_db
+                .check_and_insert_block_proposal(
+                    pk,
+                    &block(epoch * SLOTS_PER_EPOCH),
+                    DEFAULT_DOMAIN,
+                )
+                .unwrap();
+            slashing_db
+                .check_and_insert_attestation(
+                    pk,
+                    &attestation_data_builder(epoch - 1, epoch),
+                    DEFAULT_DOMAIN,
+                )
+                .unwrap();
+        }
+    }
+
+    let history = slashing_db
+        .signing_history(&query(Some(pubkeys[1]), 2, 3))
+        .unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0].pubkey, pubkeys[1]);
+    assert_eq!(
+        history[0]
+            .signed_blocks
+            .iter()
+            .map(|block| block.slot)
+            .collect::<Vec<_>>(),
+        vec![Slot::new(64), Slot::new(96)]
+    );
+    assert_eq!(
+        history[0]
+            .signed_attestations
+            .iter()
+            .map(|attestation| attestation.target_epoch)
+            .collect::<Vec<_>>(),
+        vec![Epoch::new(2), Epoch::new(3)]
+    );
+    assert!(history[0]
+        .signed_blocks
+        .iter()
+        .all(|block| block.signing_root.is_some()));
+
+    let all = slashing_db
+        .signing_history(&query(None, 0, u64::MAX))
+        .unwrap();
+    assert_eq!(all.len(), 2);
+    assert!(all
+        .iter()
+        .all(|history| history.signed_blocks.len() == 4 && history.signed_attestations.len() == 4));
+}
+
+#[test]
+fn refusals_pruned() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let pk = pubkey(0);
+    let unregistered = pubkey(1);
+    slashing_db.register_validator(pk).unwrap();
+
+    for slot in [33, 65] {
+        slashing_db
+            .check_and_insert_block_proposal(&pk, &block(slot), DEFAULT_DOMAIN)
+            .unwrap();
+        slashing_db
+            .check_and_insert_block_proposal(&pk, &block(slot), Hash256::from_low_u64_be(1))
+            .unwrap_err();
+    }
+    slashing_db
+        .check_and_insert_attestation(&pk, &attestation_data_builder(2, 5), DEFAULT_DOMAIN)
+        .unwrap();
+    slashing_db
+        .check_and_insert_attestation(&pk, &attestation_data_builder(3, 4), DEFAULT_DOMAIN)
+        .unwrap_err();
+    slashing_db
+        .check_and_insert_attestation(&pk, &attestation_data_builder(7, 6), DEFAULT_DOMAIN)
+        .unwrap_err();
+    slashing_db
+        .check_and_insert_attestation(
+            &unregistered,
+            &attestation_data_builder(0, 1),
+            DEFAULT_DOMAIN,
+        )
+        .unwrap_err();
+    assert_eq!(
+        slashing_db
+            .refused_signing_attempts(&query(None, 0, 10))
+            .unwrap()
+            .len(),
+        5
+    );
+
+    slashing_db
+        .prune_all_signed_blocks(std::iter::once(&pk), Slot::new(64))
+        .unwrap();
+    slashing_db
+        .prune_all_signed_attestations(std::iter::once(&pk), Epoch::new(5))
+        .unwrap();
+
+    // Refusals are pruned for unregistered validators too.
+    let refusals = slashing_db
+        .refused_signing_attempts(&query(None, 0, 10))
+        .unwrap();
+    assert_eq!(refusals.len(), 2);
+    assert_eq!(refusals[0].target_epoch, Some(Epoch::new(6)));
+    assert_eq!(refusals[1].slot, Some(Slot::new(65)));
+}
@@ -0,0 +1,133 @@
+//! A common interface for the databases which can store slashing protection data.
+//!
+//! Most validator clients use a local SQLite database (`SlashingDatabase`). Validator clients in
+//! an active/standby deployment can instead share a single PostgreSQL database
+//! (`PostgresSlashingDatabase`), so that a standby which takes over can never sign a message which
+//! conflicts with one signed by the active validator client.
+use crate::interchange::Interchange;
+use crate::{
+    AuditQuery, InterchangeError, InterchangeImportOutcome, NotSafe, RefusedSigningAttempt, Safe,
+    SigningHistory, SigningRoot,
+};
+use std::fmt::Debug;
+use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
+
+/// A database which records the messages signed by validators, and refuses to record any message
+/// which is slashable with respect to those already recorded.
+///
+/// Each `check_and_insert_*` call must check and record the message atomically, with respect to
+/// all other users of the same database. Messages which are refused are recorded as a
+/// `RefusedSigningAttempt`.
+pub trait SlashingProtectionBackend: Debug + Send + Sync {
+    /// Register validators with the database, or re-enable them if they were disabled.
+    fn register_validators(&self, public_keys: &[PublicKeyBytes]) -> Result<(), NotSafe>;
+
+    /// Check that all of the given validators are registered and enabled.
+    fn check_validator_registrations(&self, public_keys: &[PublicKeyBytes]) -> Result<(), NotSafe>;
+
+    /// Check a block proposal for slash safety, and if it is safe, record it in the database.
+    fn check_and_insert_block_signing_root(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        slot: Slot,
+        signing_root: SigningRoot,
+    ) -> Result<Safe, NotSafe>;
+
+    /// Check an attestation for slash safety, and if it is safe, record it in the database.
+    fn check_and_insert_attestation_signing_root(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        att_signing_root: SigningRoot,
+    ) -> Result<Safe, NotSafe>;
+
+    /// Atomically import an interchange file, failing if *any* record cannot be imported.
+    fn import_interchange_info(
+        &self,
+        interchange: Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError>;
+
+    /// Export the data for the `selected_pubkeys`, or all validators if `None`.
+    fn export_interchange_info(
+        &self,
+        genesis_validators_root: Hash256,
+        selected_pubkeys: Option<&[PublicKeyBytes]>,
+    ) -> Result<Interchange, InterchangeError>;
+
+    /// Disable the given validators and export their data, atomically.
+    ///
+    /// Keys which are unknown to the database are omitted from the result.
+    fn export_interchange_info_and_disable(
+        &self,
+        genesis_validators_root: Hash256,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<Interchange, InterchangeError>;
+
+    /// Remove blocks with slots less than `new_min_slot`, keeping at least one block for each
+    /// validator.
+    ///
+    /// Refused block proposals with slots less than `new_min_slot` are removed for all validators,
+    /// including those which are not in `public_keys`.
+    fn prune_all_signed_blocks(
+        &self,
+        public_keys: &[PublicKeyBytes],
+        new_min_slot: Slot,
+    ) -> Result<(), NotSafe>;
+
+    /// Remove attestations with targets less than `new_min_target`, keeping at least one
+    /// attestation for each validator.
+    ///
+    /// Refused attestations with targets less than `new_min_target` are removed for all
+    /// validators, including those which are not in `public_keys`.
+    fn prune_all_signed_attestations(
+        &self,
+        public_keys: &[PublicKeyBytes],
+        new_min_target: Epoch,
+    ) -> Result<(), NotSafe>;
+
+    /// Return the messages signed by the validators selected by `query`.
+    fn signing_history(&self, query: &AuditQuery) -> Result<Vec<SigningHistory>, NotSafe>;
+
+    /// Return the most recent refused signing attempts selected by `query`.
+    fn refused_signing_attempts(
+        &self,
+        query: &AuditQuery,
+    ) -> Result<Vec<RefusedSigningAttempt>, NotSafe>;
+
+    /// Register a single validator with the database.
+    fn register_validator(&self, validator_pk: PublicKeyBytes) -> Result<(), NotSafe> {
+        self.register_validators(&[validator_pk])
+    }
+
+    /// As for `check_and_insert_block_signing_root`, computing the signing root of the header.
+    fn check_and_insert_block_proposal(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        block_header: &BeaconBlockHeader,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.check_and_insert_block_signing_root(
+            validator_pubkey,
+            block_header.slot,
+            block_header.signing_root(domain).into(),
+        )
+    }
+
+    /// As for `check_and_insert_attestation_signing_root`, computing the signing root of the
+    /// attestation.
+    fn check_and_insert_attestation(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        attestation: &AttestationData,
+        domain: Hash256,
+    ) -> Result<Safe, NotSafe> {
+        self.check_and_insert_attestation_signing_root(
+            validator_pubkey,
+            attestation.source.epoch,
+            attestation.target.epoch,
+            attestation.signing_root(domain).into(),
+        )
+    }
+}
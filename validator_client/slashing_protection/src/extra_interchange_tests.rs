@@ -1,9 +1,46 @@
 #![cfg(test)]
 
-use crate::test_utils::pubkey;
+use crate::attestation_tests::attestation_data_builder;
+use crate::block_tests::block;
+use crate::interchange::{
+    Interchange, InterchangeData, InterchangeMetadata, InterchangeMinifier, InterchangeWriter,
+    SignedAttestation as InterchangeAttestation, SignedBlock as InterchangeBlock,
+};
+use crate::test_utils::{pubkey, DEFAULT_DOMAIN, DEFAULT_GENESIS_VALIDATORS_ROOT};
 use crate::*;
 use tempfile::tempdir;
-use types::FixedBytesExtended;
+use types::{Epoch, FixedBytesExtended, Slot};
+
+fn metadata() -> InterchangeMetadata {
+    InterchangeMetadata {
+        interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+        genesis_validators_root: DEFAULT_GENESIS_VALIDATORS_ROOT,
+    }
+}
+
+fn record(index: usize, slot: u64, source: u64, target: u64) -> InterchangeData {
+    InterchangeData {
+        pubkey: pubkey(index),
+        signed_blocks: vec![InterchangeBlock {
+            slot: Slot::new(slot),
+            signing_root: Some(Hash256::from_low_u64_be(slot)),
+        }],
+        signed_attestations: vec![InterchangeAttestation {
+            source_epoch: Epoch::new(source),
+            target_epoch: Epoch::new(target),
+            signing_root: None,
+        }],
+    }
+}
+
+fn read_all(json: &[u8]) -> Result<Interchange, InterchangeError> {
+    let mut data = vec![];
+    let metadata = Interchange::stream_json_reader(json, |_, record| {
+        data.push(record);
+        Ok(())
+    })?;
+    Ok(Interchange { metadata, data })
+}
 
 #[test]
 fn export_non_existent_key() {
@@ -74,3 +111,241 @@ fn export_same_key_twice() {
         export_double.minify().unwrap()
     );
 }
+
+#[test]
+fn streaming_write_matches_write_to() {
+    let interchange = Interchange {
+        metadata: metadata(),
+        data: vec![record(0, 10, 1, 2), record(1, 20, 3, 4)],
+    };
+
+    let mut expected = vec![];
+    interchange.write_to(&mut expected).unwrap();
+
+    let mut streamed = vec![];
+    let mut writer = InterchangeWriter::new(&mut streamed, &interchange.metadata).unwrap();
+    for record in &interchange.data {
+        writer.write_record(record).unwrap();
+    }
+    assert_eq!(writer.finish().unwrap(), 2);
+    assert_eq!(streamed, expected);
+
+    // Empty files are also valid.
+    let mut empty = vec![];
+    assert_eq!(
+        InterchangeWriter::new(&mut empty, &interchange.metadata)
+            .unwrap()
+            .finish()
+            .unwrap(),
+        0
+    );
+    assert!(
+        Interchange::from_json_str(std::str::from_utf8(&empty).unwrap())
+            .unwrap()
+            .is_empty()
+    );
+}
+
+#[test]
+fn streaming_read() {
+    let interchange = Interchange {
+        metadata: metadata(),
+        data: vec![record(0, 10, 1, 2), record(1, 20, 3, 4)],
+    };
+    let mut json = vec![];
+    interchange.write_to(&mut json).unwrap();
+    assert_eq!(read_all(&json).unwrap(), interchange);
+
+    // Records listed before the metadata are buffered until the metadata is read.
+    let reordered = serde_json::json!({
+        "data": interchange.data,
+        "metadata": interchange.metadata,
+    });
+    assert_eq!(
+        read_all(reordered.to_string().as_bytes()).unwrap(),
+        interchange
+    );
+
+    // Missing fields and trailing data are rejected.
+    let no_data = serde_json::json!({ "metadata": interchange.metadata });
+    assert!(read_all(no_data.to_string().as_bytes()).is_err());
+    json.extend_from_slice(b"{}");
+    assert!(read_all(&json).is_err());
+
+    // Errors from the callback are returned unchanged.
+    let result = Interchange::stream_json_reader(reordered.to_string().as_bytes(), |_, _| {
+        Err(InterchangeError::MaxInconsistent)
+    });
+    assert!(matches!(result, Err(InterchangeError::MaxInconsistent)));
+}
+
+#[test]
+fn streaming_import() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+
+    let interchange = Interchange {
+        metadata: metadata(),
+        data: vec![record(0, 10, 1, 2), record(1, 20, 3, 4)],
+    };
+    let mut json = vec![];
+    interchange.write_to(&mut json).unwrap();
+
+    // A mismatched genesis validators root is rejected without importing anything.
+    assert!(matches!(
+        slashing_db.import_interchange_stream(json.as_slice(), Hash256::repeat_byte(1)),
+        Err(InterchangeError::GenesisValidatorsMismatch { .. })
+    ));
+    assert_eq!(
+        slashing_db.check_validator_registrations([pubkey(0)].iter()),
+        Err(NotSafe::UnregisteredValidator(pubkey(0)))
+    );
+
+    let outcomes = slashing_db
+        .import_interchange_stream(json.as_slice(), DEFAULT_GENESIS_VALIDATORS_ROOT)
+        .unwrap();
+    assert_eq!(outcomes.len(), 2);
+    assert!(outcomes.iter().all(|outcome| !outcome.failed()));
+
+    let exported = slashing_db
+        .export_interchange_info(DEFAULT_GENESIS_VALIDATORS_ROOT, None)
+        .unwrap();
+    assert!(exported.equiv(&interchange.minify().unwrap()));
+}
+
+#[test]
+fn streaming_export_since_epoch() {
+    let dir = tempdir().unwrap();
+    let slashing_db_file = dir.path().join("slashing_protection.sqlite");
+    let slashing_db = SlashingDatabase::create(&slashing_db_file).unwrap();
+    let slots_per_epoch = 32;
+
+    let (active, inactive) = (pubkey(0), pubkey(1));
+    slashing_db
+        .register_validators([active, inactive].iter())
+        .unwrap();
+    for epoch in 1..=3 {
+        slashing_db
+            .check_and_insert_block_proposal(
+                &active,
+                &block(epoch * slots_per_epoch),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+        slashing_db
+            .check_and_insert_attestation(
+                &active,
+                &attestation_data_builder(epoch - 1, epoch),
+                DEFAULT_DOMAIN,
+            )
+            .unwrap();
+    }
+    slashing_db
+        .check_and_insert_attestation(&inactive, &attestation_data_builder(0, 1), DEFAULT_DOMAIN)
+        .unwrap();
+
+    // A full export matches the in-memory export.
+    let mut json = vec![];
+    let num_validators = slashing_db
+        .export_interchange_stream(
+            DEFAULT_GENESIS_VALIDATORS_ROOT,
+            None,
+            None,
+            slots_per_epoch,
+            &mut json,
+        )
+        .unwrap();
+    assert_eq!(num_validators, 2);
+    assert_eq!(
+        read_all(&json).unwrap(),
+        slashing_db
+            .export_interchange_info(DEFAULT_GENESIS_VALIDATORS_ROOT, None)
+            .unwrap()
+    );
+
+    // Exporting from epoch 2 omits older messages, and the inactive validator.
+    let mut json = vec![];
+    let num_validators = slashing_db
+        .export_interchange_stream(
+            DEFAULT_GENESIS_VALIDATORS_ROOT,
+            None,
+            Some(Epoch::new(2)),
+            slots_per_epoch,
+            &mut json,
+        )
+        .unwrap();
+    assert_eq!(num_validators, 1);
+    let delta = read_all(&json).unwrap();
+    assert_eq!(delta.data[0].pubkey, active);
+    assert_eq!(
+        delta.data[0]
+            .signed_blocks
+            .iter()
+            .map(|block| block.slot)
+            .collect::<Vec<_>>(),
+        vec![Slot::new(64), Slot::new(96)]
+    );
+    assert_eq!(
+        delta.data[0]
+            .signed_attestations
+            .iter()
+            .map(|attestation| attestation.target_epoch)
+            .collect::<Vec<_>>(),
+        vec![Epoch::new(2), Epoch::new(3)]
+    );
+
+    // Epochs too large to be stored in the database export nothing.
+    let mut json = vec![];
+    let num_validators = slashing_db
+        .export_interchange_stream(
+            DEFAULT_GENESIS_VALIDATORS_ROOT,
+            None,
+            Some(Epoch::new(u64::MAX)),
+            slots_per_epoch,
+            &mut json,
+        )
+        .unwrap();
+    assert_eq!(num_validators, 0);
+}
+
+#[test]
+fn merge_interchange_files() {
+    let first = Interchange {
+        metadata: metadata(),
+        data: vec![record(0, 10, 1, 2), record(1, 20, 3, 4)],
+    };
+    let second = Interchange {
+        metadata: metadata(),
+        data: vec![record(1, 15, 5, 6), record(2, 30, 7, 8)],
+    };
+    let mut first_json = vec![];
+    first.write_to(&mut first_json).unwrap();
+    let mut second_json = vec![];
+    second.write_to(&mut second_json).unwrap();
+
+    let mut minifier = InterchangeMinifier::new(metadata());
+    minifier.add_json_reader(first_json.as_slice()).unwrap();
+    minifier.add_json_reader(second_json.as_slice()).unwrap();
+    assert_eq!(minifier.len(), 3);
+
+    let mut merged_json = vec![];
+    assert_eq!(minifier.write_to(&mut merged_json).unwrap(), 3);
+    let merged = read_all(&merged_json).unwrap();
+
+    let combined = Interchange {
+        metadata: metadata(),
+        data: first.data.into_iter().chain(second.data).collect(),
+    };
+    assert!(merged.equiv(&combined.minify().unwrap()));
+
+    // Files for a different network can't be merged.
+    let mut other_network = InterchangeMinifier::new(InterchangeMetadata {
+        genesis_validators_root: Hash256::repeat_byte(1),
+        ..metadata()
+    });
+    assert!(matches!(
+        other_network.add_json_reader(first_json.as_slice()),
+        Err(InterchangeError::GenesisValidatorsMismatch { .. })
+    ));
+}
@@ -1,8 +1,10 @@
-use crate::InterchangeError;
-use serde::{Deserialize, Serialize};
+use crate::{InterchangeError, SUPPORTED_INTERCHANGE_FORMAT_VERSION};
+use serde::de::{self, DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize};
 use std::cmp::max;
 use std::collections::{HashMap, HashSet};
-use std::io;
+use std::fmt;
+use std::io::{self, BufReader, Read, Write};
 use types::{Epoch, Hash256, PublicKeyBytes, Slot};
 
 #[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
@@ -14,6 +16,27 @@ pub struct InterchangeMetadata {
     pub genesis_validators_root: Hash256,
 }
 
+impl InterchangeMetadata {
+    /// Check that an interchange file with this metadata can be imported by a client with the
+    /// given `genesis_validators_root`.
+    pub fn check(&self, genesis_validators_root: Hash256) -> Result<(), InterchangeError> {
+        if self.interchange_format_version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
+            return Err(InterchangeError::UnsupportedVersion(
+                self.interchange_format_version,
+            ));
+        }
+
+        if self.genesis_validators_root != genesis_validators_root {
+            return Err(InterchangeError::GenesisValidatorsMismatch {
+                client: genesis_validators_root,
+                interchange_file: self.genesis_validators_root,
+            });
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize, Serialize)]
 #[serde(deny_unknown_fields)]
 #[cfg_attr(feature = "arbitrary-fuzz", derive(arbitrary::Arbitrary))]
@@ -65,6 +88,38 @@ impl Interchange {
         Ok(Interchange::from_json_str(&json_str)?)
     }
 
+    /// Read an interchange file one record at a time, calling `on_record` with the file's metadata
+    /// and each of its records in turn. Returns the metadata of the file.
+    ///
+    /// Unlike `from_json_reader` this never holds the whole file in memory, which makes it
+    /// suitable for files containing tens of thousands of validators. The exception is a file
+    /// which lists its `data` before its `metadata`, in which case records are buffered until the
+    /// metadata is read.
+    ///
+    /// If `on_record` returns an error then reading stops and the error is returned.
+    pub fn stream_json_reader<F>(
+        reader: impl Read,
+        on_record: F,
+    ) -> Result<InterchangeMetadata, InterchangeError>
+    where
+        F: FnMut(&InterchangeMetadata, InterchangeData) -> Result<(), InterchangeError>,
+    {
+        let mut sink = RecordSink {
+            on_record,
+            error: None,
+        };
+        let mut deserializer = serde_json::Deserializer::from_reader(BufReader::new(reader));
+        let result = deserializer
+            .deserialize_map(InterchangeVisitor { sink: &mut sink })
+            .and_then(|metadata| deserializer.end().map(|()| metadata));
+
+        match (result, sink.error) {
+            (_, Some(error)) => Err(error),
+            (Ok(metadata), None) => Ok(metadata),
+            (Err(error), None) => Err(error.into()),
+        }
+    }
+
     pub fn write_to(&self, writer: impl std::io::Write) -> Result<(), serde_json::Error> {
         serde_json::to_writer(writer, self)
     }
@@ -88,72 +143,297 @@ impl Interchange {
 
     /// Minify an interchange by constructing a synthetic block & attestation for each validator.
     pub fn minify(&self) -> Result<Self, InterchangeError> {
-        // Map from pubkey to optional max block and max attestation.
-        let mut validator_data =
-            HashMap::<PublicKeyBytes, (Option<SignedBlock>, Option<SignedAttestation>)>::new();
-
+        let mut minifier = InterchangeMinifier::new(self.metadata.clone());
         for data in self.data.iter() {
-            // Existing maximum attestation and maximum block.
-            let (max_block, max_attestation) = validator_data
-                .entry(data.pubkey)
-                .or_insert_with(|| (None, None));
-
-            // Find maximum source and target epochs.
-            let max_source_epoch = data
-                .signed_attestations
-                .iter()
-                .map(|attestation| attestation.source_epoch)
-                .max();
-            let max_target_epoch = data
-                .signed_attestations
-                .iter()
-                .map(|attestation| attestation.target_epoch)
-                .max();
-
-            match (max_source_epoch, max_target_epoch) {
-                (Some(source_epoch), Some(target_epoch)) => {
-                    if let Some(prev_max) = max_attestation {
-                        prev_max.source_epoch = max(prev_max.source_epoch, source_epoch);
-                        prev_max.target_epoch = max(prev_max.target_epoch, target_epoch);
-                    } else {
-                        *max_attestation = Some(SignedAttestation {
-                            source_epoch,
-                            target_epoch,
-                            signing_root: None,
-                        });
-                    }
-                }
-                (None, None) => {}
-                _ => return Err(InterchangeError::MaxInconsistent),
-            };
+            minifier.add_record(data)?;
+        }
+        Ok(minifier.into_interchange())
+    }
+}
+
+/// Accumulates the maximum block and attestation for each validator across any number of
+/// interchange records.
+///
+/// This is used to minify a single interchange, and to merge several interchange files into one.
+#[derive(Debug)]
+pub struct InterchangeMinifier {
+    metadata: InterchangeMetadata,
+    /// Map from pubkey to optional max block and max attestation.
+    validator_data: HashMap<PublicKeyBytes, (Option<SignedBlock>, Option<SignedAttestation>)>,
+}
+
+impl InterchangeMinifier {
+    pub fn new(metadata: InterchangeMetadata) -> Self {
+        Self {
+            metadata,
+            validator_data: HashMap::new(),
+        }
+    }
+
+    pub fn add_record(&mut self, data: &InterchangeData) -> Result<(), InterchangeError> {
+        // Existing maximum attestation and maximum block.
+        let (max_block, max_attestation) = self
+            .validator_data
+            .entry(data.pubkey)
+            .or_insert_with(|| (None, None));
 
-            // Find maximum block slot.
-            let max_block_slot = data.signed_blocks.iter().map(|block| block.slot).max();
+        // Find maximum source and target epochs.
+        let max_source_epoch = data
+            .signed_attestations
+            .iter()
+            .map(|attestation| attestation.source_epoch)
+            .max();
+        let max_target_epoch = data
+            .signed_attestations
+            .iter()
+            .map(|attestation| attestation.target_epoch)
+            .max();
 
-            if let Some(max_slot) = max_block_slot {
-                if let Some(prev_max) = max_block {
-                    prev_max.slot = max(prev_max.slot, max_slot);
+        match (max_source_epoch, max_target_epoch) {
+            (Some(source_epoch), Some(target_epoch)) => {
+                if let Some(prev_max) = max_attestation {
+                    prev_max.source_epoch = max(prev_max.source_epoch, source_epoch);
+                    prev_max.target_epoch = max(prev_max.target_epoch, target_epoch);
                 } else {
-                    *max_block = Some(SignedBlock {
-                        slot: max_slot,
+                    *max_attestation = Some(SignedAttestation {
+                        source_epoch,
+                        target_epoch,
                         signing_root: None,
                     });
                 }
             }
+            (None, None) => {}
+            _ => return Err(InterchangeError::MaxInconsistent),
+        };
+
+        // Find maximum block slot.
+        let max_block_slot = data.signed_blocks.iter().map(|block| block.slot).max();
+
+        if let Some(max_slot) = max_block_slot {
+            if let Some(prev_max) = max_block {
+                prev_max.slot = max(prev_max.slot, max_slot);
+            } else {
+                *max_block = Some(SignedBlock {
+                    slot: max_slot,
+                    signing_root: None,
+                });
+            }
         }
 
-        let data = validator_data
+        Ok(())
+    }
+
+    /// Add every record of an interchange file, without reading the whole file into memory.
+    ///
+    /// The file must have the same format version and genesis validators root as the minifier.
+    pub fn add_json_reader(&mut self, reader: impl Read) -> Result<(), InterchangeError> {
+        let genesis_validators_root = self.metadata.genesis_validators_root;
+        let metadata = Interchange::stream_json_reader(reader, |metadata, record| {
+            metadata.check(genesis_validators_root)?;
+            self.add_record(&record)
+        })?;
+        metadata.check(genesis_validators_root)
+    }
+
+    /// The number of distinct validators seen so far.
+    pub fn len(&self) -> usize {
+        self.validator_data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.validator_data.is_empty()
+    }
+
+    fn into_data(self) -> (InterchangeMetadata, impl Iterator<Item = InterchangeData>) {
+        let data = self
+            .validator_data
             .into_iter()
             .map(|(pubkey, (maybe_block, maybe_att))| InterchangeData {
                 pubkey,
                 signed_blocks: maybe_block.into_iter().collect(),
                 signed_attestations: maybe_att.into_iter().collect(),
-            })
-            .collect();
+            });
+        (self.metadata, data)
+    }
+
+    pub fn into_interchange(self) -> Interchange {
+        let (metadata, data) = self.into_data();
+        Interchange {
+            metadata,
+            data: data.collect(),
+        }
+    }
+
+    /// Write the minified interchange to `writer` one record at a time, returning the number of
+    /// records written.
+    pub fn write_to(self, writer: impl Write) -> Result<usize, serde_json::Error> {
+        let (metadata, data) = self.into_data();
+        let mut writer = InterchangeWriter::new(writer, &metadata)?;
+        for record in data {
+            writer.write_record(&record)?;
+        }
+        writer.finish()
+    }
+}
+
+/// Writes an interchange file one record at a time, so that it never has to be held in memory in
+/// its entirety.
+///
+/// The output is identical to that of `Interchange::write_to` for the same metadata and records.
+pub struct InterchangeWriter<W: Write> {
+    writer: W,
+    num_records: usize,
+}
 
+impl<W: Write> InterchangeWriter<W> {
+    /// Start a new interchange file by writing its `metadata`.
+    pub fn new(mut writer: W, metadata: &InterchangeMetadata) -> Result<Self, serde_json::Error> {
+        writer
+            .write_all(b"{\"metadata\":")
+            .map_err(serde_json::Error::io)?;
+        serde_json::to_writer(&mut writer, metadata)?;
+        writer
+            .write_all(b",\"data\":[")
+            .map_err(serde_json::Error::io)?;
         Ok(Self {
-            metadata: self.metadata.clone(),
-            data,
+            writer,
+            num_records: 0,
+        })
+    }
+
+    pub fn write_record(&mut self, record: &InterchangeData) -> Result<(), serde_json::Error> {
+        if self.num_records > 0 {
+            self.writer.write_all(b",").map_err(serde_json::Error::io)?;
+        }
+        serde_json::to_writer(&mut self.writer, record)?;
+        self.num_records += 1;
+        Ok(())
+    }
+
+    /// Complete the file, returning the number of records written.
+    pub fn finish(mut self) -> Result<usize, serde_json::Error> {
+        self.writer
+            .write_all(b"]}")
+            .map_err(serde_json::Error::io)?;
+        self.writer.flush().map_err(serde_json::Error::io)?;
+        Ok(self.num_records)
+    }
+}
+
+/// Passes records to a callback, holding onto the first error it returns so that it can be
+/// surfaced after deserialization is aborted.
+struct RecordSink<F> {
+    on_record: F,
+    error: Option<InterchangeError>,
+}
+
+impl<F> RecordSink<F>
+where
+    F: FnMut(&InterchangeMetadata, InterchangeData) -> Result<(), InterchangeError>,
+{
+    fn send<E: de::Error>(
+        &mut self,
+        metadata: &InterchangeMetadata,
+        record: InterchangeData,
+    ) -> Result<(), E> {
+        (self.on_record)(metadata, record).map_err(|error| {
+            self.error = Some(error);
+            E::custom("interchange record rejected")
         })
     }
 }
+
+/// Visits the top-level interchange object, streaming the records of its `data` field.
+struct InterchangeVisitor<'a, F> {
+    sink: &'a mut RecordSink<F>,
+}
+
+impl<'de, F> Visitor<'de> for InterchangeVisitor<'_, F>
+where
+    F: FnMut(&InterchangeMetadata, InterchangeData) -> Result<(), InterchangeError>,
+{
+    type Value = InterchangeMetadata;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an interchange object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut metadata: Option<InterchangeMetadata> = None;
+        let mut seen_data = false;
+        let mut buffered = vec![];
+
+        while let Some(key) = map.next_key::<String>()? {
+            match key.as_str() {
+                "metadata" => {
+                    if metadata.is_some() {
+                        return Err(de::Error::duplicate_field("metadata"));
+                    }
+                    let new_metadata = map.next_value::<InterchangeMetadata>()?;
+                    for record in buffered.drain(..) {
+                        self.sink.send::<A::Error>(&new_metadata, record)?;
+                    }
+                    metadata = Some(new_metadata);
+                }
+                "data" => {
+                    if seen_data {
+                        return Err(de::Error::duplicate_field("data"));
+                    }
+                    seen_data = true;
+                    map.next_value_seed(DataVisitor {
+                        metadata: metadata.as_ref(),
+                        buffered: &mut buffered,
+                        sink: &mut *self.sink,
+                    })?;
+                }
+                _ => {
+                    map.next_value::<IgnoredAny>()?;
+                }
+            }
+        }
+
+        if !seen_data {
+            return Err(de::Error::missing_field("data"));
+        }
+        metadata.ok_or_else(|| de::Error::missing_field("metadata"))
+    }
+}
+
+/// Visits the `data` array, sending each record to the sink as soon as it is parsed.
+struct DataVisitor<'a, F> {
+    metadata: Option<&'a InterchangeMetadata>,
+    buffered: &'a mut Vec<InterchangeData>,
+    sink: &'a mut RecordSink<F>,
+}
+
+impl<'de, F> DeserializeSeed<'de> for DataVisitor<'_, F>
+where
+    F: FnMut(&InterchangeMetadata, InterchangeData) -> Result<(), InterchangeError>,
+{
+    type Value = ();
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<(), D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de, F> Visitor<'de> for DataVisitor<'_, F>
+where
+    F: FnMut(&InterchangeMetadata, InterchangeData) -> Result<(), InterchangeError>,
+{
+    type Value = ();
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a list of interchange records")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<(), A::Error> {
+        while let Some(record) = seq.next_element::<InterchangeData>()? {
+            match self.metadata {
+                Some(metadata) => self.sink.send::<A::Error>(metadata, record)?,
+                None => self.buffered.push(record),
+            }
+        }
+        Ok(())
+    }
+}
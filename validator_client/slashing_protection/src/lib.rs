@@ -1,15 +1,26 @@
 mod attestation_tests;
+mod audit;
+mod audit_tests;
+mod backend;
 mod block_tests;
 mod extra_interchange_tests;
 pub mod interchange;
 pub mod interchange_test;
 mod parallel_tests;
+#[cfg(feature = "postgres")]
+mod postgres_database;
+#[cfg(feature = "postgres")]
+mod postgres_tests;
 mod registration_tests;
 mod signed_attestation;
 mod signed_block;
 mod slashing_database;
 pub mod test_utils;
 
+pub use crate::audit::{AuditQuery, RefusedSigningAttempt, SigningAttemptKind, SigningHistory};
+pub use crate::backend::SlashingProtectionBackend;
+#[cfg(feature = "postgres")]
+pub use crate::postgres_database::PostgresSlashingDatabase;
 pub use crate::signed_attestation::{InvalidAttestation, SignedAttestation};
 pub use crate::signed_block::{InvalidBlock, SignedBlock};
 pub use crate::slashing_database::{
@@ -37,6 +48,8 @@ pub enum NotSafe {
     IOError(ErrorKind),
     SQLError(String),
     SQLPoolError(String),
+    /// The transaction conflicted with a concurrent transaction, and was rolled back.
+    SerializationFailure,
     ConsistencyError,
 }
 
@@ -115,6 +128,17 @@ impl From<SQLError> for NotSafe {
     }
 }
 
+#[cfg(feature = "postgres")]
+impl From<postgres::Error> for NotSafe {
+    fn from(error: postgres::Error) -> NotSafe {
+        if error.code() == Some(&postgres::error::SqlState::T_R_SERIALIZATION_FAILURE) {
+            NotSafe::SerializationFailure
+        } else {
+            NotSafe::SQLError(error.to_string())
+        }
+    }
+}
+
 impl From<r2d2::Error> for NotSafe {
     fn from(error: r2d2::Error) -> Self {
         // Use `Display` impl to print "timed out waiting for connection"
@@ -122,6 +146,20 @@ impl From<r2d2::Error> for NotSafe {
     }
 }
 
+impl NotSafe {
+    /// Returns `true` if the message was refused because it is unsafe to sign, rather than due
+    /// to an error accessing the database.
+    pub fn is_refusal(&self) -> bool {
+        matches!(
+            self,
+            NotSafe::UnregisteredValidator(_)
+                | NotSafe::DisabledValidator(_)
+                | NotSafe::InvalidBlock(_)
+                | NotSafe::InvalidAttestation(_)
+        )
+    }
+}
+
 impl Display for NotSafe {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:?}", self)
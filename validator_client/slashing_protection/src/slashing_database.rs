@@ -1,14 +1,19 @@
 use crate::interchange::{
-    Interchange, InterchangeData, InterchangeMetadata, SignedAttestation as InterchangeAttestation,
-    SignedBlock as InterchangeBlock,
+    Interchange, InterchangeData, InterchangeMetadata, InterchangeWriter,
+    SignedAttestation as InterchangeAttestation, SignedBlock as InterchangeBlock,
 };
 use crate::signed_attestation::InvalidAttestation;
 use crate::signed_block::InvalidBlock;
-use crate::{signing_root_from_row, NotSafe, Safe, SignedAttestation, SignedBlock, SigningRoot};
+use crate::{
+    signing_root_from_row, AuditQuery, NotSafe, RefusedSigningAttempt, Safe, SignedAttestation,
+    SignedBlock, SigningHistory, SigningRoot, SlashingProtectionBackend,
+};
 use filesystem::restrict_file_permissions;
 use r2d2_sqlite::SqliteConnectionManager;
 use rusqlite::{params, OptionalExtension, Transaction, TransactionBehavior};
+use std::cmp::min;
 use std::fs::File;
+use std::io::{Read, Write};
 use std::path::Path;
 use std::time::Duration;
 use types::{AttestationData, BeaconBlockHeader, Epoch, Hash256, PublicKeyBytes, SignedRoot, Slot};
@@ -139,6 +144,28 @@ impl SlashingDatabase {
             )?;
         }
 
+        // Add the table of refused signing attempts if it does not already exist. Public keys are
+        // stored directly so that attempts by unregistered validators can be recorded.
+        txn.execute(
+            "CREATE TABLE IF NOT EXISTS refused_signing_attempts (
+                id INTEGER PRIMARY KEY,
+                public_key TEXT NOT NULL,
+                kind TEXT NOT NULL,
+                slot INTEGER,
+                source_epoch INTEGER,
+                target_epoch INTEGER,
+                signing_root BLOB NOT NULL,
+                reason TEXT NOT NULL,
+                timestamp INTEGER NOT NULL
+            )",
+            params![],
+        )?;
+        txn.execute(
+            "CREATE INDEX IF NOT EXISTS refused_signing_attempts_public_key
+             ON refused_signing_attempts (public_key)",
+            params![],
+        )?;
+
         Ok(())
     }
 
@@ -573,14 +600,27 @@ impl SlashingDatabase {
     ) -> Result<Safe, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
-        let safe = self.check_and_insert_block_signing_root_txn(
+        let result = self.check_and_insert_block_signing_root_txn(
             validator_pubkey,
             slot,
             signing_root,
             &txn,
-        )?;
+        );
+        match &result {
+            Err(error) if error.is_refusal() => {
+                let attempt = RefusedSigningAttempt::block(
+                    *validator_pubkey,
+                    slot,
+                    signing_root.to_hash256_raw(),
+                    error,
+                );
+                self.insert_refused_signing_attempt(&txn, &attempt)?;
+            }
+            Err(_) => return result,
+            Ok(_) => (),
+        }
         txn.commit()?;
-        Ok(safe)
+        result
     }
 
     /// Transactional variant of `check_and_insert_block_signing_root`.
@@ -630,15 +670,29 @@ impl SlashingDatabase {
     ) -> Result<Safe, NotSafe> {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction_with_behavior(TransactionBehavior::Exclusive)?;
-        let safe = self.check_and_insert_attestation_signing_root_txn(
+        let result = self.check_and_insert_attestation_signing_root_txn(
             validator_pubkey,
             att_source_epoch,
             att_target_epoch,
             att_signing_root,
             &txn,
-        )?;
+        );
+        match &result {
+            Err(error) if error.is_refusal() => {
+                let attempt = RefusedSigningAttempt::attestation(
+                    *validator_pubkey,
+                    att_source_epoch,
+                    att_target_epoch,
+                    att_signing_root.to_hash256_raw(),
+                    error,
+                );
+                self.insert_refused_signing_attempt(&txn, &attempt)?;
+            }
+            Err(_) => return result,
+            Ok(_) => (),
+        }
         txn.commit()?;
-        Ok(safe)
+        result
     }
 
     /// Transactional variant of `check_and_insert_attestation_signing_root`.
@@ -679,17 +733,7 @@ impl SlashingDatabase {
         interchange: Interchange,
         genesis_validators_root: Hash256,
     ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
-        let version = interchange.metadata.interchange_format_version;
-        if version != SUPPORTED_INTERCHANGE_FORMAT_VERSION {
-            return Err(InterchangeError::UnsupportedVersion(version));
-        }
-
-        if genesis_validators_root != interchange.metadata.genesis_validators_root {
-            return Err(InterchangeError::GenesisValidatorsMismatch {
-                client: genesis_validators_root,
-                interchange_file: interchange.metadata.genesis_validators_root,
-            });
-        }
+        interchange.metadata.check(genesis_validators_root)?;
 
         // Create a single transaction for the entire batch, which will only be committed if
         // all records are imported successfully.
@@ -720,6 +764,47 @@ impl SlashingDatabase {
         }
     }
 
+    /// Import an interchange file from `reader` one record at a time.
+    ///
+    /// As with `import_interchange_info` all records are imported in a single transaction which is
+    /// only committed if every record is imported successfully, but the file is never held in
+    /// memory in its entirety.
+    pub fn import_interchange_stream(
+        &self,
+        reader: impl Read,
+        genesis_validators_root: Hash256,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
+        let mut conn = self.conn_pool.get()?;
+        let txn = conn.transaction()?;
+
+        let mut import_outcomes = vec![];
+        let mut commit = true;
+
+        let metadata = Interchange::stream_json_reader(reader, |metadata, record| {
+            metadata.check(genesis_validators_root)?;
+
+            let pubkey = record.pubkey;
+            match self.import_interchange_record(record, &txn) {
+                Ok(summary) => {
+                    import_outcomes.push(InterchangeImportOutcome::Success { pubkey, summary });
+                }
+                Err(error) => {
+                    import_outcomes.push(InterchangeImportOutcome::Failure { pubkey, error });
+                    commit = false;
+                }
+            }
+            Ok(())
+        })?;
+        metadata.check(genesis_validators_root)?;
+
+        if commit {
+            txn.commit()?;
+            Ok(import_outcomes)
+        } else {
+            Err(InterchangeError::AtomicBatchAborted(import_outcomes))
+        }
+    }
+
     pub fn import_interchange_record(
         &self,
         record: InterchangeData,
@@ -815,26 +900,17 @@ impl SlashingDatabase {
         selected_pubkeys: Option<&[PublicKeyBytes]>,
         txn: &Transaction,
     ) -> Result<Interchange, InterchangeError> {
-        // Determine the validator IDs and public keys to export data for.
-        let to_export = if let Some(selected_pubkeys) = selected_pubkeys {
-            selected_pubkeys
-                .iter()
-                .map(|pubkey| {
-                    let id = self.get_validator_id_ignoring_status(txn, pubkey)?;
-                    Ok((id, *pubkey))
-                })
-                .collect::<Result<_, InterchangeError>>()?
-        } else {
-            self.list_all_registered_validators(txn)?
-        };
-
-        let data = to_export
+        let data = self
+            .validators_to_export(selected_pubkeys, txn)?
             .into_iter()
             .map(|(validator_id, pubkey)| {
                 let signed_blocks =
-                    self.export_interchange_blocks_for_validator(validator_id, txn)?;
-                let signed_attestations =
-                    self.export_interchange_attestations_for_validator(validator_id, txn)?;
+                    self.export_interchange_blocks_for_validator(validator_id, Slot::new(0), txn)?;
+                let signed_attestations = self.export_interchange_attestations_for_validator(
+                    validator_id,
+                    Epoch::new(0),
+                    txn,
+                )?;
                 Ok(InterchangeData {
                     pubkey,
                     signed_blocks,
@@ -851,18 +927,119 @@ impl SlashingDatabase {
         Ok(Interchange { metadata, data })
     }
 
+    /// Export an interchange file to `writer` one validator at a time, returning the number of
+    /// validators written.
+    ///
+    /// If `since_epoch` is set then only blocks and attestations from that epoch onwards are
+    /// exported, and validators without any such messages are omitted. This is useful for
+    /// producing small incremental exports on top of an earlier full export.
+    pub fn export_interchange_stream(
+        &self,
+        genesis_validators_root: Hash256,
+        selected_pubkeys: Option<&[PublicKeyBytes]>,
+        since_epoch: Option<Epoch>,
+        slots_per_epoch: u64,
+        writer: impl Write,
+    ) -> Result<usize, InterchangeError> {
+        let (min_slot, min_target_epoch) =
+            since_epoch.map_or((Slot::new(0), Epoch::new(0)), |epoch| {
+                (
+                    min(
+                        epoch.start_slot(slots_per_epoch),
+                        Slot::new(i64::MAX as u64),
+                    ),
+                    min(epoch, Epoch::new(i64::MAX as u64)),
+                )
+            });
+
+        let mut conn = self.conn_pool.get()?;
+        let txn = &conn.transaction()?;
+
+        let metadata = InterchangeMetadata {
+            interchange_format_version: SUPPORTED_INTERCHANGE_FORMAT_VERSION,
+            genesis_validators_root,
+        };
+        let mut writer = InterchangeWriter::new(writer, &metadata)?;
+
+        for (validator_id, pubkey) in self.validators_to_export(selected_pubkeys, txn)? {
+            let signed_blocks =
+                self.export_interchange_blocks_for_validator(validator_id, min_slot, txn)?;
+            let signed_attestations = self.export_interchange_attestations_for_validator(
+                validator_id,
+                min_target_epoch,
+                txn,
+            )?;
+
+            if since_epoch.is_some() && signed_blocks.is_empty() && signed_attestations.is_empty() {
+                continue;
+            }
+
+            writer.write_record(&InterchangeData {
+                pubkey,
+                signed_blocks,
+                signed_attestations,
+            })?;
+        }
+
+        Ok(writer.finish()?)
+    }
+
+    /// Determine the validator IDs and public keys to export data for.
+    fn validators_to_export(
+        &self,
+        selected_pubkeys: Option<&[PublicKeyBytes]>,
+        txn: &Transaction,
+    ) -> Result<Vec<(i64, PublicKeyBytes)>, InterchangeError> {
+        if let Some(selected_pubkeys) = selected_pubkeys {
+            selected_pubkeys
+                .iter()
+                .map(|pubkey| {
+                    let id = self.get_validator_id_ignoring_status(txn, pubkey)?;
+                    Ok((id, *pubkey))
+                })
+                .collect()
+        } else {
+            self.list_all_registered_validators(txn)
+        }
+    }
+
+    /// Disable the given validators and export their data, in a single transaction.
+    ///
+    /// Keys which are unknown to the database are omitted from the result.
+    pub fn export_interchange_info_and_disable(
+        &self,
+        genesis_validators_root: Hash256,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<Interchange, InterchangeError> {
+        self.with_transaction(|txn| {
+            let known_pubkeys = pubkeys
+                .iter()
+                .filter_map(|pubkey| {
+                    let validator_id = self.get_validator_id_ignoring_status(txn, pubkey).ok()?;
+
+                    Some(
+                        self.update_validator_status(txn, validator_id, false)
+                            .map(|()| *pubkey),
+                    )
+                })
+                .collect::<Result<Vec<PublicKeyBytes>, _>>()?;
+            self.export_interchange_info_in_txn(genesis_validators_root, Some(&known_pubkeys), txn)
+        })
+    }
+
     fn export_interchange_blocks_for_validator(
         &self,
         validator_id: i64,
+        min_slot: Slot,
         txn: &Transaction,
     ) -> Result<Vec<InterchangeBlock>, InterchangeError> {
         txn.prepare(
             "SELECT slot, signing_root
              FROM signed_blocks
-             WHERE signed_blocks.validator_id = ?1
+             WHERE signed_blocks.validator_id = ?1 AND slot >= ?2
              ORDER BY slot ASC",
         )?
-        .query_and_then(params![validator_id], |row| {
+        .query_and_then(params![validator_id, min_slot], |row| {
             let slot = row.get(0)?;
             let signing_root = signing_root_from_row(1, row)?.to_hash256();
             Ok(InterchangeBlock { slot, signing_root })
@@ -873,15 +1050,16 @@ impl SlashingDatabase {
     fn export_interchange_attestations_for_validator(
         &self,
         validator_id: i64,
+        min_target_epoch: Epoch,
         txn: &Transaction,
     ) -> Result<Vec<InterchangeAttestation>, InterchangeError> {
         txn.prepare(
             "SELECT source_epoch, target_epoch, signing_root
              FROM signed_attestations
-             WHERE signed_attestations.validator_id = ?1
+             WHERE signed_attestations.validator_id = ?1 AND target_epoch >= ?2
              ORDER BY source_epoch ASC, target_epoch ASC",
         )?
-        .query_and_then(params![validator_id], |row| {
+        .query_and_then(params![validator_id, min_target_epoch], |row| {
             let source_epoch = row.get(0)?;
             let target_epoch = row.get(1)?;
             let signing_root = signing_root_from_row(2, row)?.to_hash256();
@@ -919,6 +1097,9 @@ impl SlashingDatabase {
     }
 
     /// Prune the signed blocks table for the given public keys.
+    ///
+    /// Refused block proposals with slots less than `new_min_slot` are also removed, for all
+    /// validators.
     pub fn prune_all_signed_blocks<'a>(
         &self,
         mut public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
@@ -927,6 +1108,10 @@ impl SlashingDatabase {
         let mut conn = self.conn_pool.get()?;
         let txn = conn.transaction()?;
         public_keys.try_for_each(|pubkey| self.prune_signed_blocks(pubkey, new_min_slot, &txn))?;
+        txn.execute(
+            "DELETE FROM refused_signing_attempts WHERE slot < ?1",
+            params![new_min_slot],
+        )?;
         txn.commit()?;
         Ok(())
     }
@@ -999,6 +1184,9 @@ impl SlashingDatabase {
     }
 
     /// Prune the signed attestations table for the given validator keys.
+    ///
+    /// Refused attestations with targets less than `new_min_target` are also removed, for all
+    /// validators.
     pub fn prune_all_signed_attestations<'a>(
         &self,
         mut public_keys: impl Iterator<Item = &'a PublicKeyBytes>,
@@ -1008,6 +1196,10 @@ impl SlashingDatabase {
         let txn = conn.transaction()?;
         public_keys
             .try_for_each(|pubkey| self.prune_signed_attestations(pubkey, new_min_target, &txn))?;
+        txn.execute(
+            "DELETE FROM refused_signing_attempts WHERE target_epoch < ?1",
+            params![new_min_target],
+        )?;
         txn.commit()?;
         Ok(())
     }
@@ -1060,6 +1252,244 @@ impl SlashingDatabase {
             max_attestation_target,
         })
     }
+
+    /// Record a signing attempt which was refused.
+    ///
+    /// This should be called in the same transaction as the failed check, which is committed
+    /// afterwards so that the refusal is recorded.
+    fn insert_refused_signing_attempt(
+        &self,
+        txn: &Transaction,
+        attempt: &RefusedSigningAttempt,
+    ) -> Result<(), NotSafe> {
+        txn.execute(
+            "INSERT INTO refused_signing_attempts
+                (public_key, kind, slot, source_epoch, target_epoch, signing_root, reason, timestamp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                attempt.pubkey.as_hex_string(),
+                attempt.kind.as_str(),
+                attempt.slot,
+                attempt.source_epoch,
+                attempt.target_epoch,
+                attempt.signing_root.as_slice(),
+                attempt.reason,
+                i64::try_from(attempt.timestamp).unwrap_or(i64::MAX),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Return the messages signed by the validators selected by `query`.
+    pub fn signing_history(&self, query: &AuditQuery) -> Result<Vec<SigningHistory>, NotSafe> {
+        self.with_transaction(|txn| {
+            let validators = if let Some(pubkey) = query.pubkey {
+                let validator_id = self.get_validator_id_ignoring_status(txn, &pubkey)?;
+                vec![(validator_id, pubkey)]
+            } else {
+                txn.prepare("SELECT id, public_key FROM validators ORDER BY id ASC")?
+                    .query_and_then(params![], |row| {
+                        let validator_id = row.get(0)?;
+                        let pubkey_str: String = row.get(1)?;
+                        let pubkey = pubkey_str.parse().map_err(NotSafe::SQLError)?;
+                        Ok((validator_id, pubkey))
+                    })?
+                    .collect::<Result<_, NotSafe>>()?
+            };
+
+            validators
+                .into_iter()
+                .map(|(validator_id, pubkey): (i64, PublicKeyBytes)| {
+                    let signed_blocks = txn
+                        .prepare(
+                            "SELECT slot, signing_root
+                             FROM signed_blocks
+                             WHERE validator_id = ?1 AND slot >= ?2 AND slot <= ?3
+                             ORDER BY slot ASC",
+                        )?
+                        .query_and_then(
+                            params![validator_id, query.start_slot(), query.end_slot()],
+                            |row| {
+                                let slot = row.get(0)?;
+                                let signing_root = signing_root_from_row(1, row)?.to_hash256();
+                                Ok(InterchangeBlock { slot, signing_root })
+                            },
+                        )?
+                        .collect::<Result<_, NotSafe>>()?;
+                    let signed_attestations = txn
+                        .prepare(
+                            "SELECT source_epoch, target_epoch, signing_root
+                             FROM signed_attestations
+                             WHERE validator_id = ?1 AND target_epoch >= ?2 AND target_epoch <= ?3
+                             ORDER BY target_epoch ASC",
+                        )?
+                        .query_and_then(
+                            params![validator_id, query.start_epoch(), query.end_epoch()],
+                            |row| {
+                                let source_epoch = row.get(0)?;
+                                let target_epoch = row.get(1)?;
+                                let signing_root = signing_root_from_row(2, row)?.to_hash256();
+                                Ok(InterchangeAttestation {
+                                    source_epoch,
+                                    target_epoch,
+                                    signing_root,
+                                })
+                            },
+                        )?
+                        .collect::<Result<_, NotSafe>>()?;
+                    Ok(SigningHistory {
+                        pubkey,
+                        signed_blocks,
+                        signed_attestations,
+                    })
+                })
+                .collect()
+        })
+    }
+
+    /// Return the most recent refused signing attempts selected by `query`.
+    ///
+    /// Block proposals are selected by their slot, and attestations by their target epoch.
+    pub fn refused_signing_attempts(
+        &self,
+        query: &AuditQuery,
+    ) -> Result<Vec<RefusedSigningAttempt>, NotSafe> {
+        let pubkey = query.pubkey.map(|pubkey| pubkey.as_hex_string());
+        let limit = i64::try_from(query.limit).unwrap_or(i64::MAX);
+        self.with_transaction(|txn| {
+            txn.prepare(
+                "SELECT public_key, kind, slot, source_epoch, target_epoch, signing_root, reason,
+                        timestamp
+                 FROM refused_signing_attempts
+                 WHERE (?1 IS NULL OR public_key = ?1) AND
+                       ((slot >= ?2 AND slot <= ?3) OR
+                        (target_epoch >= ?4 AND target_epoch <= ?5))
+                 ORDER BY id DESC
+                 LIMIT ?6",
+            )?
+            .query_and_then(
+                params![
+                    pubkey,
+                    query.start_slot(),
+                    query.end_slot(),
+                    query.start_epoch(),
+                    query.end_epoch(),
+                    limit
+                ],
+                |row| {
+                    let pubkey_str: String = row.get(0)?;
+                    let kind_str: String = row.get(1)?;
+                    let timestamp: i64 = row.get(7)?;
+                    Ok(RefusedSigningAttempt {
+                        pubkey: pubkey_str.parse().map_err(NotSafe::SQLError)?,
+                        kind: kind_str.parse().map_err(NotSafe::SQLError)?,
+                        slot: row.get(2)?,
+                        source_epoch: row.get(3)?,
+                        target_epoch: row.get(4)?,
+                        signing_root: signing_root_from_row(5, row)?.to_hash256_raw(),
+                        reason: row.get(6)?,
+                        timestamp: timestamp as u64,
+                    })
+                },
+            )?
+            .collect()
+        })
+    }
+}
+
+impl SlashingProtectionBackend for SlashingDatabase {
+    fn register_validators(&self, public_keys: &[PublicKeyBytes]) -> Result<(), NotSafe> {
+        SlashingDatabase::register_validators(self, public_keys.iter())
+    }
+
+    fn check_validator_registrations(&self, public_keys: &[PublicKeyBytes]) -> Result<(), NotSafe> {
+        SlashingDatabase::check_validator_registrations(self, public_keys.iter())
+    }
+
+    fn check_and_insert_block_signing_root(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        slot: Slot,
+        signing_root: SigningRoot,
+    ) -> Result<Safe, NotSafe> {
+        SlashingDatabase::check_and_insert_block_signing_root(
+            self,
+            validator_pubkey,
+            slot,
+            signing_root,
+        )
+    }
+
+    fn check_and_insert_attestation_signing_root(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        att_source_epoch: Epoch,
+        att_target_epoch: Epoch,
+        att_signing_root: SigningRoot,
+    ) -> Result<Safe, NotSafe> {
+        SlashingDatabase::check_and_insert_attestation_signing_root(
+            self,
+            validator_pubkey,
+            att_source_epoch,
+            att_target_epoch,
+            att_signing_root,
+        )
+    }
+
+    fn import_interchange_info(
+        &self,
+        interchange: Interchange,
+        genesis_validators_root: Hash256,
+    ) -> Result<Vec<InterchangeImportOutcome>, InterchangeError> {
+        SlashingDatabase::import_interchange_info(self, interchange, genesis_validators_root)
+    }
+
+    fn export_interchange_info(
+        &self,
+        genesis_validators_root: Hash256,
+        selected_pubkeys: Option<&[PublicKeyBytes]>,
+    ) -> Result<Interchange, InterchangeError> {
+        SlashingDatabase::export_interchange_info(self, genesis_validators_root, selected_pubkeys)
+    }
+
+    fn export_interchange_info_and_disable(
+        &self,
+        genesis_validators_root: Hash256,
+        pubkeys: &[PublicKeyBytes],
+    ) -> Result<Interchange, InterchangeError> {
+        SlashingDatabase::export_interchange_info_and_disable(
+            self,
+            genesis_validators_root,
+            pubkeys,
+        )
+    }
+
+    fn prune_all_signed_blocks(
+        &self,
+        public_keys: &[PublicKeyBytes],
+        new_min_slot: Slot,
+    ) -> Result<(), NotSafe> {
+        SlashingDatabase::prune_all_signed_blocks(self, public_keys.iter(), new_min_slot)
+    }
+
+    fn prune_all_signed_attestations(
+        &self,
+        public_keys: &[PublicKeyBytes],
+        new_min_target: Epoch,
+    ) -> Result<(), NotSafe> {
+        SlashingDatabase::prune_all_signed_attestations(self, public_keys.iter(), new_min_target)
+    }
+
+    fn signing_history(&self, query: &AuditQuery) -> Result<Vec<SigningHistory>, NotSafe> {
+        SlashingDatabase::signing_history(self, query)
+    }
+
+    fn refused_signing_attempts(
+        &self,
+        query: &AuditQuery,
+    ) -> Result<Vec<RefusedSigningAttempt>, NotSafe> {
+        SlashingDatabase::refused_signing_attempts(self, query)
+    }
 }
 
 /// Minimum and maximum slots and epochs signed by a validator.
@@ -1074,7 +1504,7 @@ pub struct ValidatorSummary {
 }
 
 impl ValidatorSummary {
-    fn check_block_consistency(&self, prev: &Self, imported_blocks: bool) -> bool {
+    pub(crate) fn check_block_consistency(&self, prev: &Self, imported_blocks: bool) -> bool {
         if imported_blocks {
             // Max block slot should be monotonically increasing and non-null.
             // Minimum should match maximum due to pruning.
@@ -1086,7 +1516,11 @@ impl ValidatorSummary {
         }
     }
 
-    fn check_attestation_consistency(&self, prev: &Self, imported_attestations: bool) -> bool {
+    pub(crate) fn check_attestation_consistency(
+        &self,
+        prev: &Self,
+        imported_attestations: bool,
+    ) -> bool {
         if imported_attestations {
             // Max source and target epochs should be monotically increasing and non-null.
             // Minimums should match maximums due to pruning.
@@ -1105,7 +1539,7 @@ impl ValidatorSummary {
 }
 
 /// Take the maximum of `opt_x` and `y`, returning `y` if `opt_x` is `None`.
-fn max_or<T: Copy + Ord>(opt_x: Option<T>, y: T) -> T {
+pub(crate) fn max_or<T: Copy + Ord>(opt_x: Option<T>, y: T) -> T {
     opt_x.map_or(y, |x| std::cmp::max(x, y))
 }
 
@@ -1166,6 +1600,12 @@ impl From<rusqlite::Error> for InterchangeError {
     }
 }
 
+impl From<postgres::Error> for InterchangeError {
+    fn from(error: postgres::Error) -> Self {
+        Self::SQLError(error.to_string())
+    }
+}
+
 impl From<r2d2::Error> for InterchangeError {
     fn from(error: r2d2::Error) -> Self {
         InterchangeError::SQLPoolError(error)
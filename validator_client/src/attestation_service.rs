@@ -5,7 +5,9 @@ use crate::{
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
 };
 use environment::RuntimeContext;
+use eth2::trace_context::with_traceparent;
 use futures::future::join_all;
+use otlp::Span;
 use slog::{crit, debug, error, info, trace, warn};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
@@ -336,9 +338,15 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .ok_or("Unable to determine current slot from clock")?
             .epoch(E::slots_per_epoch());
 
-        let attestation_data = self
-            .beacon_nodes
-            .first_success(|beacon_node| async move {
+        let mut span = Span::root("attestation");
+        span.set_attribute("slot", slot.as_u64());
+        span.set_attribute("committee_index", committee_index);
+        span.set_attribute("validators", validator_duties.len());
+
+        let mut produce_span = span.child("produce");
+        let attestation_data = with_traceparent(
+            produce_span.traceparent(),
+            self.beacon_nodes.first_success(|beacon_node| async move {
                 let _timer = metrics::start_timer_vec(
                     &metrics::ATTESTATION_SERVICE_TIMES,
                     &[metrics::ATTESTATIONS_HTTP_GET],
@@ -348,9 +356,39 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     .await
                     .map_err(|e| format!("Failed to produce attestation data: {:?}", e))
                     .map(|result| result.data)
-            })
-            .await
-            .map_err(|e| e.to_string())?;
+            }),
+        )
+        .await
+        .map_err(|e| {
+            produce_span.set_error(&e);
+            e.to_string()
+        })?;
+        drop(produce_span);
+
+        if self.validator_store.dry_run() {
+            let validator_indices: Vec<_> = validator_duties
+                .iter()
+                .map(|duty_and_proof| duty_and_proof.duty.validator_index)
+                .collect();
+            info!(
+                log,
+                "Skipped publishing attestations in dry run";
+                "count" => validator_indices.len(),
+                "validator_indices" => ?validator_indices,
+                "head_block" => ?attestation_data.beacon_block_root,
+                "source_epoch" => attestation_data.source.epoch,
+                "target_epoch" => attestation_data.target.epoch,
+                "committee_index" => attestation_data.index,
+                "slot" => attestation_data.slot.as_u64(),
+                "type" => "unaggregated",
+            );
+            metrics::inc_counter_vec_by(
+                &metrics::DRY_RUN_MESSAGES_TOTAL,
+                &[metrics::ATTESTATIONS],
+                validator_indices.len() as u64,
+            );
+            return Ok(Some(attestation_data));
+        }
 
         // Create futures to produce signed `Attestation` objects.
         let attestation_data_ref = &attestation_data;
@@ -434,11 +472,14 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
+        let mut sign_span = span.child("sign");
         let (ref attestations, ref validator_indices): (Vec<_>, Vec<_>) = join_all(signing_futures)
             .await
             .into_iter()
             .flatten()
             .unzip();
+        sign_span.set_attribute("signatures", attestations.len());
+        drop(sign_span);
 
         if attestations.is_empty() {
             warn!(log, "No attestations were published");
@@ -451,24 +492,27 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .fork_name_at_slot::<E>(attestation_data.slot);
 
         // Post the attestations to the BN.
-        match self
-            .beacon_nodes
-            .request(ApiTopic::Attestations, |beacon_node| async move {
-                let _timer = metrics::start_timer_vec(
-                    &metrics::ATTESTATION_SERVICE_TIMES,
-                    &[metrics::ATTESTATIONS_HTTP_POST],
-                );
-                if fork_name.electra_enabled() {
-                    beacon_node
-                        .post_beacon_pool_attestations_v2(attestations, fork_name)
-                        .await
-                } else {
-                    beacon_node
-                        .post_beacon_pool_attestations_v1(attestations)
-                        .await
-                }
-            })
-            .await
+        let mut publish_span = span.child("publish");
+        match with_traceparent(
+            publish_span.traceparent(),
+            self.beacon_nodes
+                .request(ApiTopic::Attestations, |beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::ATTESTATION_SERVICE_TIMES,
+                        &[metrics::ATTESTATIONS_HTTP_POST],
+                    );
+                    if fork_name.electra_enabled() {
+                        beacon_node
+                            .post_beacon_pool_attestations_v2(attestations, fork_name)
+                            .await
+                    } else {
+                        beacon_node
+                            .post_beacon_pool_attestations_v1(attestations)
+                            .await
+                    }
+                }),
+        )
+        .await
         {
             Ok(()) => info!(
                 log,
@@ -480,14 +524,17 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                 "slot" => attestation_data.slot.as_u64(),
                 "type" => "unaggregated",
             ),
-            Err(e) => error!(
-                log,
-                "Unable to publish attestations";
-                "error" => %e,
-                "committee_index" => attestation_data.index,
-                "slot" => slot.as_u64(),
-                "type" => "unaggregated",
-            ),
+            Err(e) => {
+                publish_span.set_error(&e);
+                error!(
+                    log,
+                    "Unable to publish attestations";
+                    "error" => %e,
+                    "committee_index" => attestation_data.index,
+                    "slot" => slot.as_u64(),
+                    "type" => "unaggregated",
+                )
+            }
         }
 
         Ok(Some(attestation_data))
@@ -528,9 +575,14 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
             .spec
             .fork_name_at_slot::<E>(attestation_data.slot);
 
-        let aggregated_attestation = &self
-            .beacon_nodes
-            .first_success(|beacon_node| async move {
+        let mut span = Span::root("aggregate");
+        span.set_attribute("slot", attestation_data.slot.as_u64());
+        span.set_attribute("committee_index", committee_index);
+
+        let mut produce_span = span.child("produce");
+        let aggregated_attestation = &with_traceparent(
+            produce_span.traceparent(),
+            self.beacon_nodes.first_success(|beacon_node| async move {
                 let _timer = metrics::start_timer_vec(
                     &metrics::ATTESTATION_SERVICE_TIMES,
                     &[metrics::AGGREGATES_HTTP_GET],
@@ -561,9 +613,38 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                         .ok_or_else(|| format!("No aggregate available for {:?}", attestation_data))
                         .map(|result| result.data)
                 }
-            })
-            .await
-            .map_err(|e| e.to_string())?;
+            }),
+        )
+        .await
+        .map_err(|e| {
+            produce_span.set_error(&e);
+            e.to_string()
+        })?;
+        drop(produce_span);
+
+        if self.validator_store.dry_run() {
+            let aggregators: Vec<_> = validator_duties
+                .iter()
+                .filter(|duty_and_proof| duty_and_proof.selection_proof.is_some())
+                .map(|duty_and_proof| duty_and_proof.duty.validator_index)
+                .collect();
+            info!(
+                log,
+                "Skipped publishing attestations in dry run";
+                "aggregators" => ?aggregators,
+                "signatures" => aggregated_attestation.num_set_aggregation_bits(),
+                "head_block" => ?attestation_data.beacon_block_root,
+                "committee_index" => committee_index,
+                "slot" => attestation_data.slot.as_u64(),
+                "type" => "aggregated",
+            );
+            metrics::inc_counter_vec_by(
+                &metrics::DRY_RUN_MESSAGES_TOTAL,
+                &[metrics::AGGREGATES],
+                aggregators.len() as u64,
+            );
+            return Ok(());
+        }
 
         // Create futures to produce the signed aggregated attestations.
         let signing_futures = validator_duties.iter().map(|duty_and_proof| async move {
@@ -609,17 +690,21 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
         });
 
         // Execute all the futures in parallel, collecting any successful results.
+        let mut sign_span = span.child("sign");
         let signed_aggregate_and_proofs = join_all(signing_futures)
             .await
             .into_iter()
             .flatten()
             .collect::<Vec<_>>();
+        sign_span.set_attribute("signatures", signed_aggregate_and_proofs.len());
+        drop(sign_span);
 
         if !signed_aggregate_and_proofs.is_empty() {
             let signed_aggregate_and_proofs_slice = signed_aggregate_and_proofs.as_slice();
-            match self
-                .beacon_nodes
-                .first_success(|beacon_node| async move {
+            let mut publish_span = span.child("publish");
+            match with_traceparent(
+                publish_span.traceparent(),
+                self.beacon_nodes.first_success(|beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::ATTESTATION_SERVICE_TIMES,
                         &[metrics::AGGREGATES_HTTP_POST],
@@ -638,8 +723,9 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                             )
                             .await
                     }
-                })
-                .await
+                }),
+            )
+            .await
             {
                 Ok(()) => {
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
@@ -657,6 +743,7 @@ impl<T: SlotClock + 'static, E: EthSpec> AttestationService<T, E> {
                     }
                 }
                 Err(e) => {
+                    publish_span.set_error(&e);
                     for signed_aggregate_and_proof in signed_aggregate_and_proofs {
                         let attestation = &signed_aggregate_and_proof.message().aggregate();
                         crit!(
@@ -7,18 +7,22 @@ use crate::beacon_node_health::{
     SyncDistanceTier,
 };
 use crate::check_synced::check_node_health;
-use crate::http_metrics::metrics::{inc_counter_vec, ENDPOINT_ERRORS, ENDPOINT_REQUESTS};
+use crate::http_metrics::metrics::{
+    inc_counter_vec, ENDPOINT_CIRCUIT_BREAKER_TRIPS, ENDPOINT_ERRORS, ENDPOINT_REQUESTS,
+};
 use environment::RuntimeContext;
 use eth2::BeaconNodeHttpClient;
-use futures::future;
+use futures::{future, stream::FuturesUnordered, StreamExt};
+use parking_lot::Mutex;
 use serde::{ser::SerializeStruct, Deserialize, Serialize, Serializer};
-use slog::{debug, error, warn, Logger};
+use slog::{debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::cmp::Ordering;
 use std::fmt;
 use std::fmt::Debug;
 use std::future::Future;
 use std::marker::PhantomData;
+use std::sync::atomic::{self, AtomicU64};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 use strum::{EnumString, EnumVariantNames};
@@ -42,9 +46,37 @@ const SLOT_LOOKAHEAD: Duration = Duration::from_secs(2);
 const FUTURE_SLOT_TOLERANCE: Slot = Slot::new(1);
 
 // Configuration for the Beacon Node fallback.
-#[derive(Copy, Clone, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct Config {
     pub sync_tolerances: BeaconNodeSyncDistanceTiers,
+    /// The number of the most suitable candidates which are sent duty-critical requests
+    /// concurrently, taking the first successful response. Values below 2 disable racing.
+    pub race_count: usize,
+    /// The number of consecutive health checks for which the primary candidate must be in a worse
+    /// health tier than another candidate before the primary is replaced. If `None`, the most
+    /// suitable candidate is always tried first.
+    pub sticky_primary_hysteresis: Option<usize>,
+    /// Weights for distributing non-critical requests across the healthy candidates, in the order
+    /// of `--beacon-nodes`. If `None`, non-critical requests are ordered like duty-critical ones.
+    pub weights: Option<Vec<u64>>,
+    pub circuit_breaker: Option<CircuitBreakerConfig>,
+}
+
+/// Configuration for the circuit breaker of each candidate.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CircuitBreakerConfig {
+    /// The number of consecutive failed requests which trips the breaker.
+    pub threshold: usize,
+    /// How long a candidate is skipped on the first pass after its breaker trips.
+    pub cooldown: Duration,
+}
+
+/// Distinguishes requests which are required to perform a duty from those which are not, since
+/// they are distributed across candidates differently.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum RequestPriority {
+    Critical,
+    NonCritical,
 }
 
 /// Indicates a measurement of latency between the VC and a BN.
@@ -181,6 +213,23 @@ impl Serialize for CandidateInfo {
     }
 }
 
+/// The state of the circuit breaker of a `CandidateBeaconNode`.
+///
+/// The breaker trips after `CircuitBreakerConfig::threshold` consecutive failures. Once the cooldown
+/// has elapsed the candidate is tried again and a single further failure trips the breaker again,
+/// whilst a success resets it.
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: usize,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn is_open(&self, now: Instant) -> bool {
+        self.open_until.is_some_and(|open_until| now < open_until)
+    }
+}
+
 /// Represents a `BeaconNodeHttpClient` inside a `BeaconNodeFallback` that may or may not be used
 /// for a query.
 #[derive(Clone, Debug)]
@@ -188,6 +237,7 @@ pub struct CandidateBeaconNode<E> {
     pub index: usize,
     pub beacon_node: BeaconNodeHttpClient,
     pub health: Arc<RwLock<Result<BeaconNodeHealth, CandidateError>>>,
+    circuit_breaker: Arc<Mutex<CircuitBreaker>>,
     _phantom: PhantomData<E>,
 }
 
@@ -206,6 +256,7 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
             index,
             beacon_node,
             health: Arc::new(RwLock::new(Err(CandidateError::Uninitialized))),
+            circuit_breaker: <_>::default(),
             _phantom: PhantomData,
         }
     }
@@ -215,6 +266,29 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
         *self.health.read().await
     }
 
+    /// Returns `true` if the circuit breaker of `self` has tripped and its cooldown has not yet
+    /// elapsed.
+    pub fn is_circuit_open(&self) -> bool {
+        self.circuit_breaker.lock().is_open(Instant::now())
+    }
+
+    fn record_success(&self) {
+        *self.circuit_breaker.lock() = CircuitBreaker::default();
+    }
+
+    /// Record a failed request, returning `true` if this trips the circuit breaker.
+    fn record_failure(&self, config: &CircuitBreakerConfig) -> bool {
+        let now = Instant::now();
+        let mut breaker = self.circuit_breaker.lock();
+        breaker.consecutive_failures += 1;
+        if breaker.consecutive_failures < config.threshold {
+            return false;
+        }
+        let tripped = !breaker.is_open(now);
+        breaker.open_until = Some(now + config.cooldown);
+        tripped
+    }
+
     pub async fn refresh_health<T: SlotClock>(
         &self,
         distance_tiers: &BeaconNodeSyncDistanceTiers,
@@ -367,6 +441,69 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
     }
 }
 
+/// The candidate which is tried first when `Config::sticky_primary_hysteresis` is set.
+#[derive(Debug, Default)]
+struct StickyPrimary {
+    index: Option<usize>,
+    /// The number of consecutive health checks for which the primary has been in a worse health
+    /// tier than the most suitable candidate.
+    worse_checks: usize,
+}
+
+impl StickyPrimary {
+    /// Update the primary from the index and health tier of each candidate, ordered by health.
+    ///
+    /// A primary which is unhealthy is replaced immediately, whilst one which is merely in a worse
+    /// tier is replaced after `hysteresis` consecutive checks. Returns the index of the new primary
+    /// if it changed.
+    fn update(&mut self, candidates: &[(usize, Option<u8>)], hysteresis: usize) -> Option<usize> {
+        let &(best_index, Some(best_tier)) = candidates.first()? else {
+            // None of the candidates are healthy, there is nothing better to switch to.
+            return None;
+        };
+        let primary_tier = self
+            .index
+            .and_then(|index| candidates.iter().find(|(i, _)| *i == index))
+            .and_then(|(_, tier)| *tier);
+
+        match primary_tier {
+            Some(tier) if tier <= best_tier => {
+                self.worse_checks = 0;
+                return None;
+            }
+            Some(_) => {
+                self.worse_checks += 1;
+                if self.worse_checks < hysteresis {
+                    return None;
+                }
+            }
+            None => (),
+        }
+
+        self.index = Some(best_index);
+        self.worse_checks = 0;
+        Some(best_index)
+    }
+}
+
+/// Returns the position in `weights` to use for the `counter`th request, such that each position is
+/// chosen in proportion to its weight.
+fn weighted_choice(weights: &[u64], counter: u64) -> Option<usize> {
+    let total: u64 = weights.iter().sum();
+    if total == 0 {
+        return None;
+    }
+    let mut point = counter % total;
+    weights.iter().position(|&weight| {
+        if point < weight {
+            true
+        } else {
+            point -= weight;
+            false
+        }
+    })
+}
+
 /// A collection of `CandidateBeaconNode` that can be used to perform requests with "fallback"
 /// behaviour, where the failure of one candidate results in the next candidate receiving an
 /// identical query.
@@ -374,6 +511,12 @@ impl<E: EthSpec> CandidateBeaconNode<E> {
 pub struct BeaconNodeFallback<T, E> {
     pub candidates: Arc<RwLock<Vec<CandidateBeaconNode<E>>>>,
     distance_tiers: BeaconNodeSyncDistanceTiers,
+    race_count: usize,
+    sticky_primary_hysteresis: Option<usize>,
+    sticky_primary: Arc<Mutex<StickyPrimary>>,
+    weights: Option<Vec<u64>>,
+    round_robin_counter: Arc<AtomicU64>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
     slot_clock: Option<T>,
     broadcast_topics: Vec<ApiTopic>,
     spec: Arc<ChainSpec>,
@@ -392,6 +535,12 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Self {
             candidates: Arc::new(RwLock::new(candidates)),
             distance_tiers,
+            race_count: config.race_count,
+            sticky_primary_hysteresis: config.sticky_primary_hysteresis,
+            sticky_primary: <_>::default(),
+            weights: config.weights,
+            round_robin_counter: <_>::default(),
+            circuit_breaker: config.circuit_breaker,
             slot_clock: None,
             broadcast_topics,
             spec,
@@ -504,6 +653,59 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
         let mut candidates = self.candidates.write().await;
         sort_nodes_by_health(&mut candidates).await;
+
+        if let Some(hysteresis) = self.sticky_primary_hysteresis {
+            let mut tiers = Vec::with_capacity(candidates.len());
+            for candidate in candidates.iter() {
+                let tier = candidate.health().await.ok().map(|h| h.health_tier.tier);
+                tiers.push((candidate.index, tier));
+            }
+
+            let new_primary = self.sticky_primary.lock().update(&tiers, hysteresis);
+            if let Some(candidate) =
+                new_primary.and_then(|index| candidates.iter().find(|c| c.index == index))
+            {
+                info!(
+                    self.log,
+                    "Switched primary beacon node";
+                    "endpoint" => %candidate.beacon_node,
+                );
+            }
+        }
+    }
+
+    /// Returns the candidates in the order in which requests of `priority` should try them.
+    ///
+    /// The order set in `update_all_candidates` is used, except that the sticky primary is moved
+    /// to the front or, for non-critical requests with weights, the candidate chosen by a weighted
+    /// round-robin over the healthy candidates is.
+    async fn ordered_candidates(&self, priority: RequestPriority) -> Vec<CandidateBeaconNode<E>> {
+        let primary = self.sticky_primary.lock().index;
+        let mut candidates = self.candidates.read().await.clone();
+
+        let first = match (priority, &self.weights) {
+            (RequestPriority::NonCritical, Some(weights)) => {
+                let mut healthy = vec![];
+                let mut healthy_weights = vec![];
+                for (position, candidate) in candidates.iter().enumerate() {
+                    if candidate.health().await.is_ok() {
+                        healthy.push(position);
+                        healthy_weights.push(weights.get(candidate.index).copied().unwrap_or(1));
+                    }
+                }
+                let counter = self
+                    .round_robin_counter
+                    .fetch_add(1, atomic::Ordering::Relaxed);
+                weighted_choice(&healthy_weights, counter).map(|i| healthy[i])
+            }
+            _ => primary.and_then(|index| candidates.iter().position(|c| c.index == index)),
+        };
+
+        if let Some(position) = first {
+            let candidate = candidates.remove(position);
+            candidates.insert(0, candidate);
+        }
+        candidates
     }
 
     /// Concurrently send a request to all candidates (regardless of
@@ -551,53 +753,80 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
     /// Run `func` against each candidate in `self`, returning immediately if a result is found.
     /// Otherwise, return all the errors encountered along the way.
+    ///
+    /// This should be used for requests which are required to perform a duty. The most suitable
+    /// candidates may be raced, according to `Config::race_count`.
     pub async fn first_success<F, O, Err, R>(&self, func: F) -> Result<O, Errors<Err>>
     where
         F: Fn(BeaconNodeHttpClient) -> R,
         R: Future<Output = Result<O, Err>>,
         Err: Debug,
     {
-        let mut errors = vec![];
+        self.first_success_with_priority(RequestPriority::Critical, func)
+            .await
+    }
 
-        // First pass: try `func` on all candidates. Candidate order has already been set in
-        // `update_all_candidates`. This ensures the most suitable node is always tried first.
-        let candidates = self.candidates.read().await;
-        let mut futures = vec![];
+    /// As per `first_success`, but for requests which are not required to perform a duty. These
+    /// are never raced and may be distributed across candidates according to `Config::weights`.
+    pub async fn first_success_non_critical<F, O, Err, R>(&self, func: F) -> Result<O, Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        self.first_success_with_priority(RequestPriority::NonCritical, func)
+            .await
+    }
 
-        // Run `func` using a `candidate`, returning the value or capturing errors.
-        for candidate in candidates.iter() {
-            futures.push(Self::run_on_candidate(
-                candidate.beacon_node.clone(),
-                &func,
-                &self.log,
-            ));
-        }
-        drop(candidates);
+    async fn first_success_with_priority<F, O, Err, R>(
+        &self,
+        priority: RequestPriority,
+        func: F,
+    ) -> Result<O, Errors<Err>>
+    where
+        F: Fn(BeaconNodeHttpClient) -> R,
+        R: Future<Output = Result<O, Err>>,
+        Err: Debug,
+    {
+        let mut errors = vec![];
+
+        // First pass: try `func` on all candidates whose circuit breaker is closed. Candidate order
+        // has already been set in `update_all_candidates`. This ensures the most suitable node is
+        // always tried first.
+        let candidates = self.ordered_candidates(priority).await;
+        let closed: Vec<_> = candidates
+            .iter()
+            .filter(|candidate| !candidate.is_circuit_open())
+            .cloned()
+            .collect();
 
-        for future in futures {
-            match future.await {
+        // Race the first `race_count` candidates, taking the first successful response.
+        let num_raced = match priority {
+            RequestPriority::Critical if self.race_count > 1 => self.race_count.min(closed.len()),
+            _ => 0,
+        };
+        let mut race: FuturesUnordered<_> = closed[..num_raced]
+            .iter()
+            .map(|candidate| self.run_on_candidate(candidate.clone(), &func))
+            .collect();
+        while let Some(result) = race.next().await {
+            match result {
                 Ok(val) => return Ok(val),
                 Err(e) => errors.push(e),
             }
         }
 
-        // Second pass. No candidates returned successfully. Try again with the same order.
-        // This will duplicate errors.
-        let candidates = self.candidates.read().await;
-        let mut futures = vec![];
-
-        // Run `func` using a `candidate`, returning the value or capturing errors.
-        for candidate in candidates.iter() {
-            futures.push(Self::run_on_candidate(
-                candidate.beacon_node.clone(),
-                &func,
-                &self.log,
-            ));
+        for candidate in &closed[num_raced..] {
+            match self.run_on_candidate(candidate.clone(), &func).await {
+                Ok(val) => return Ok(val),
+                Err(e) => errors.push(e),
+            }
         }
-        drop(candidates);
 
-        for future in futures {
-            match future.await {
+        // Second pass. No candidates returned successfully. Try again with the same order,
+        // including candidates whose circuit breaker is open. This will duplicate errors.
+        for candidate in candidates {
+            match self.run_on_candidate(candidate, &func).await {
                 Ok(val) => return Ok(val),
                 Err(e) => errors.push(e),
             }
@@ -607,32 +836,52 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
         Err(Errors(errors))
     }
 
-    /// Run the future `func` on `candidate` while reporting metrics.
+    /// Run the future `func` on `candidate` while reporting metrics and updating its circuit
+    /// breaker.
     async fn run_on_candidate<F, R, Err, O>(
-        candidate: BeaconNodeHttpClient,
+        &self,
+        candidate: CandidateBeaconNode<E>,
         func: F,
-        log: &Logger,
     ) -> Result<O, (String, Error<Err>)>
     where
         F: Fn(BeaconNodeHttpClient) -> R,
         R: Future<Output = Result<O, Err>>,
         Err: Debug,
     {
-        inc_counter_vec(&ENDPOINT_REQUESTS, &[candidate.as_ref()]);
+        let beacon_node = &candidate.beacon_node;
+        inc_counter_vec(&ENDPOINT_REQUESTS, &[beacon_node.as_ref()]);
 
         // There exists a race condition where `func` may be called when the candidate is
         // actually not ready. We deem this an acceptable inefficiency.
-        match func(candidate.clone()).await {
-            Ok(val) => Ok(val),
+        match func(beacon_node.clone()).await {
+            Ok(val) => {
+                if self.circuit_breaker.is_some() {
+                    candidate.record_success();
+                }
+                Ok(val)
+            }
             Err(e) => {
                 debug!(
-                    log,
+                    self.log,
                     "Request to beacon node failed";
-                    "node" => %candidate,
+                    "node" => %beacon_node,
                     "error" => ?e,
                 );
-                inc_counter_vec(&ENDPOINT_ERRORS, &[candidate.as_ref()]);
-                Err((candidate.to_string(), Error::RequestFailed(e)))
+                inc_counter_vec(&ENDPOINT_ERRORS, &[beacon_node.as_ref()]);
+
+                if let Some(config) = &self.circuit_breaker {
+                    if candidate.record_failure(config) {
+                        warn!(
+                            self.log,
+                            "Beacon node circuit breaker tripped";
+                            "endpoint" => %beacon_node,
+                            "threshold" => config.threshold,
+                            "cooldown" => ?config.cooldown,
+                        );
+                        inc_counter_vec(&ENDPOINT_CIRCUIT_BREAKER_TRIPS, &[beacon_node.as_ref()]);
+                    }
+                }
+                Err((beacon_node.to_string(), Error::RequestFailed(e)))
             }
         }
     }
@@ -656,11 +905,7 @@ impl<T: SlotClock, E: EthSpec> BeaconNodeFallback<T, E> {
 
         // Run `func` using a `candidate`, returning the value or capturing errors.
         for candidate in candidates.iter() {
-            futures.push(Self::run_on_candidate(
-                candidate.beacon_node.clone(),
-                &func,
-                &self.log,
-            ));
+            futures.push(self.run_on_candidate(candidate.clone(), &func));
         }
         drop(candidates);
 
@@ -741,12 +986,36 @@ mod tests {
     use crate::beacon_node_health::BeaconNodeHealthTier;
     use crate::SensitiveUrl;
     use eth2::Timeouts;
+    use logging::test_logger;
+    use slot_clock::TestingSlotClock;
     use std::str::FromStr;
+    use std::sync::atomic::AtomicUsize;
     use strum::VariantNames;
     use types::{MainnetEthSpec, Slot};
 
     type E = MainnetEthSpec;
 
+    fn new_candidate(index: usize) -> CandidateBeaconNode<E> {
+        let beacon_node = BeaconNodeHttpClient::new(
+            SensitiveUrl::parse(&format!("http://example_{index}.com")).unwrap(),
+            Timeouts::set_all(Duration::from_secs(index as u64)),
+        );
+        CandidateBeaconNode::new(beacon_node, index)
+    }
+
+    fn new_fallback(
+        candidates: Vec<CandidateBeaconNode<E>>,
+        config: Config,
+    ) -> BeaconNodeFallback<TestingSlotClock, E> {
+        BeaconNodeFallback::new(
+            candidates,
+            config,
+            vec![],
+            Arc::new(E::default_spec()),
+            test_logger(),
+        )
+    }
+
     #[test]
     fn api_topic_all() {
         let all = ApiTopic::all();
@@ -764,14 +1033,6 @@ mod tests {
         let optimistic_status = IsOptimistic::No;
         let execution_status = ExecutionEngineHealth::Healthy;
 
-        fn new_candidate(index: usize) -> CandidateBeaconNode<E> {
-            let beacon_node = BeaconNodeHttpClient::new(
-                SensitiveUrl::parse(&format!("http://example_{index}.com")).unwrap(),
-                Timeouts::set_all(Duration::from_secs(index as u64)),
-            );
-            CandidateBeaconNode::new(beacon_node, index)
-        }
-
         let candidate_1 = new_candidate(1);
         let expected_candidate_1 = new_candidate(1);
         let candidate_2 = new_candidate(2);
@@ -867,4 +1128,126 @@ mod tests {
 
         assert_eq!(candidates, expected_candidates);
     }
+
+    #[test]
+    fn sticky_primary_hysteresis() {
+        let mut primary = StickyPrimary::default();
+
+        // The most suitable healthy candidate becomes the first primary.
+        assert_eq!(primary.update(&[(1, Some(1)), (2, Some(1))], 2), Some(1));
+        // Candidate 2 is in a better tier for a single check only.
+        assert_eq!(primary.update(&[(2, Some(1)), (1, Some(2))], 2), None);
+        assert_eq!(primary.update(&[(1, Some(1)), (2, Some(1))], 2), None);
+        // Candidate 2 is in a better tier for two consecutive checks.
+        assert_eq!(primary.update(&[(2, Some(1)), (1, Some(2))], 2), None);
+        assert_eq!(primary.update(&[(2, Some(1)), (1, Some(2))], 2), Some(2));
+        // Candidate 1 is sorted first on `user_index`, but is not in a better tier.
+        assert_eq!(primary.update(&[(1, Some(1)), (2, Some(1))], 2), None);
+        // An unhealthy primary is replaced immediately.
+        assert_eq!(primary.update(&[(1, Some(3)), (2, None)], 2), Some(1));
+        // There is nothing to switch to if no candidates are healthy.
+        assert_eq!(primary.update(&[(1, None), (2, None)], 2), None);
+        assert_eq!(primary.index, Some(1));
+    }
+
+    #[tokio::test]
+    async fn sticky_primary_is_tried_first() {
+        let fallback = new_fallback(
+            vec![new_candidate(1), new_candidate(2), new_candidate(3)],
+            Config::default(),
+        );
+        fallback.sticky_primary.lock().index = Some(2);
+
+        let order: Vec<_> = fallback
+            .ordered_candidates(RequestPriority::Critical)
+            .await
+            .into_iter()
+            .map(|candidate| candidate.index)
+            .collect();
+        assert_eq!(order, vec![2, 1, 3]);
+    }
+
+    #[test]
+    fn weighted_round_robin() {
+        let weights = [3, 0, 1];
+        let choices: Vec<_> = (0..8)
+            .map(|counter| weighted_choice(&weights, counter))
+            .collect();
+        assert_eq!(choices, [0, 0, 0, 2, 0, 0, 0, 2].map(Some));
+        assert_eq!(weighted_choice(&[0, 0], 0), None);
+        assert_eq!(weighted_choice(&[], 0), None);
+    }
+
+    #[tokio::test]
+    async fn race_top_candidates() {
+        // The first candidate is slower to respond than the second.
+        let func = |beacon_node: BeaconNodeHttpClient| async move {
+            if beacon_node.to_string().contains("example_1") {
+                sleep(Duration::from_millis(200)).await;
+            }
+            Ok::<_, ()>(beacon_node.to_string())
+        };
+        let candidates = vec![new_candidate(1), new_candidate(2)];
+
+        let fallback = new_fallback(candidates.clone(), Config::default());
+        let response = fallback.first_success(func).await.unwrap();
+        assert!(response.contains("example_1"));
+
+        let config = Config {
+            race_count: 2,
+            ..Config::default()
+        };
+        let fallback = new_fallback(candidates, config);
+        let response = fallback.first_success(func).await.unwrap();
+        assert!(response.contains("example_2"));
+        // Non-critical requests are never raced.
+        let response = fallback.first_success_non_critical(func).await.unwrap();
+        assert!(response.contains("example_1"));
+    }
+
+    #[tokio::test]
+    async fn circuit_breaker() {
+        let config = Config {
+            circuit_breaker: Some(CircuitBreakerConfig {
+                threshold: 2,
+                cooldown: Duration::from_secs(60),
+            }),
+            ..Config::default()
+        };
+        let fallback = new_fallback(vec![new_candidate(1), new_candidate(2)], config);
+        let candidate_1 = fallback.candidates.read().await[0].clone();
+
+        // The first candidate always fails.
+        let requests_to_1 = AtomicUsize::new(0);
+        let func = |beacon_node: BeaconNodeHttpClient| {
+            let requests_to_1 = &requests_to_1;
+            async move {
+                if beacon_node.to_string().contains("example_1") {
+                    requests_to_1.fetch_add(1, atomic::Ordering::Relaxed);
+                    Err(())
+                } else {
+                    Ok(())
+                }
+            }
+        };
+
+        fallback.first_success(func).await.unwrap();
+        assert!(!candidate_1.is_circuit_open());
+        fallback.first_success(func).await.unwrap();
+        assert!(candidate_1.is_circuit_open());
+
+        // The first candidate is skipped whilst its breaker is open.
+        fallback.first_success(func).await.unwrap();
+        assert_eq!(requests_to_1.load(atomic::Ordering::Relaxed), 2);
+
+        // Unless all other candidates have failed.
+        let errors = fallback
+            .first_success(|_| async { Err::<(), _>(()) })
+            .await
+            .unwrap_err();
+        assert_eq!(errors.num_errors(), 3);
+
+        candidate_1.record_success();
+        assert!(!candidate_1.is_circuit_open());
+    }
 }
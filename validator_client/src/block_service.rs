@@ -8,10 +8,12 @@ use crate::{
     http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
 };
-use bls::SignatureBytes;
+use bls::{Signature, SignatureBytes};
 use environment::RuntimeContext;
-use eth2::types::{FullBlockContents, PublishBlockRequest};
+use eth2::trace_context::with_traceparent;
+use eth2::types::{FullBlockContents, PublishBlockRequest, SkipRandaoVerification};
 use eth2::{BeaconNodeHttpClient, StatusCode};
+use otlp::Span;
 use slog::{crit, debug, error, info, trace, warn, Logger};
 use slot_clock::SlotClock;
 use std::fmt::Debug;
@@ -21,8 +23,8 @@ use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use types::{
-    BlindedBeaconBlock, BlockType, EthSpec, Graffiti, PublicKeyBytes, SignedBlindedBeaconBlock,
-    Slot,
+    Address, BlindedBeaconBlock, BlockType, EthSpec, ExecPayload, Graffiti, PublicKeyBytes,
+    SignedBlindedBeaconBlock, Slot,
 };
 
 #[derive(Debug)]
@@ -335,9 +337,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         graffiti: Option<Graffiti>,
         validator_pubkey: &PublicKeyBytes,
         unsigned_block: UnsignedBlock<E>,
+        span: &Span,
     ) -> Result<(), BlockError> {
         let log = self.context.log();
         let signing_timer = metrics::start_timer(&metrics::BLOCK_SIGNING_TIMES);
+        let mut sign_span = span.child("sign");
 
         let res = match unsigned_block {
             UnsignedBlock::Full(block_contents) => {
@@ -370,12 +374,14 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 return Ok(());
             }
             Err(e) => {
+                sign_span.set_error(format!("{:?}", e));
                 return Err(BlockError::Recoverable(format!(
                     "Unable to sign block: {:?}",
                     e
-                )))
+                )));
             }
         };
+        drop(sign_span);
 
         let signing_time_ms =
             Duration::from_secs_f64(signing_timer.map_or(0.0, |t| t.stop_and_record())).as_millis();
@@ -392,12 +398,21 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         // Try the proposer nodes first, since we've likely gone to efforts to
         // protect them from DoS attacks and they're most likely to successfully
         // publish a block.
-        proposer_fallback
-            .request_proposers_first(|beacon_node| async {
+        let mut publish_span = span.child("publish");
+        with_traceparent(
+            publish_span.traceparent(),
+            proposer_fallback.request_proposers_first(|beacon_node| async {
                 self.publish_signed_block_contents(&signed_block, beacon_node)
                     .await
-            })
-            .await?;
+            }),
+        )
+        .await
+        .map_err(|e| {
+            let e = BlockError::from(e);
+            publish_span.set_error(format!("{:?}", e));
+            e
+        })?;
+        drop(publish_span);
 
         info!(
             log,
@@ -420,30 +435,46 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         let log = self.context.log();
         let _timer =
             metrics::start_timer_vec(&metrics::BLOCK_SERVICE_TIMES, &[metrics::BEACON_BLOCK]);
-
-        let randao_reveal = match self
-            .validator_store
-            .randao_reveal(validator_pubkey, slot.epoch(E::slots_per_epoch()))
-            .await
-        {
-            Ok(signature) => signature.into(),
-            Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
-                // A pubkey can be missing when a validator was recently removed
-                // via the API.
-                warn!(
-                    log,
-                    "Missing pubkey for block randao";
-                    "info" => "a validator may have recently been removed from this VC",
-                    "pubkey" => ?pubkey,
-                    "slot" => ?slot
-                );
-                return Ok(());
-            }
-            Err(e) => {
-                return Err(BlockError::Recoverable(format!(
-                    "Unable to produce randao reveal signature: {:?}",
-                    e
-                )))
+        let mut span = Span::root("block_proposal");
+        span.set_attribute("slot", slot.as_u64());
+
+        let dry_run = self.validator_store.dry_run();
+        let randao_reveal = if dry_run {
+            // Blocks are not signed in dry-run mode, so the randao reveal is the point at infinity
+            // and the beacon node is asked not to verify it.
+            Signature::infinity()
+                .map_err(|e| {
+                    BlockError::Recoverable(format!(
+                        "Unable to create empty randao reveal: {:?}",
+                        e
+                    ))
+                })?
+                .into()
+        } else {
+            match self
+                .validator_store
+                .randao_reveal(validator_pubkey, slot.epoch(E::slots_per_epoch()))
+                .await
+            {
+                Ok(signature) => signature.into(),
+                Err(ValidatorStoreError::UnknownPubkey(pubkey)) => {
+                    // A pubkey can be missing when a validator was recently removed
+                    // via the API.
+                    warn!(
+                        log,
+                        "Missing pubkey for block randao";
+                        "info" => "a validator may have recently been removed from this VC",
+                        "pubkey" => ?pubkey,
+                        "slot" => ?slot
+                    );
+                    return Ok(());
+                }
+                Err(e) => {
+                    return Err(BlockError::Recoverable(format!(
+                        "Unable to produce randao reveal signature: {:?}",
+                        e
+                    )))
+                }
             }
         };
 
@@ -456,6 +487,11 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         );
 
         let randao_reveal_ref = &randao_reveal;
+        let skip_randao_verification = if dry_run {
+            SkipRandaoVerification::Yes
+        } else {
+            SkipRandaoVerification::No
+        };
         let self_ref = &self;
         let proposer_index = self.validator_store.validator_index(&validator_pubkey);
         let proposer_fallback = ProposerFallback {
@@ -473,8 +509,10 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         //
         // Try the proposer nodes last, since it's likely that they don't have a
         // great view of attestations on the network.
-        let unsigned_block = proposer_fallback
-            .request_proposers_last(|beacon_node| async move {
+        let mut produce_span = span.child("produce");
+        let unsigned_block = with_traceparent(
+            produce_span.traceparent(),
+            proposer_fallback.request_proposers_last(|beacon_node| async move {
                 let _get_timer = metrics::start_timer_vec(
                     &metrics::BLOCK_SERVICE_TIMES,
                     &[metrics::BEACON_BLOCK_HTTP_GET],
@@ -483,6 +521,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                     &beacon_node,
                     slot,
                     randao_reveal_ref,
+                    skip_randao_verification,
                     graffiti,
                     proposer_index,
                     builder_boost_factor,
@@ -495,8 +534,31 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                         e
                     ))
                 })
-            })
-            .await?;
+            }),
+        )
+        .await
+        .map_err(|e| {
+            let e = BlockError::from(e);
+            produce_span.set_error(format!("{:?}", e));
+            e
+        })?;
+        produce_span.set_attribute("block_type", format!("{:?}", unsigned_block.block_type()));
+        drop(produce_span);
+
+        if dry_run {
+            info!(
+                log,
+                "Skipped publishing block in dry run";
+                "block_type" => ?unsigned_block.block_type(),
+                "fee_recipient" => ?unsigned_block.fee_recipient(),
+                "expected_fee_recipient" => ?self.validator_store.get_fee_recipient(&validator_pubkey),
+                "attestations" => unsigned_block.num_attestations(),
+                "graffiti" => ?graffiti.map(|g| g.as_utf8_lossy()),
+                "slot" => slot.as_u64(),
+            );
+            metrics::inc_counter_vec(&metrics::DRY_RUN_MESSAGES_TOTAL, &[metrics::BEACON_BLOCK]);
+            return Ok(());
+        }
 
         self_ref
             .sign_and_publish_block(
@@ -505,6 +567,7 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
                 graffiti,
                 &validator_pubkey,
                 unsigned_block,
+                &span,
             )
             .await?;
 
@@ -543,20 +606,23 @@ impl<T: SlotClock + 'static, E: EthSpec> BlockService<T, E> {
         Ok::<_, BlockError>(())
     }
 
+    #[allow(clippy::too_many_arguments)]
     async fn get_validator_block(
         beacon_node: &BeaconNodeHttpClient,
         slot: Slot,
         randao_reveal_ref: &SignatureBytes,
+        skip_randao_verification: SkipRandaoVerification,
         graffiti: Option<Graffiti>,
         proposer_index: Option<u64>,
         builder_boost_factor: Option<u64>,
         log: &Logger,
     ) -> Result<UnsignedBlock<E>, BlockError> {
         let (block_response, _) = beacon_node
-            .get_validator_blocks_v3::<E>(
+            .get_validator_blocks_v3_modular::<E>(
                 slot,
                 randao_reveal_ref,
                 graffiti.as_ref(),
+                skip_randao_verification,
                 builder_boost_factor,
             )
             .await
@@ -629,6 +695,33 @@ impl<E: EthSpec> UnsignedBlock<E> {
             UnsignedBlock::Blinded(block) => block.proposer_index(),
         }
     }
+    pub fn block_type(&self) -> BlockType {
+        match self {
+            UnsignedBlock::Full(_) => BlockType::Full,
+            UnsignedBlock::Blinded(_) => BlockType::Blinded,
+        }
+    }
+    pub fn fee_recipient(&self) -> Option<Address> {
+        match self {
+            UnsignedBlock::Full(block) => block
+                .block()
+                .body()
+                .execution_payload()
+                .ok()
+                .map(|payload| payload.fee_recipient()),
+            UnsignedBlock::Blinded(block) => block
+                .body()
+                .execution_payload()
+                .ok()
+                .map(|payload| payload.fee_recipient()),
+        }
+    }
+    pub fn num_attestations(&self) -> usize {
+        match self {
+            UnsignedBlock::Full(block) => block.block().body().attestations_len(),
+            UnsignedBlock::Blinded(block) => block.body().attestations_len(),
+        }
+    }
 }
 
 #[derive(Debug)]
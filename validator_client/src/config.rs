@@ -1,4 +1,4 @@
-use crate::beacon_node_fallback::ApiTopic;
+use crate::beacon_node_fallback::{ApiTopic, CircuitBreakerConfig};
 use crate::graffiti_file::GraffitiFile;
 use crate::{
     beacon_node_fallback, beacon_node_health::BeaconNodeSyncDistanceTiers, http_api, http_metrics,
@@ -22,6 +22,12 @@ use types::{Address, GRAFFITI_BYTES_LEN};
 
 pub const DEFAULT_BEACON_NODE: &str = "http://localhost:5052/";
 pub const DEFAULT_WEB3SIGNER_KEEP_ALIVE: Option<Duration> = Some(Duration::from_secs(20));
+/// The `service.name` reported to an OpenTelemetry collector.
+pub const OTLP_SERVICE_NAME: &str = "lighthouse-validator-client";
+/// The error returned if a PostgreSQL slashing protection database is configured in a build without
+/// support for it.
+pub const POSTGRES_DISABLED_ERROR: &str = "--slashing-protection-postgres-url requires a build \
+    of Lighthouse with the `slashing-protection-postgres` feature";
 
 /// Stores the core configuration for this validator instance.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -41,8 +47,12 @@ pub struct Config {
     pub allow_unsynced_beacon_node: bool,
     /// If true, don't scan the validators dir for new keystores.
     pub disable_auto_discover: bool,
+    /// If true, reload `validator_definitions.yml` when it is modified or on SIGHUP.
+    pub reload_validator_definitions: bool,
     /// If true, re-register existing validators in definitions.yml for slashing protection.
     pub init_slashing_protection: bool,
+    /// URL of a shared PostgreSQL slashing protection database, used instead of SQLite.
+    pub slashing_protection_postgres_url: Option<SensitiveUrl>,
     /// If true, use longer timeouts for requests made to the beacon node.
     pub use_long_timeouts: bool,
     /// Graffiti to be inserted everytime we create a block.
@@ -59,9 +69,14 @@ pub struct Config {
     pub beacon_node_fallback: beacon_node_fallback::Config,
     /// Configuration for sending metrics to a remote explorer endpoint.
     pub monitoring_api: Option<monitoring_api::Config>,
+    /// Configuration for pushing metrics and traces to an OpenTelemetry collector.
+    pub otlp: Option<otlp::Config>,
     /// If true, enable functionality that monitors the network for attestations or proposals from
     /// any of the validators managed by this client before starting up.
     pub enable_doppelganger_protection: bool,
+    /// If true, perform duties without signing or publishing any messages, logging what would have
+    /// been published instead.
+    pub dry_run: bool,
     /// If true, then we publish validator specific metrics (e.g next attestation duty slot)
     /// for all our managed validators.
     /// Note: We publish validator specific metrics for low validator counts without this flag
@@ -115,7 +130,9 @@ impl Default for Config {
             proposer_nodes: Vec::new(),
             allow_unsynced_beacon_node: false,
             disable_auto_discover: false,
+            reload_validator_definitions: false,
             init_slashing_protection: false,
+            slashing_protection_postgres_url: None,
             use_long_timeouts: false,
             graffiti: None,
             graffiti_file: None,
@@ -124,7 +141,9 @@ impl Default for Config {
             http_metrics: <_>::default(),
             beacon_node_fallback: <_>::default(),
             monitoring_api: None,
+            otlp: None,
             enable_doppelganger_protection: false,
+            dry_run: false,
             enable_high_validator_count_metrics: false,
             beacon_nodes_tls_certs: None,
             builder_proposals: false,
@@ -199,7 +218,18 @@ impl Config {
         }
 
         config.disable_auto_discover = cli_args.get_flag("disable-auto-discover");
+        config.reload_validator_definitions = cli_args.get_flag("reload-validator-definitions");
         config.init_slashing_protection = cli_args.get_flag("init-slashing-protection");
+
+        if let Some(url) = parse_optional::<String>(cli_args, "slashing-protection-postgres-url")? {
+            if !cfg!(feature = "slashing-protection-postgres") {
+                return Err(POSTGRES_DISABLED_ERROR.to_string());
+            }
+            config.slashing_protection_postgres_url = Some(
+                SensitiveUrl::parse(&url)
+                    .map_err(|e| format!("Invalid slashing protection database URL: {:?}", e))?,
+            );
+        }
         config.use_long_timeouts = cli_args.get_flag("use-long-timeouts");
 
         if let Some(graffiti_file_path) = cli_args.get_one::<String>("graffiti-file") {
@@ -266,6 +296,49 @@ impl Config {
             config.beacon_node_fallback.sync_tolerances = BeaconNodeSyncDistanceTiers::default();
         }
 
+        if let Some(race_count) = parse_optional(cli_args, "beacon-nodes-race-count")? {
+            config.beacon_node_fallback.race_count = race_count;
+        }
+
+        config.beacon_node_fallback.sticky_primary_hysteresis =
+            parse_optional(cli_args, "beacon-nodes-sticky-primary-hysteresis")?;
+
+        if let Some(weights) = cli_args.get_one::<String>("beacon-nodes-weights") {
+            let weights = weights
+                .split(',')
+                .map(|weight| {
+                    weight
+                        .trim()
+                        .parse::<u64>()
+                        .map_err(|_| format!("Invalid beacon node weight: {weight}"))
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if weights.len() != config.beacon_nodes.len() {
+                return Err(format!(
+                    "--beacon-nodes-weights has {} weights but there are {} beacon nodes",
+                    weights.len(),
+                    config.beacon_nodes.len()
+                ));
+            }
+            config.beacon_node_fallback.weights = Some(weights);
+        }
+
+        if let Some(threshold) =
+            parse_optional::<usize>(cli_args, "beacon-nodes-circuit-breaker-threshold")?
+        {
+            if threshold == 0 {
+                return Err(
+                    "--beacon-nodes-circuit-breaker-threshold must be greater than 0".to_string(),
+                );
+            }
+            let cooldown =
+                parse_optional(cli_args, "beacon-nodes-circuit-breaker-cooldown")?.unwrap_or(12);
+            config.beacon_node_fallback.circuit_breaker = Some(CircuitBreakerConfig {
+                threshold,
+                cooldown: Duration::from_secs(cooldown),
+            });
+        }
+
         /*
          * Web3 signer
          */
@@ -377,10 +450,24 @@ impl Config {
             });
         }
 
+        /*
+         * OpenTelemetry
+         */
+        if let Some(otlp_endpoint) = cli_args.get_one::<String>("otlp-endpoint") {
+            let update_period_secs = clap_utils::parse_optional(cli_args, "otlp-endpoint-period")?;
+            config.otlp = Some(otlp::Config {
+                otlp_endpoint: otlp_endpoint.to_string(),
+                service_name: OTLP_SERVICE_NAME.to_string(),
+                update_period_secs,
+            });
+        }
+
         if cli_args.get_flag("enable-doppelganger-protection") {
             config.enable_doppelganger_protection = true;
         }
 
+        config.dry_run = cli_args.get_flag("dry-run");
+
         if cli_args.get_flag("builder-proposals") {
             config.builder_proposals = true;
         }
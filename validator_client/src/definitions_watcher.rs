@@ -0,0 +1,91 @@
+use crate::ValidatorStore;
+use account_utils::validator_definitions::CONFIG_FILENAME;
+use environment::RuntimeContext;
+use slog::{debug, info};
+use slot_clock::SlotClock;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+#[cfg(unix)]
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::time::interval;
+use types::EthSpec;
+
+/// How often to check the validator definitions file for modifications.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Starts a service which reloads the validator definitions file whenever it is modified, or when
+/// the process receives SIGHUP.
+///
+/// Modifications are detected by polling the modification time of the file. Saving the file via
+/// the HTTP API also triggers a reload, which is a no-op since the running validators already
+/// match the file.
+pub fn start_definitions_watcher<T: SlotClock + 'static, E: EthSpec>(
+    context: RuntimeContext<E>,
+    validator_store: Arc<ValidatorStore<T, E>>,
+    validators_dir: PathBuf,
+) -> Result<(), String> {
+    let log = context.log().clone();
+    let executor = context.executor.clone();
+    let definitions_path = validators_dir.join(CONFIG_FILENAME);
+
+    #[cfg(unix)]
+    let mut sighup = signal(SignalKind::hangup())
+        .map_err(|e| format!("Unable to register SIGHUP handler: {}", e))?;
+
+    let future = async move {
+        let mut last_modified = modified_time(&definitions_path);
+        let mut poll_interval = interval(POLL_INTERVAL);
+
+        loop {
+            #[cfg(unix)]
+            let received_sighup = tokio::select! {
+                _ = poll_interval.tick() => false,
+                _ = sighup.recv() => true,
+            };
+            #[cfg(not(unix))]
+            let received_sighup = {
+                poll_interval.tick().await;
+                false
+            };
+
+            let modified = modified_time(&definitions_path);
+            if received_sighup {
+                info!(log, "Received SIGHUP, reloading validator definitions");
+            } else if modified != last_modified {
+                debug!(
+                    log,
+                    "Validator definitions modified";
+                    "path" => definitions_path.display(),
+                );
+            } else {
+                continue;
+            }
+            last_modified = modified;
+
+            // The validator store holds a lock across an await point while reloading, so the
+            // reload is driven from a blocking thread. Errors are logged by the validator store.
+            let Some(handle) = executor.handle() else {
+                break;
+            };
+            let validator_store = validator_store.clone();
+            if let Some(reload) = executor.spawn_blocking_handle(
+                move || handle.block_on(validator_store.reload_validator_definitions()),
+                "reload_validator_definitions",
+            ) {
+                let _ = reload.await;
+            }
+        }
+    };
+
+    context.executor.spawn(future, "definitions_watcher");
+
+    Ok(())
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
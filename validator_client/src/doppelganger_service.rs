@@ -174,7 +174,7 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
     } else {
         // Request the previous epoch liveness state from the beacon node.
         beacon_nodes
-            .first_success(|beacon_node| {
+            .first_success_non_critical(|beacon_node| {
                 let validator_indices_ref = &validator_indices;
                 async move {
                     beacon_node
@@ -210,7 +210,7 @@ async fn beacon_node_liveness<'a, T: 'static + SlotClock, E: EthSpec>(
 
     // Request the current epoch liveness state from the beacon node.
     let current_epoch_responses = beacon_nodes
-        .first_success(|beacon_node| {
+        .first_success_non_critical(|beacon_node| {
             let validator_indices_ref = &validator_indices;
             async move {
                 beacon_node
@@ -16,10 +16,12 @@ use crate::{
     validator_store::{DoppelgangerStatus, Error as ValidatorStoreError, ValidatorStore},
 };
 use environment::RuntimeContext;
+use eth2::trace_context::with_traceparent;
 use eth2::types::{
     AttesterData, BeaconCommitteeSubscription, DutiesResponse, ProposerData, StateId, ValidatorId,
 };
 use futures::{stream, StreamExt};
+use otlp::Span;
 use parking_lot::RwLock;
 use safe_arith::{ArithError, SafeArith};
 use slog::{debug, error, info, warn, Logger};
@@ -517,7 +519,7 @@ async fn poll_validator_indices<T: SlotClock + 'static, E: EthSpec>(
             // Query the remote BN to resolve a pubkey to a validator index.
             let download_result = duties_service
                 .beacon_nodes
-                .first_success(|beacon_node| async move {
+                .first_success_non_critical(|beacon_node| async move {
                     let _timer = metrics::start_timer_vec(
                         &metrics::DUTIES_SERVICE_TIMES,
                         &[metrics::VALIDATOR_ID_HTTP_GET],
@@ -1026,19 +1028,29 @@ async fn post_validator_duties_attester<T: SlotClock + 'static, E: EthSpec>(
     epoch: Epoch,
     validator_indices: &[u64],
 ) -> Result<DutiesResponse<Vec<AttesterData>>, Error> {
-    duties_service
-        .beacon_nodes
-        .first_success(|beacon_node| async move {
-            let _timer = metrics::start_timer_vec(
-                &metrics::DUTIES_SERVICE_TIMES,
-                &[metrics::ATTESTER_DUTIES_HTTP_POST],
-            );
-            beacon_node
-                .post_validator_duties_attester(epoch, validator_indices)
-                .await
-        })
-        .await
-        .map_err(|e| Error::FailedToDownloadAttesters(e.to_string()))
+    let mut span = Span::root("fetch_attester_duties");
+    span.set_attribute("epoch", epoch.as_u64());
+    span.set_attribute("validators", validator_indices.len());
+
+    with_traceparent(
+        span.traceparent(),
+        duties_service
+            .beacon_nodes
+            .first_success(|beacon_node| async move {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::DUTIES_SERVICE_TIMES,
+                    &[metrics::ATTESTER_DUTIES_HTTP_POST],
+                );
+                beacon_node
+                    .post_validator_duties_attester(epoch, validator_indices)
+                    .await
+            }),
+    )
+    .await
+    .map_err(|e| {
+        span.set_error(&e);
+        Error::FailedToDownloadAttesters(e.to_string())
+    })
 }
 
 /// Compute the attestation selection proofs for the `duties` and add them to the `attesters` map.
@@ -1258,18 +1270,27 @@ async fn poll_beacon_proposers<T: SlotClock + 'static, E: EthSpec>(
     // Only download duties and push out additional block production events if we have some
     // validators.
     if !local_pubkeys.is_empty() {
-        let download_result = duties_service
-            .beacon_nodes
-            .first_success(|beacon_node| async move {
-                let _timer = metrics::start_timer_vec(
-                    &metrics::DUTIES_SERVICE_TIMES,
-                    &[metrics::PROPOSER_DUTIES_HTTP_GET],
-                );
-                beacon_node
-                    .get_validator_duties_proposer(current_epoch)
-                    .await
-            })
-            .await;
+        let mut span = Span::root("fetch_proposer_duties");
+        span.set_attribute("epoch", current_epoch.as_u64());
+        let download_result = with_traceparent(
+            span.traceparent(),
+            duties_service
+                .beacon_nodes
+                .first_success(|beacon_node| async move {
+                    let _timer = metrics::start_timer_vec(
+                        &metrics::DUTIES_SERVICE_TIMES,
+                        &[metrics::PROPOSER_DUTIES_HTTP_GET],
+                    );
+                    beacon_node
+                        .get_validator_duties_proposer(current_epoch)
+                        .await
+                }),
+        )
+        .await;
+        if let Err(e) = &download_result {
+            span.set_error(e);
+        }
+        drop(span);
 
         match download_result {
             Ok(response) => {
@@ -5,7 +5,9 @@ use crate::{
     validator_store::Error as ValidatorStoreError,
 };
 
+use eth2::trace_context::with_traceparent;
 use futures::future::join_all;
+use otlp::Span;
 use parking_lot::{MappedRwLockReadGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
 use slog::{crit, debug, info, warn};
 use slot_clock::SlotClock;
@@ -439,18 +441,28 @@ pub async fn poll_sync_committee_duties_for_period<T: SlotClock + 'static, E: Et
 
     let period_start_epoch = spec.epochs_per_sync_committee_period * sync_committee_period;
 
-    let duties_response = duties_service
-        .beacon_nodes
-        .first_success(|beacon_node| async move {
-            let _timer = metrics::start_timer_vec(
-                &metrics::DUTIES_SERVICE_TIMES,
-                &[metrics::VALIDATOR_DUTIES_SYNC_HTTP_POST],
-            );
-            beacon_node
-                .post_validator_duties_sync(period_start_epoch, local_indices)
-                .await
-        })
-        .await;
+    let mut span = Span::root("fetch_sync_committee_duties");
+    span.set_attribute("sync_committee_period", sync_committee_period);
+    span.set_attribute("validators", local_indices.len());
+    let duties_response = with_traceparent(
+        span.traceparent(),
+        duties_service
+            .beacon_nodes
+            .first_success(|beacon_node| async move {
+                let _timer = metrics::start_timer_vec(
+                    &metrics::DUTIES_SERVICE_TIMES,
+                    &[metrics::VALIDATOR_DUTIES_SYNC_HTTP_POST],
+                );
+                beacon_node
+                    .post_validator_duties_sync(period_start_epoch, local_indices)
+                    .await
+            }),
+    )
+    .await;
+    if let Err(e) = &duties_response {
+        span.set_error(e);
+    }
+    drop(span);
 
     let duties = match duties_response {
         Ok(res) => res.data,
@@ -44,7 +44,11 @@ pub fn list<T: SlotClock + 'static, E: EthSpec>(
                         ref voting_keystore,
                         ..
                     } => (voting_keystore.path(), Some(false)),
-                    SigningMethod::Web3Signer { .. } => (None, Some(true)),
+                    // Key shares and keys held in a PKCS#11 token cannot be exported or deleted
+                    // via the keystore API.
+                    SigningMethod::Web3Signer { .. }
+                    | SigningMethod::ThresholdShare { .. }
+                    | SigningMethod::Pkcs11 { .. } => (None, Some(true)),
                 });
 
             SingleKeystoreResponse {
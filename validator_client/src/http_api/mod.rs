@@ -24,7 +24,7 @@ use create_validator::{
 use eth2::lighthouse_vc::{
     std_types::{AuthResponse, GetFeeRecipientResponse, GetGasLimitResponse},
     types::{
-        self as api_types, GenericResponse, GetGraffitiResponse, Graffiti, PublicKey,
+        self as api_types, GenericResponse, GetGraffitiResponse, Graffiti, Hash256, PublicKey,
         PublicKeyBytes, SetGraffitiRequest,
     },
 };
@@ -32,6 +32,7 @@ use lighthouse_version::version_with_platform;
 use logging::SSELoggingComponents;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
+use slashing_protection::NotSafe;
 use slog::{crit, info, warn, Logger};
 use slot_clock::SlotClock;
 use std::collections::HashMap;
@@ -44,11 +45,15 @@ use sysinfo::{System, SystemExt};
 use system_health::observe_system_health_vc;
 use task_executor::TaskExecutor;
 use tokio_stream::{wrappers::BroadcastStream, StreamExt};
-use types::{ChainSpec, ConfigAndPreset, EthSpec};
+use types::{ChainSpec, ConfigAndPreset, Epoch, EthSpec};
 use validator_dir::Builder as ValidatorDirBuilder;
 use warp::{sse::Event, Filter};
 use warp_utils::task::blocking_json_task;
 
+/// Number of refused signing attempts returned by `lighthouse/slashing_protection/refusals` if no
+/// limit is specified.
+const DEFAULT_SLASHING_PROTECTION_REFUSALS_LIMIT: usize = 1_000;
+
 #[derive(Debug)]
 pub enum Error {
     Warp(warp::Error),
@@ -328,6 +333,24 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             })
         });
 
+    // GET lighthouse/validators/reload
+    let get_lighthouse_validators_reload = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("reload"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .then(|validator_store: Arc<ValidatorStore<T, E>>| {
+            blocking_json_task(move || {
+                let reload = validator_store.last_definitions_reload().ok_or_else(|| {
+                    warp_utils::reject::custom_not_found(
+                        "validator definitions have not been reloaded".to_string(),
+                    )
+                })?;
+
+                Ok(api_types::GenericResponse::from(reload))
+            })
+        });
+
     // GET lighthouse/validators/{validator_pubkey}
     let get_lighthouse_validators_pubkey = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -360,6 +383,87 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // GET lighthouse/threshold/partial_signatures/{validator_pubkey}/{signing_root}
+    let get_lighthouse_threshold_partial_signature = warp::path("lighthouse")
+        .and(warp::path("threshold"))
+        .and(warp::path("partial_signatures"))
+        .and(warp::path::param::<PublicKey>())
+        .and(warp::path::param::<Hash256>())
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .then(
+            |validator_pubkey: PublicKey,
+             signing_root: Hash256,
+             validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    let (share_index, signature) = validator_store
+                        .threshold_partial_signature(&validator_pubkey.compress(), signing_root)
+                        .ok_or_else(|| {
+                            warp_utils::reject::custom_not_found(format!(
+                                "no partial signature for {:?} by {:?}",
+                                signing_root, validator_pubkey
+                            ))
+                        })?;
+
+                    Ok(api_types::GenericResponse::from(
+                        api_types::ThresholdPartialSignature {
+                            share_index,
+                            signature,
+                        },
+                    ))
+                })
+            },
+        );
+
+    // GET lighthouse/slashing_protection/history
+    let get_lighthouse_slashing_protection_history = warp::path("lighthouse")
+        .and(warp::path("slashing_protection"))
+        .and(warp::path("history"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::SlashingProtectionAuditQuery>())
+        .and(validator_store_filter.clone())
+        .then(
+            |query: api_types::SlashingProtectionAuditQuery,
+             validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    let history = validator_store
+                        .slashing_protection_history(
+                            query.pubkey,
+                            query.start_epoch.unwrap_or(Epoch::new(0)),
+                            query.end_epoch.unwrap_or(Epoch::new(u64::MAX)),
+                        )
+                        .map_err(slashing_protection_query_error)?;
+                    Ok(api_types::GenericResponse::from(history))
+                })
+            },
+        );
+
+    // GET lighthouse/slashing_protection/refusals
+    let get_lighthouse_slashing_protection_refusals = warp::path("lighthouse")
+        .and(warp::path("slashing_protection"))
+        .and(warp::path("refusals"))
+        .and(warp::path::end())
+        .and(warp::query::<api_types::SlashingProtectionAuditQuery>())
+        .and(validator_store_filter.clone())
+        .then(
+            |query: api_types::SlashingProtectionAuditQuery,
+             validator_store: Arc<ValidatorStore<T, E>>| {
+                blocking_json_task(move || {
+                    let refusals = validator_store
+                        .slashing_protection_refusals(
+                            query.pubkey,
+                            query.start_epoch.unwrap_or(Epoch::new(0)),
+                            query.end_epoch.unwrap_or(Epoch::new(u64::MAX)),
+                            query
+                                .limit
+                                .unwrap_or(DEFAULT_SLASHING_PROTECTION_REFUSALS_LIMIT),
+                        )
+                        .map_err(slashing_protection_query_error)?;
+                    Ok(api_types::GenericResponse::from(refusals))
+                })
+            },
+        );
+
     // GET lighthouse/ui/health
     let get_lighthouse_ui_health = warp::path("lighthouse")
         .and(warp::path("ui"))
@@ -489,6 +593,30 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
             },
         );
 
+    // POST lighthouse/validators/reload
+    let post_validators_reload = warp::path("lighthouse")
+        .and(warp::path("validators"))
+        .and(warp::path("reload"))
+        .and(warp::path::end())
+        .and(validator_store_filter.clone())
+        .and(task_executor_filter.clone())
+        .then(
+            |validator_store: Arc<ValidatorStore<T, E>>, task_executor: TaskExecutor| {
+                blocking_json_task(move || {
+                    if let Some(handle) = task_executor.handle() {
+                        let reload = handle
+                            .block_on(validator_store.reload_validator_definitions())
+                            .map_err(warp_utils::reject::custom_server_error)?;
+                        Ok(api_types::GenericResponse::from(reload))
+                    } else {
+                        Err(warp_utils::reject::custom_server_error(
+                            "Lighthouse shutting down".into(),
+                        ))
+                    }
+                })
+            },
+        );
+
     // POST lighthouse/validators/mnemonic
     let post_validators_mnemonic = warp::path("lighthouse")
         .and(warp::path("validators"))
@@ -653,6 +781,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                                 builder_boost_factor: web3signer.builder_boost_factor,
                                 prefer_builder_proposals: web3signer.prefer_builder_proposals,
                                 description: web3signer.description,
+                                signing_policy: None,
                                 signing_definition: SigningDefinition::Web3Signer(
                                     Web3SignerDefinition {
                                         url: web3signer.url,
@@ -1297,7 +1426,11 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                         .or(get_lighthouse_health)
                         .or(get_lighthouse_spec)
                         .or(get_lighthouse_validators)
+                        .or(get_lighthouse_validators_reload)
                         .or(get_lighthouse_validators_pubkey)
+                        .or(get_lighthouse_threshold_partial_signature)
+                        .or(get_lighthouse_slashing_protection_history)
+                        .or(get_lighthouse_slashing_protection_refusals)
                         .or(get_lighthouse_ui_health)
                         .or(get_lighthouse_ui_graffiti)
                         .or(get_lighthouse_ui_fallback_health)
@@ -1310,6 +1443,7 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
                 )
                 .or(warp::post().and(
                     post_validators
+                        .or(post_validators_reload)
                         .or(post_validators_keystore)
                         .or(post_validators_mnemonic)
                         .or(post_validators_web3signer)
@@ -1357,3 +1491,17 @@ pub fn serve<T: 'static + SlotClock + Clone, E: EthSpec>(
 
     Ok((listening_socket, server))
 }
+
+/// Convert an error querying the slashing protection database into a rejection.
+fn slashing_protection_query_error(error: NotSafe) -> warp::Rejection {
+    match error {
+        NotSafe::UnregisteredValidator(pubkey) => warp_utils::reject::custom_not_found(format!(
+            "validator {:?} is not registered with slashing protection",
+            pubkey
+        )),
+        error => warp_utils::reject::custom_server_error(format!(
+            "unable to query slashing protection database: {:?}",
+            error
+        )),
+    }
+}
@@ -32,7 +32,9 @@ pub fn list<T: SlotClock + 'static, E: EthSpec>(
             let validating_pubkey = def.voting_public_key.compress();
 
             match &def.signing_definition {
-                SigningDefinition::LocalKeystore { .. } => None,
+                SigningDefinition::LocalKeystore { .. }
+                | SigningDefinition::ThresholdShare(_)
+                | SigningDefinition::Pkcs11(_) => None,
                 SigningDefinition::Web3Signer(Web3SignerDefinition { url, .. }) => {
                     Some(SingleListRemotekeysResponse {
                         pubkey: validating_pubkey,
@@ -128,6 +130,7 @@ fn import_single_remotekey<T: SlotClock + 'static, E: EthSpec>(
         builder_boost_factor: None,
         prefer_builder_proposals: None,
         description: String::from("Added by remotekey API"),
+        signing_policy: None,
         signing_definition: SigningDefinition::Web3Signer(Web3SignerDefinition {
             url,
             root_certificate_path: None,
@@ -108,7 +108,7 @@ impl ApiTester {
 
         let validator_store = Arc::new(ValidatorStore::<_, E>::new(
             initialized_validators,
-            slashing_protection,
+            Arc::new(slashing_protection),
             Hash256::repeat_byte(42),
             spec.clone(),
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
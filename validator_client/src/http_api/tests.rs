@@ -11,7 +11,7 @@ use crate::{
 };
 use account_utils::{
     eth2_wallet::WalletBuilder, mnemonic_from_phrase, random_mnemonic, random_password,
-    random_password_string, ZeroizeString,
+    random_password_string, validator_definitions::CONFIG_FILENAME, ZeroizeString,
 };
 use deposit_contract::decode_eth1_tx_data;
 use eth2::{
@@ -46,7 +46,7 @@ struct ApiTester {
     validator_store: Arc<ValidatorStore<TestingSlotClock, E>>,
     url: SensitiveUrl,
     slot_clock: TestingSlotClock,
-    _validator_dir: TempDir,
+    validator_dir: TempDir,
     _test_runtime: TestRuntime,
 }
 
@@ -96,7 +96,7 @@ impl ApiTester {
 
         let validator_store = Arc::new(ValidatorStore::<_, E>::new(
             initialized_validators,
-            slashing_protection,
+            Arc::new(slashing_protection),
             Hash256::repeat_byte(42),
             spec.clone(),
             Some(Arc::new(DoppelgangerService::new(log.clone()))),
@@ -156,7 +156,7 @@ impl ApiTester {
             validator_store,
             url,
             slot_clock,
-            _validator_dir: validator_dir,
+            validator_dir,
             _test_runtime: test_runtime,
         }
     }
@@ -521,6 +521,69 @@ impl ApiTester {
         self
     }
 
+    pub async fn test_get_threshold_partial_signature_unavailable(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+
+        // Validators with a whole key never serve partial signatures.
+        let resp = self
+            .client
+            .get_lighthouse_threshold_partial_signature(
+                &validator.voting_pubkey,
+                Hash256::repeat_byte(1),
+            )
+            .await
+            .unwrap();
+        assert!(resp.is_none());
+
+        self
+    }
+
+    pub async fn test_get_slashing_protection_audit(self, index: usize) -> Self {
+        let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
+        let query = SlashingProtectionAuditQuery {
+            pubkey: Some(validator.voting_pubkey),
+            ..<_>::default()
+        };
+
+        // Newly created validators have not signed anything.
+        let history = self
+            .client
+            .get_lighthouse_slashing_protection_history(&query)
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            history,
+            vec![SigningHistory {
+                pubkey: validator.voting_pubkey,
+                signed_blocks: vec![],
+                signed_attestations: vec![],
+            }]
+        );
+
+        let refusals = self
+            .client
+            .get_lighthouse_slashing_protection_refusals(&query)
+            .await
+            .unwrap()
+            .data;
+        assert!(refusals.is_empty());
+
+        // Unknown validators are not found.
+        let unknown_query = SlashingProtectionAuditQuery {
+            pubkey: Some(PublicKeyBytes::empty()),
+            ..<_>::default()
+        };
+        let err = self
+            .client
+            .get_lighthouse_slashing_protection_history(&unknown_query)
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 404);
+
+        self
+    }
+
     fn get_current_epoch(&self) -> Epoch {
         self.slot_clock
             .now()
@@ -528,6 +591,103 @@ impl ApiTester {
             .unwrap()
     }
 
+    /// Edit the validator definitions file on disk and reload it via the API.
+    ///
+    /// Disables the validator at `disable`, removes the one at `remove` and changes the graffiti of
+    /// the one at `update`.
+    pub async fn test_reload_validator_definitions(
+        self,
+        disable: usize,
+        remove: usize,
+        update: usize,
+    ) -> Self {
+        let initial_vals = self.vals_total();
+        let initial_enabled_vals = self.vals_enabled();
+        let validators = self.client.get_lighthouse_validators().await.unwrap().data;
+        let pubkey = |index: usize| validators[index].voting_pubkey;
+
+        let mut definitions = ValidatorDefinitions::open(self.validator_dir.path()).unwrap();
+        for def in definitions.as_mut_slice() {
+            let def_pubkey = def.voting_public_key.compress();
+            if def_pubkey == pubkey(disable) {
+                def.enabled = false;
+            } else if def_pubkey == pubkey(update) {
+                def.graffiti = Some(GraffitiString::from_str("reloaded").unwrap());
+            }
+        }
+        definitions.retain(|def| def.voting_public_key.compress() != pubkey(remove));
+        definitions.save(self.validator_dir.path()).unwrap();
+
+        let reload = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(reload.added, vec![]);
+        assert_eq!(reload.removed, vec![pubkey(remove)]);
+        assert_eq!(reload.enabled, vec![]);
+        assert_eq!(reload.disabled, vec![pubkey(disable)]);
+        assert_eq!(reload.updated, vec![pubkey(update)]);
+        assert_eq!(reload.error, None);
+
+        assert_eq!(self.vals_total(), initial_vals - 1);
+        assert_eq!(self.vals_enabled(), initial_enabled_vals - 2);
+        assert_eq!(
+            self.validator_store.graffiti(&pubkey(update)),
+            Some(GraffitiString::from_str("reloaded").unwrap().into())
+        );
+        assert_eq!(
+            self.client
+                .get_lighthouse_validators_reload()
+                .await
+                .unwrap()
+                .unwrap()
+                .data,
+            reload
+        );
+
+        // Reloading an unchanged file is a no-op.
+        let reload = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap()
+            .data;
+        assert_eq!(
+            reload,
+            ValidatorDefinitionsReload {
+                timestamp: reload.timestamp,
+                ..<_>::default()
+            }
+        );
+
+        // An invalid file is rejected, leaving the running validators untouched.
+        let definitions_path = self.validator_dir.path().join(CONFIG_FILENAME);
+        let contents = std::fs::read(&definitions_path).unwrap();
+        std::fs::write(&definitions_path, "not yaml: [").unwrap();
+        let err = self
+            .client
+            .post_lighthouse_validators_reload()
+            .await
+            .unwrap_err();
+        assert_eq!(err.status().unwrap(), 500);
+        assert!(self
+            .client
+            .get_lighthouse_validators_reload()
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .error
+            .is_some());
+        assert_eq!(self.vals_total(), initial_vals - 1);
+        assert_eq!(self.vals_enabled(), initial_enabled_vals - 2);
+        std::fs::write(&definitions_path, contents).unwrap();
+
+        self
+    }
+
     pub async fn set_validator_enabled(self, index: usize, enabled: bool) -> Self {
         let validator = &self.client.get_lighthouse_validators().await.unwrap().data[index];
 
@@ -859,6 +1019,14 @@ async fn routes_with_invalid_auth() {
                 .await
         })
         .await
+        .test_with_invalid_auth(
+            |client| async move { client.get_lighthouse_validators_reload().await },
+        )
+        .await
+        .test_with_invalid_auth(
+            |client| async move { client.post_lighthouse_validators_reload().await },
+        )
+        .await
         .test_with_invalid_auth(|client| async move {
             client
                 .post_lighthouse_validators(vec![ValidatorRequest {
@@ -1031,6 +1199,62 @@ async fn validator_exit() {
         .await;
 }
 
+#[tokio::test]
+async fn threshold_partial_signature_unavailable() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 1,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_get_threshold_partial_signature_unavailable(0)
+        .await;
+}
+
+#[tokio::test]
+async fn slashing_protection_audit() {
+    ApiTester::new()
+        .await
+        .create_hd_validators(HdValidatorScenario {
+            count: 2,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_get_slashing_protection_audit(0)
+        .await
+        .test_get_slashing_protection_audit(1)
+        .await;
+}
+
+#[tokio::test]
+async fn validator_definitions_reload() {
+    let tester = ApiTester::new().await;
+    assert!(tester
+        .client
+        .get_lighthouse_validators_reload()
+        .await
+        .unwrap()
+        .is_none());
+
+    tester
+        .create_hd_validators(HdValidatorScenario {
+            count: 4,
+            specify_mnemonic: false,
+            key_derivation_path_offset: 0,
+            disabled: vec![],
+        })
+        .await
+        .test_reload_validator_definitions(0, 1, 2)
+        .await
+        .assert_enabled_validators_count(2)
+        .assert_validators_count(3);
+}
+
 #[tokio::test]
 async fn validator_enabling() {
     ApiTester::new()
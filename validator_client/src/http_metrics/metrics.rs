@@ -9,8 +9,13 @@ pub const SUCCESS: &str = "success";
 pub const SLASHABLE: &str = "slashable";
 pub const SAME_DATA: &str = "same_data";
 pub const UNREGISTERED: &str = "unregistered";
+pub const POLICY_VIOLATION: &str = "policy_violation";
 pub const FULL_UPDATE: &str = "full_update";
 pub const BEACON_BLOCK: &str = "beacon_block";
+pub const VOLUNTARY_EXIT: &str = "voluntary_exit";
+pub const VALIDATOR_REGISTRATION: &str = "validator_registration";
+pub const SYNC_COMMITTEE_MESSAGES: &str = "sync_committee_messages";
+pub const SYNC_CONTRIBUTIONS: &str = "sync_contributions";
 pub const BEACON_BLOCK_HTTP_GET: &str = "beacon_block_http_get";
 pub const BEACON_BLOCK_HTTP_POST: &str = "beacon_block_http_post";
 pub const BLINDED_BEACON_BLOCK_HTTP_POST: &str = "blinded_beacon_block_http_post";
@@ -37,6 +42,8 @@ pub const ATTESTATION_SELECTION_PROOFS: &str = "attestation_selection_proofs";
 pub const SUBSCRIPTIONS: &str = "subscriptions";
 pub const LOCAL_KEYSTORE: &str = "local_keystore";
 pub const WEB3SIGNER: &str = "web3signer";
+pub const THRESHOLD_SHARE: &str = "threshold_share";
+pub const PKCS11: &str = "pkcs11";
 
 pub use lighthouse_metrics::*;
 
@@ -126,6 +133,20 @@ pub static SIGNED_VALIDATOR_REGISTRATIONS_TOTAL: LazyLock<Result<IntCounterVec>>
             &["status"],
         )
     });
+pub static SIGNING_POLICY_VIOLATIONS_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_signing_policy_violations_total",
+        "Total count of messages refused by a validator's signing policy",
+        &["message_type", "policy"],
+    )
+});
+pub static DRY_RUN_MESSAGES_TOTAL: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "vc_dry_run_messages_total",
+        "Total count of messages which were not signed or published due to dry-run mode",
+        &["type"],
+    )
+});
 pub static DUTIES_SERVICE_TIMES: LazyLock<Result<HistogramVec>> = LazyLock::new(|| {
     try_create_histogram_vec(
         "vc_duties_service_task_times_seconds",
@@ -183,6 +204,13 @@ pub static ENDPOINT_ERRORS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
         &["endpoint"],
     )
 });
+pub static ENDPOINT_CIRCUIT_BREAKER_TRIPS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
+    try_create_int_counter_vec(
+        "bn_endpoint_circuit_breaker_trips",
+        "The number of times the circuit breaker of each beacon node endpoint has tripped",
+        &["endpoint"],
+    )
+});
 pub static ENDPOINT_REQUESTS: LazyLock<Result<IntCounterVec>> = LazyLock::new(|| {
     try_create_int_counter_vec(
         "bn_endpoint_requests",
@@ -6,20 +6,24 @@
 //! The `InitializedValidators` struct in this file serves as the source-of-truth of which
 //! validators are managed by this validator client.
 
-use crate::signing_method::SigningMethod;
+use crate::signing_method::{
+    Pkcs11Error, Pkcs11Signer, SigningMethod, ThresholdPeerClient, ThresholdSigner,
+};
 use account_utils::{
     read_password, read_password_from_user, read_password_string,
     validator_definitions::{
-        self, SigningDefinition, ValidatorDefinition, ValidatorDefinitions, Web3SignerDefinition,
-        CONFIG_FILENAME,
+        self, SigningDefinition, SigningPolicy, ThresholdShareDefinition, ValidatorDefinition,
+        ValidatorDefinitions, Web3SignerDefinition, CONFIG_FILENAME,
     },
-    ZeroizeString,
+    PlainText, ZeroizeString,
 };
+use eth2::lighthouse_vc::http_client::ValidatorClientHttpClient;
 use eth2_keystore::Keystore;
 use lighthouse_metrics::set_gauge;
 use lockfile::{Lockfile, LockfileError};
 use parking_lot::{MappedMutexGuard, Mutex, MutexGuard};
 use reqwest::{Certificate, Client, Error as ReqwestError, Identity};
+use sensitive_url::SensitiveUrl;
 use slog::{debug, error, info, warn, Logger};
 use std::collections::{HashMap, HashSet};
 use std::fs::{self, File};
@@ -58,6 +62,36 @@ pub struct KeystoreAndPassword {
     pub password: Option<ZeroizeString>,
 }
 
+/// The differences between the validator definitions held by `InitializedValidators` and those in
+/// the `validator_definitions.yml` file on disk.
+pub struct DefinitionsDiff {
+    /// The definitions read from disk.
+    definitions: ValidatorDefinitions,
+    /// Validators which are only defined on disk.
+    pub added: Vec<PublicKeyBytes>,
+    /// Validators which are no longer defined on disk.
+    pub removed: Vec<PublicKeyBytes>,
+    /// Validators which are disabled in memory and enabled on disk.
+    pub enabled: Vec<PublicKeyBytes>,
+    /// Validators which are enabled in memory and disabled on disk.
+    pub disabled: Vec<PublicKeyBytes>,
+    /// Validators with other changes to their definitions, e.g. a new fee recipient.
+    pub updated: Vec<PublicKeyBytes>,
+    /// Validators which will start performing duties once the diff is applied.
+    pub newly_enabled: Vec<PublicKeyBytes>,
+}
+
+impl DefinitionsDiff {
+    /// Returns `true` if the definitions on disk are the same as those in memory.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.enabled.is_empty()
+            && self.disabled.is_empty()
+            && self.updated.is_empty()
+    }
+}
+
 #[derive(Debug)]
 pub enum Error {
     /// Refused to open a validator with an existing lockfile since that validator may be in-use by
@@ -83,6 +117,8 @@ pub enum Error {
     UnableToReadVotingKeystorePassword(io::Error),
     /// There was an error updating the on-disk validator definitions file.
     UnableToSaveDefinitions(validator_definitions::Error),
+    /// There was an error reading the on-disk validator definitions file.
+    UnableToOpenDefinitions(validator_definitions::Error),
     /// It is not legal to try and initialize a disabled validator definition.
     UnableToInitializeDisabledValidator,
     /// There was an error while deleting a keystore file.
@@ -117,6 +153,22 @@ pub enum Error {
     UnableToSaveKeyCache(key_cache::Error),
     UnableToDecryptKeyCache(key_cache::Error),
     UnableToDeletePasswordFile(PathBuf, io::Error),
+    /// The threshold of a key share is zero or greater than the number of shares.
+    InvalidThreshold {
+        threshold: usize,
+        num_shares: usize,
+    },
+    /// The public keys of the key shares could not be combined.
+    InvalidThresholdShares(bls::Error),
+    /// The public keys of the key shares with these indices do not belong to the voting key.
+    ThresholdSharePublicKeyMismatch(Vec<u64>),
+    /// The URL of a validator client holding another key share cannot be parsed.
+    InvalidThresholdPeerUrl(String),
+    /// Neither a user PIN nor the path to a user PIN was supplied for a PKCS#11 token.
+    MissingPkcs11UserPin,
+    UnableToReadPkcs11UserPin(String),
+    /// A session with the PKCS#11 token which signs with the key could not be opened.
+    UnableToOpenPkcs11Key(Pkcs11Error),
 }
 
 impl From<LockfileError> for Error {
@@ -134,6 +186,7 @@ pub struct InitializedValidator {
     builder_proposals: Option<bool>,
     builder_boost_factor: Option<u64>,
     prefer_builder_proposals: Option<bool>,
+    signing_policy: Option<SigningPolicy>,
     /// The validators index in `state.validators`, to be updated by an external service.
     index: Option<u64>,
 }
@@ -149,8 +202,15 @@ impl InitializedValidator {
                 option_lockfile.as_mut()
             })
             .ok(),
-            // Web3Signer validators do not have any lockfiles.
-            SigningMethod::Web3Signer { .. } => None,
+            SigningMethod::ThresholdShare {
+                ref share_keystore_lockfile,
+                ..
+            } => MutexGuard::try_map(share_keystore_lockfile.lock(), |option_lockfile| {
+                option_lockfile.as_mut()
+            })
+            .ok(),
+            // Web3Signer and PKCS#11 validators do not have any lockfiles.
+            SigningMethod::Web3Signer { .. } | SigningMethod::Pkcs11 { .. } => None,
         }
     }
 
@@ -238,34 +298,11 @@ impl InitializedValidator {
                     // to keep if off the core executor. This also has the fortunate effect of
                     // interrupting the potentially long-running task during shut down.
                     let (password, keypair) = tokio::task::spawn_blocking(move || {
-                        Result::<_, Error>::Ok(
-                            match (voting_keystore_password_path, voting_keystore_password) {
-                                // If the password is supplied, use it and ignore the path
-                                // (if supplied).
-                                (_, Some(password)) => (
-                                    password.as_ref().to_vec().into(),
-                                    keystore
-                                        .decrypt_keypair(password.as_ref())
-                                        .map_err(Error::UnableToDecryptKeystore)?,
-                                ),
-                                // If only the path is supplied, use the path.
-                                (Some(path), None) => {
-                                    let password = read_password(path)
-                                        .map_err(Error::UnableToReadVotingKeystorePassword)?;
-                                    let keypair = keystore
-                                        .decrypt_keypair(password.as_bytes())
-                                        .map_err(Error::UnableToDecryptKeystore)?;
-                                    (password, keypair)
-                                }
-                                // If there is no password available, maybe prompt for a password.
-                                (None, None) => {
-                                    let (password, keypair) = unlock_keystore_via_stdin_password(
-                                        &keystore,
-                                        &keystore_path,
-                                    )?;
-                                    (password.as_ref().to_vec().into(), keypair)
-                                }
-                            },
+                        decrypt_keystore(
+                            &keystore,
+                            &keystore_path,
+                            voting_keystore_password_path,
+                            voting_keystore_password,
                         )
                     })
                     .await
@@ -343,6 +380,146 @@ impl InitializedValidator {
                     voting_public_key: def.voting_public_key,
                 }
             }
+            // Load and decrypt this validator client's share of the key, and create clients for
+            // the peers holding the other shares. Key shares are not stored in the key cache.
+            SigningDefinition::ThresholdShare(share) => {
+                let threshold = share.threshold as usize;
+                if threshold == 0 || threshold > share.peers.len() + 1 {
+                    return Err(Error::InvalidThreshold {
+                        threshold,
+                        num_shares: share.peers.len() + 1,
+                    });
+                }
+
+                let share_keystore = open_keystore(&share.share_keystore_path)?;
+                let keystore = share_keystore.clone();
+                let keystore_path = share.share_keystore_path.clone();
+                let password_path = share.share_keystore_password_path.clone();
+                let password = share.share_keystore_password.clone();
+                let (_, share_keypair) = tokio::task::spawn_blocking(move || {
+                    decrypt_keystore(&keystore, &keystore_path, password_path, password)
+                })
+                .await
+                .map_err(Error::TokioJoin)??;
+
+                // Check that every share belongs to the validator's key, so that a misconfigured
+                // peer is detected now rather than when it is needed for signing.
+                let share_public_keys =
+                    std::iter::once((share.share_index, share_keypair.pk.clone()))
+                        .chain(
+                            share
+                                .peers
+                                .iter()
+                                .map(|peer| (peer.share_index, peer.share_public_key.clone())),
+                        )
+                        .collect::<Vec<_>>();
+                let (combined_public_key, invalid_shares) =
+                    bls::threshold::verify_share_public_keys(&share_public_keys, threshold)
+                        .map_err(Error::InvalidThresholdShares)?;
+                if combined_public_key != def.voting_public_key {
+                    return Err(Error::VotingPublicKeyMismatch {
+                        definition: Box::new(def.voting_public_key),
+                        keystore: Box::new(combined_public_key),
+                    });
+                }
+                if !invalid_shares.is_empty() {
+                    return Err(Error::ThresholdSharePublicKeyMismatch(invalid_shares));
+                }
+
+                let request_timeout = share
+                    .request_timeout_ms
+                    .map(Duration::from_millis)
+                    .unwrap_or(DEFAULT_REMOTE_SIGNER_REQUEST_TIMEOUT);
+
+                let peers = share
+                    .peers
+                    .into_iter()
+                    .map(|peer| {
+                        let http_client = build_web3_signer_client(
+                            peer.root_certificate_path,
+                            None,
+                            None,
+                            request_timeout,
+                            config.web3_signer_keep_alive_timeout,
+                            config.web3_signer_max_idle_connections,
+                        )?;
+                        let url = SensitiveUrl::parse(&peer.url)
+                            .map_err(|e| Error::InvalidThresholdPeerUrl(format!("{:?}", e)))?;
+                        let client = ValidatorClientHttpClient::from_components(
+                            url,
+                            http_client,
+                            peer.api_token.as_str().to_string(),
+                        )
+                        .map_err(|e| Error::InvalidThresholdPeerUrl(format!("{:?}", e)))?;
+                        Ok(ThresholdPeerClient {
+                            share_index: peer.share_index,
+                            share_public_key: peer.share_public_key,
+                            client,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?;
+
+                // Append a `.lock` suffix to the share keystore.
+                let lockfile_path =
+                    get_lockfile_path(&share.share_keystore_path).ok_or_else(|| {
+                        Error::BadVotingKeystorePath(share.share_keystore_path.clone())
+                    })?;
+                let share_keystore_lockfile = Mutex::new(Some(Lockfile::new(lockfile_path)?));
+
+                SigningMethod::ThresholdShare {
+                    share_keystore_path: share.share_keystore_path,
+                    share_keystore_lockfile,
+                    share_keystore,
+                    signer: ThresholdSigner::new(
+                        share.share_index,
+                        threshold,
+                        share_keypair,
+                        def.voting_public_key,
+                        peers,
+                        request_timeout,
+                    ),
+                }
+            }
+            // Open a session with the PKCS#11 token which signs with the key. Keys held in a token
+            // are not stored in the key cache.
+            SigningDefinition::Pkcs11(pkcs11) => {
+                let user_pin = match (pkcs11.user_pin, &pkcs11.user_pin_path) {
+                    // If the PIN is supplied, use it and ignore the path (if supplied).
+                    (Some(user_pin), _) => user_pin,
+                    (None, Some(path)) => {
+                        read_password_string(path).map_err(Error::UnableToReadPkcs11UserPin)?
+                    }
+                    (None, None) => return Err(Error::MissingPkcs11UserPin),
+                };
+
+                let module_path = pkcs11.module_path.clone();
+                let token_label = pkcs11.token_label.clone();
+                let key_label = pkcs11.key_label.clone();
+                let mechanism = pkcs11.mechanism;
+                let voting_public_key = def.voting_public_key.clone();
+                // Calls to the PKCS#11 module are blocking, so keep them off the core executor.
+                let signer = tokio::task::spawn_blocking(move || {
+                    Pkcs11Signer::open(
+                        &module_path,
+                        &token_label,
+                        &key_label,
+                        &user_pin,
+                        mechanism,
+                        voting_public_key,
+                    )
+                })
+                .await
+                .map_err(Error::TokioJoin)?
+                .map_err(Error::UnableToOpenPkcs11Key)?;
+
+                SigningMethod::Pkcs11 {
+                    module_path: pkcs11.module_path,
+                    token_label: pkcs11.token_label,
+                    key_label: pkcs11.key_label,
+                    voting_public_key: def.voting_public_key,
+                    signer: Arc::new(signer),
+                }
+            }
         };
 
         Ok(Self {
@@ -353,6 +530,7 @@ impl InitializedValidator {
             builder_proposals: def.builder_proposals,
             builder_boost_factor: def.builder_boost_factor,
             prefer_builder_proposals: def.prefer_builder_proposals,
+            signing_policy: def.signing_policy,
             index: None,
         })
     }
@@ -364,6 +542,43 @@ impl InitializedValidator {
             SigningMethod::Web3Signer {
                 voting_public_key, ..
             } => voting_public_key,
+            SigningMethod::ThresholdShare { signer, .. } => signer.voting_public_key(),
+            SigningMethod::Pkcs11 {
+                voting_public_key, ..
+            } => voting_public_key,
+        }
+    }
+}
+
+/// Decrypt `keystore` using the supplied password, or the password at `password_path`, prompting
+/// the user for a password if neither is available.
+fn decrypt_keystore(
+    keystore: &Keystore,
+    keystore_path: &Path,
+    password_path: Option<PathBuf>,
+    password: Option<ZeroizeString>,
+) -> Result<(PlainText, Keypair), Error> {
+    match (password_path, password) {
+        // If the password is supplied, use it and ignore the path (if supplied).
+        (_, Some(password)) => Ok((
+            password.as_ref().to_vec().into(),
+            keystore
+                .decrypt_keypair(password.as_ref())
+                .map_err(Error::UnableToDecryptKeystore)?,
+        )),
+        // If only the path is supplied, use the path.
+        (Some(path), None) => {
+            let password =
+                read_password(path).map_err(Error::UnableToReadVotingKeystorePassword)?;
+            let keypair = keystore
+                .decrypt_keypair(password.as_bytes())
+                .map_err(Error::UnableToDecryptKeystore)?;
+            Ok((password, keypair))
+        }
+        // If there is no password available, maybe prompt for a password.
+        (None, None) => {
+            let (password, keypair) = unlock_keystore_via_stdin_password(keystore, keystore_path)?;
+            Ok((password.as_ref().to_vec().into(), keypair))
         }
     }
 }
@@ -827,6 +1042,14 @@ impl InitializedValidators {
             .and_then(|v| v.suggested_fee_recipient)
     }
 
+    /// Returns the `signing_policy` for a given public key specified in the
+    /// `ValidatorDefinitions`.
+    pub fn signing_policy(&self, public_key: &PublicKeyBytes) -> Option<SigningPolicy> {
+        self.validators
+            .get(public_key)
+            .and_then(|v| v.signing_policy.clone())
+    }
+
     /// Returns the `gas_limit` for a given public key specified in the
     /// `ValidatorDefinitions`.
     pub fn gas_limit(&self, public_key: &PublicKeyBytes) -> Option<u64> {
@@ -1123,8 +1346,11 @@ impl InitializedValidators {
                     };
                     definitions_map.insert(*key_store.uuid(), def);
                 }
-                // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
+                // Remote signer, key share and PKCS#11 validators don't interact with the key
+                // cache.
+                SigningDefinition::Web3Signer { .. }
+                | SigningDefinition::ThresholdShare(_)
+                | SigningDefinition::Pkcs11(_) => (),
             }
         }
 
@@ -1167,8 +1393,11 @@ impl InitializedValidators {
                     passwords.push(pw);
                     public_keys.push(def.voting_public_key.clone());
                 }
-                // Remote signer validators don't interact with the key cache.
-                SigningDefinition::Web3Signer { .. } => (),
+                // Remote signer, key share and PKCS#11 validators don't interact with the key
+                // cache.
+                SigningDefinition::Web3Signer { .. }
+                | SigningDefinition::ThresholdShare(_)
+                | SigningDefinition::Pkcs11(_) => (),
             };
         }
 
@@ -1183,6 +1412,104 @@ impl InitializedValidators {
         .map_err(Error::UnableToDecryptKeyCache)
     }
 
+    /// Read the validator definitions file from disk and compare it to the definitions in `self`.
+    ///
+    /// Nothing is changed until the result is passed to `Self::apply_definitions_diff`.
+    pub fn diff_definitions_on_disk(&self) -> Result<DefinitionsDiff, Error> {
+        let definitions = ValidatorDefinitions::open(&self.validators_dir)
+            .map_err(Error::UnableToOpenDefinitions)?;
+
+        // Only the first definition for each public key is used, as in `Self::update_validators`.
+        let index_by_pubkey = |defs: &[ValidatorDefinition]| {
+            let mut map = HashMap::new();
+            for (i, def) in defs.iter().enumerate() {
+                map.entry(def.voting_public_key.compress()).or_insert(i);
+            }
+            map
+        };
+        let current = self.definitions.as_slice();
+        let current_by_pubkey = index_by_pubkey(current);
+        let new = definitions.as_slice();
+        let new_by_pubkey = index_by_pubkey(new);
+
+        let mut diff = DefinitionsDiff {
+            added: vec![],
+            removed: vec![],
+            enabled: vec![],
+            disabled: vec![],
+            updated: vec![],
+            newly_enabled: vec![],
+            definitions: ValidatorDefinitions::default(),
+        };
+
+        for (i, new_def) in new.iter().enumerate() {
+            let pubkey = new_def.voting_public_key.compress();
+            if new_by_pubkey.get(&pubkey) != Some(&i) {
+                continue;
+            }
+
+            match current_by_pubkey.get(&pubkey).map(|&j| &current[j]) {
+                None => {
+                    diff.added.push(pubkey);
+                    if new_def.enabled {
+                        diff.newly_enabled.push(pubkey);
+                    }
+                }
+                Some(current_def) if !current_def.enabled && new_def.enabled => {
+                    diff.enabled.push(pubkey);
+                    diff.newly_enabled.push(pubkey);
+                }
+                Some(current_def) if current_def.enabled && !new_def.enabled => {
+                    diff.disabled.push(pubkey);
+                }
+                Some(current_def) if current_def != new_def => diff.updated.push(pubkey),
+                Some(_) => {}
+            }
+        }
+        for (i, def) in current.iter().enumerate() {
+            let pubkey = def.voting_public_key.compress();
+            if current_by_pubkey.get(&pubkey) == Some(&i) && !new_by_pubkey.contains_key(&pubkey) {
+                diff.removed.push(pubkey);
+            }
+        }
+
+        diff.definitions = definitions;
+        Ok(diff)
+    }
+
+    /// Replace the definitions in `self` with those read by `Self::diff_definitions_on_disk`,
+    /// initializing, reinitializing and dropping validators as required.
+    ///
+    /// If any validator fails to initialize then the previous definitions are restored, so that a
+    /// bad edit to the file doesn't stop the validators which were already running.
+    pub async fn apply_definitions_diff(&mut self, diff: DefinitionsDiff) -> Result<(), Error> {
+        let previous_definitions = std::mem::replace(&mut self.definitions, diff.definitions);
+
+        // Removed validators are not dropped by `update_validators`, and updated validators must
+        // be reinitialized to pick up their new definitions.
+        for pubkey in diff.removed.iter().chain(&diff.updated) {
+            self.validators.remove(pubkey);
+        }
+
+        if let Err(e) = self.update_validators().await {
+            self.definitions = previous_definitions;
+
+            let enabled = self
+                .definitions
+                .as_slice()
+                .iter()
+                .filter(|def| def.enabled)
+                .map(|def| def.voting_public_key.compress())
+                .collect::<HashSet<_>>();
+            self.validators.retain(|pubkey, _| enabled.contains(pubkey));
+            self.update_validators().await?;
+
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
     /// Scans `self.definitions` and attempts to initialize and validators which are not already
     /// initialized.
     ///
@@ -1323,6 +1650,76 @@ impl InitializedValidators {
                                     "validator" => format!("{:?}", def.voting_public_key)
                                 );
 
+                                // Exit on an invalid validator.
+                                return Err(e);
+                            }
+                        }
+                    }
+                    SigningDefinition::ThresholdShare(_) => {
+                        match InitializedValidator::from_definition(
+                            def.clone(),
+                            &mut key_cache,
+                            &mut key_stores,
+                            &mut None,
+                            &self.config,
+                        )
+                        .await
+                        {
+                            Ok(init) => {
+                                self.validators
+                                    .insert(init.voting_public_key().compress(), init);
+
+                                info!(
+                                    self.log,
+                                    "Enabled validator";
+                                    "signing_method" => "threshold_share",
+                                    "voting_pubkey" => format!("{:?}", def.voting_public_key),
+                                );
+                            }
+                            Err(e) => {
+                                error!(
+                                    self.log,
+                                    "Failed to initialize validator";
+                                    "error" => format!("{:?}", e),
+                                    "signing_method" => "threshold_share",
+                                    "validator" => format!("{:?}", def.voting_public_key)
+                                );
+
+                                // Exit on an invalid validator.
+                                return Err(e);
+                            }
+                        }
+                    }
+                    SigningDefinition::Pkcs11(_) => {
+                        match InitializedValidator::from_definition(
+                            def.clone(),
+                            &mut key_cache,
+                            &mut key_stores,
+                            &mut None,
+                            &self.config,
+                        )
+                        .await
+                        {
+                            Ok(init) => {
+                                self.validators
+                                    .insert(init.voting_public_key().compress(), init);
+
+                                info!(
+                                    self.log,
+                                    "Enabled validator";
+                                    "signing_method" => "pkcs11",
+                                    "voting_pubkey" => format!("{:?}", def.voting_public_key),
+                                );
+                            }
+                            Err(e) => {
+                                error!(
+                                    self.log,
+                                    "Failed to initialize validator";
+                                    "error" => format!("{:?}", e),
+                                    "signing_method" => "pkcs11",
+                                    "validator" => format!("{:?}", def.voting_public_key)
+                                );
+
                                 // Exit on an invalid validator.
                                 return Err(e);
                             }
@@ -1340,8 +1737,11 @@ impl InitializedValidators {
                             disabled_uuids.insert(*key_store.uuid());
                         }
                     }
-                    // Remote signers do not interact with the key cache.
-                    SigningDefinition::Web3Signer { .. } => (),
+                    // Remote signers, key shares and PKCS#11 validators do not interact with the key
+                    // cache.
+                    SigningDefinition::Web3Signer { .. }
+                    | SigningDefinition::ThresholdShare(_)
+                    | SigningDefinition::Pkcs11(_) => {}
                 }
 
                 info!(
@@ -1419,8 +1819,17 @@ impl InitializedValidators {
                         passwords.insert(def.voting_public_key.clone(), password);
                     }
                 }
-                // Remote signers don't have passwords.
-                SigningDefinition::Web3Signer { .. } => (),
+                SigningDefinition::ThresholdShare(ThresholdShareDefinition {
+                    ref mut share_keystore_password,
+                    ..
+                }) => {
+                    if let Some(password) = share_keystore_password.take() {
+                        passwords.insert(def.voting_public_key.clone(), password);
+                    }
+                }
+                // Remote signers don't have passwords, and PKCS#11 user PINs aren't keystore
+                // passwords.
+                SigningDefinition::Web3Signer { .. } | SigningDefinition::Pkcs11(_) => (),
             };
         }
 
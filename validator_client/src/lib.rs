@@ -4,6 +4,7 @@ mod beacon_node_health;
 mod block_service;
 mod check_synced;
 mod cli;
+mod definitions_watcher;
 mod duties_service;
 mod graffiti_file;
 mod http_metrics;
@@ -12,6 +13,7 @@ mod latency;
 mod notifier;
 mod preparation_service;
 mod signing_method;
+mod signing_policy;
 mod sync_committee_service;
 
 pub mod config;
@@ -27,8 +29,13 @@ pub use config::Config;
 use initialized_validators::InitializedValidators;
 use lighthouse_metrics::set_gauge;
 use monitoring_api::{MonitoringHttpClient, ProcessType};
+use otlp::OtlpHttpClient;
 use sensitive_url::SensitiveUrl;
-pub use slashing_protection::{SlashingDatabase, SLASHING_PROTECTION_FILENAME};
+#[cfg(feature = "slashing-protection-postgres")]
+pub use slashing_protection::PostgresSlashingDatabase;
+pub use slashing_protection::{
+    SlashingDatabase, SlashingProtectionBackend, SLASHING_PROTECTION_FILENAME,
+};
 
 use crate::beacon_node_fallback::{
     start_fallback_updater_service, BeaconNodeFallback, CandidateBeaconNode,
@@ -192,6 +199,13 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             );
         };
 
+        // Start the exporter which periodically pushes metrics and duty traces to an
+        // OpenTelemetry collector.
+        if let Some(otlp_config) = &config.otlp {
+            let otlp_client = OtlpHttpClient::new(otlp_config, context.log().clone())?;
+            otlp_client.auto_update(context.executor.clone());
+        };
+
         let mut validator_defs = ValidatorDefinitions::open_or_create(&config.validator_dir)
             .map_err(|e| format!("Unable to open or create validator definitions: {:?}", e))?;
 
@@ -230,7 +244,7 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
                 }
             })?;
 
-        let voting_pubkeys: Vec<_> = validators.iter_voting_pubkeys().collect();
+        let voting_pubkeys: Vec<_> = validators.iter_voting_pubkeys().copied().collect();
 
         info!(
             log,
@@ -252,32 +266,47 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
         // Create the slashing database if there are no validators, even if
         // `init_slashing_protection` is not supplied. There is no risk in creating a slashing
         // database without any validators in it.
+        //
+        // A shared PostgreSQL database is always created if necessary, as it is expected to be
+        // provisioned by the operator.
         let slashing_db_path = config.validator_dir.join(SLASHING_PROTECTION_FILENAME);
-        let slashing_protection = if config.init_slashing_protection || voting_pubkeys.is_empty() {
-            SlashingDatabase::open_or_create(&slashing_db_path).map_err(|e| {
-                format!(
-                    "Failed to open or create slashing protection database: {:?}",
-                    e
-                )
-            })
-        } else {
-            SlashingDatabase::open(&slashing_db_path).map_err(|e| {
-                format!(
-                    "Failed to open slashing protection database: {:?}.\n\
+        let slashing_protection: Arc<dyn SlashingProtectionBackend> =
+            if let Some(url) = &config.slashing_protection_postgres_url {
+                info!(
+                    log,
+                    "Using PostgreSQL slashing protection database";
+                    "url" => %url
+                );
+                open_postgres_slashing_protection(url)?
+            } else if config.init_slashing_protection || voting_pubkeys.is_empty() {
+                SlashingDatabase::open_or_create(&slashing_db_path)
+                    .map_err(|e| {
+                        format!(
+                            "Failed to open or create slashing protection database: {:?}",
+                            e
+                        )
+                    })
+                    .map(|db| Arc::new(db) as _)?
+            } else {
+                SlashingDatabase::open(&slashing_db_path)
+                    .map_err(|e| {
+                        format!(
+                            "Failed to open slashing protection database: {:?}.\n\
                      Ensure that `slashing_protection.sqlite` is in {:?} folder",
-                    e, config.validator_dir
-                )
-            })
-        }?;
+                            e, config.validator_dir
+                        )
+                    })
+                    .map(|db| Arc::new(db) as _)?
+            };
 
         // Check validator registration with slashing protection, or auto-register all validators.
         if config.init_slashing_protection {
             slashing_protection
-                .register_validators(voting_pubkeys.iter().copied())
+                .register_validators(&voting_pubkeys)
                 .map_err(|e| format!("Error while registering slashing protection: {:?}", e))?;
         } else {
             slashing_protection
-                .check_validator_registrations(voting_pubkeys.iter().copied())
+                .check_validator_registrations(&voting_pubkeys)
                 .map_err(|e| {
                     format!(
                         "One or more validators not found in slashing protection database.\n\
@@ -401,15 +430,19 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
 
         let mut beacon_nodes: BeaconNodeFallback<_, E> = BeaconNodeFallback::new(
             candidates,
-            config.beacon_node_fallback,
+            config.beacon_node_fallback.clone(),
             config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
         );
 
+        // The weights are those of `--beacon-nodes`, so they are not applied to proposer nodes.
         let mut proposer_nodes: BeaconNodeFallback<_, E> = BeaconNodeFallback::new(
             proposer_candidates,
-            config.beacon_node_fallback,
+            beacon_node_fallback::Config {
+                weights: None,
+                ..config.beacon_node_fallback.clone()
+            },
             config.broadcast_topics.clone(),
             context.eth2_config.spec.clone(),
             log.clone(),
@@ -641,6 +674,14 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             info!(log, "Doppelganger protection disabled.")
         }
 
+        if self.config.dry_run {
+            warn!(
+                log,
+                "Running in dry-run mode";
+                "info" => "duties will be performed without signing or publishing any messages",
+            );
+        }
+
         spawn_notifier(self).map_err(|e| format!("Failed to start notifier: {}", e))?;
 
         if self.config.enable_latency_measurement_service {
@@ -651,6 +692,15 @@ impl<E: EthSpec> ProductionValidatorClient<E> {
             );
         }
 
+        if self.config.reload_validator_definitions {
+            definitions_watcher::start_definitions_watcher(
+                self.context.clone(),
+                self.validator_store.clone(),
+                self.config.validator_dir.clone(),
+            )
+            .map_err(|e| format!("Unable to start validator definitions watcher: {}", e))?;
+        }
+
         Ok(())
     }
 }
@@ -714,7 +764,7 @@ async fn init_from_beacon_node<E: EthSpec>(
 
     let genesis = loop {
         match beacon_nodes
-            .first_success(|node| async move { node.get_beacon_genesis().await })
+            .first_success_non_critical(|node| async move { node.get_beacon_genesis().await })
             .await
         {
             Ok(genesis) => break genesis.data,
@@ -801,7 +851,9 @@ async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
 ) -> Result<(), String> {
     loop {
         match beacon_nodes
-            .first_success(|beacon_node| async move { beacon_node.get_lighthouse_staking().await })
+            .first_success_non_critical(|beacon_node| async move {
+                beacon_node.get_lighthouse_staking().await
+            })
             .await
         {
             Ok(is_staking) => {
@@ -842,6 +894,30 @@ async fn poll_whilst_waiting_for_genesis<E: EthSpec>(
     }
 }
 
+/// Open the PostgreSQL slashing protection database at `url`.
+#[cfg(feature = "slashing-protection-postgres")]
+fn open_postgres_slashing_protection(
+    url: &SensitiveUrl,
+) -> Result<Arc<dyn SlashingProtectionBackend>, String> {
+    PostgresSlashingDatabase::open(url.full.as_str())
+        .map(|db| Arc::new(db) as _)
+        .map_err(|e| {
+            format!(
+                "Failed to open PostgreSQL slashing protection database: {:?}",
+                e
+            )
+        })
+}
+
+/// Without support for PostgreSQL the URL is rejected when the configuration is parsed, so this
+/// is only reached if the configuration was constructed some other way.
+#[cfg(not(feature = "slashing-protection-postgres"))]
+fn open_postgres_slashing_protection(
+    _url: &SensitiveUrl,
+) -> Result<Arc<dyn SlashingProtectionBackend>, String> {
+    Err(config::POSTGRES_DISABLED_ERROR.to_string())
+}
+
 pub fn load_pem_certificate<P: AsRef<Path>>(pem_path: P) -> Result<Certificate, String> {
     let mut buf = Vec::new();
     File::open(&pem_path)
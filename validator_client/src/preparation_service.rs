@@ -264,6 +264,14 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
     /// Prepare proposer preparations and send to beacon node
     async fn prepare_proposers_and_publish(&self, spec: &ChainSpec) -> Result<(), String> {
         let preparation_data = self.collect_preparation_data(spec);
+        if self.validator_store.dry_run() {
+            debug!(
+                self.context.log(),
+                "Skipped publishing proposer preparation in dry run";
+                "count" => preparation_data.len(),
+            );
+            return Ok(());
+        }
         if !preparation_data.is_empty() {
             self.publish_preparation_data(preparation_data).await?;
         }
@@ -365,6 +373,14 @@ impl<T: SlotClock + 'static, E: EthSpec> PreparationService<T, E> {
     /// Register validators with builders, used in the blinded block proposal flow.
     async fn register_validators(&self) -> Result<(), String> {
         let registration_keys = self.collect_validator_registration_keys();
+        if self.validator_store.dry_run() {
+            debug!(
+                self.context.log(),
+                "Skipped validator registrations in dry run";
+                "count" => registration_keys.len(),
+            );
+            return Ok(());
+        }
 
         let mut changed_keys = vec![];
 
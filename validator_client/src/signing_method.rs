@@ -2,6 +2,8 @@
 //!
 //! - Via a local `Keypair`.
 //! - Via a remote signer (Web3Signer)
+//! - Via a share of a key which is split between several validator clients.
+//! - Via a key held in a PKCS#11 token.
 
 use crate::http_metrics::metrics;
 use eth2_keystore::Keystore;
@@ -15,8 +17,12 @@ use types::*;
 use url::Url;
 use web3signer::{ForkInfo, SigningRequest, SigningResponse};
 
+pub use pkcs11::{Pkcs11Error, Pkcs11Signer};
+pub use threshold::{ThresholdPeerClient, ThresholdSigner};
 pub use web3signer::Web3SignerObject;
 
+mod pkcs11;
+mod threshold;
 mod web3signer;
 
 #[derive(Debug, PartialEq)]
@@ -31,6 +37,15 @@ pub enum Error {
     TokioJoin(String),
     MergeForkNotSupported,
     GenesisForkVersionRequired,
+    /// Too few peers returned a partial signature before the request timeout.
+    InsufficientPartialSignatures {
+        received: usize,
+        threshold: usize,
+    },
+    ThresholdCombination(bls::Error),
+    /// The combined partial signatures did not verify against the validator's public key.
+    InvalidThresholdSignature,
+    Pkcs11SigningFailed(Pkcs11Error),
 }
 
 /// Enumerates all messages that can be signed by a validator.
@@ -93,6 +108,23 @@ pub enum SigningMethod {
         http_client: Client,
         voting_public_key: PublicKey,
     },
+    /// A validator whose key is split between several validator clients, defined by an EIP-2335
+    /// keystore containing this validator client's share.
+    ThresholdShare {
+        share_keystore_path: PathBuf,
+        share_keystore_lockfile: Mutex<Option<Lockfile>>,
+        share_keystore: Keystore,
+        signer: ThresholdSigner,
+    },
+    /// A validator whose secret key is held in a PKCS#11 token, such as a hardware security
+    /// module.
+    Pkcs11 {
+        module_path: PathBuf,
+        token_label: String,
+        key_label: String,
+        voting_public_key: PublicKey,
+        signer: Arc<Pkcs11Signer>,
+    },
 }
 
 /// The additional information used to construct a signature. Mostly used for protection from replay
@@ -128,6 +160,24 @@ impl SigningMethod {
             // Slashing protection is only required for remote signer keys when the configuration
             // dictates that it is desired.
             SigningMethod::Web3Signer { .. } => enable_web3signer_slashing_protection,
+            // Each share is protected by the validator client which holds it. DO NOT TURN THIS OFF.
+            SigningMethod::ThresholdShare { .. } => true,
+            // Slashing protection is ALWAYS required for keys held in a token. DO NOT TURN THIS
+            // OFF.
+            SigningMethod::Pkcs11 { .. } => true,
+        }
+    }
+
+    /// Return the partial signature of `signing_root` produced with this validator client's share
+    /// of the key, along with the index of the share.
+    pub fn threshold_partial_signature(&self, signing_root: Hash256) -> Option<(u64, Signature)> {
+        match self {
+            SigningMethod::ThresholdShare { signer, .. } => signer
+                .partial_signature(signing_root)
+                .map(|signature| (signer.share_index(), signature)),
+            SigningMethod::LocalKeystore { .. }
+            | SigningMethod::Web3Signer { .. }
+            | SigningMethod::Pkcs11 { .. } => None,
         }
     }
 
@@ -256,6 +306,35 @@ impl SigningMethod {
 
                 Ok(response.signature)
             }
+            SigningMethod::ThresholdShare { signer, .. } => {
+                let _timer =
+                    metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::THRESHOLD_SHARE]);
+
+                let share_keypair = signer.share_keypair();
+                let partial_signature = executor
+                    .spawn_blocking_handle(
+                        move || share_keypair.sk.sign(signing_root),
+                        "threshold_share_signer",
+                    )
+                    .ok_or(Error::ShuttingDown)?
+                    .await
+                    .map_err(|e| Error::TokioJoin(e.to_string()))?;
+
+                signer
+                    .combine_with_peers(signing_root, partial_signature)
+                    .await
+            }
+            SigningMethod::Pkcs11 { signer, .. } => {
+                let _timer = metrics::start_timer_vec(&metrics::SIGNING_TIMES, &[metrics::PKCS11]);
+
+                let signer = signer.clone();
+                executor
+                    .spawn_blocking_handle(move || signer.sign(signing_root), "pkcs11_signer")
+                    .ok_or(Error::ShuttingDown)?
+                    .await
+                    .map_err(|e| Error::TokioJoin(e.to_string()))?
+                    .map_err(Error::Pkcs11SigningFailed)
+            }
         }
     }
 }
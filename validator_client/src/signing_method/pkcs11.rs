@@ -0,0 +1,350 @@
+//! Signing with validator keys held in a PKCS#11 token, such as a hardware security module.
+//!
+//! BLS12-381 signing is not a standard PKCS#11 mechanism, so the token must provide it as a
+//! vendor-defined mechanism which signs a signing root with the Ethereum consensus ciphersuite and
+//! returns a compressed signature. Signing happens inside the token via `C_Sign`: the value of the
+//! key is never read, so the key object may (and should) be sensitive and non-extractable.
+//!
+//! Each signature returned by the token is verified against the validator's public key before it
+//! is used, so a token or mechanism which produces invalid signatures is detected rather than
+//! causing missed duties silently.
+
+use account_utils::ZeroizeString;
+use bls::{PublicKey, Signature};
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::{Error as CryptokiError, RvError};
+use cryptoki::mechanism::{Mechanism, MechanismType, VendorDefinedMechanism};
+use cryptoki::object::{Attribute, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::types::AuthPin;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use types::Hash256;
+
+/// The first vendor-defined PKCS#11 mechanism type (`CKM_VENDOR_DEFINED`).
+pub const VENDOR_DEFINED_MECHANISM: u64 = 0x8000_0000;
+
+/// The initialized PKCS#11 modules, keyed by path.
+///
+/// A module may only be initialized once per process, so its context is shared by the sessions of
+/// all validators using it. Modules are never finalized.
+static MODULES: LazyLock<Mutex<HashMap<PathBuf, Pkcs11>>> = LazyLock::new(Default::default);
+
+#[derive(Debug, PartialEq)]
+pub enum Pkcs11Error {
+    /// The PKCS#11 module could not be loaded or initialized.
+    UnableToLoadModule(String),
+    /// No token with the given label is present.
+    TokenNotFound(String),
+    UnableToOpenSession(String),
+    /// The user PIN was rejected by the token.
+    UnableToLogin(String),
+    UnableToFindKey(String),
+    /// No private key object with the given label exists in the token.
+    KeyNotFound(String),
+    /// Several private key objects in the token have the given label.
+    DuplicateKeyLabel(String),
+    /// The mechanism is not a vendor-defined mechanism.
+    InvalidMechanism(u64),
+    /// The token does not support the mechanism.
+    MechanismNotSupported(u64),
+    /// The token failed to produce a signature.
+    SigningFailed(String),
+    /// The token returned bytes which are not a BLS signature.
+    InvalidSignature(bls::Error),
+    /// The token returned a signature which does not verify against the validator's public key.
+    SignatureMismatch,
+}
+
+/// A session with a PKCS#11 token, logged in as the user, which signs with a single key.
+pub struct Pkcs11Signer {
+    /// Sessions must not be used by several threads at once.
+    session: Mutex<Session>,
+    key: ObjectHandle,
+    mechanism: MechanismType,
+    voting_public_key: PublicKey,
+}
+
+impl Pkcs11Signer {
+    /// Open a session with the token labelled `token_label`, via the PKCS#11 module at
+    /// `module_path`, which signs with the private key labelled `key_label` using the
+    /// vendor-defined `mechanism`.
+    ///
+    /// All calls to the module are blocking, so this function should not be called from the core
+    /// executor.
+    pub fn open(
+        module_path: &Path,
+        token_label: &str,
+        key_label: &str,
+        user_pin: &ZeroizeString,
+        mechanism_value: u64,
+        voting_public_key: PublicKey,
+    ) -> Result<Self, Pkcs11Error> {
+        let mechanism = mechanism_value
+            .checked_sub(VENDOR_DEFINED_MECHANISM)
+            .and_then(|offset| offset.try_into().ok())
+            .and_then(|offset| MechanismType::new_vendor_defined(offset).ok())
+            .ok_or(Pkcs11Error::InvalidMechanism(mechanism_value))?;
+
+        let pkcs11 = load_module(module_path)?;
+        let slot = pkcs11
+            .get_slots_with_token()
+            .map_err(|e| Pkcs11Error::UnableToLoadModule(e.to_string()))?
+            .into_iter()
+            .find(|slot| {
+                pkcs11
+                    .get_token_info(*slot)
+                    .is_ok_and(|info| info.label() == token_label)
+            })
+            .ok_or_else(|| Pkcs11Error::TokenNotFound(token_label.to_string()))?;
+
+        let session = pkcs11
+            .open_ro_session(slot)
+            .map_err(|e| Pkcs11Error::UnableToOpenSession(e.to_string()))?;
+        // Logging in applies to all sessions with the token, so it has already been done if the
+        // token holds the key of another validator.
+        match session.login(
+            UserType::User,
+            Some(&AuthPin::new(user_pin.as_str().to_string())),
+        ) {
+            Ok(()) | Err(CryptokiError::Pkcs11(RvError::UserAlreadyLoggedIn, ..)) => {}
+            Err(e) => return Err(Pkcs11Error::UnableToLogin(e.to_string())),
+        }
+
+        let objects = session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::PRIVATE_KEY),
+                Attribute::Label(key_label.as_bytes().to_vec()),
+            ])
+            .map_err(|e| Pkcs11Error::UnableToFindKey(e.to_string()))?;
+        let key = match objects.as_slice() {
+            [object] => *object,
+            [] => return Err(Pkcs11Error::KeyNotFound(key_label.to_string())),
+            _ => return Err(Pkcs11Error::DuplicateKeyLabel(key_label.to_string())),
+        };
+
+        let is_supported = pkcs11
+            .get_mechanism_list(slot)
+            .map_err(|e| Pkcs11Error::UnableToLoadModule(e.to_string()))?
+            .contains(&mechanism);
+        if !is_supported {
+            return Err(Pkcs11Error::MechanismNotSupported(mechanism_value));
+        }
+
+        Ok(Self {
+            session: Mutex::new(session),
+            key,
+            mechanism,
+            voting_public_key,
+        })
+    }
+
+    /// Sign `signing_root` inside the token.
+    ///
+    /// This function is blocking, so it should not be called from the core executor.
+    pub fn sign(&self, signing_root: Hash256) -> Result<Signature, Pkcs11Error> {
+        let mechanism =
+            Mechanism::VendorDefined(VendorDefinedMechanism::new::<()>(self.mechanism, None));
+        let bytes = self
+            .session
+            .lock()
+            .sign(&mechanism, self.key, signing_root.as_slice())
+            .map_err(|e| Pkcs11Error::SigningFailed(e.to_string()))?;
+
+        let signature = Signature::deserialize(&bytes).map_err(Pkcs11Error::InvalidSignature)?;
+        if !signature.verify(&self.voting_public_key, signing_root) {
+            return Err(Pkcs11Error::SignatureMismatch);
+        }
+        Ok(signature)
+    }
+}
+
+/// Load and initialize the PKCS#11 module at `module_path`, or return its context if it has already
+/// been initialized.
+fn load_module(module_path: &Path) -> Result<Pkcs11, Pkcs11Error> {
+    let mut modules = MODULES.lock();
+    if let Some(pkcs11) = modules.get(module_path) {
+        return Ok(pkcs11.clone());
+    }
+
+    let pkcs11 =
+        Pkcs11::new(module_path).map_err(|e| Pkcs11Error::UnableToLoadModule(e.to_string()))?;
+    pkcs11
+        .initialize(CInitializeArgs::OsThreads)
+        .map_err(|e| Pkcs11Error::UnableToLoadModule(e.to_string()))?;
+    modules.insert(module_path.to_path_buf(), pkcs11.clone());
+    Ok(pkcs11)
+}
+
+/// Tests against SoftHSM and against the mock module in `testing/pkcs11_mock`. SoftHSM does not
+/// provide a BLS mechanism, so it only covers the lookup of the token and key, whereas the mock
+/// module provides one and so covers signing.
+///
+/// The tests are ignored by default, and run with `make test-pkcs11`. They require the path to the
+/// SoftHSM module in the `SOFTHSM2_MODULE` environment variable, a SoftHSM configuration in
+/// `SOFTHSM2_CONF` whose token directory is used only for testing, and the path to the mock module
+/// in `PKCS11_MOCK_MODULE`, e.g.
+///
+/// ```bash
+/// mkdir /tmp/softhsm-tokens
+/// echo "directories.tokendir = /tmp/softhsm-tokens" > /tmp/softhsm2.conf
+/// cargo build -p pkcs11_mock
+/// SOFTHSM2_MODULE=/usr/lib/softhsm/libsofthsm2.so SOFTHSM2_CONF=/tmp/softhsm2.conf \
+///     PKCS11_MOCK_MODULE=target/debug/libpkcs11_mock.so \
+///     cargo test -p validator_client pkcs11 -- --ignored
+/// ```
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bls::Keypair;
+    use types::test_utils::generate_deterministic_keypair;
+
+    const SOFTHSM_ENV_VAR: &str = "SOFTHSM2_MODULE";
+    const MOCK_ENV_VAR: &str = "PKCS11_MOCK_MODULE";
+    const SO_PIN: &str = "5678";
+    const USER_PIN: &str = "1234";
+    /// A mechanism which SoftHSM does not support.
+    const MECHANISM: u64 = VENDOR_DEFINED_MECHANISM + 0x4c48;
+    /// The DER encoding of the OID of the P-256 curve.
+    const P256_PARAMS: [u8; 10] = [0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07];
+    /// The token and BLS mechanism of the mock module, whose keys `validator` and `other` are the
+    /// interop keypairs with indices 0 and 1.
+    const MOCK_TOKEN_LABEL: &str = "lighthouse-mock";
+    const MOCK_MECHANISM: u64 = VENDOR_DEFINED_MECHANISM + 0x424c53;
+
+    fn module_path(env_var: &str) -> PathBuf {
+        std::env::var(env_var)
+            .unwrap_or_else(|_| panic!("{env_var} must be set to run the PKCS#11 tests"))
+            .into()
+    }
+
+    /// Initialize a SoftHSM token labelled `token_label` in the free slot, containing a private key
+    /// for each of `key_labels`.
+    fn softhsm_token(module_path: &Path, token_label: &str, key_labels: &[&str]) {
+        let pkcs11 = load_module(module_path).unwrap();
+        // SoftHSM always provides a single slot without a token, after those with tokens.
+        let slot = *pkcs11.get_all_slots().unwrap().last().unwrap();
+        pkcs11
+            .init_token(slot, &AuthPin::new(SO_PIN.into()), token_label)
+            .unwrap();
+
+        let session = pkcs11.open_rw_session(slot).unwrap();
+        session
+            .login(UserType::So, Some(&AuthPin::new(SO_PIN.into())))
+            .unwrap();
+        session.init_pin(&AuthPin::new(USER_PIN.into())).unwrap();
+        session.logout().unwrap();
+        session
+            .login(UserType::User, Some(&AuthPin::new(USER_PIN.into())))
+            .unwrap();
+
+        for label in key_labels {
+            session
+                .generate_key_pair(
+                    &Mechanism::EccKeyPairGen,
+                    &[
+                        Attribute::Token(true),
+                        Attribute::EcParams(P256_PARAMS.to_vec()),
+                    ],
+                    &[
+                        Attribute::Token(true),
+                        Attribute::Private(true),
+                        Attribute::Sensitive(true),
+                        Attribute::Extractable(false),
+                        Attribute::Sign(true),
+                        Attribute::Label(label.as_bytes().to_vec()),
+                    ],
+                )
+                .unwrap();
+        }
+    }
+
+    fn open(
+        module_path: &Path,
+        token_label: &str,
+        key_label: &str,
+        user_pin: &str,
+        mechanism: u64,
+    ) -> Result<Pkcs11Signer, Pkcs11Error> {
+        Pkcs11Signer::open(
+            module_path,
+            token_label,
+            key_label,
+            &ZeroizeString::from(user_pin.to_string()),
+            mechanism,
+            Keypair::random().pk,
+        )
+    }
+
+    #[test]
+    #[ignore = "requires SoftHSM"]
+    fn open_softhsm_token() {
+        let module_path = module_path(SOFTHSM_ENV_VAR);
+        let module_path = module_path.as_path();
+        let token_label = format!("lighthouse-{}", rand::random::<u32>());
+        softhsm_token(
+            module_path,
+            &token_label,
+            &["validator", "duplicate", "duplicate"],
+        );
+
+        assert_eq!(
+            open(module_path, &token_label, "validator", USER_PIN, 0x1040).err(),
+            Some(Pkcs11Error::InvalidMechanism(0x1040))
+        );
+        assert_eq!(
+            open(module_path, "other-token", "validator", USER_PIN, MECHANISM).err(),
+            Some(Pkcs11Error::TokenNotFound("other-token".to_string()))
+        );
+        assert!(matches!(
+            open(module_path, &token_label, "validator", "0000", MECHANISM),
+            Err(Pkcs11Error::UnableToLogin(_))
+        ));
+        assert_eq!(
+            open(module_path, &token_label, "missing", USER_PIN, MECHANISM).err(),
+            Some(Pkcs11Error::KeyNotFound("missing".to_string()))
+        );
+        assert_eq!(
+            open(module_path, &token_label, "duplicate", USER_PIN, MECHANISM).err(),
+            Some(Pkcs11Error::DuplicateKeyLabel("duplicate".to_string()))
+        );
+        assert_eq!(
+            open(module_path, &token_label, "validator", USER_PIN, MECHANISM).err(),
+            Some(Pkcs11Error::MechanismNotSupported(MECHANISM))
+        );
+    }
+
+    #[test]
+    #[ignore = "requires the mock PKCS#11 module"]
+    fn sign_with_mock_token() {
+        let module_path = module_path(MOCK_ENV_VAR);
+        let keypair = generate_deterministic_keypair(0);
+        let signing_root = Hash256::repeat_byte(42);
+        let open = |key_label: &str| {
+            Pkcs11Signer::open(
+                &module_path,
+                MOCK_TOKEN_LABEL,
+                key_label,
+                &ZeroizeString::from(USER_PIN.to_string()),
+                MOCK_MECHANISM,
+                keypair.pk.clone(),
+            )
+        };
+
+        let signer = open("validator").unwrap();
+        let signature = signer.sign(signing_root).unwrap();
+        assert_eq!(signature, keypair.sk.sign(signing_root));
+        // Signing again uses a new operation in the same session.
+        assert_eq!(signer.sign(signing_root).unwrap(), signature);
+
+        // The token is already logged in by the session of the first signer.
+        let other = open("other").unwrap();
+        assert_eq!(
+            other.sign(signing_root),
+            Err(Pkcs11Error::SignatureMismatch)
+        );
+        assert_eq!(signer.sign(signing_root).unwrap(), signature);
+    }
+}
@@ -0,0 +1,187 @@
+//! Signing with a share of a validator key which is split between several validator clients.
+//!
+//! Each validator client signs with its own share after applying its own slashing protection, and
+//! makes the resulting partial signature available to its peers via the authenticated validator
+//! client HTTP API. A signature is only produced once partial signatures from `threshold` shares
+//! have been collected, combined and verified against the validator's public key.
+//!
+//! Peers never sign on request: a partial signature is only served once the peer has signed the
+//! same message itself, so every share is covered by the slashing protection of its own client.
+
+use super::Error;
+use bls::threshold::combine_signatures;
+use eth2::lighthouse_vc::http_client::ValidatorClientHttpClient;
+use futures::stream::{FuturesUnordered, StreamExt};
+use parking_lot::Mutex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::{sleep, timeout_at, Instant};
+use types::{Hash256, Keypair, PublicKey, PublicKeyBytes, Signature};
+
+/// Number of partial signatures retained for peers to fetch.
+const PARTIAL_SIGNATURE_CACHE_SIZE: usize = 1_024;
+/// Interval between requests to a peer which has not yet signed a message.
+const PEER_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// A validator client holding another share of the key.
+pub struct ThresholdPeerClient {
+    pub share_index: u64,
+    pub share_public_key: PublicKey,
+    pub client: ValidatorClientHttpClient,
+}
+
+/// Partial signatures produced by this validator client, by signing root.
+#[derive(Default)]
+struct PartialSignatureCache {
+    signatures: HashMap<Hash256, Signature>,
+    order: VecDeque<Hash256>,
+}
+
+impl PartialSignatureCache {
+    fn insert(&mut self, signing_root: Hash256, signature: Signature) {
+        if self.signatures.insert(signing_root, signature).is_none() {
+            self.order.push_back(signing_root);
+        }
+        while self.order.len() > PARTIAL_SIGNATURE_CACHE_SIZE {
+            if let Some(oldest) = self.order.pop_front() {
+                self.signatures.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Produces signatures for a validator by combining partial signatures from its key shares.
+pub struct ThresholdSigner {
+    share_index: u64,
+    threshold: usize,
+    share_keypair: Arc<Keypair>,
+    voting_public_key: PublicKey,
+    peers: Vec<ThresholdPeerClient>,
+    request_timeout: Duration,
+    partial_signatures: Mutex<PartialSignatureCache>,
+}
+
+impl ThresholdSigner {
+    pub fn new(
+        share_index: u64,
+        threshold: usize,
+        share_keypair: Keypair,
+        voting_public_key: PublicKey,
+        peers: Vec<ThresholdPeerClient>,
+        request_timeout: Duration,
+    ) -> Self {
+        Self {
+            share_index,
+            threshold,
+            share_keypair: Arc::new(share_keypair),
+            voting_public_key,
+            peers,
+            request_timeout,
+            partial_signatures: <_>::default(),
+        }
+    }
+
+    pub fn share_index(&self) -> u64 {
+        self.share_index
+    }
+
+    pub fn share_keypair(&self) -> Arc<Keypair> {
+        self.share_keypair.clone()
+    }
+
+    pub fn voting_public_key(&self) -> &PublicKey {
+        &self.voting_public_key
+    }
+
+    /// Returns the partial signature of `signing_root` produced by this validator client, if any.
+    pub fn partial_signature(&self, signing_root: Hash256) -> Option<Signature> {
+        self.partial_signatures
+            .lock()
+            .signatures
+            .get(&signing_root)
+            .cloned()
+    }
+
+    /// Make this validator client's `partial_signature` of `signing_root` available to peers, and
+    /// wait for enough partial signatures from peers to produce a signature.
+    pub async fn combine_with_peers(
+        &self,
+        signing_root: Hash256,
+        partial_signature: Signature,
+    ) -> Result<Signature, Error> {
+        self.partial_signatures
+            .lock()
+            .insert(signing_root, partial_signature.clone());
+
+        let deadline = Instant::now() + self.request_timeout;
+        let validator_pubkey = self.voting_public_key.compress();
+
+        let mut partial_signatures = vec![(self.share_index, partial_signature)];
+        let mut requests = self
+            .peers
+            .iter()
+            .map(|peer| fetch_partial_signature(peer, &validator_pubkey, signing_root, deadline))
+            .collect::<FuturesUnordered<_>>();
+
+        // Remaining requests are cancelled once enough partial signatures have been received.
+        while partial_signatures.len() < self.threshold {
+            match requests.next().await {
+                Some(Some(partial_signature)) => partial_signatures.push(partial_signature),
+                Some(None) => (),
+                None => break,
+            }
+        }
+        drop(requests);
+
+        if partial_signatures.len() < self.threshold {
+            return Err(Error::InsufficientPartialSignatures {
+                received: partial_signatures.len(),
+                threshold: self.threshold,
+            });
+        }
+
+        let signature =
+            combine_signatures(&partial_signatures).map_err(Error::ThresholdCombination)?;
+        if !signature.verify(&self.voting_public_key, signing_root) {
+            return Err(Error::InvalidThresholdSignature);
+        }
+        Ok(signature)
+    }
+}
+
+/// Poll `peer` for its partial signature of `signing_root` until it is available or `deadline` is
+/// reached.
+///
+/// Returns `None` if the peer did not sign in time, or returned an invalid partial signature.
+async fn fetch_partial_signature(
+    peer: &ThresholdPeerClient,
+    validator_pubkey: &PublicKeyBytes,
+    signing_root: Hash256,
+    deadline: Instant,
+) -> Option<(u64, Signature)> {
+    loop {
+        let response = timeout_at(
+            deadline,
+            peer.client
+                .get_lighthouse_threshold_partial_signature(validator_pubkey, signing_root),
+        )
+        .await
+        .ok()?;
+
+        if let Ok(Some(response)) = response {
+            let partial_signature = response.data;
+            let is_valid = partial_signature.share_index == peer.share_index
+                && partial_signature
+                    .signature
+                    .verify(&peer.share_public_key, signing_root);
+            return is_valid.then_some((peer.share_index, partial_signature.signature));
+        }
+
+        // The peer has not signed yet, or is temporarily unavailable.
+        if Instant::now() + PEER_POLL_INTERVAL >= deadline {
+            return None;
+        }
+        sleep(PEER_POLL_INTERVAL).await;
+    }
+}
@@ -0,0 +1,280 @@
+//! Evaluation of the per-validator signing policies defined in `validator_definitions.yml`.
+//!
+//! Policies are evaluated by the `ValidatorStore` before the slashing protection database is
+//! consulted, so a message refused by a policy is never recorded as signed.
+
+use account_utils::validator_definitions::SigningPolicy;
+use std::path::PathBuf;
+use types::{
+    AbstractExecPayload, Address, BeaconBlock, BlockType, EthSpec, ExecPayload, ExecutionBlockHash,
+    ValidatorRegistrationData,
+};
+
+pub const REQUIRE_FEE_RECIPIENT: &str = "require_fee_recipient";
+pub const BUILDER_FEE_RECIPIENTS: &str = "builder_fee_recipients";
+pub const MAX_GAS_LIMIT: &str = "max_gas_limit";
+pub const EXIT_CONFIRMATION_PATH: &str = "exit_confirmation_path";
+
+/// The reason a signing policy refused to sign a message.
+#[derive(Debug, PartialEq)]
+pub enum PolicyViolation {
+    /// A full execution payload does not pay the validator's fee recipient.
+    FeeRecipientMismatch {
+        expected: Option<Address>,
+        found: Address,
+    },
+    /// A blinded execution payload pays neither the validator's fee recipient nor a trusted
+    /// builder.
+    UntrustedBuilder {
+        fee_recipient: Address,
+    },
+    GasLimitTooHigh {
+        gas_limit: u64,
+        max_gas_limit: u64,
+    },
+    /// A voluntary exit was requested without creating the confirmation file.
+    ExitNotConfirmed {
+        confirmation_path: PathBuf,
+    },
+}
+
+impl PolicyViolation {
+    /// Returns the name of the policy which was violated, as used in `validator_definitions.yml`.
+    pub fn policy(&self) -> &'static str {
+        match self {
+            PolicyViolation::FeeRecipientMismatch { .. } => REQUIRE_FEE_RECIPIENT,
+            PolicyViolation::UntrustedBuilder { .. } => BUILDER_FEE_RECIPIENTS,
+            PolicyViolation::GasLimitTooHigh { .. } => MAX_GAS_LIMIT,
+            PolicyViolation::ExitNotConfirmed { .. } => EXIT_CONFIRMATION_PATH,
+        }
+    }
+}
+
+/// Check `block` against `policy`, where `fee_recipient` is the fee recipient configured for the
+/// validator.
+pub fn check_block<E: EthSpec, Payload: AbstractExecPayload<E>>(
+    policy: &SigningPolicy,
+    block: &BeaconBlock<E, Payload>,
+    fee_recipient: Option<Address>,
+) -> Result<(), PolicyViolation> {
+    // Blocks prior to the merge have no execution payload, or an empty one.
+    let Ok(payload) = block.body().execution_payload() else {
+        return Ok(());
+    };
+    if payload.block_hash() == ExecutionBlockHash::zero() {
+        return Ok(());
+    }
+
+    let found = payload.fee_recipient();
+    match Payload::block_type() {
+        BlockType::Full => {
+            if policy.require_fee_recipient && Some(found) != fee_recipient {
+                return Err(PolicyViolation::FeeRecipientMismatch {
+                    expected: fee_recipient,
+                    found,
+                });
+            }
+        }
+        BlockType::Blinded => {
+            if let Some(builder_fee_recipients) = &policy.builder_fee_recipients {
+                if Some(found) != fee_recipient && !builder_fee_recipients.contains(&found) {
+                    return Err(PolicyViolation::UntrustedBuilder {
+                        fee_recipient: found,
+                    });
+                }
+            }
+        }
+    }
+
+    check_gas_limit(policy, payload.gas_limit())
+}
+
+/// Check a validator registration against `policy`.
+pub fn check_validator_registration(
+    policy: &SigningPolicy,
+    registration: &ValidatorRegistrationData,
+) -> Result<(), PolicyViolation> {
+    check_gas_limit(policy, registration.gas_limit)
+}
+
+/// Check a voluntary exit against `policy`.
+pub fn check_voluntary_exit(policy: &SigningPolicy) -> Result<(), PolicyViolation> {
+    match &policy.exit_confirmation_path {
+        Some(confirmation_path) if !confirmation_path.exists() => {
+            Err(PolicyViolation::ExitNotConfirmed {
+                confirmation_path: confirmation_path.clone(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+fn check_gas_limit(policy: &SigningPolicy, gas_limit: u64) -> Result<(), PolicyViolation> {
+    match policy.max_gas_limit {
+        Some(max_gas_limit) if gas_limit > max_gas_limit => Err(PolicyViolation::GasLimitTooHigh {
+            gas_limit,
+            max_gas_limit,
+        }),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+    use types::{
+        BlindedPayload, ChainSpec, ForkName, FullPayload, Hash256, MainnetEthSpec, PublicKeyBytes,
+    };
+
+    type E = MainnetEthSpec;
+
+    fn spec() -> ChainSpec {
+        ForkName::Capella.make_genesis_spec(E::default_spec())
+    }
+
+    fn full_block(fee_recipient: Address, gas_limit: u64) -> BeaconBlock<E, FullPayload<E>> {
+        let mut block = BeaconBlock::empty(&spec());
+        let BeaconBlock::Capella(ref mut inner) = block else {
+            panic!("expected a Capella block");
+        };
+        let payload = &mut inner.body.execution_payload.execution_payload;
+        payload.block_hash = ExecutionBlockHash::from_root(Hash256::repeat_byte(1));
+        payload.fee_recipient = fee_recipient;
+        payload.gas_limit = gas_limit;
+        block
+    }
+
+    fn blinded_block(fee_recipient: Address, gas_limit: u64) -> BeaconBlock<E, BlindedPayload<E>> {
+        let mut block = BeaconBlock::empty(&spec());
+        let BeaconBlock::Capella(ref mut inner) = block else {
+            panic!("expected a Capella block");
+        };
+        let header = &mut inner.body.execution_payload.execution_payload_header;
+        header.block_hash = ExecutionBlockHash::from_root(Hash256::repeat_byte(1));
+        header.fee_recipient = fee_recipient;
+        header.gas_limit = gas_limit;
+        block
+    }
+
+    #[test]
+    fn fee_recipient() {
+        let policy = SigningPolicy {
+            require_fee_recipient: true,
+            ..SigningPolicy::default()
+        };
+        let ours = Address::repeat_byte(1);
+        let other = Address::repeat_byte(2);
+
+        assert_eq!(
+            check_block(&policy, &full_block(ours, 0), Some(ours)),
+            Ok(())
+        );
+        assert_eq!(
+            check_block(&policy, &full_block(other, 0), Some(ours)),
+            Err(PolicyViolation::FeeRecipientMismatch {
+                expected: Some(ours),
+                found: other
+            })
+        );
+        assert!(check_block(&policy, &full_block(other, 0), None).is_err());
+        // Blinded payloads pay the builder, so they are not subject to this policy.
+        assert_eq!(
+            check_block(&policy, &blinded_block(other, 0), Some(ours)),
+            Ok(())
+        );
+        // Blocks without an execution payload are always allowed.
+        let empty = BeaconBlock::<E, FullPayload<E>>::empty(&spec());
+        assert_eq!(check_block(&policy, &empty, Some(ours)), Ok(()));
+    }
+
+    #[test]
+    fn builder_fee_recipients() {
+        let ours = Address::repeat_byte(1);
+        let trusted = Address::repeat_byte(2);
+        let untrusted = Address::repeat_byte(3);
+        let policy = SigningPolicy {
+            builder_fee_recipients: Some(vec![trusted]),
+            ..SigningPolicy::default()
+        };
+
+        assert_eq!(
+            check_block(&policy, &blinded_block(trusted, 0), Some(ours)),
+            Ok(())
+        );
+        assert_eq!(
+            check_block(&policy, &blinded_block(ours, 0), Some(ours)),
+            Ok(())
+        );
+        assert_eq!(
+            check_block(&policy, &blinded_block(untrusted, 0), Some(ours)),
+            Err(PolicyViolation::UntrustedBuilder {
+                fee_recipient: untrusted
+            })
+        );
+        assert_eq!(
+            check_block(&policy, &full_block(untrusted, 0), Some(ours)),
+            Ok(())
+        );
+        assert_eq!(
+            check_block(
+                &SigningPolicy::default(),
+                &blinded_block(untrusted, 0),
+                Some(ours)
+            ),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn max_gas_limit() {
+        let policy = SigningPolicy {
+            max_gas_limit: Some(30_000_000),
+            ..SigningPolicy::default()
+        };
+        let fee_recipient = Address::repeat_byte(1);
+
+        assert_eq!(
+            check_block(&policy, &full_block(fee_recipient, 30_000_000), None),
+            Ok(())
+        );
+        assert_eq!(
+            check_block(&policy, &blinded_block(fee_recipient, 36_000_000), None),
+            Err(PolicyViolation::GasLimitTooHigh {
+                gas_limit: 36_000_000,
+                max_gas_limit: 30_000_000
+            })
+        );
+
+        let mut registration = ValidatorRegistrationData {
+            fee_recipient,
+            gas_limit: 30_000_000,
+            timestamp: 0,
+            pubkey: PublicKeyBytes::empty(),
+        };
+        assert_eq!(check_validator_registration(&policy, &registration), Ok(()));
+        registration.gas_limit = 30_000_001;
+        assert!(check_validator_registration(&policy, &registration).is_err());
+    }
+
+    #[test]
+    fn exit_confirmation_path() {
+        let dir = tempdir().unwrap();
+        let confirmation_path = dir.path().join("confirm-exit");
+        let policy = SigningPolicy {
+            exit_confirmation_path: Some(confirmation_path.clone()),
+            ..SigningPolicy::default()
+        };
+
+        assert_eq!(check_voluntary_exit(&SigningPolicy::default()), Ok(()));
+        assert_eq!(
+            check_voluntary_exit(&policy),
+            Err(PolicyViolation::ExitNotConfirmed {
+                confirmation_path: confirmation_path.clone()
+            })
+        );
+        fs::write(&confirmation_path, "").unwrap();
+        assert_eq!(check_voluntary_exit(&policy), Ok(()));
+    }
+}
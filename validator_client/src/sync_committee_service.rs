@@ -1,6 +1,7 @@
 use crate::beacon_node_fallback::{ApiTopic, BeaconNodeFallback};
 use crate::{
     duties_service::DutiesService,
+    http_metrics::metrics,
     validator_store::{Error as ValidatorStoreError, ValidatorStore},
 };
 use environment::RuntimeContext;
@@ -250,6 +251,27 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
     ) -> Result<(), ()> {
         let log = self.context.log();
 
+        if self.validator_store.dry_run() {
+            let validator_indices: Vec<_> = validator_duties
+                .iter()
+                .map(|duty| duty.validator_index)
+                .collect();
+            info!(
+                log,
+                "Skipped publishing sync committee messages in dry run";
+                "count" => validator_indices.len(),
+                "validator_indices" => ?validator_indices,
+                "head_block" => ?beacon_block_root,
+                "slot" => slot,
+            );
+            metrics::inc_counter_vec_by(
+                &metrics::DRY_RUN_MESSAGES_TOTAL,
+                &[metrics::SYNC_COMMITTEE_MESSAGES],
+                validator_indices.len() as u64,
+            );
+            return Ok(());
+        }
+
         // Create futures to produce sync committee signatures.
         let signature_futures = validator_duties.iter().map(|duty| async move {
             match self
@@ -394,6 +416,28 @@ impl<T: SlotClock + 'static, E: EthSpec> SyncCommitteeService<T, E> {
             })?
             .data;
 
+        if self.validator_store.dry_run() {
+            let aggregators: Vec<_> = subnet_aggregators
+                .iter()
+                .map(|(aggregator_index, _, _)| *aggregator_index)
+                .collect();
+            info!(
+                log,
+                "Skipped publishing sync contributions in dry run";
+                "aggregators" => ?aggregators,
+                "subnet" => %subnet_id,
+                "beacon_block_root" => %beacon_block_root,
+                "num_signers" => contribution.aggregation_bits.num_set_bits(),
+                "slot" => slot,
+            );
+            metrics::inc_counter_vec_by(
+                &metrics::DRY_RUN_MESSAGES_TOTAL,
+                &[metrics::SYNC_CONTRIBUTIONS],
+                aggregators.len() as u64,
+            );
+            return Ok(());
+        }
+
         // Create futures to produce signed contributions.
         let signature_futures = subnet_aggregators.into_iter().map(
             |(aggregator_index, aggregator_pk, selection_proof)| async move {
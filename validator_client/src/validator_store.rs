@@ -3,24 +3,29 @@ use crate::{
     http_metrics::metrics,
     initialized_validators::InitializedValidators,
     signing_method::{Error as SigningError, SignableMessage, SigningContext, SigningMethod},
+    signing_policy::{self, PolicyViolation},
     Config,
 };
-use account_utils::validator_definitions::{PasswordStorage, ValidatorDefinition};
+use account_utils::validator_definitions::{PasswordStorage, SigningPolicy, ValidatorDefinition};
+use eth2::lighthouse_vc::types::ValidatorDefinitionsReload;
 use parking_lot::{Mutex, RwLock};
 use slashing_protection::{
-    interchange::Interchange, InterchangeError, NotSafe, Safe, SlashingDatabase,
+    interchange::Interchange, AuditQuery, InterchangeError, NotSafe, RefusedSigningAttempt, Safe,
+    SigningHistory, SlashingProtectionBackend,
 };
-use slog::{crit, error, info, warn, Logger};
+use slog::{crit, debug, error, info, warn, Logger};
 use slot_clock::SlotClock;
+use std::collections::HashMap;
 use std::marker::PhantomData;
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
 use task_executor::TaskExecutor;
 use types::{
     attestation::Error as AttestationError, graffiti::GraffitiString, AbstractExecPayload, Address,
     AggregateAndProof, Attestation, BeaconBlock, BlindedPayload, ChainSpec, ContributionAndProof,
-    Domain, Epoch, EthSpec, Fork, Graffiti, Hash256, PublicKeyBytes, SelectionProof, Signature,
-    SignedAggregateAndProof, SignedBeaconBlock, SignedContributionAndProof, SignedRoot,
+    Domain, Epoch, EthSpec, Fork, Graffiti, Hash256, PublicKeyBytes, SecretKey, SelectionProof,
+    Signature, SignedAggregateAndProof, SignedBeaconBlock, SignedContributionAndProof, SignedRoot,
     SignedValidatorRegistrationData, SignedVoluntaryExit, Slot, SyncAggregatorSelectionData,
     SyncCommitteeContribution, SyncCommitteeMessage, SyncSelectionProof, SyncSubnetId,
     ValidatorRegistrationData, VoluntaryExit,
@@ -40,6 +45,8 @@ pub enum Error {
     GreaterThanCurrentEpoch { epoch: Epoch, current_epoch: Epoch },
     UnableToSignAttestation(AttestationError),
     UnableToSign(SigningError),
+    PolicyViolation(PolicyViolation),
+    DryRun,
 }
 
 impl From<SigningError> for Error {
@@ -60,8 +67,10 @@ pub const DEFAULT_GAS_LIMIT: u64 = 30_000_000;
 
 pub struct ValidatorStore<T, E: EthSpec> {
     validators: Arc<RwLock<InitializedValidators>>,
-    slashing_protection: SlashingDatabase,
+    slashing_protection: Arc<dyn SlashingProtectionBackend>,
     slashing_protection_last_prune: Arc<Mutex<Epoch>>,
+    /// The outcome of the most recent reload of the validator definitions file.
+    last_definitions_reload: Mutex<Option<ValidatorDefinitionsReload>>,
     genesis_validators_root: Hash256,
     spec: Arc<ChainSpec>,
     log: Logger,
@@ -73,6 +82,8 @@ pub struct ValidatorStore<T, E: EthSpec> {
     enable_web3signer_slashing_protection: bool,
     prefer_builder_proposals: bool,
     builder_boost_factor: Option<u64>,
+    dry_run: bool,
+    dry_run_keys: DryRunKeys,
     task_executor: TaskExecutor,
     _phantom: PhantomData<E>,
 }
@@ -83,7 +94,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
     #[allow(clippy::too_many_arguments)]
     pub fn new(
         validators: InitializedValidators,
-        slashing_protection: SlashingDatabase,
+        slashing_protection: Arc<dyn SlashingProtectionBackend>,
         genesis_validators_root: Hash256,
         spec: Arc<ChainSpec>,
         doppelganger_service: Option<Arc<DoppelgangerService>>,
@@ -96,6 +107,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             validators: Arc::new(RwLock::new(validators)),
             slashing_protection,
             slashing_protection_last_prune: Arc::new(Mutex::new(Epoch::new(0))),
+            last_definitions_reload: Mutex::new(None),
             genesis_validators_root,
             spec,
             log,
@@ -107,6 +119,8 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             enable_web3signer_slashing_protection: config.enable_web3signer_slashing_protection,
             prefer_builder_proposals: config.prefer_builder_proposals,
             builder_boost_factor: config.builder_boost_factor,
+            dry_run: config.dry_run,
+            dry_run_keys: DryRunKeys::default(),
             task_executor,
             _phantom: PhantomData,
         }
@@ -131,6 +145,12 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.doppelganger_service.is_some()
     }
 
+    /// Returns `true` if the validator client is running in dry-run mode, in which case services
+    /// should not sign or publish messages.
+    pub fn dry_run(&self) -> bool {
+        self.dry_run
+    }
+
     pub fn initialized_validators(&self) -> Arc<RwLock<InitializedValidators>> {
         self.validators.clone()
     }
@@ -208,6 +228,115 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         Ok(validator_def)
     }
 
+    /// Reload the validator definitions file from disk, starting and stopping validators so that
+    /// the running set matches the file.
+    ///
+    /// As in `Self::add_validator`, validators which become enabled are registered with slashing
+    /// protection and doppelganger protection before they start performing duties. If the reload
+    /// fails then the previous definitions remain in use.
+    ///
+    /// The outcome is logged, and is available from `Self::last_definitions_reload` afterwards.
+    // FIXME: ignore this clippy lint until the validator store is refactored to use async locks
+    #[allow(clippy::await_holding_lock)]
+    pub async fn reload_validator_definitions(&self) -> Result<ValidatorDefinitionsReload, String> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or(0);
+
+        let mut validators = self.validators.write();
+        let result = self
+            .apply_definitions_on_disk(&mut validators, timestamp)
+            .await;
+        drop(validators);
+
+        match &result {
+            Ok(reload) => {
+                let num_changes = reload.added.len()
+                    + reload.removed.len()
+                    + reload.enabled.len()
+                    + reload.disabled.len()
+                    + reload.updated.len();
+                if num_changes == 0 {
+                    debug!(self.log, "Validator definitions unchanged");
+                } else {
+                    info!(
+                        self.log,
+                        "Reloaded validator definitions";
+                        "added" => reload.added.len(),
+                        "removed" => reload.removed.len(),
+                        "enabled" => reload.enabled.len(),
+                        "disabled" => reload.disabled.len(),
+                        "updated" => reload.updated.len(),
+                    );
+                }
+            }
+            Err(e) => error!(
+                self.log,
+                "Failed to reload validator definitions";
+                "error" => e,
+                "info" => "the previous validator definitions remain in use",
+            ),
+        }
+
+        *self.last_definitions_reload.lock() = Some(match &result {
+            Ok(reload) => reload.clone(),
+            Err(e) => ValidatorDefinitionsReload {
+                timestamp,
+                error: Some(e.clone()),
+                ..<_>::default()
+            },
+        });
+
+        result
+    }
+
+    /// Apply the differences between the validator definitions file and `validators`.
+    async fn apply_definitions_on_disk(
+        &self,
+        validators: &mut InitializedValidators,
+        timestamp: u64,
+    ) -> Result<ValidatorDefinitionsReload, String> {
+        let diff = validators
+            .diff_definitions_on_disk()
+            .map_err(|e| format!("Unable to read validator definitions: {:?}", e))?;
+        let reload = ValidatorDefinitionsReload {
+            timestamp,
+            added: diff.added.clone(),
+            removed: diff.removed.clone(),
+            enabled: diff.enabled.clone(),
+            disabled: diff.disabled.clone(),
+            updated: diff.updated.clone(),
+            error: None,
+        };
+
+        if diff.is_empty() {
+            return Ok(reload);
+        }
+
+        for pubkey in &diff.newly_enabled {
+            self.slashing_protection
+                .register_validator(*pubkey)
+                .map_err(|e| format!("failed to register validator {:?}: {:?}", pubkey, e))?;
+
+            if let Some(doppelganger_service) = &self.doppelganger_service {
+                doppelganger_service.register_new_validator::<E, _>(*pubkey, &self.slot_clock)?;
+            }
+        }
+
+        validators
+            .apply_definitions_diff(diff)
+            .await
+            .map_err(|e| format!("Unable to apply validator definitions: {:?}", e))?;
+
+        Ok(reload)
+    }
+
+    /// The outcome of the most recent call to `Self::reload_validator_definitions`, if any.
+    pub fn last_definitions_reload(&self) -> Option<ValidatorDefinitionsReload> {
+        self.last_definitions_reload.lock().clone()
+    }
+
     /// Returns `ProposalData` for the provided `pubkey` if it exists in `InitializedValidators`.
     /// `ProposalData` fields include defaulting logic described in `get_fee_recipient_defaulting`,
     /// `get_gas_limit_defaulting`, and `get_builder_proposals_defaulting`.
@@ -315,6 +444,22 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         self.validators.read().num_enabled()
     }
 
+    /// Returns the partial signature of `signing_root` produced with this validator client's share
+    /// of the key of `validator_pubkey`, along with the index of the share.
+    ///
+    /// Returns `None` unless the validator's key is split between several validator clients and
+    /// this validator client has already signed `signing_root`.
+    pub fn threshold_partial_signature(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        signing_root: Hash256,
+    ) -> Option<(u64, Signature)> {
+        self.validators
+            .read()
+            .signing_method(validator_pubkey)?
+            .threshold_partial_signature(signing_root)
+    }
+
     fn fork(&self, epoch: Epoch) -> Fork {
         self.spec.fork_at_epoch(epoch)
     }
@@ -335,6 +480,47 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         }
     }
 
+    /// Refuse to sign messages which would be published if running in dry-run mode.
+    ///
+    /// Selection proofs are needed to determine aggregation duties, so they are signed with
+    /// throwaway keys instead (see `Self::sign_selection_proof`).
+    fn check_not_dry_run(&self) -> Result<(), Error> {
+        if self.dry_run {
+            Err(Error::DryRun)
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Sign a selection proof for `validator_pubkey` with `signing_method`, or with the validator's
+    /// throwaway key if running in dry-run mode.
+    ///
+    /// A proof signed with a throwaway key selects the validator as an aggregator about as often
+    /// as a real one, so that aggregation duties are still performed (without being published).
+    async fn sign_selection_proof(
+        &self,
+        validator_pubkey: PublicKeyBytes,
+        signing_method: &SigningMethod,
+        signable_message: SignableMessage<'_, E, BlindedPayload<E>>,
+        signing_context: SigningContext,
+    ) -> Result<Signature, Error> {
+        if self.dry_run {
+            let signing_root =
+                signable_message.signing_root(signing_context.domain_hash(&self.spec));
+            return Ok(self.dry_run_keys.sign(validator_pubkey, signing_root));
+        }
+
+        signing_method
+            .get_signature(
+                signable_message,
+                signing_context,
+                &self.spec,
+                &self.task_executor,
+            )
+            .await
+            .map_err(Error::UnableToSign)
+    }
+
     /// Returns a `SigningMethod` for `validator_pubkey` regardless of that validators doppelganger
     /// protection status.
     ///
@@ -351,6 +537,37 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             .ok_or(Error::UnknownPubkey(validator_pubkey))
     }
 
+    /// Check a message for `validator_pubkey` against the validator's signing policy, if it has
+    /// one. Violations are logged and counted in `signed_total` and the policy violation metrics.
+    fn check_signing_policy(
+        &self,
+        validator_pubkey: &PublicKeyBytes,
+        message_type: &str,
+        signed_total: &metrics::Result<metrics::IntCounterVec>,
+        check: impl FnOnce(&SigningPolicy) -> Result<(), PolicyViolation>,
+    ) -> Result<(), Error> {
+        let Some(policy) = self.validators.read().signing_policy(validator_pubkey) else {
+            return Ok(());
+        };
+
+        check(&policy).map_err(|violation| {
+            warn!(
+                self.log,
+                "Not signing message refused by signing policy";
+                "message_type" => message_type,
+                "policy" => violation.policy(),
+                "violation" => ?violation,
+                "validator" => ?validator_pubkey,
+            );
+            metrics::inc_counter_vec(signed_total, &[metrics::POLICY_VIOLATION]);
+            metrics::inc_counter_vec(
+                &metrics::SIGNING_POLICY_VIOLATIONS_TOTAL,
+                &[message_type, violation.policy()],
+            );
+            Error::PolicyViolation(violation)
+        })
+    }
+
     fn signing_context(&self, domain: Domain, signing_epoch: Epoch) -> SigningContext {
         if domain == Domain::VoluntaryExit {
             if self.spec.fork_name_at_epoch(signing_epoch).deneb_enabled() {
@@ -388,6 +605,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_pubkey: PublicKeyBytes,
         signing_epoch: Epoch,
     ) -> Result<Signature, Error> {
+        self.check_not_dry_run()?;
         let signing_method = self.doppelganger_checked_signing_method(validator_pubkey)?;
         let signing_context = self.signing_context(Domain::Randao, signing_epoch);
 
@@ -554,6 +772,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         block: BeaconBlock<E, Payload>,
         current_slot: Slot,
     ) -> Result<SignedBeaconBlock<E, Payload>, Error> {
+        self.check_not_dry_run()?;
         // Make sure the block slot is not higher than the current slot to avoid potential attacks.
         if block.slot() > current_slot {
             warn!(
@@ -568,6 +787,16 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             });
         }
 
+        // Check the signing policy before the slashing protection database, so that a refused
+        // block is not recorded as signed.
+        let fee_recipient = self.get_fee_recipient(&validator_pubkey);
+        self.check_signing_policy(
+            &validator_pubkey,
+            metrics::BEACON_BLOCK,
+            &metrics::SIGNED_BLOCKS_TOTAL,
+            |policy| signing_policy::check_block(policy, &block, fee_recipient),
+        )?;
+
         let signing_epoch = block.epoch();
         let signing_context = self.signing_context(Domain::BeaconProposer, signing_epoch);
         let domain_hash = signing_context.domain_hash(&self.spec);
@@ -639,6 +868,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         attestation: &mut Attestation<E>,
         current_epoch: Epoch,
     ) -> Result<(), Error> {
+        self.check_not_dry_run()?;
         // Make sure the target epoch is not higher than the current epoch to avoid potential attacks.
         if attestation.data().target.epoch > current_epoch {
             return Err(Error::GreaterThanCurrentEpoch {
@@ -730,10 +960,18 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_pubkey: PublicKeyBytes,
         voluntary_exit: VoluntaryExit,
     ) -> Result<SignedVoluntaryExit, Error> {
+        self.check_not_dry_run()?;
         let signing_epoch = voluntary_exit.epoch;
         let signing_context = self.signing_context(Domain::VoluntaryExit, signing_epoch);
         let signing_method = self.doppelganger_bypassed_signing_method(validator_pubkey)?;
 
+        self.check_signing_policy(
+            &validator_pubkey,
+            metrics::VOLUNTARY_EXIT,
+            &metrics::SIGNED_VOLUNTARY_EXITS_TOTAL,
+            signing_policy::check_voluntary_exit,
+        )?;
+
         let signature = signing_method
             .get_signature::<E, BlindedPayload<E>>(
                 SignableMessage::VoluntaryExit(&voluntary_exit),
@@ -755,11 +993,21 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         &self,
         validator_registration_data: ValidatorRegistrationData,
     ) -> Result<SignedValidatorRegistrationData, Error> {
+        self.check_not_dry_run()?;
         let domain_hash = self.spec.get_builder_domain();
         let signing_root = validator_registration_data.signing_root(domain_hash);
 
         let signing_method =
             self.doppelganger_bypassed_signing_method(validator_registration_data.pubkey)?;
+
+        self.check_signing_policy(
+            &validator_registration_data.pubkey,
+            metrics::VALIDATOR_REGISTRATION,
+            &metrics::SIGNED_VALIDATOR_REGISTRATIONS_TOTAL,
+            |policy| {
+                signing_policy::check_validator_registration(policy, &validator_registration_data)
+            },
+        )?;
         let signature = signing_method
             .get_signature_from_root::<E, BlindedPayload<E>>(
                 SignableMessage::ValidatorRegistration(&validator_registration_data),
@@ -791,6 +1039,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         aggregate: Attestation<E>,
         selection_proof: SelectionProof,
     ) -> Result<SignedAggregateAndProof<E>, Error> {
+        self.check_not_dry_run()?;
         let signing_epoch = aggregate.data().target.epoch;
         let signing_context = self.signing_context(Domain::AggregateAndProof, signing_epoch);
 
@@ -833,15 +1082,14 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         // be published on the network.
         let signing_method = self.doppelganger_bypassed_signing_method(validator_pubkey)?;
 
-        let signature = signing_method
-            .get_signature::<E, BlindedPayload<E>>(
+        let signature = self
+            .sign_selection_proof(
+                validator_pubkey,
+                &signing_method,
                 SignableMessage::SelectionProof(slot),
                 signing_context,
-                &self.spec,
-                &self.task_executor,
             )
-            .await
-            .map_err(Error::UnableToSign)?;
+            .await?;
 
         metrics::inc_counter_vec(&metrics::SIGNED_SELECTION_PROOFS_TOTAL, &[metrics::SUCCESS]);
 
@@ -872,15 +1120,14 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
             subcommittee_index: subnet_id.into(),
         };
 
-        let signature = signing_method
-            .get_signature::<E, BlindedPayload<E>>(
+        let signature = self
+            .sign_selection_proof(
+                *validator_pubkey,
+                &signing_method,
                 SignableMessage::SyncSelectionProof(&message),
                 signing_context,
-                &self.spec,
-                &self.task_executor,
             )
-            .await
-            .map_err(Error::UnableToSign)?;
+            .await?;
 
         Ok(signature.into())
     }
@@ -892,6 +1139,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         validator_index: u64,
         validator_pubkey: &PublicKeyBytes,
     ) -> Result<SyncCommitteeMessage, Error> {
+        self.check_not_dry_run()?;
         let signing_epoch = slot.epoch(E::slots_per_epoch());
         let signing_context = self.signing_context(Domain::SyncCommittee, signing_epoch);
 
@@ -931,6 +1179,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         contribution: SyncCommitteeContribution<E>,
         selection_proof: SyncSelectionProof,
     ) -> Result<SignedContributionAndProof<E>, Error> {
+        self.check_not_dry_run()?;
         let signing_epoch = contribution.slot.epoch(E::slots_per_epoch());
         let signing_context = self.signing_context(Domain::ContributionAndProof, signing_epoch);
 
@@ -979,30 +1228,47 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         &self,
         pubkeys: &[PublicKeyBytes],
     ) -> Result<Interchange, InterchangeError> {
-        self.slashing_protection.with_transaction(|txn| {
-            let known_pubkeys = pubkeys
-                .iter()
-                .filter_map(|pubkey| {
-                    let validator_id = self
-                        .slashing_protection
-                        .get_validator_id_ignoring_status(txn, pubkey)
-                        .ok()?;
-
-                    Some(
-                        self.slashing_protection
-                            .update_validator_status(txn, validator_id, false)
-                            .map(|()| *pubkey),
-                    )
-                })
-                .collect::<Result<Vec<PublicKeyBytes>, _>>()?;
-            self.slashing_protection.export_interchange_info_in_txn(
-                self.genesis_validators_root,
-                Some(&known_pubkeys),
-                txn,
-            )
+        self.slashing_protection
+            .export_interchange_info_and_disable(self.genesis_validators_root, pubkeys)
+    }
+
+    /// Return the messages signed by the selected validators between `start_epoch` and
+    /// `end_epoch` (inclusive), which have not yet been pruned from the slashing protection
+    /// database.
+    pub fn slashing_protection_history(
+        &self,
+        pubkey: Option<PublicKeyBytes>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+    ) -> Result<Vec<SigningHistory>, NotSafe> {
+        self.slashing_protection.signing_history(&AuditQuery {
+            pubkey,
+            start_epoch,
+            end_epoch,
+            slots_per_epoch: E::slots_per_epoch(),
+            limit: usize::MAX,
         })
     }
 
+    /// Return the most recent signing attempts refused by the slashing protection database for
+    /// the selected validators between `start_epoch` and `end_epoch` (inclusive).
+    pub fn slashing_protection_refusals(
+        &self,
+        pubkey: Option<PublicKeyBytes>,
+        start_epoch: Epoch,
+        end_epoch: Epoch,
+        limit: usize,
+    ) -> Result<Vec<RefusedSigningAttempt>, NotSafe> {
+        self.slashing_protection
+            .refused_signing_attempts(&AuditQuery {
+                pubkey,
+                start_epoch,
+                end_epoch,
+                slots_per_epoch: E::slots_per_epoch(),
+                limit,
+            })
+    }
+
     /// Prune the slashing protection database so that it remains performant.
     ///
     /// This function will only do actual pruning periodically, so it should usually be
@@ -1034,11 +1300,11 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         let new_min_target_epoch = current_epoch.saturating_sub(SLASHING_PROTECTION_HISTORY_EPOCHS);
         let new_min_slot = new_min_target_epoch.start_slot(E::slots_per_epoch());
 
-        let all_pubkeys: Vec<_> = self.voting_pubkeys(DoppelgangerStatus::ignored);
+        let all_pubkeys: Vec<PublicKeyBytes> = self.voting_pubkeys(DoppelgangerStatus::ignored);
 
         if let Err(e) = self
             .slashing_protection
-            .prune_all_signed_attestations(all_pubkeys.iter(), new_min_target_epoch)
+            .prune_all_signed_attestations(&all_pubkeys, new_min_target_epoch)
         {
             error!(
                 self.log,
@@ -1050,7 +1316,7 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
 
         if let Err(e) = self
             .slashing_protection
-            .prune_all_signed_blocks(all_pubkeys.iter(), new_min_slot)
+            .prune_all_signed_blocks(&all_pubkeys, new_min_slot)
         {
             error!(
                 self.log,
@@ -1065,3 +1331,43 @@ impl<T: SlotClock + 'static, E: EthSpec> ValidatorStore<T, E> {
         info!(self.log, "Completed pruning of slashing protection DB");
     }
 }
+
+/// The throwaway keys with which selection proofs are signed in dry-run mode, generated randomly for
+/// each validator when first used.
+#[derive(Default)]
+struct DryRunKeys {
+    keys: Mutex<HashMap<PublicKeyBytes, SecretKey>>,
+}
+
+impl DryRunKeys {
+    fn sign(&self, validator_pubkey: PublicKeyBytes, signing_root: Hash256) -> Signature {
+        self.keys
+            .lock()
+            .entry(validator_pubkey)
+            .or_insert_with(SecretKey::random)
+            .sign(signing_root)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use types::test_utils::generate_deterministic_keypair;
+
+    #[test]
+    fn dry_run_keys_are_throwaway() {
+        let dry_run_keys = DryRunKeys::default();
+        let keypair = generate_deterministic_keypair(0);
+        let validator_pubkey = keypair.pk.compress();
+        let other_pubkey = generate_deterministic_keypair(1).pk.compress();
+        let signing_root = Hash256::repeat_byte(1);
+
+        let signature = dry_run_keys.sign(validator_pubkey, signing_root);
+        assert!(!signature.verify(&keypair.pk, signing_root));
+        assert_ne!(signature, keypair.sk.sign(signing_root));
+
+        // Each validator has its own key, which is reused.
+        assert_eq!(dry_run_keys.sign(validator_pubkey, signing_root), signature);
+        assert_ne!(dry_run_keys.sign(other_pubkey, signing_root), signature);
+    }
+}